@@ -2,25 +2,26 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use glob::glob;
+use sha2::{Digest, Sha256};
 use sphinx_helper::build_program;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 const PROGRAM_PATTERNS: [&str; 2] = ["../programs/*", "../programs/benchmarks/*"];
 const TARGET_DIR: [&str; 2] = ["./artifacts", "./artifacts/benchmarks"];
 
+/// Library every program is built against, besides its own sources.
+const CORE_DIR: &str = "../core";
+
 fn main() {
     // Get `LC_PROGRAM_AUTOBUILD` env variable, default to 0
     let should_build: bool =
         std::env::var("LC_PROGRAM_AUTOBUILD").unwrap_or_else(|_| "0".into()) == "1";
 
-    if !should_build {
-        return;
-    }
-
-    // Re-run if the core library changes
-    let core_dir = std::path::Path::new("../core");
-    println!("cargo:rerun-if-changed={}", core_dir.display());
+    // Re-run if the programs or the core library change
+    println!("cargo:rerun-if-changed=../programs");
+    println!("cargo:rerun-if-changed={}", CORE_DIR);
+    println!("cargo:rerun-if-env-changed=LC_PROGRAM_AUTOBUILD");
 
     for (program_pattern, artifacts_folder) in PROGRAM_PATTERNS.iter().zip(TARGET_DIR.iter()) {
         // Create the target directory if it doesn't exist
@@ -37,17 +38,28 @@ fn main() {
                         continue;
                     }
 
-                    build_program(path.to_str().unwrap());
-
                     let dir_name = path.file_name().unwrap().to_str().unwrap();
+                    let new_path = format!("{}/{}-program", artifacts_folder, dir_name);
+                    let digest_path = format!("{}.digest", new_path);
+
+                    // An artifact is only reused if it was built from the current sources
+                    let digest = sources_digest(&path);
+                    let up_to_date = PathBuf::from(&new_path).exists()
+                        && fs::read_to_string(&digest_path)
+                            .is_ok_and(|built_from| built_from.trim() == digest);
+                    if !should_build && up_to_date {
+                        continue;
+                    }
+
+                    build_program(path.to_str().unwrap());
 
                     let old_path = path.join("elf/riscv32im-succinct-zkvm-elf");
-                    let new_path = format!("{}/{}-program", artifacts_folder, dir_name);
 
                     // If the file exists, move and rename it
                     if old_path.exists() {
                         fs::rename(old_path, new_path).unwrap();
                         fs::remove_dir_all(path.join("elf")).unwrap();
+                        fs::write(digest_path, format!("{digest}\n")).unwrap();
                     }
                 }
                 Err(e) => panic!("{:?}", e),
@@ -55,3 +67,39 @@ fn main() {
         }
     }
 }
+
+/// Computes the SHA-256 digest of the sources a program is built from, that is its
+/// manifest, lock file and sources, and those of the core library.
+///
+/// # Arguments
+///
+/// * `program_dir` - The directory of the program.
+///
+/// # Returns
+///
+/// The hex encoded digest.
+fn sources_digest(program_dir: &Path) -> String {
+    let mut files: Vec<PathBuf> = [program_dir, Path::new(CORE_DIR)]
+        .iter()
+        .flat_map(|dir| {
+            ["Cargo.toml", "Cargo.lock", "src/**/*.rs"]
+                .iter()
+                .flat_map(move |pattern| {
+                    glob(dir.join(pattern).to_str().unwrap())
+                        .expect("Failed to read glob pattern")
+                        .filter_map(Result::ok)
+                })
+        })
+        .collect();
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for file in files {
+        // Hash the path as well, so that moving code between files changes the digest
+        hasher.update(file.to_string_lossy().as_bytes());
+        hasher.update(b"\0");
+        hasher.update(fs::read(&file).unwrap());
+    }
+
+    hex::encode(hasher.finalize())
+}
@@ -3,8 +3,17 @@
 
 pub const INCLUSION_PROGRAM: &[u8] = include_bytes!("../artifacts/inclusion-program");
 
+pub const BATCH_INCLUSION_PROGRAM: &[u8] = include_bytes!("../artifacts/batch-inclusion-program");
+
 pub const EPOCH_CHANGE_PROGRAM: &[u8] = include_bytes!("../artifacts/epoch-change-program");
 
+pub const AGGREGATION_PROGRAM: &[u8] = include_bytes!("../artifacts/aggregation-program");
+
+pub const EVENT_PROGRAM: &[u8] = include_bytes!("../artifacts/event-program");
+
+pub const TRANSACTION_RANGE_PROGRAM: &[u8] =
+    include_bytes!("../artifacts/transaction-range-program");
+
 pub mod bench {
     pub const SIGNATURE_VERIFICATION_PROGRAM: &[u8] =
         include_bytes!("../artifacts/benchmarks/signature-verification-program");
@@ -0,0 +1,821 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! # Scenario module
+//!
+//! This module provides a `ScenarioBuilder` on top of the `AptosWrapper` to
+//! generate adversarial data for the light client: validator stake changes,
+//! validator set growth and shrink, ledger infos signed below quorum and
+//! forged ledger infos. It also provides helpers to tamper with serialized
+//! proofs, so that their verification is expected to fail.
+
+use crate::aptos_test_utils::error::AptosError;
+use crate::aptos_test_utils::wrapper::AptosWrapper;
+use aptos_crypto::bls12381::Signature;
+use aptos_crypto::HashValue;
+use aptos_types::aggregate_signature::PartialSignatures;
+use aptos_types::block_info::BlockInfo;
+use aptos_types::epoch_change::EpochChangeProof;
+use aptos_types::epoch_state::EpochState;
+use aptos_types::ledger_info::{LedgerInfo, LedgerInfoWithSignatures};
+use aptos_types::proof::{
+    SparseMerkleProof, TransactionAccumulatorProof, TransactionAccumulatorRangeProof,
+};
+use aptos_types::trusted_state::TrustedState;
+use aptos_types::validator_signer::ValidatorSigner;
+use aptos_types::validator_verifier::{ValidatorConsensusInfo, ValidatorVerifier};
+use aptos_types::waypoint::Waypoint;
+use aptos_types::PeerId;
+use getset::Getters;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// A validator of a scenario, along with its voting power.
+#[derive(Debug, Clone, Getters)]
+#[getset(get = "pub")]
+pub struct ScenarioValidator {
+    /// Signer of the validator
+    signer: ValidatorSigner,
+    /// Voting power of the validator
+    voting_power: u64,
+}
+
+/// The validators signing the ledger infos produced by a `ScenarioBuilder`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SignerSelection {
+    /// All validators of the epoch sign.
+    All,
+    /// Only the first validators of the epoch sign.
+    First(usize),
+    /// The first validators of the epoch sign, as long as their voting power stays below quorum.
+    BelowQuorum,
+}
+
+/// The field of a `LedgerInfo` altered by [`forge_ledger_info`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum LedgerInfoForgery {
+    /// Increments the version.
+    Version,
+    /// Increments the timestamp.
+    Timestamp,
+    /// Replaces the root hash of the transaction accumulator.
+    ExecutedStateId,
+    /// Replaces the next epoch state with one made of unknown validators.
+    NextEpochState,
+}
+
+/// Builder of adversarial scenarios on top of an `AptosWrapper`.
+///
+/// The builder keeps track of the validator set of its current epoch, which
+/// starts as the validator set of the wrapped chain. Changes to the validator
+/// set are staged for the next epoch, and applied by
+/// [`ScenarioBuilder::commit_epoch_changes`], which produces the epoch change
+/// ledger infos signed by the selected signers of each epoch.
+#[derive(Getters)]
+#[getset(get = "pub")]
+pub struct ScenarioBuilder {
+    /// Wrapped chain, producing the transactions and proofs of the scenario
+    aptos_wrapper: AptosWrapper,
+    /// Trusted state of the current epoch of the scenario
+    trusted_state: TrustedState,
+    /// Validators of the current epoch
+    validators: Vec<ScenarioValidator>,
+    /// Validators staged for the next epoch
+    next_validators: Vec<ScenarioValidator>,
+    /// Validators signing the ledger infos
+    signers: SignerSelection,
+    /// Seed of the next validator to generate
+    #[getset(skip)]
+    next_seed: u8,
+}
+
+impl ScenarioBuilder {
+    /// Creates a new `ScenarioBuilder` over a new chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `nbr_local_accounts` - The number of local accounts to create.
+    /// * `nbr_validators` - The number of validators of the chain.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of the `ScenarioBuilder`, where all validators sign.
+    pub fn new(nbr_local_accounts: usize, nbr_validators: usize) -> Result<Self, AptosError> {
+        Self::from_wrapper(AptosWrapper::new(
+            nbr_local_accounts,
+            nbr_validators,
+            nbr_validators,
+        )?)
+    }
+
+    /// Creates a new `ScenarioBuilder` over an existing chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `aptos_wrapper` - The wrapped chain.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new instance of the `ScenarioBuilder`, where all validators sign.
+    pub fn from_wrapper(aptos_wrapper: AptosWrapper) -> Result<Self, AptosError> {
+        let trusted_state = aptos_wrapper.trusted_state().clone();
+        let TrustedState::EpochState { epoch_state, .. } = &trusted_state else {
+            return Err(AptosError::UnexpectedNone(
+                "trusted epoch state".to_string(),
+            ));
+        };
+
+        let validators = aptos_wrapper
+            .signers()
+            .iter()
+            .map(|signer| {
+                epoch_state
+                    .verifier
+                    .get_voting_power(&signer.author())
+                    .ok_or(AptosError::UnexpectedNone(
+                        "validator voting power".to_string(),
+                    ))
+                    .map(|voting_power| ScenarioValidator {
+                        signer: signer.clone(),
+                        voting_power,
+                    })
+            })
+            .collect::<Result<Vec<_>, AptosError>>()?;
+
+        Ok(Self {
+            aptos_wrapper,
+            trusted_state,
+            next_validators: validators.clone(),
+            validators,
+            signers: SignerSelection::All,
+            next_seed: 1,
+        })
+    }
+
+    /// Sets the voting power of a validator for the next epoch.
+    ///
+    /// # Arguments
+    ///
+    /// * `validator_idx` - The index of the validator in the next validator set.
+    /// * `voting_power` - The new voting power of the validator.
+    pub fn set_voting_power(
+        &mut self,
+        validator_idx: usize,
+        voting_power: u64,
+    ) -> Result<&mut Self, AptosError> {
+        self.next_validators
+            .get_mut(validator_idx)
+            .ok_or(AptosError::UnexpectedNone("next validator".to_string()))?
+            .voting_power = voting_power;
+
+        Ok(self)
+    }
+
+    /// Adds freshly generated validators to the validator set of the next epoch.
+    ///
+    /// # Arguments
+    ///
+    /// * `nbr_validators` - The number of validators to add.
+    /// * `voting_power` - The voting power of each added validator.
+    pub fn add_validators(&mut self, nbr_validators: usize, voting_power: u64) -> &mut Self {
+        for _ in 0..nbr_validators {
+            let signer = self.generate_signer();
+            self.next_validators.push(ScenarioValidator {
+                signer,
+                voting_power,
+            });
+        }
+
+        self
+    }
+
+    /// Removes the last validators from the validator set of the next epoch.
+    ///
+    /// # Arguments
+    ///
+    /// * `nbr_validators` - The number of validators to remove.
+    pub fn remove_validators(&mut self, nbr_validators: usize) -> Result<&mut Self, AptosError> {
+        let remaining = self
+            .next_validators
+            .len()
+            .checked_sub(nbr_validators)
+            .filter(|remaining| *remaining > 0)
+            .ok_or(AptosError::UnexpectedNone(
+                "remaining validator".to_string(),
+            ))?;
+        self.next_validators.truncate(remaining);
+
+        Ok(self)
+    }
+
+    /// Sets the validators signing the ledger infos.
+    ///
+    /// # Arguments
+    ///
+    /// * `signers` - The validators signing the ledger infos.
+    pub fn with_signers(&mut self, signers: SignerSelection) -> &mut Self {
+        self.signers = signers;
+        self
+    }
+
+    /// Creates some random transfers on the wrapped chain.
+    pub fn generate_traffic(&mut self) -> Result<&mut Self, AptosError> {
+        self.aptos_wrapper.generate_traffic()?;
+        Ok(self)
+    }
+
+    /// Commits `nbr_epochs` epoch changes. The first one moves to the staged
+    /// validator set, the following ones keep it.
+    ///
+    /// Each epoch change `LedgerInfo` is derived from the latest `LedgerInfo`
+    /// of the wrapped chain, and signed by the selected signers of the epoch
+    /// it ends.
+    ///
+    /// # Arguments
+    ///
+    /// * `nbr_epochs` - The number of epoch changes to commit.
+    ///
+    /// # Returns
+    ///
+    /// * `(Vec<u8>, Vec<u8>)` - The serialized `TrustedState` before the epoch changes, and the
+    ///   serialized `EpochChangeProof` ratcheting it through the epoch changes.
+    pub fn commit_epoch_changes(
+        &mut self,
+        nbr_epochs: usize,
+    ) -> Result<(Vec<u8>, Vec<u8>), AptosError> {
+        let trusted_state = self.trusted_state_bytes()?;
+        let base_info = self.aptos_wrapper.get_latest_li()?.ledger_info().clone();
+
+        let mut ledger_infos = Vec::with_capacity(nbr_epochs);
+        for _ in 0..nbr_epochs {
+            let epoch = self.epoch()?;
+            let next_epoch_state = EpochState::new(epoch + 1, verifier(&self.next_validators));
+            let commit_info = base_info.commit_info();
+            let ledger_info = LedgerInfo::new(
+                BlockInfo::new(
+                    epoch,
+                    commit_info.round(),
+                    commit_info.id(),
+                    commit_info.executed_state_id(),
+                    commit_info.version(),
+                    commit_info.timestamp_usecs(),
+                    Some(next_epoch_state.clone()),
+                ),
+                base_info.consensus_data_hash(),
+            );
+            let ledger_info = self.sign(ledger_info)?;
+
+            self.trusted_state = TrustedState::EpochState {
+                waypoint: Waypoint::new_epoch_boundary(ledger_info.ledger_info())
+                    .map_err(|e| AptosError::Internal { source: e.into() })?,
+                epoch_state: next_epoch_state,
+            };
+            self.validators.clone_from(&self.next_validators);
+            ledger_infos.push(ledger_info);
+        }
+
+        let epoch_change_proof = serialize(
+            "EpochChangeProof",
+            &EpochChangeProof::new(ledger_infos, false),
+        )?;
+
+        Ok((trusted_state, epoch_change_proof))
+    }
+
+    /// Returns the latest `LedgerInfoWithSignatures` of the wrapped chain, signed
+    /// by the selected signers of the current epoch.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<u8>` - The serialized `LedgerInfoWithSignatures`.
+    pub fn latest_li_bytes(&self) -> Result<Vec<u8>, AptosError> {
+        let ledger_info = self.aptos_wrapper.get_latest_li()?.ledger_info().clone();
+        serialize("LedgerInfoWithSignatures", &self.sign(ledger_info)?)
+    }
+
+    /// Returns the serialized `TrustedState` of the current epoch.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<u8>` - The serialized `TrustedState`.
+    pub fn trusted_state_bytes(&self) -> Result<Vec<u8>, AptosError> {
+        serialize("TrustedState", &self.trusted_state)
+    }
+
+    /// Returns a copy of a `LedgerInfoWithSignatures` whose signatures are
+    /// produced by unknown keys impersonating the selected signers.
+    ///
+    /// # Arguments
+    ///
+    /// * `li_bytes` - The serialized `LedgerInfoWithSignatures`.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<u8>` - The serialized `LedgerInfoWithSignatures`, with impersonated signatures.
+    pub fn impersonate_signers(&mut self, li_bytes: &[u8]) -> Result<Vec<u8>, AptosError> {
+        let li: LedgerInfoWithSignatures = deserialize("LedgerInfoWithSignatures", li_bytes)?;
+
+        let signers = self.selected_signers()?;
+        let partial_sig = PartialSignatures::new(
+            signers
+                .iter()
+                .map(|validator| {
+                    self.generate_signer()
+                        .sign(li.ledger_info())
+                        .map_err(|e| AptosError::Internal { source: e.into() })
+                        .map(|s| (validator.signer.author(), s))
+                })
+                .collect::<Result<BTreeMap<PeerId, Signature>, AptosError>>()?,
+        );
+        let signatures = verifier(&self.validators)
+            .aggregate_signatures(&partial_sig)
+            .map_err(|e| AptosError::Internal { source: e.into() })?;
+
+        serialize(
+            "LedgerInfoWithSignatures",
+            &LedgerInfoWithSignatures::new(li.ledger_info().clone(), signatures),
+        )
+    }
+
+    /// Returns the epoch of the current trusted state.
+    fn epoch(&self) -> Result<u64, AptosError> {
+        match &self.trusted_state {
+            TrustedState::EpochState { epoch_state, .. } => Ok(epoch_state.epoch),
+            _ => Err(AptosError::UnexpectedNone(
+                "trusted epoch state".to_string(),
+            )),
+        }
+    }
+
+    /// Returns the validators of the current epoch signing the ledger infos.
+    fn selected_signers(&self) -> Result<Vec<ScenarioValidator>, AptosError> {
+        match self.signers {
+            SignerSelection::All => Ok(self.validators.clone()),
+            SignerSelection::First(nbr_signers) => self
+                .validators
+                .get(..nbr_signers)
+                .map(<[ScenarioValidator]>::to_vec)
+                .ok_or(AptosError::UnexpectedNone("ValidatorSigner".to_string())),
+            SignerSelection::BelowQuorum => {
+                let quorum = verifier(&self.validators).quorum_voting_power();
+                let mut voting_power = 0u128;
+
+                Ok(self
+                    .validators
+                    .iter()
+                    .take_while(|validator| {
+                        voting_power += u128::from(validator.voting_power);
+                        voting_power < quorum
+                    })
+                    .cloned()
+                    .collect())
+            }
+        }
+    }
+
+    /// Signs a `LedgerInfo` with the selected signers of the current epoch.
+    fn sign(&self, ledger_info: LedgerInfo) -> Result<LedgerInfoWithSignatures, AptosError> {
+        let partial_sig = PartialSignatures::new(
+            self.selected_signers()?
+                .iter()
+                .map(|validator| {
+                    validator
+                        .signer
+                        .sign(&ledger_info)
+                        .map_err(|e| AptosError::Internal { source: e.into() })
+                        .map(|s| (validator.signer.author(), s))
+                })
+                .collect::<Result<BTreeMap<PeerId, Signature>, AptosError>>()?,
+        );
+
+        let signatures = verifier(&self.validators)
+            .aggregate_signatures(&partial_sig)
+            .map_err(|e| AptosError::Internal { source: e.into() })?;
+
+        Ok(LedgerInfoWithSignatures::new(ledger_info, signatures))
+    }
+
+    /// Generates a new validator signer, deterministically.
+    fn generate_signer(&mut self) -> ValidatorSigner {
+        let signer = ValidatorSigner::random([self.next_seed; 32]);
+        self.next_seed = self.next_seed.wrapping_add(1);
+        signer
+    }
+}
+
+/// Returns a copy of a `LedgerInfoWithSignatures` with one forged field,
+/// keeping its original signatures.
+///
+/// # Arguments
+///
+/// * `li_bytes` - The serialized `LedgerInfoWithSignatures`.
+/// * `forgery` - The field of the `LedgerInfo` to forge.
+///
+/// # Returns
+///
+/// * `Vec<u8>` - The serialized forged `LedgerInfoWithSignatures`.
+pub fn forge_ledger_info(
+    li_bytes: &[u8],
+    forgery: LedgerInfoForgery,
+) -> Result<Vec<u8>, AptosError> {
+    let li: LedgerInfoWithSignatures = deserialize("LedgerInfoWithSignatures", li_bytes)?;
+    let commit_info = li.ledger_info().commit_info();
+
+    let mut version = commit_info.version();
+    let mut timestamp_usecs = commit_info.timestamp_usecs();
+    let mut executed_state_id = commit_info.executed_state_id();
+    let mut next_epoch_state = commit_info.next_epoch_state().cloned();
+    match forgery {
+        LedgerInfoForgery::Version => version += 1,
+        LedgerInfoForgery::Timestamp => timestamp_usecs += 1,
+        LedgerInfoForgery::ExecutedStateId => {
+            executed_state_id = HashValue::sha3_256_of(executed_state_id.as_ref())
+        }
+        LedgerInfoForgery::NextEpochState => {
+            let validators = (0..4)
+                .map(|seed| ScenarioValidator {
+                    signer: ValidatorSigner::random([u8::MAX - seed; 32]),
+                    voting_power: 1,
+                })
+                .collect::<Vec<_>>();
+            next_epoch_state = Some(EpochState::new(
+                commit_info.epoch() + 1,
+                verifier(&validators),
+            ));
+        }
+    }
+
+    let ledger_info = LedgerInfo::new(
+        BlockInfo::new(
+            commit_info.epoch(),
+            commit_info.round(),
+            commit_info.id(),
+            executed_state_id,
+            version,
+            timestamp_usecs,
+            next_epoch_state,
+        ),
+        li.ledger_info().consensus_data_hash(),
+    );
+
+    serialize(
+        "LedgerInfoWithSignatures",
+        &LedgerInfoWithSignatures::new(ledger_info, li.signatures().clone()),
+    )
+}
+
+/// Removes the last sibling from a serialized `SparseMerkleProof`.
+///
+/// # Arguments
+///
+/// * `proof_bytes` - The serialized `SparseMerkleProof`.
+///
+/// # Returns
+///
+/// * `Vec<u8>` - The serialized truncated `SparseMerkleProof`.
+pub fn truncate_sparse_merkle_proof(proof_bytes: &[u8]) -> Result<Vec<u8>, AptosError> {
+    let proof: SparseMerkleProof = deserialize("SparseMerkleProof", proof_bytes)?;
+    let siblings = truncated(proof.siblings())?;
+
+    serialize(
+        "SparseMerkleProof",
+        &SparseMerkleProof::new(proof.leaf(), siblings),
+    )
+}
+
+/// Removes the last sibling from a serialized accumulator proof. Transaction
+/// and event accumulator proofs share the same encoding.
+///
+/// # Arguments
+///
+/// * `proof_bytes` - The serialized `TransactionAccumulatorProof` or `EventAccumulatorProof`.
+///
+/// # Returns
+///
+/// * `Vec<u8>` - The serialized truncated accumulator proof.
+pub fn truncate_accumulator_proof(proof_bytes: &[u8]) -> Result<Vec<u8>, AptosError> {
+    let proof: TransactionAccumulatorProof = deserialize("AccumulatorProof", proof_bytes)?;
+    let siblings = truncated(proof.siblings())?;
+
+    serialize(
+        "AccumulatorProof",
+        &TransactionAccumulatorProof::new(siblings),
+    )
+}
+
+/// Removes the last sibling from a serialized `TransactionAccumulatorRangeProof`,
+/// on its right side when it has one.
+///
+/// # Arguments
+///
+/// * `proof_bytes` - The serialized `TransactionAccumulatorRangeProof`.
+///
+/// # Returns
+///
+/// * `Vec<u8>` - The serialized truncated `TransactionAccumulatorRangeProof`.
+pub fn truncate_range_proof(proof_bytes: &[u8]) -> Result<Vec<u8>, AptosError> {
+    let proof: TransactionAccumulatorRangeProof =
+        deserialize("TransactionAccumulatorRangeProof", proof_bytes)?;
+
+    let (left_siblings, right_siblings) = if proof.right_siblings().is_empty() {
+        (truncated(proof.left_siblings())?, vec![])
+    } else {
+        (
+            proof.left_siblings().clone(),
+            truncated(proof.right_siblings())?,
+        )
+    };
+
+    serialize(
+        "TransactionAccumulatorRangeProof",
+        &TransactionAccumulatorRangeProof::new(left_siblings, right_siblings),
+    )
+}
+
+/// Removes the first `LedgerInfoWithSignatures` from a serialized `EpochChangeProof`.
+///
+/// # Arguments
+///
+/// * `proof_bytes` - The serialized `EpochChangeProof`.
+///
+/// # Returns
+///
+/// * `Vec<u8>` - The serialized truncated `EpochChangeProof`.
+pub fn truncate_epoch_change_proof(proof_bytes: &[u8]) -> Result<Vec<u8>, AptosError> {
+    let proof: EpochChangeProof = deserialize("EpochChangeProof", proof_bytes)?;
+    if proof.ledger_info_with_sigs.len() < 2 {
+        return Err(AptosError::UnexpectedNone(
+            "remaining LedgerInfoWithSignatures".to_string(),
+        ));
+    }
+
+    serialize(
+        "EpochChangeProof",
+        &EpochChangeProof::new(proof.ledger_info_with_sigs[1..].to_vec(), proof.more),
+    )
+}
+
+/// Returns the validator verifier of a set of scenario validators.
+fn verifier(validators: &[ScenarioValidator]) -> ValidatorVerifier {
+    ValidatorVerifier::new(
+        validators
+            .iter()
+            .map(|validator| {
+                ValidatorConsensusInfo::new(
+                    validator.signer.author(),
+                    validator.signer.public_key(),
+                    validator.voting_power,
+                )
+            })
+            .collect(),
+    )
+}
+
+/// Returns the siblings of a proof without the last one.
+fn truncated(siblings: &[HashValue]) -> Result<Vec<HashValue>, AptosError> {
+    siblings
+        .split_last()
+        .map(|(_, siblings)| siblings.to_vec())
+        .ok_or(AptosError::UnexpectedNone("proof sibling".to_string()))
+}
+
+fn serialize<T: Serialize>(structure: &str, value: &T) -> Result<Vec<u8>, AptosError> {
+    bcs::to_bytes(value).map_err(|e| AptosError::Serialization {
+        structure: structure.to_string(),
+        source: e.into(),
+    })
+}
+
+fn deserialize<T: DeserializeOwned>(structure: &str, bytes: &[u8]) -> Result<T, AptosError> {
+    bcs::from_bytes(bytes).map_err(|e| AptosError::Serialization {
+        structure: structure.to_string(),
+        source: e.into(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::crypto::hash::HashValue as LcHashValue;
+    use crate::merkle::event_proof::EventAccumulatorProof as LcEventAccumulatorProof;
+    use crate::merkle::sparse_proof::SparseMerkleProof as LcSparseMerkleProof;
+    use crate::merkle::transaction_proof::{
+        TransactionAccumulatorProof as LcTransactionAccumulatorProof,
+        TransactionAccumulatorRangeProof as LcTransactionAccumulatorRangeProof,
+    };
+    use crate::types::error::VerifyError;
+    use crate::types::ledger_info::LedgerInfoWithSignatures as LcLedgerInfoWithSignatures;
+    use crate::types::trusted_state::{
+        EpochChangeProof as LcEpochChangeProof, TrustedState as LcTrustedState, TrustedStateChange,
+    };
+    use crate::types::validator::ValidatorVerifier as LcValidatorVerifier;
+    use crate::types::AccountAddress;
+    use aptos_crypto::hash::CryptoHash;
+
+    fn lc_hash(hash: HashValue) -> LcHashValue {
+        LcHashValue::from_slice(hash.as_ref()).unwrap()
+    }
+
+    fn verifier_of(trusted_state: &[u8]) -> LcValidatorVerifier {
+        match LcTrustedState::from_bytes(trusted_state).unwrap() {
+            LcTrustedState::EpochState { epoch_state, .. } => epoch_state.verifier().clone(),
+            _ => panic!("Expected epoch state"),
+        }
+    }
+
+    fn ratchet(trusted_state: &[u8], epoch_change_proof: &[u8]) -> anyhow::Result<LcTrustedState> {
+        let epoch_change_proof = LcEpochChangeProof::from_bytes(epoch_change_proof).unwrap();
+        match LcTrustedState::from_bytes(trusted_state)
+            .unwrap()
+            .verify_and_ratchet_inner(&epoch_change_proof)?
+        {
+            TrustedStateChange::Epoch { new_state, .. } => Ok(new_state),
+            _ => panic!("Expected epoch change"),
+        }
+    }
+
+    #[test]
+    fn test_scenario_validator_set_changes() {
+        let mut scenario = ScenarioBuilder::new(4, 10).unwrap();
+        scenario.generate_traffic().unwrap();
+
+        // Change the stake of a validator and grow the validator set
+        scenario
+            .set_voting_power(0, 1)
+            .unwrap()
+            .add_validators(3, 10);
+        let (trusted_state, epoch_change_proof) = scenario.commit_epoch_changes(1).unwrap();
+        let new_state = ratchet(&trusted_state, &epoch_change_proof).unwrap();
+        assert_eq!(new_state.epoch(), Some(2));
+
+        let new_verifier = verifier_of(&scenario.trusted_state_bytes().unwrap());
+        assert_eq!(new_verifier.len(), 13);
+        let author = scenario.validators()[0].signer().author();
+        assert_eq!(
+            new_verifier.get_voting_power(&AccountAddress::new(author.into_bytes())),
+            Some(1)
+        );
+
+        // Shrink the validator set over two epochs
+        scenario.remove_validators(5).unwrap();
+        let (trusted_state, epoch_change_proof) = scenario.commit_epoch_changes(2).unwrap();
+        let new_state = ratchet(&trusted_state, &epoch_change_proof).unwrap();
+        assert_eq!(new_state.epoch(), Some(4));
+        assert_eq!(
+            verifier_of(&scenario.trusted_state_bytes().unwrap()).len(),
+            8
+        );
+
+        // Ledger infos are signed by the latest validator set
+        let li =
+            LcLedgerInfoWithSignatures::from_bytes(&scenario.latest_li_bytes().unwrap()).unwrap();
+        li.verify_signatures(&verifier_of(&scenario.trusted_state_bytes().unwrap()))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_scenario_below_quorum() {
+        let mut scenario = ScenarioBuilder::new(4, 10).unwrap();
+        scenario
+            .generate_traffic()
+            .unwrap()
+            .with_signers(SignerSelection::BelowQuorum);
+
+        let verifier = verifier_of(&scenario.trusted_state_bytes().unwrap());
+        let li =
+            LcLedgerInfoWithSignatures::from_bytes(&scenario.latest_li_bytes().unwrap()).unwrap();
+        assert!(matches!(
+            li.verify_signatures(&verifier),
+            Err(VerifyError::TooLittleVotingPower { .. })
+        ));
+
+        let (trusted_state, epoch_change_proof) = scenario.commit_epoch_changes(1).unwrap();
+        assert!(ratchet(&trusted_state, &epoch_change_proof).is_err());
+    }
+
+    #[test]
+    fn test_scenario_forged_ledger_infos() {
+        let mut scenario = ScenarioBuilder::new(4, 10).unwrap();
+        scenario.generate_traffic().unwrap();
+
+        let verifier = verifier_of(&scenario.trusted_state_bytes().unwrap());
+        let li_bytes = scenario.latest_li_bytes().unwrap();
+        LcLedgerInfoWithSignatures::from_bytes(&li_bytes)
+            .unwrap()
+            .verify_signatures(&verifier)
+            .unwrap();
+
+        for forgery in [
+            LedgerInfoForgery::Version,
+            LedgerInfoForgery::Timestamp,
+            LedgerInfoForgery::ExecutedStateId,
+            LedgerInfoForgery::NextEpochState,
+        ] {
+            let forged_li = forge_ledger_info(&li_bytes, forgery).unwrap();
+            assert!(
+                LcLedgerInfoWithSignatures::from_bytes(&forged_li)
+                    .unwrap()
+                    .verify_signatures(&verifier)
+                    .is_err(),
+                "{forgery:?} forgery should be rejected"
+            );
+        }
+
+        let impersonated_li = scenario.impersonate_signers(&li_bytes).unwrap();
+        assert_eq!(
+            LcLedgerInfoWithSignatures::from_bytes(&impersonated_li)
+                .unwrap()
+                .verify_signatures(&verifier),
+            Err(VerifyError::InvalidMultiSignature)
+        );
+    }
+
+    #[test]
+    fn test_scenario_tampered_proofs() {
+        let mut scenario = ScenarioBuilder::new(4, 10).unwrap();
+        scenario.generate_traffic().unwrap();
+        let aptos_wrapper = scenario.aptos_wrapper();
+        let li =
+            LcLedgerInfoWithSignatures::from_bytes(&scenario.latest_li_bytes().unwrap()).unwrap();
+        let accumulator_hash = li.ledger_info().transaction_accumulator_hash();
+
+        // Sparse Merkle proof
+        let proof_assets = aptos_wrapper.get_latest_proof_account(0).unwrap();
+        let truncated_proof =
+            truncate_sparse_merkle_proof(&bcs::to_bytes(proof_assets.state_proof()).unwrap())
+                .unwrap();
+        assert!(LcSparseMerkleProof::from_bytes(&truncated_proof)
+            .unwrap()
+            .verify_by_hash(
+                lc_hash(*proof_assets.root_hash()),
+                lc_hash(*proof_assets.key()),
+                lc_hash(proof_assets.state_value_hash().unwrap()),
+            )
+            .is_err());
+
+        // Transaction accumulator proof
+        let transaction_proof = bcs::to_bytes(proof_assets.transaction_proof()).unwrap();
+        let transaction_hash = lc_hash(proof_assets.transaction().hash());
+        let transaction_version = *proof_assets.transaction_version();
+        let truncated_proof = truncate_accumulator_proof(&transaction_proof).unwrap();
+        assert!(LcTransactionAccumulatorProof::from_bytes(&truncated_proof)
+            .unwrap()
+            .verify(accumulator_hash, transaction_hash, transaction_version)
+            .is_err());
+        assert!(
+            LcTransactionAccumulatorProof::from_bytes(&transaction_proof)
+                .unwrap()
+                .verify(accumulator_hash, transaction_hash, transaction_version - 1)
+                .is_err()
+        );
+
+        // Transaction accumulator range proof
+        let range_assets = aptos_wrapper.get_latest_transaction_range_proof(4).unwrap();
+        let range_proof = bcs::to_bytes(range_assets.range_proof()).unwrap();
+        let transaction_hashes: Vec<LcHashValue> = range_assets
+            .transactions()
+            .iter()
+            .map(|transaction| lc_hash(transaction.hash()))
+            .collect();
+        let first_version = *range_assets.first_transaction_version();
+        let truncated_proof = truncate_range_proof(&range_proof).unwrap();
+        assert!(
+            LcTransactionAccumulatorRangeProof::from_bytes(&truncated_proof)
+                .unwrap()
+                .verify(accumulator_hash, first_version, &transaction_hashes)
+                .is_err()
+        );
+        assert!(LcTransactionAccumulatorRangeProof::from_bytes(&range_proof)
+            .unwrap()
+            .verify(accumulator_hash, first_version - 1, &transaction_hashes)
+            .is_err());
+
+        // Event accumulator proof
+        let event_assets = aptos_wrapper.get_latest_event_proof(1).unwrap();
+        let event_root_hash = lc_hash(event_assets.transaction().event_root_hash());
+        let event_hash = lc_hash(event_assets.event().hash());
+        let event_proof = bcs::to_bytes(event_assets.event_proof()).unwrap();
+        let truncated_proof = truncate_accumulator_proof(&event_proof).unwrap();
+        assert!(LcEventAccumulatorProof::from_bytes(&truncated_proof)
+            .unwrap()
+            .verify(event_root_hash, event_hash, *event_assets.event_index())
+            .is_err());
+        assert!(LcEventAccumulatorProof::from_bytes(&event_proof)
+            .unwrap()
+            .verify(event_root_hash, event_hash, *event_assets.event_index() - 1)
+            .is_err());
+    }
+
+    #[test]
+    fn test_scenario_truncated_epoch_change_proof() {
+        let mut scenario = ScenarioBuilder::new(4, 10).unwrap();
+        scenario.generate_traffic().unwrap();
+
+        let (trusted_state, epoch_change_proof) = scenario.commit_epoch_changes(2).unwrap();
+        ratchet(&trusted_state, &epoch_change_proof).unwrap();
+
+        let truncated_proof = truncate_epoch_change_proof(&epoch_change_proof).unwrap();
+        assert!(ratchet(&trusted_state, &truncated_proof).is_err());
+    }
+}
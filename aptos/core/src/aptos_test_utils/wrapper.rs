@@ -6,24 +6,32 @@
 //! It is primarily used for testing purposes.
 use crate::aptos_test_utils::error::AptosError;
 use aptos_crypto::bls12381::Signature;
-use aptos_crypto::hash::{CryptoHash, TransactionAccumulatorHasher};
+use aptos_crypto::hash::{
+    CryptoHash, EventAccumulatorHasher, TransactionAccumulatorHasher, ACCUMULATOR_PLACEHOLDER_HASH,
+};
 use aptos_crypto::HashValue;
 use aptos_executor::block_executor::BlockExecutor;
 use aptos_executor_test_helpers::gen_block_id;
 use aptos_executor_test_helpers::integration_test_impl::create_db_and_executor;
 use aptos_executor_types::BlockExecutorTrait;
+use aptos_sdk::move_types::language_storage::StructTag;
 use aptos_sdk::move_types::move_resource::MoveStructType;
 use aptos_sdk::transaction_builder::aptos_stdlib::version_set_version;
 use aptos_sdk::transaction_builder::{aptos_stdlib, TransactionFactory};
 use aptos_sdk::types::{AccountKey, LocalAccount};
 use aptos_storage_interface::DbReaderWriter;
+use aptos_types::account_address::AccountAddress;
 use aptos_types::account_config::{aptos_test_root_address, AccountResource};
 use aptos_types::aggregate_signature::PartialSignatures;
 use aptos_types::block_info::BlockInfo;
 use aptos_types::block_metadata::BlockMetadata;
 use aptos_types::chain_id::ChainId;
+use aptos_types::contract_event::ContractEvent;
 use aptos_types::ledger_info::LedgerInfoWithSignatures;
-use aptos_types::proof::{AccumulatorProof, SparseMerkleProof};
+use aptos_types::proof::{
+    AccumulatorProof, EventAccumulatorProof, MerkleTreeInternalNode, SparseMerkleProof,
+    TransactionAccumulatorRangeProof,
+};
 use aptos_types::state_proof::StateProof;
 use aptos_types::state_store::state_key::StateKey;
 use aptos_types::state_store::state_value::StateValue;
@@ -44,6 +52,7 @@ use rand::prelude::SliceRandom;
 use rand::SeedableRng;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
+use std::str::FromStr;
 
 /// Multiplier to fund accounts, so that they can interact with the chain without
 /// worrying about it.
@@ -77,6 +86,52 @@ impl SparseMerkleProofAssets {
             .map(|sv| sv.hash())
             .ok_or(AptosError::UnexpectedNone("state_value".to_string()))
     }
+
+    /// Returns the BCS serialized state value, `None` if the key is absent from the state
+    pub fn state_value_bytes(&self) -> Result<Option<Vec<u8>>, AptosError> {
+        self.state_value
+            .as_ref()
+            .map(bcs::to_bytes)
+            .transpose()
+            .map_err(|e| AptosError::Serialization {
+                structure: "StateValue".to_string(),
+                source: e.into(),
+            })
+    }
+}
+
+/// Structure containing an `EventAccumulatorProof` for an event emitted by a transaction, along
+/// with the parameters to verify it.
+#[derive(Getters, Clone, Debug, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct EventProofAssets {
+    /// Proof for the event inclusion in the transaction
+    event_proof: EventAccumulatorProof,
+    /// Event to prove
+    event: ContractEvent,
+    /// Index of the event in the transaction
+    event_index: u64,
+    /// All events emitted by the transaction
+    transaction_events: Vec<ContractEvent>,
+    /// Proof for the transaction inclusion
+    transaction_proof: AccumulatorProof<TransactionAccumulatorHasher>,
+    /// Hashed representation of the transaction
+    transaction: TransactionInfo,
+    /// Transaction version
+    transaction_version: u64,
+}
+
+/// Structure containing a `TransactionAccumulatorRangeProof` for consecutive transactions, along
+/// with the parameters to verify it.
+#[derive(Getters, Clone, Debug, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct TransactionRangeProofAssets {
+    /// Proof for the inclusion of the transactions range
+    range_proof: TransactionAccumulatorRangeProof,
+    /// Hashed representation of the consecutive transactions
+    transactions: Vec<TransactionInfo>,
+    /// Version of the first transaction of the range
+    first_transaction_version: u64,
 }
 
 /// Wrapper around the Aptos execution layer for testing purposes.
@@ -509,17 +564,88 @@ impl AptosWrapper {
     pub fn get_latest_proof_account(
         &self,
         account_idx: usize,
+    ) -> Result<SparseMerkleProofAssets, AptosError> {
+        let address = self
+            .accounts()
+            .get(account_idx)
+            .ok_or(AptosError::UnexpectedNone("get accounts".into()))?
+            .address();
+
+        self.get_latest_proof_address(&address)
+    }
+
+    /// Returns a `SparseMerkleProofAssets` for the `CoinStore<AptosCoin>` of a specified account.
+    ///
+    /// # Arguments
+    ///
+    /// * `account_idx` - The index of the account for which to get the `SparseMerkleProofAssets`.
+    ///
+    /// # Returns
+    ///
+    /// * `SparseMerkleProofAssets` - The `SparseMerkleProofAssets` for the coin store of the specified account.
+    pub fn get_latest_proof_coin_store(
+        &self,
+        account_idx: usize,
+    ) -> Result<SparseMerkleProofAssets, AptosError> {
+        let address = self
+            .accounts()
+            .get(account_idx)
+            .ok_or(AptosError::UnexpectedNone("get accounts".into()))?
+            .address();
+        let coin_store_tag =
+            StructTag::from_str("0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>")
+                .map_err(|e| AptosError::Internal { source: e.into() })?;
+
+        self.get_latest_proof_resource(&address, &coin_store_tag)
+    }
+
+    /// Returns a `SparseMerkleProofAssets` for an account that was never created on the chain.
+    ///
+    /// The returned assets contain a non-inclusion proof, and their `state_value` is `None`.
+    ///
+    /// # Returns
+    ///
+    /// * `SparseMerkleProofAssets` - The `SparseMerkleProofAssets` for the absent account.
+    pub fn get_latest_proof_absent_account(&self) -> Result<SparseMerkleProofAssets, AptosError> {
+        self.get_latest_proof_address(&AccountAddress::new([0xab; AccountAddress::LENGTH]))
+    }
+
+    /// Returns a `SparseMerkleProofAssets` for the `AccountResource` of a given address.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The address for which to get the `SparseMerkleProofAssets`.
+    ///
+    /// # Returns
+    ///
+    /// * `SparseMerkleProofAssets` - The `SparseMerkleProofAssets` for the specified address. The
+    ///   `state_value` is `None` if no account exists at this address.
+    pub fn get_latest_proof_address(
+        &self,
+        address: &AccountAddress,
+    ) -> Result<SparseMerkleProofAssets, AptosError> {
+        self.get_latest_proof_resource(address, &AccountResource::struct_tag())
+    }
+
+    /// Returns a `SparseMerkleProofAssets` for a Move resource stored at a given address.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The address holding the resource.
+    /// * `struct_tag` - The type of the resource.
+    ///
+    /// # Returns
+    ///
+    /// * `SparseMerkleProofAssets` - The `SparseMerkleProofAssets` for the specified resource. The
+    ///   `state_value` is `None` if the resource does not exist at this address.
+    pub fn get_latest_proof_resource(
+        &self,
+        address: &AccountAddress,
+        struct_tag: &StructTag,
     ) -> Result<SparseMerkleProofAssets, AptosError> {
         // Create a state key to get the info
-        let account_0_resource_path = StateKey::resource(
-            &self
-                .accounts()
-                .get(account_idx)
-                .ok_or(AptosError::UnexpectedNone("get accounts".into()))?
-                .address(),
-            &AccountResource::struct_tag(),
-        )
-        .map_err(|e| AptosError::Internal { source: e.into() })?;
+        let account_0_resource_path = StateKey::resource(address, struct_tag)
+            .map_err(|e| AptosError::Internal { source: e.into() })?;
 
         // Get the state proof for the current version
         let (state_value, state_proof) = self
@@ -557,6 +683,148 @@ impl AptosWrapper {
             transaction_version,
         })
     }
+
+    /// Returns an `EventProofAssets` for the latest transaction that emitted more than
+    /// `event_index` events.
+    ///
+    /// # Arguments
+    ///
+    /// * `event_index` - The index of the event in the transaction for which to get the `EventProofAssets`.
+    ///
+    /// # Returns
+    ///
+    /// * `EventProofAssets` - The `EventProofAssets` for the specified event.
+    pub fn get_latest_event_proof(&self, event_index: u64) -> Result<EventProofAssets, AptosError> {
+        for version in (0..=*self.current_version()).rev() {
+            let txn_w_proof = self
+                .db()
+                .reader
+                .get_transaction_by_version(version, *self.current_version(), true)
+                .map_err(|e| AptosError::Internal { source: e.into() })?;
+
+            let transaction_events = txn_w_proof
+                .events
+                .ok_or(AptosError::UnexpectedNone("events".to_string()))?;
+            let Some(event) = transaction_events.get(event_index as usize).cloned() else {
+                continue;
+            };
+
+            let event_hashes: Vec<HashValue> =
+                transaction_events.iter().map(CryptoHash::hash).collect();
+            let event_proof =
+                EventAccumulatorProof::new(compute_event_siblings(&event_hashes, event_index));
+            let txn_info = txn_w_proof.proof.transaction_info;
+
+            event_proof
+                .verify(txn_info.event_root_hash(), event.hash(), event_index)
+                .map_err(|e| AptosError::Internal { source: e.into() })?;
+
+            return Ok(EventProofAssets {
+                event_proof,
+                event,
+                event_index,
+                transaction_events,
+                transaction_proof: txn_w_proof.proof.ledger_info_to_transaction_info_proof,
+                transaction: txn_info,
+                transaction_version: txn_w_proof.version,
+            });
+        }
+
+        Err(AptosError::UnexpectedNone(format!(
+            "transaction with event at index {event_index}"
+        )))
+    }
+
+    /// Returns a `TransactionRangeProofAssets` for the latest `num_transactions` transactions.
+    ///
+    /// # Arguments
+    ///
+    /// * `num_transactions` - The number of consecutive transactions to prove, ending at the current version.
+    ///
+    /// # Returns
+    ///
+    /// * `TransactionRangeProofAssets` - The `TransactionRangeProofAssets` for the transactions.
+    pub fn get_latest_transaction_range_proof(
+        &self,
+        num_transactions: u64,
+    ) -> Result<TransactionRangeProofAssets, AptosError> {
+        let first_version = (*self.current_version() + 1)
+            .checked_sub(num_transactions)
+            .ok_or(AptosError::UnexpectedNone("first version".to_string()))?;
+
+        let txn_list_w_proof = self
+            .db()
+            .reader
+            .get_transactions(
+                first_version,
+                num_transactions,
+                *self.current_version(),
+                false,
+            )
+            .map_err(|e| AptosError::Internal { source: e.into() })?;
+
+        let first_transaction_version =
+            txn_list_w_proof
+                .first_transaction_version
+                .ok_or(AptosError::UnexpectedNone(
+                    "first_transaction_version".to_string(),
+                ))?;
+
+        Ok(TransactionRangeProofAssets {
+            range_proof: txn_list_w_proof
+                .proof
+                .ledger_info_to_transaction_infos_proof,
+            transactions: txn_list_w_proof.proof.transaction_infos,
+            first_transaction_version,
+        })
+    }
+}
+
+/// Computes the siblings of the leaf at `leaf_index` in the event accumulator built over
+/// `event_hashes`, ordered from the bottom level to the root level.
+///
+/// # Arguments
+///
+/// * `event_hashes` - The hashes of all events emitted by a transaction.
+/// * `leaf_index` - The index of the leaf for which to compute the siblings.
+///
+/// # Returns
+///
+/// * `Vec<HashValue>` - The siblings of the leaf.
+fn compute_event_siblings(event_hashes: &[HashValue], leaf_index: u64) -> Vec<HashValue> {
+    let num_leaves = event_hashes.len() as u64;
+    let depth = num_leaves.next_power_of_two().trailing_zeros();
+
+    (0..depth)
+        .map(|level| event_accumulator_node(event_hashes, level, (leaf_index >> level) ^ 1))
+        .collect()
+}
+
+/// Computes the hash of the node at position `index` of `level` in the event accumulator built
+/// over `event_hashes`. Subtrees containing no leaf are represented by the placeholder hash.
+///
+/// # Arguments
+///
+/// * `event_hashes` - The hashes of all events emitted by a transaction.
+/// * `level` - The level of the node, leaves being at level 0.
+/// * `index` - The index of the node at its level.
+///
+/// # Returns
+///
+/// * `HashValue` - The hash of the node.
+fn event_accumulator_node(event_hashes: &[HashValue], level: u32, index: u64) -> HashValue {
+    if index << level >= event_hashes.len() as u64 {
+        return *ACCUMULATOR_PLACEHOLDER_HASH;
+    }
+    if level == 0 {
+        return event_hashes[index as usize];
+    }
+
+    MerkleTreeInternalNode::<EventAccumulatorHasher>::new(
+        event_accumulator_node(event_hashes, level - 1, index * 2),
+        event_accumulator_node(event_hashes, level - 1, index * 2 + 1),
+    )
+    .hash()
 }
 
 /// Generates a specified number of local accounts.
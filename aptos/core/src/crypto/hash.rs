@@ -134,6 +134,23 @@ impl HashValue {
         HashValueBitIterator::new(self)
     }
 
+    /// Returns the length of the common prefix, in bits, between
+    /// this `HashValue` and another one.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The `HashValue` to compare against.
+    ///
+    /// # Returns
+    ///
+    /// The number of leading bits shared by both `HashValue`.
+    pub fn common_prefix_bits_len(&self, other: &HashValue) -> usize {
+        self.iter_bits()
+            .zip(other.iter_bits())
+            .take_while(|(x, y)| x == y)
+            .count()
+    }
+
     /// Converts the `HashValue` into a vector.
     ///
     /// This method takes the hash value and converts it into a vector of bytes.
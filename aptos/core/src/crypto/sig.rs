@@ -9,15 +9,14 @@
 //! ## Usage
 //!
 //! This module is used for creating, manipulating, and verifying cryptographic signatures in the Aptos codebase.
-//! The `PublicKey`, `Signature`, and `AggregateSignature` structures provide functionality for working with public keys and signatures.
+//! The `PublicKey`, `PreparedPublicKey`, `Signature`, and `AggregateSignature` structures provide functionality for working with public keys and signatures.
 use crate::crypto::error::CryptoError;
 use crate::serde_error;
+use crate::types::codec;
 use crate::types::error::TypesError;
-use crate::types::utils::{read_leb128, write_leb128};
 use anyhow::Result;
 use bls12_381::hash_to_curve::{ExpandMsgXmd, HashToCurve};
 use bls12_381::{multi_miller_loop, G1Affine, G2Affine, G2Prepared, G2Projective, Gt};
-use bytes::{Buf, BufMut, BytesMut};
 use getset::Getters;
 use serde::de::Error;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
@@ -32,6 +31,9 @@ const DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
 /// Length of a public key in bytes.
 pub const PUB_KEY_LEN: usize = 48;
 
+/// Length of an uncompressed public key in bytes.
+pub const PREPARED_PUB_KEY_LEN: usize = 96;
+
 /// Length of a signature in bytes.
 pub const SIG_LEN: usize = 96;
 
@@ -89,6 +91,15 @@ impl PublicKey {
             .get_or_init(|| G1Affine::from_compressed_unchecked(&self.compressed_pubkey).unwrap())
     }
 
+    /// Returns the compressed byte array representing the public key.
+    ///
+    /// # Returns
+    ///
+    /// A reference to the compressed public key.
+    pub(crate) const fn compressed_pubkey(&self) -> &[u8; PUB_KEY_LEN] {
+        &self.compressed_pubkey
+    }
+
     /// Aggregates a vector of public keys into a single public key.
     ///
     /// # Arguments
@@ -119,20 +130,14 @@ impl PublicKey {
     ///
     /// A `Vec<u8>` representing the public key.
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = BytesMut::new();
-        let pub_key_bytes: &[u8] = self.compressed_pubkey.as_ref();
-
-        bytes.put_slice(&write_leb128(pub_key_bytes.len() as u64));
-        bytes.put_slice(pub_key_bytes);
-        bytes.to_vec()
+        codec::to_bytes(self)
     }
 
-    /// Creates a `PublicKey` from a slice of bytes, following a BCS
-    /// (Binary Canonical Serialization) standard.
+    /// Creates a `PublicKey` from its raw compressed bytes.
     ///
     /// # Arguments
     ///
-    /// * `bytes` - A byte slice from which to create the `PublicKey`.
+    /// * `bytes` - A byte slice of length `PUB_KEY_LEN` from which to create the `PublicKey`.
     ///
     /// # Returns
     ///
@@ -143,13 +148,22 @@ impl PublicKey {
             return Err(serde_error!("PublicKey", "Invalid public key byte length"));
         }
 
-        let bytes_fixed =
-            <&[u8; PUB_KEY_LEN]>::try_from(bytes).map_err(|e| serde_error!("PublicKey", e))?;
+        Self::try_from(bytes).map_err(|e| serde_error!("PublicKey", e))
+    }
 
-        Ok(Self {
-            compressed_pubkey: *bytes_fixed,
-            pubkey: OnceCell::new(),
-        })
+    /// Creates a `PublicKey` from a slice of bytes, following a BCS
+    /// (Binary Canonical Serialization) standard.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - A byte slice from which to create the `PublicKey`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the `PublicKey` could be created successfully. If the slice is not a valid
+    /// encoding, the `Result` is `Err` with an error message.
+    pub fn from_bcs_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
+        codec::from_bytes("PublicKey", bytes)
     }
 }
 
@@ -199,6 +213,179 @@ impl TryFrom<&[u8]> for PublicKey {
     }
 }
 
+/// A structure representing a prepared public key.
+///
+/// The public key is stored as a decompressed `G1Affine` point and is
+/// serialized uncompressed, so that it can be read back without paying
+/// for a decompression. Prepared public keys can be added and subtracted
+/// to compute the aggregated key of a set of signers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PreparedPublicKey {
+    pubkey: G1Affine,
+}
+
+impl Default for PreparedPublicKey {
+    fn default() -> Self {
+        Self {
+            pubkey: G1Affine::identity(),
+        }
+    }
+}
+
+impl PreparedPublicKey {
+    /// Aggregates prepared public keys into a single prepared public key.
+    ///
+    /// # Arguments
+    ///
+    /// * `pubkeys` - An iterator over the `PreparedPublicKey` instances to be aggregated.
+    ///
+    /// # Returns
+    ///
+    /// The aggregated `PreparedPublicKey`.
+    pub fn aggregate<'a>(pubkeys: impl IntoIterator<Item = &'a Self>) -> Self {
+        Self::default().add(pubkeys)
+    }
+
+    /// Adds prepared public keys to this prepared public key.
+    ///
+    /// # Arguments
+    ///
+    /// * `pubkeys` - An iterator over the `PreparedPublicKey` instances to add.
+    ///
+    /// # Returns
+    ///
+    /// The resulting `PreparedPublicKey`.
+    pub fn add<'a>(&self, pubkeys: impl IntoIterator<Item = &'a Self>) -> Self {
+        Self {
+            pubkey: pubkeys
+                .into_iter()
+                .fold(self.pubkey, |acc, pk| acc.add_affine(&pk.pubkey)),
+        }
+    }
+
+    /// Subtracts prepared public keys from this prepared public key.
+    ///
+    /// # Arguments
+    ///
+    /// * `pubkeys` - An iterator over the `PreparedPublicKey` instances to subtract.
+    ///
+    /// # Returns
+    ///
+    /// The resulting `PreparedPublicKey`.
+    pub fn subtract<'a>(&self, pubkeys: impl IntoIterator<Item = &'a Self>) -> Self {
+        Self {
+            pubkey: pubkeys
+                .into_iter()
+                .fold(self.pubkey, |acc, pk| acc.add_affine(&-pk.pubkey)),
+        }
+    }
+
+    /// Compresses the `PreparedPublicKey` back into a `PublicKey`.
+    ///
+    /// # Returns
+    ///
+    /// The `PublicKey` whose decompressed point is this prepared public key.
+    pub fn compress(&self) -> PublicKey {
+        let pubkey = OnceCell::new();
+        pubkey.set(self.pubkey).unwrap();
+
+        PublicKey {
+            compressed_pubkey: self.pubkey.to_compressed(),
+            pubkey,
+        }
+    }
+
+    /// Converts the `PreparedPublicKey` into a vector of bytes, following a BCS
+    /// (Binary Canonical Serialization) standard.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<u8>` representing the prepared public key.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        codec::to_bytes(self)
+    }
+
+    /// Creates a `PreparedPublicKey` from a slice of bytes, following a BCS
+    /// (Binary Canonical Serialization) standard.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - A byte slice from which to create the `PreparedPublicKey`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the `PreparedPublicKey` could be created
+    /// successfully. If the slice has an invalid length or is not a point
+    /// of the curve, the `Result` is `Err` with a `TypesError`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
+        codec::from_bytes("PreparedPublicKey", bytes)
+    }
+}
+
+impl From<&PublicKey> for PreparedPublicKey {
+    fn from(public_key: &PublicKey) -> Self {
+        Self {
+            pubkey: *public_key.pubkey(),
+        }
+    }
+}
+
+impl Serialize for PreparedPublicKey {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_newtype_struct(
+            "PreparedPublicKey",
+            serde_bytes::Bytes::new(self.pubkey.to_uncompressed().as_slice()),
+        )
+    }
+}
+
+impl<'de> Deserialize<'de> for PreparedPublicKey {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize, Debug)]
+        #[serde(rename = "PreparedPublicKey")]
+        struct Value<'a>(&'a [u8]);
+
+        let value = Value::deserialize(deserializer)?;
+        PreparedPublicKey::try_from(value.0)
+            .map_err(|s| <D::Error as Error>::custom(format!("{} with {}", s, "PreparedPublicKey")))
+    }
+}
+
+impl TryFrom<&[u8]> for PreparedPublicKey {
+    type Error = CryptoError;
+
+    /// Deserializes a PreparedPublicKey from a sequence of bytes.
+    ///
+    /// WARNING: Like for `PublicKey`, does NOT subgroup-check the point,
+    /// only that it lies on the curve.
+    fn try_from(bytes: &[u8]) -> Result<PreparedPublicKey, Self::Error> {
+        let g1_affine_option: Option<G1Affine> = G1Affine::from_uncompressed_unchecked(
+            <&[u8; PREPARED_PUB_KEY_LEN]>::try_from(bytes).map_err(|e| {
+                CryptoError::DeserializationError {
+                    structure: String::from("PreparedPublicKey"),
+                    source: e.into(),
+                }
+            })?,
+        )
+        .into();
+
+        match g1_affine_option {
+            Some(g1_affine) if bool::from(g1_affine.is_on_curve()) => {
+                Ok(Self { pubkey: g1_affine })
+            }
+            _ => Err(CryptoError::DecompressionError {
+                structure: String::from("PreparedPublicKey"),
+            }),
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Signature {
     pub(crate) sig: G2Affine,
@@ -217,10 +404,34 @@ impl Signature {
     /// A `Result` which is `Ok` if the signature is valid. If the signature is invalid,
     /// the `Result` is `Err` with a `CryptoError`.
     pub fn verify(&self, msg: &[u8], pubkey: &PublicKey) -> Result<(), CryptoError> {
+        self.verify_pubkey(msg, pubkey.pubkey())
+    }
+
+    /// Verifies the signature against a given message and prepared public key.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - A byte slice representing the message against which to verify the signature.
+    /// * `pubkey` - A reference to the `PreparedPublicKey` against which to verify the signature.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the signature is valid. If the signature is invalid,
+    /// the `Result` is `Err` with a `CryptoError`.
+    pub fn verify_prepared(
+        &self,
+        msg: &[u8],
+        pubkey: &PreparedPublicKey,
+    ) -> Result<(), CryptoError> {
+        self.verify_pubkey(msg, &pubkey.pubkey)
+    }
+
+    /// Runs the pairing check of the signature against a message and a public key point.
+    fn verify_pubkey(&self, msg: &[u8], pubkey: &G1Affine) -> Result<(), CryptoError> {
         let msg = G2Prepared::from(G2Affine::from(hash(msg)));
         let g1 = G1Affine::generator();
 
-        let ml_terms = [(&-g1, &G2Prepared::from(self.sig)), (pubkey.pubkey(), &msg)];
+        let ml_terms = [(&-g1, &G2Prepared::from(self.sig)), (pubkey, &msg)];
 
         if multi_miller_loop(&ml_terms).final_exponentiation() == Gt::identity() {
             Ok(())
@@ -236,20 +447,14 @@ impl Signature {
     ///
     /// A `Vec<u8>` representing the signature.
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = BytesMut::new();
-        let sig_bytes = self.sig.to_compressed();
-
-        bytes.put_slice(&write_leb128(sig_bytes.len() as u64));
-        bytes.put_slice(&sig_bytes);
-        bytes.to_vec()
+        codec::to_bytes(self)
     }
 
-    /// Creates a `Signature` from a slice of bytes, following a BCS
-    /// (Binary Canonical Serialization) standard.
+    /// Creates a `Signature` from its raw compressed bytes.
     ///
     /// # Arguments
     ///
-    /// * `bytes` - A byte slice from which to create the `Signature`.
+    /// * `bytes` - A byte slice of length `SIG_LEN` from which to create the `Signature`.
     ///
     /// # Returns
     ///
@@ -261,21 +466,23 @@ impl Signature {
             return Err(serde_error!("Signature", "Invalid signature byte length"));
         }
 
-        let bytes_fixed =
-            <&[u8; SIG_LEN]>::try_from(bytes).map_err(|e| serde_error!("PublicKey", e))?;
-
-        let decompressed = G2Affine::from_compressed(bytes_fixed);
-
-        if decompressed.is_none().into() {
-            return Err(serde_error!(
-                "PublicKey",
-                "G2Affine::from_compressed returned None"
-            ));
-        }
+        Self::try_from(bytes).map_err(|e| serde_error!("Signature", e))
+    }
 
-        Ok(Self {
-            sig: decompressed.unwrap(),
-        })
+    /// Creates a `Signature` from a slice of bytes, following a BCS
+    /// (Binary Canonical Serialization) standard.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - A byte slice from which to create the `Signature`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the `Signature` could be created
+    /// successfully. If the slice is not a valid encoding, the `Result`
+    /// is `Err` with a `TypesError`.
+    pub fn from_bcs_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
+        codec::from_bytes("Signature", bytes)
     }
 }
 
@@ -416,20 +623,21 @@ impl BitVec {
             })
     }
 
+    /// Converts the `BitVec` into a vector of bytes, following a BCS
+    /// (Binary Canonical Serialization) standard.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<u8>` representing the bit vector.
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = BytesMut::new();
-
-        bytes.put_u8(self.inner.len() as u8);
-        bytes.put_slice(&self.inner);
-        bytes.to_vec()
+        codec::to_bytes(self)
     }
 
-    /// Creates a `BitVec` from a slice of bytes, following a BCS
-    /// (Binary Canonical Serialization) standard.
+    /// Creates a `BitVec` from its raw buckets.
     ///
     /// # Arguments
     ///
-    /// * `bytes` - A byte slice from which to create the `BitVec`.
+    /// * `bytes` - A byte slice holding the buckets of the `BitVec`.
     ///
     /// # Returns
     ///
@@ -439,6 +647,22 @@ impl BitVec {
             inner: bytes.to_vec(),
         }
     }
+
+    /// Creates a `BitVec` from a slice of bytes, following a BCS
+    /// (Binary Canonical Serialization) standard.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - A byte slice from which to create the `BitVec`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the `BitVec` could be created
+    /// successfully. If the bit vector has more than 8192 buckets,
+    /// the `Result` is `Err` with a `TypesError`.
+    pub fn from_bcs_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
+        codec::from_bytes("BitVec", bytes)
+    }
 }
 
 impl<'de> Deserialize<'de> for BitVec {
@@ -480,15 +704,7 @@ impl AggregateSignature {
     ///
     /// A `Vec<u8>` representing the aggregate signature.
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = BytesMut::new();
-        bytes.put_slice(&self.validator_bitmask.to_bytes());
-        if let Some(sig) = &self.sig {
-            bytes.put_u8(1); // Indicate that there is a signature
-            bytes.put_slice(&sig.to_bytes());
-        } else {
-            bytes.put_u8(0); // Indicate that there is no signature
-        }
-        bytes.to_vec()
+        codec::to_bytes(self)
     }
 
     /// Creates an `AggregateSignature` from a slice of bytes, following a BCS
@@ -503,50 +719,8 @@ impl AggregateSignature {
     /// A `Result` which is `Ok` if the `AggregateSignature` could be created
     /// successfully. If the slice has an invalid length, the `Result`
     /// is `Err` with a `TypesError`.
-    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, TypesError> {
-        let bitvec_len = bytes.get_u8() as usize;
-
-        let validator_bitmask = BitVec::from_bytes(
-            bytes
-                .chunk()
-                .get(..bitvec_len)
-                .ok_or_else(|| serde_error!("AggregateSignature", "Not enough data for BitVec"))?,
-        );
-
-        bytes.advance(bitvec_len);
-
-        let sig = match bytes.get_u8() {
-            1 => {
-                let (slice_len, bytes_read) = read_leb128(bytes).map_err(|e| {
-                    serde_error!(
-                        "AggregateSignature",
-                        format!("Failed to read length of public_key: {e}")
-                    )
-                })?;
-                bytes.advance(bytes_read);
-
-                let sig =
-                    Signature::from_bytes(bytes.chunk().get(..slice_len as usize).ok_or_else(
-                        || serde_error!("AggregateSignature", "Not enough data for Signature"),
-                    )?)?;
-                bytes.advance(slice_len as usize);
-
-                Some(sig)
-            }
-            _ => None,
-        };
-
-        if bytes.remaining() != 0 {
-            return Err(serde_error!(
-                "AggregateSignature",
-                "Unexpected data after completing deserialization"
-            ));
-        }
-
-        Ok(Self {
-            validator_bitmask,
-            sig,
-        })
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
+        codec::from_bytes("AggregateSignature", bytes)
     }
 }
 
@@ -555,7 +729,7 @@ mod test {
     #[test]
     fn test_bytes_conversion() {
         use crate::aptos_test_utils::wrapper::AptosWrapper;
-        use crate::crypto::sig::AggregateSignature;
+        use crate::crypto::sig::{AggregateSignature, BitVec, Signature};
 
         let mut aptos_wrapper = AptosWrapper::new(2, 130, 130).unwrap();
 
@@ -571,5 +745,35 @@ mod test {
         let intern_bytes = intern_agg_sig.to_bytes();
 
         assert_eq!(bytes, intern_bytes);
+
+        // Raw and BCS constructors of the signature and its bitmask
+        let sig = intern_agg_sig.sig().as_ref().unwrap();
+        let raw_sig = sig.to_bytes()[1..].to_vec();
+        assert_eq!(&Signature::from_bytes(&raw_sig).unwrap(), sig);
+        assert_eq!(&Signature::from_bcs_bytes(&sig.to_bytes()).unwrap(), sig);
+        assert!(Signature::from_bytes(&sig.to_bytes()).is_err());
+
+        let bitmask = intern_agg_sig.validator_bitmask();
+        let raw_bitmask = bitmask.to_bytes()[1..].to_vec();
+        assert_eq!(&BitVec::from_bytes(&raw_bitmask), bitmask);
+        assert_eq!(
+            &BitVec::from_bcs_bytes(&bitmask.to_bytes()).unwrap(),
+            bitmask
+        );
+    }
+
+    #[test]
+    fn test_public_key_bytes_conversion() {
+        use crate::crypto::sig::{PublicKey, PUB_KEY_LEN};
+
+        let raw_pubkey = bls12_381::G1Affine::generator().to_compressed();
+        let pubkey = PublicKey::from_bytes(&raw_pubkey).unwrap();
+
+        assert_eq!(pubkey.to_bytes()[1..], raw_pubkey[..]);
+        assert_eq!(
+            PublicKey::from_bcs_bytes(&pubkey.to_bytes()).unwrap(),
+            pubkey
+        );
+        assert!(PublicKey::from_bytes(&raw_pubkey[..PUB_KEY_LEN - 1]).is_err());
     }
 }
@@ -0,0 +1,180 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! # Event Proof Module
+//!
+//! This module provides the structures and functions
+//! necessary for handling Event Accumulator Proofs
+//! from the Aptos chain.
+//!
+//! ## Usage
+//!
+//! The `EventAccumulatorProof` structure is used to
+//! authenticate whether a given event was emitted by a
+//! transaction or not. It contains a list of sibling nodes,
+//! ordered from the bottom level to the root level of the
+//! Merkle Tree whose root is the `event_root_hash` of the
+//! `TransactionInfo`.
+
+// SPDX-License-Identifier: Apache-2.0
+use crate::crypto::hash::{CryptoHash, HashValue};
+use crate::merkle::node::{EventAccumulatorHasher, MerkleInternalNode};
+use crate::merkle::transaction_proof::MAX_ACCUMULATOR_PROOF_DEPTH;
+use crate::types::codec;
+use crate::types::error::TypesError;
+use anyhow::{ensure, Result};
+use serde::{Deserialize, Serialize};
+
+/// A proof that can be used to authenticate an event in the
+/// event accumulator of a transaction given a trusted root hash.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EventAccumulatorProof {
+    /// All siblings in this proof, including the default ones. Siblings
+    /// are ordered from the bottom level to the root level.
+    siblings: Vec<HashValue>,
+}
+
+impl EventAccumulatorProof {
+    /// Verifies an event whose hash is `element_hash` and index is `element_index` exists in
+    /// the accumulator whose root hash is `expected_root_hash` using the provided proof.
+    ///
+    /// # Arguments
+    ///
+    /// * `expected_root_hash: HashValue` - The expected root hash of the Event Accumulator.
+    /// * `element_hash: HashValue` - The hash of the event to verify.
+    /// * `element_index: u64` - The index of the event in the transaction.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the event exists in the Event Accumulator and the proof is valid, and `Err` otherwise.
+    pub fn verify(
+        &self,
+        expected_root_hash: HashValue,
+        element_hash: HashValue,
+        element_index: u64,
+    ) -> Result<()> {
+        ensure!(
+            self.siblings.len() <= MAX_ACCUMULATOR_PROOF_DEPTH,
+            "Accumulator proof has more than {} ({}) siblings.",
+            MAX_ACCUMULATOR_PROOF_DEPTH,
+            self.siblings.len()
+        );
+
+        let actual_root_hash = self
+            .siblings
+            .iter()
+            .fold(
+                (element_hash, element_index),
+                // `index` denotes the index of the ancestor of the element at the current level.
+                |(hash, index), sibling_hash| {
+                    (
+                        if index % 2 == 0 {
+                            // the current node is a left child.
+                            MerkleInternalNode::<EventAccumulatorHasher>::new(hash, *sibling_hash)
+                                .hash()
+                        } else {
+                            // the current node is a right child.
+                            MerkleInternalNode::<EventAccumulatorHasher>::new(*sibling_hash, hash)
+                                .hash()
+                        },
+                        // The index of the parent at its level.
+                        index / 2,
+                    )
+                },
+            )
+            .0;
+        ensure!(
+            actual_root_hash == expected_root_hash,
+            "{}: Root hashes do not match.",
+            "EventAccumulatorProof",
+        );
+
+        Ok(())
+    }
+
+    /// Converts the `EventAccumulatorProof` to a byte vector.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<u8>` representing the `EventAccumulatorProof`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        codec::to_bytes(self)
+    }
+
+    /// Creates an `EventAccumulatorProof` from a byte slice.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes: &[u8]` - A byte slice from which to create the `EventAccumulatorProof`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the `EventAccumulatorProof` could be successfully created, and `Err` otherwise.
+    pub fn from_bytes(bytes: &[u8]) -> std::result::Result<Self, TypesError> {
+        codec::from_bytes("EventAccumulatorProof", bytes)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::crypto::hash::{hash_data, CryptoHash, HashValue};
+    use crate::merkle::event_proof::EventAccumulatorProof;
+    use crate::merkle::node::{EventAccumulatorHasher, MerkleInternalNode};
+
+    #[test]
+    fn test_verify_event_proof() {
+        let leaves: Vec<HashValue> = (0u8..4)
+            .map(|i| HashValue::new(hash_data(&[], vec![&[i]])))
+            .collect();
+
+        let left = MerkleInternalNode::<EventAccumulatorHasher>::new(leaves[0], leaves[1]).hash();
+        let right = MerkleInternalNode::<EventAccumulatorHasher>::new(leaves[2], leaves[3]).hash();
+        let root = MerkleInternalNode::<EventAccumulatorHasher>::new(left, right).hash();
+
+        let proof = EventAccumulatorProof {
+            siblings: vec![leaves[3], left],
+        };
+
+        proof.verify(root, leaves[2], 2).unwrap();
+        assert!(proof.verify(root, leaves[2], 3).is_err());
+        assert!(proof.verify(root, leaves[3], 2).is_err());
+
+        let proof_deserialized = EventAccumulatorProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert_eq!(
+            bcs::to_bytes(&proof).unwrap(),
+            proof_deserialized.to_bytes()
+        );
+        proof_deserialized.verify(root, leaves[2], 2).unwrap();
+    }
+
+    #[cfg(feature = "aptos")]
+    #[test]
+    fn test_event_accumulator() {
+        use crate::aptos_test_utils::wrapper::AptosWrapper;
+        use crate::types::event::ContractEvent;
+        use crate::types::transaction::TransactionInfo;
+
+        let mut aptos_wrapper = AptosWrapper::new(4, 1, 1).unwrap();
+        aptos_wrapper.generate_traffic().unwrap();
+
+        let event_assets = aptos_wrapper.get_latest_event_proof(0).unwrap();
+
+        let aptos_proof_bytes = bcs::to_bytes(event_assets.event_proof()).unwrap();
+        let proof = EventAccumulatorProof::from_bytes(&aptos_proof_bytes).unwrap();
+        assert_eq!(aptos_proof_bytes, proof.to_bytes());
+
+        let transaction =
+            TransactionInfo::from_bytes(&bcs::to_bytes(event_assets.transaction()).unwrap())
+                .unwrap();
+        let event =
+            ContractEvent::from_bytes(&bcs::to_bytes(event_assets.event()).unwrap()).unwrap();
+
+        proof
+            .verify(
+                transaction.event_root_hash(),
+                event.hash(),
+                *event_assets.event_index(),
+            )
+            .unwrap();
+    }
+}
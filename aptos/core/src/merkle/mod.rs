@@ -7,11 +7,13 @@
 //!
 //! ## Sub-modules
 //!
+//! - `event_proof`: This sub-module contains the `EventAccumulatorProof` structure and associated methods. It is used to represent and verify proofs in the Event Accumulator of a transaction.
 //! - `node`: This sub-module contains the `SparseMerkleNode` structure and associated methods. It is used to represent nodes in the Sparse Merkle Tree and the Transaction Accumulator.
 //! - `sparse_proof`: This sub-module contains the `SparseMerkleProof` structure and associated methods. It is used to represent and verify proofs in the Sparse Merkle Tree.
 //! - `transaction_proof`: This sub-module contains the `TransactionAccumulatorProof` structure and associated methods. It is used to represent and verify proofs in the Transaction Accumulator.
 //!
 //! For more detailed information, users should refer to the specific documentation for each sub-module.
+pub mod event_proof;
 pub mod node;
 pub mod sparse_proof;
 pub mod transaction_proof;
@@ -25,9 +25,9 @@
 //! `NodeHasher` circumvents around that as it is precisely an implementation
 //!  that makes the prefix dynamic for Internal Merkle Nodes.
 //!
-//! The `SparseMerkleInternalHasher` and `TransactionAccumulatorHasher`
-//! structures implement the `NodeHasher` trait, each providing a
-//! different prefix for hashing.
+//! The `SparseMerkleInternalHasher`, `TransactionAccumulatorHasher`
+//! and `EventAccumulatorHasher` structures implement the `NodeHasher`
+//! trait, each providing a different prefix for hashing.
 //!
 //! ## Usage
 //!
@@ -42,14 +42,13 @@
 //! The `NodeHasher` trait provides a method for hashing (`hash`),
 //! which takes in the left and right child nodes and returns a `HashValue`.
 //!
-//! The `SparseMerkleInternalHasher` and `TransactionAccumulatorHasher`
-//! structures implement the `NodeHasher` trait, each providing
-//! a different prefix for hashing.
+//! The `SparseMerkleInternalHasher`, `TransactionAccumulatorHasher`
+//! and `EventAccumulatorHasher` structures implement the `NodeHasher`
+//! trait, each providing a different prefix for hashing.
 //!
-use crate::crypto::hash::{hash_data, prefixed_sha3, CryptoHash, HashValue, HASH_LENGTH};
-use crate::serde_error;
+use crate::crypto::hash::{hash_data, prefixed_sha3, CryptoHash, HashValue};
+use crate::types::codec;
 use crate::types::error::TypesError;
-use bytes::{Buf, BufMut, BytesMut};
 use getset::CopyGetters;
 use serde::{Deserialize, Serialize};
 use std::marker::PhantomData;
@@ -96,10 +95,7 @@ impl SparseMerkleLeafNode {
     /// A `Vec<u8>` containing the bytes of the `key`
     /// followed by the bytes of the `value_hash`.
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = BytesMut::new();
-        bytes.put_slice(self.key.as_ref());
-        bytes.put_slice(self.value_hash.as_ref());
-        bytes.to_vec()
+        codec::to_bytes(self)
     }
 
     /// Creates a `SparseMerkleLeafNode` from a byte
@@ -116,31 +112,7 @@ impl SparseMerkleLeafNode {
     /// A `Result` which is `Ok` if the `SparseMerkleLeafNode`
     /// could be successfully created, and `Err` otherwise.
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
-        let mut buf = bytes;
-
-        let key = HashValue::from_slice(
-            buf.chunk()
-                .get(..HASH_LENGTH)
-                .ok_or_else(|| serde_error!("SparseMerkleLeafNode", "Not enough data for key"))?,
-        )
-        .map_err(|e| serde_error!("SparseMerkleLeafNode", e))?;
-        buf.advance(HASH_LENGTH);
-
-        let value_hash =
-            HashValue::from_slice(buf.chunk().get(..HASH_LENGTH).ok_or_else(|| {
-                serde_error!("SparseMerkleLeafNode", "Not enough data for value_hash")
-            })?)
-            .map_err(|e| serde_error!("SparseMerkleLeafNode", e))?;
-        buf.advance(HASH_LENGTH);
-
-        if buf.remaining() != 0 {
-            return Err(serde_error!(
-                "SparseMerkleLeafNode",
-                "Unexpected data after completing deserialization"
-            ));
-        }
-
-        Ok(Self { key, value_hash })
+        codec::from_bytes("SparseMerkleLeafNode", bytes)
     }
 }
 
@@ -257,6 +229,24 @@ impl NodeHasher for TransactionAccumulatorHasher {
     }
 }
 
+/// `EventAccumulatorHasher` is a structure representing
+/// the hasher for event accumulator in order to prove
+/// an event inclusion in a `TransactionInfo`.
+#[derive(Clone, Debug, Default)]
+pub struct EventAccumulatorHasher {}
+
+impl NodeHasher for EventAccumulatorHasher {
+    /// Returns the prefix used for hashing in the context of
+    /// an Event Accumulator.
+    ///
+    /// # Returns
+    ///
+    /// A static string slice representing the prefix.
+    fn prefix(&self) -> &'static str {
+        "EventAccumulator"
+    }
+}
+
 #[cfg(all(test, feature = "aptos"))]
 mod test {
     #[test]
@@ -15,25 +15,31 @@
 //! root level of the Sparse Merkle Tree.
 //!
 //! The `SparseMerkleProof` structure provides methods
-//! for verifying the proof (`verify_by_hash`), converting
+//! for verifying the proof (`verify_by_hash` for inclusion
+//! and `verify_non_inclusion` for non-inclusion), converting
 //! the proof to bytes (`to_bytes`), and creating a proof
 //! from bytes (`from_bytes`). These methods are used
-//! to authenticate the existence of a leaf in the Sparse
-//! Merkle Tree, serialize the proof for storage or
+//! to authenticate the existence or absence of a leaf in
+//! the Sparse Merkle Tree, serialize the proof for storage or
 //! transmission, and deserialize the proof for verification,
 //! respectively.
 
 // SPDX-License-Identifier: Apache-2.0
 use crate::crypto::hash::{CryptoHash, HashValue, HASH_LENGTH};
 use crate::merkle::node::{MerkleInternalNode, SparseMerkleInternalHasher, SparseMerkleLeafNode};
-use crate::serde_error;
+use crate::types::codec;
 use crate::types::error::TypesError;
-use crate::types::utils::{read_leb128, write_leb128};
-use anyhow::{ensure, Result};
-use bytes::{Buf, BufMut, BytesMut};
+use anyhow::{anyhow, ensure, Result};
 use getset::Getters;
 use serde::{Deserialize, Serialize};
 
+/// Placeholder hash for an empty subtree in the Sparse Merkle Tree.
+///
+/// Corresponds to the ASCII string `SPARSE_MERKLE_PLACEHOLDER_HASH`
+/// right-padded with zeroes, as defined in the Aptos codebase.
+pub const SPARSE_MERKLE_PLACEHOLDER_HASH: HashValue =
+    HashValue::new(*b"SPARSE_MERKLE_PLACEHOLDER_HASH\0\0");
+
 /// `SparseMerkleProof` is a structure representing a proof
 /// in a Sparse Merkle Tree.
 ///
@@ -48,8 +54,10 @@ pub struct SparseMerkleProof {
     ///     - If this is `Some(leaf_node)`
     ///         - If `leaf_node.key` equals requested key, this is an inclusion proof and
     ///           `leaf_node.value_hash` equals the hash of the corresponding account blob.
-    ///         - Otherwise this is a non-inclusion proof, which we do not handle.
-    ///     - If this is `None`, this is also a non-inclusion proof, which we do not handle in the light client.
+    ///         - Otherwise this is a non-inclusion proof. `leaf_node.key` is the only key
+    ///           that exists in the subtree and it shares the same prefix with the requested key.
+    ///     - If this is `None`, this is also a non-inclusion proof which indicates the subtree is
+    ///       empty.
     leaf: Option<SparseMerkleLeafNode>,
 
     /// All siblings in this proof, including the default ones. Siblings are ordered from the bottom
@@ -73,22 +81,21 @@ impl SparseMerkleProof {
     /// A `Result` which is `Ok` if the element exists in
     /// the Sparse Merkle Tree and the proof is valid, and
     /// `Err` otherwise.
-    #[allow(dead_code)]
     pub fn verify_by_hash(
         &self,
         expected_root_hash: HashValue,
         element_key: HashValue,
         element_hash: HashValue,
     ) -> Result<HashValue> {
-        ensure!(
-            self.siblings.len() <= HASH_LENGTH * 8,
-            "Sparse Merkle Tree proof has more than {} ({}) siblings.",
-            256,
-            self.siblings.len(),
-        );
+        self.ensure_siblings_len()?;
 
         // Proof need to contain leaf if proof of inclusion
-        let leaf = self.leaf.unwrap();
+        let leaf = self.leaf.ok_or_else(|| {
+            anyhow!(
+                "Expected inclusion proof for key {:x}, but proof has no leaf.",
+                element_key
+            )
+        })?;
         ensure!(
             element_key == leaf.key(),
             "Keys do not match. Key in proof: {:x}. Expected key: {:x}. \
@@ -105,6 +112,93 @@ impl SparseMerkleProof {
             element_key
         );
 
+        self.verify_root(expected_root_hash, element_key, leaf.hash())
+    }
+
+    /// Verifies that no element whose key is `element_key`
+    /// exists in the Sparse Merkle Tree using the provided proof.
+    ///
+    /// The proof is valid for non-inclusion in two cases:
+    ///     - The proof has no leaf, meaning that the path
+    ///       for `element_key` ends in an empty subtree.
+    ///     - The proof leaf has a different key, which shares
+    ///       at least as many bits with `element_key` as there
+    ///       are siblings. The leaf is then the only one in the
+    ///       subtree where `element_key` would have been placed.
+    ///
+    /// # Arguments
+    ///
+    /// * `expected_root_hash: HashValue` - The expected root hash of the Sparse Merkle Tree.
+    /// * `element_key: HashValue` - The key of the element that should be absent.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` with the reconstructed root
+    /// hash if the element is absent from the Sparse Merkle
+    /// Tree and the proof is valid, and `Err` otherwise.
+    pub fn verify_non_inclusion(
+        &self,
+        expected_root_hash: HashValue,
+        element_key: HashValue,
+    ) -> Result<HashValue> {
+        self.ensure_siblings_len()?;
+
+        let current_hash = match self.leaf {
+            Some(leaf) => {
+                ensure!(
+                    element_key != leaf.key(),
+                    "Expected non-inclusion proof, but key {:x} exists in proof.",
+                    element_key
+                );
+                ensure!(
+                    element_key.common_prefix_bits_len(&leaf.key()) >= self.siblings.len(),
+                    "Key {:x} would not have ended up in the subtree where the key in proof \
+                     {:x} is the only existing key, so this is not a valid non-inclusion proof.",
+                    element_key,
+                    leaf.key()
+                );
+
+                leaf.hash()
+            }
+            None => SPARSE_MERKLE_PLACEHOLDER_HASH,
+        };
+
+        self.verify_root(expected_root_hash, element_key, current_hash)
+    }
+
+    /// Ensures that the proof does not contain more siblings
+    /// than the depth of the Sparse Merkle Tree.
+    fn ensure_siblings_len(&self) -> Result<()> {
+        ensure!(
+            self.siblings.len() <= HASH_LENGTH * 8,
+            "Sparse Merkle Tree proof has more than {} ({}) siblings.",
+            HASH_LENGTH * 8,
+            self.siblings.len(),
+        );
+
+        Ok(())
+    }
+
+    /// Reconstructs the root hash of the Sparse Merkle Tree
+    /// from the hash at the bottom of the path of `element_key`
+    /// and checks it against `expected_root_hash`.
+    ///
+    /// # Arguments
+    ///
+    /// * `expected_root_hash: HashValue` - The expected root hash of the Sparse Merkle Tree.
+    /// * `element_key: HashValue` - The key whose path is followed.
+    /// * `current_hash: HashValue` - The hash at the bottom of the path.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` with the reconstructed root
+    /// hash if it matches the expected one, and `Err` otherwise.
+    fn verify_root(
+        &self,
+        expected_root_hash: HashValue,
+        element_key: HashValue,
+        current_hash: HashValue,
+    ) -> Result<HashValue> {
         let reconstructed_root = self
             .siblings
             .iter()
@@ -115,7 +209,7 @@ impl SparseMerkleProof {
                     .rev()
                     .skip(HASH_LENGTH * 8 - self.siblings.len()),
             )
-            .fold(leaf.hash(), accumulator_update);
+            .fold(current_hash, accumulator_update);
 
         ensure!(
             reconstructed_root == expected_root_hash,
@@ -133,21 +227,7 @@ impl SparseMerkleProof {
     ///
     /// A `Vec<u8>` representing the `SparseMerkleProof`.
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = BytesMut::new();
-        match &self.leaf {
-            Some(node) => {
-                bytes.put_u8(1);
-                bytes.put_slice(&node.to_bytes());
-            }
-            None => {
-                bytes.put_u8(0);
-            }
-        }
-        bytes.put_slice(&write_leb128(self.siblings.len() as u64));
-        for sibling in &self.siblings {
-            bytes.put_slice(sibling.as_ref());
-        }
-        bytes.to_vec()
+        codec::to_bytes(self)
     }
 
     /// Creates a `SparseMerkleProof` from a byte slice.
@@ -162,46 +242,7 @@ impl SparseMerkleProof {
     /// A `Result` which is `Ok` if the `SparseMerkleProof`
     /// could be successfully created, and `Err` otherwise.
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
-        let mut buf = bytes;
-
-        let leaf = match buf.get_u8() {
-            1 => {
-                let node = SparseMerkleLeafNode::from_bytes(
-                    buf.chunk().get(..2 * HASH_LENGTH).ok_or_else(|| {
-                        serde_error!("SparseMerkleProof", "Not enough data for leaf")
-                    })?,
-                )
-                .map_err(|e| serde_error!("SparseMerkleProof", e))?;
-                buf.advance(2 * HASH_LENGTH);
-
-                Some(node)
-            }
-            _ => None,
-        };
-
-        let (num_siblings, len) =
-            read_leb128(buf.chunk()).map_err(|e| serde_error!("SparseMerkleProof", e))?;
-        buf.advance(len);
-
-        let mut siblings = Vec::new();
-        for _ in 0..num_siblings {
-            let sibling =
-                HashValue::from_slice(buf.chunk().get(..HASH_LENGTH).ok_or_else(|| {
-                    serde_error!("SparseMerkleProof", "Not enough data for sibling")
-                })?)
-                .map_err(|e| serde_error!("SparseMerkleProof", e))?;
-            buf.advance(HASH_LENGTH);
-            siblings.push(sibling);
-        }
-
-        if buf.remaining() != 0 {
-            return Err(serde_error!(
-                "SparseMerkleProof",
-                "Unexpected data after completing deserialization"
-            ));
-        }
-
-        Ok(Self { leaf, siblings })
+        codec::from_bytes("SparseMerkleProof", bytes)
     }
 }
 
@@ -231,7 +272,29 @@ mod test {
     use crate::merkle::node::{
         MerkleInternalNode, SparseMerkleInternalHasher, SparseMerkleLeafNode,
     };
-    use crate::merkle::sparse_proof::SparseMerkleProof;
+    use crate::merkle::sparse_proof::{SparseMerkleProof, SPARSE_MERKLE_PLACEHOLDER_HASH};
+
+    fn compute_root(siblings: &[HashValue], key: HashValue, current_hash: HashValue) -> HashValue {
+        siblings
+            .iter()
+            .rev()
+            .zip(key.iter_bits().rev().skip(HASH_LENGTH * 8 - siblings.len()))
+            .fold(current_hash, |acc_hash, (sibling_hash, bit)| {
+                if bit {
+                    MerkleInternalNode::<SparseMerkleInternalHasher>::new(*sibling_hash, acc_hash)
+                        .hash()
+                } else {
+                    MerkleInternalNode::<SparseMerkleInternalHasher>::new(acc_hash, *sibling_hash)
+                        .hash()
+                }
+            })
+    }
+
+    const fn key_with_first_byte(byte: u8) -> HashValue {
+        let mut key = [0u8; HASH_LENGTH];
+        key[0] = byte;
+        HashValue::new(key)
+    }
 
     #[test]
     fn test_verify_proof_simple() {
@@ -283,6 +346,75 @@ mod test {
             .unwrap();
     }
 
+    #[test]
+    fn test_verify_non_inclusion_empty_subtree() {
+        let siblings = vec![
+            HashValue::new(hash_data(&[], vec!["b".as_bytes()])),
+            HashValue::new(hash_data(&[], vec!["cd".as_bytes()])),
+        ];
+        let proof = SparseMerkleProof {
+            leaf: None,
+            siblings: siblings.clone(),
+        };
+
+        let key = key_with_first_byte(0b0100_0000);
+        let expected_root_hash = compute_root(&siblings, key, SPARSE_MERKLE_PLACEHOLDER_HASH);
+
+        assert_eq!(
+            proof.verify_non_inclusion(expected_root_hash, key).unwrap(),
+            expected_root_hash
+        );
+
+        // A proof without leaf can not be used as an inclusion proof
+        assert!(proof
+            .verify_by_hash(expected_root_hash, key, HashValue::default())
+            .is_err());
+
+        // Root mismatch
+        assert!(proof
+            .verify_non_inclusion(HashValue::default(), key)
+            .is_err());
+    }
+
+    #[test]
+    fn test_verify_non_inclusion_other_leaf() {
+        let leaf_node = SparseMerkleLeafNode::new(
+            key_with_first_byte(0b1000_0000),
+            HashValue::new(hash_data(&[], vec!["a".as_bytes()])),
+        );
+        let siblings = vec![
+            HashValue::new(hash_data(&[], vec!["b".as_bytes()])),
+            HashValue::new(hash_data(&[], vec!["cd".as_bytes()])),
+        ];
+        let proof = SparseMerkleProof {
+            leaf: Some(leaf_node),
+            siblings: siblings.clone(),
+        };
+
+        // Key sharing the two first bits with the leaf key
+        let key = key_with_first_byte(0b1000_0001);
+        let expected_root_hash = compute_root(&siblings, key, leaf_node.hash());
+        assert_eq!(
+            expected_root_hash,
+            compute_root(&siblings, leaf_node.key(), leaf_node.hash())
+        );
+
+        assert_eq!(
+            proof.verify_non_inclusion(expected_root_hash, key).unwrap(),
+            expected_root_hash
+        );
+
+        // Key present in the proof
+        assert!(proof
+            .verify_non_inclusion(expected_root_hash, leaf_node.key())
+            .is_err());
+
+        // Key that would not end up in the leaf subtree
+        assert!(proof
+            .verify_non_inclusion(expected_root_hash, key_with_first_byte(0b1100_0000))
+            .is_err());
+    }
+
     #[cfg(feature = "aptos")]
     #[test]
     fn test_aptos_data() {
@@ -332,4 +464,29 @@ mod test {
 
         assert_eq!(aptos_proof_bytes, lc_sparse_proof_bytes);
     }
+
+    #[cfg(feature = "aptos")]
+    #[test]
+    fn test_aptos_data_non_inclusion() {
+        use crate::aptos_test_utils::wrapper::AptosWrapper;
+
+        let mut aptos_wrapper = AptosWrapper::new(40, 1, 1).unwrap();
+        aptos_wrapper.generate_traffic().unwrap();
+
+        let proof_assets = aptos_wrapper.get_latest_proof_absent_account().unwrap();
+        assert!(proof_assets.state_value().is_none());
+
+        let intern_proof =
+            SparseMerkleProof::from_bytes(&bcs::to_bytes(proof_assets.state_proof()).unwrap())
+                .unwrap();
+        let key = HashValue::from_slice(proof_assets.key().to_vec()).unwrap();
+        let root_hash = HashValue::from_slice(proof_assets.root_hash().to_vec()).unwrap();
+
+        intern_proof.verify_non_inclusion(root_hash, key).unwrap();
+
+        // The account being absent, there is no value to prove inclusion for
+        assert!(intern_proof
+            .verify_by_hash(root_hash, key, HashValue::default())
+            .is_err());
+    }
 }
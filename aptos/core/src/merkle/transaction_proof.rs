@@ -13,16 +13,20 @@
 //! used to authenticate whether a given transaction exists
 //! in the  Aptos state or not. It contains a list of sibling nodes,
 //! ordered from the bottom level to the root level of the Merkle Tree.
+//!
+//! The `TransactionAccumulatorRangeProof` structure is used to
+//! authenticate a list of consecutive transactions at once. It
+//! contains the siblings on the left of the first transaction and
+//! on the right of the last transaction, ordered from the bottom
+//! level to the root level of the Merkle Tree.
 
 // SPDX-License-Identifier: Apache-2.0
-use crate::crypto::hash::{CryptoHash, HashValue, HASH_LENGTH};
+use crate::crypto::hash::{CryptoHash, HashValue};
 use crate::merkle::node::MerkleInternalNode;
 use crate::merkle::node::TransactionAccumulatorHasher;
-use crate::serde_error;
+use crate::types::codec;
 use crate::types::error::TypesError;
-use crate::types::utils::{read_leb128, write_leb128};
-use anyhow::{ensure, Result};
-use bytes::{Buf, BufMut, BytesMut};
+use anyhow::{anyhow, ensure, Result};
 use serde::{Deserialize, Serialize};
 
 pub const MAX_ACCUMULATOR_PROOF_DEPTH: usize = 63;
@@ -106,12 +110,7 @@ impl TransactionAccumulatorProof {
     ///
     /// A `Vec<u8>` representing the `TransactionAccumulatorProof`.
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = BytesMut::new();
-        bytes.put_slice(&write_leb128(self.siblings.len() as u64));
-        for sibling in &self.siblings {
-            bytes.put_slice(sibling.as_ref());
-        }
-        bytes.to_vec()
+        codec::to_bytes(self)
     }
 
     /// Creates a `TransactionAccumulatorProof` from a byte slice.
@@ -124,40 +123,186 @@ impl TransactionAccumulatorProof {
     ///
     /// A `Result` which is `Ok` if the `TransactionAccumulatorProof` could be successfully created, and `Err` otherwise.
     pub fn from_bytes(bytes: &[u8]) -> std::result::Result<Self, TypesError> {
-        let mut buf = BytesMut::from(bytes);
-        let (len, read_bytes) = read_leb128(&buf).map_err(|_| {
-            serde_error!("TransactionAccumulatorProof", "Not enough data for length")
-        })?;
-        buf.advance(read_bytes);
-        let mut siblings = Vec::with_capacity(len as usize);
-        for _ in 0..len {
-            if buf.remaining() < HASH_LENGTH {
-                return Err(serde_error!(
-                    "TransactionAccumulatorProof",
-                    "Not enough bytes to read HashValue"
-                ));
+        codec::from_bytes("TransactionAccumulatorProof", bytes)
+    }
+}
+
+/// A proof that can be used to authenticate a range of consecutive
+/// elements in an accumulator given trusted root hash.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransactionAccumulatorRangeProof {
+    /// The siblings on the left of the path from the first leaf to the root. Siblings are ordered
+    /// from the bottom level to the root level.
+    left_siblings: Vec<HashValue>,
+    /// The siblings on the right of the path from the last leaf to the root. Siblings are ordered
+    /// from the bottom level to the root level.
+    right_siblings: Vec<HashValue>,
+}
+
+impl TransactionAccumulatorRangeProof {
+    /// Verifies the elements whose hashes are `element_hashes` exist in the accumulator whose root
+    /// hash is `expected_root_hash` at consecutive indexes starting from `first_element_index`.
+    ///
+    /// # Arguments
+    ///
+    /// * `expected_root_hash: HashValue` - The expected root hash of the Transaction Accumulator.
+    /// * `first_element_index: u64` - The index of the first element to verify.
+    /// * `element_hashes: &[HashValue]` - The hashes of the consecutive elements to verify.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if all the elements exist in the Transaction Accumulator and the proof is valid, and `Err` otherwise.
+    pub fn verify(
+        &self,
+        expected_root_hash: HashValue,
+        first_element_index: u64,
+        element_hashes: &[HashValue],
+    ) -> Result<()> {
+        ensure!(
+            self.left_siblings.len() <= MAX_ACCUMULATOR_PROOF_DEPTH,
+            "Accumulator range proof has more than {} ({}) left siblings.",
+            MAX_ACCUMULATOR_PROOF_DEPTH,
+            self.left_siblings.len()
+        );
+        ensure!(
+            self.right_siblings.len() <= MAX_ACCUMULATOR_PROOF_DEPTH,
+            "Accumulator range proof has more than {} ({}) right siblings.",
+            MAX_ACCUMULATOR_PROOF_DEPTH,
+            self.right_siblings.len()
+        );
+        ensure!(
+            !element_hashes.is_empty(),
+            "Accumulator range proof needs at least one element to verify."
+        );
+
+        let mut left_siblings = self.left_siblings.iter().peekable();
+        let mut right_siblings = self.right_siblings.iter().peekable();
+
+        // `first_index` denotes the index of the ancestor of the first element at the current level.
+        let mut first_index = first_element_index;
+        let mut current_hashes = element_hashes.to_vec();
+
+        // Reduce the hashes level by level by combining children pairs, until only the root is left.
+        while current_hashes.len() > 1
+            || left_siblings.peek().is_some()
+            || right_siblings.peek().is_some()
+        {
+            let mut parent_hashes = Vec::with_capacity(current_hashes.len() / 2 + 2);
+            let mut children = current_hashes.as_slice();
+
+            // If the first node is a right child, it is combined with a sibling on the left.
+            if first_index % 2 == 1 {
+                let left_hash = *left_siblings
+                    .next()
+                    .ok_or_else(|| anyhow!("Accumulator range proof is incomplete."))?;
+                parent_hashes.push(
+                    MerkleInternalNode::<TransactionAccumulatorHasher>::new(left_hash, children[0])
+                        .hash(),
+                );
+                children = &children[1..];
+            }
+
+            let mut pairs = children.chunks_exact(2);
+            for pair in pairs.by_ref() {
+                parent_hashes.push(
+                    MerkleInternalNode::<TransactionAccumulatorHasher>::new(pair[0], pair[1])
+                        .hash(),
+                );
             }
-            let mut hash_value = [0u8; HASH_LENGTH];
-            buf.copy_to_slice(&mut hash_value);
-            siblings.push(
-                HashValue::from_slice(hash_value)
-                    .map_err(|e| serde_error!("TransactionAccumulatorProof", e))?,
-            );
-        }
 
-        if buf.remaining() != 0 {
-            return Err(serde_error!(
-                "TransactionAccumulatorProof",
-                "Unexpected data after completing deserialization"
-            ));
+            // If the last node is a left child, it is combined with a sibling on the right.
+            if let [left_hash] = pairs.remainder() {
+                let right_hash = *right_siblings
+                    .next()
+                    .ok_or_else(|| anyhow!("Accumulator range proof is incomplete."))?;
+                parent_hashes.push(
+                    MerkleInternalNode::<TransactionAccumulatorHasher>::new(*left_hash, right_hash)
+                        .hash(),
+                );
+            }
+
+            first_index /= 2;
+            current_hashes = parent_hashes;
         }
 
-        Ok(Self { siblings })
+        ensure!(
+            current_hashes[0] == expected_root_hash,
+            "{}: Root hashes do not match.",
+            "AccumulatorRangeProof",
+        );
+
+        Ok(())
+    }
+
+    /// Converts the `TransactionAccumulatorRangeProof` to a byte vector.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<u8>` representing the `TransactionAccumulatorRangeProof`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        codec::to_bytes(self)
+    }
+
+    /// Creates a `TransactionAccumulatorRangeProof` from a byte slice.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes: &[u8]` - A byte slice from which to create the `TransactionAccumulatorRangeProof`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the `TransactionAccumulatorRangeProof` could be successfully created, and `Err` otherwise.
+    pub fn from_bytes(bytes: &[u8]) -> std::result::Result<Self, TypesError> {
+        codec::from_bytes("TransactionAccumulatorRangeProof", bytes)
     }
 }
 
-#[cfg(all(test, feature = "aptos"))]
+#[cfg(test)]
 mod test {
+    #[test]
+    fn test_verify_transaction_range_proof() {
+        use crate::crypto::hash::{hash_data, CryptoHash, HashValue};
+        use crate::merkle::node::{MerkleInternalNode, TransactionAccumulatorHasher};
+        use crate::merkle::transaction_proof::TransactionAccumulatorRangeProof;
+
+        const ACCUMULATOR_PLACEHOLDER_HASH: HashValue =
+            HashValue::new(*b"ACCUMULATOR_PLACEHOLDER_HASH\0\0\0\0");
+
+        let node = |left, right| {
+            MerkleInternalNode::<TransactionAccumulatorHasher>::new(left, right).hash()
+        };
+        let leaves: Vec<HashValue> = (0u8..5)
+            .map(|i| HashValue::new(hash_data(&[], vec![&[i]])))
+            .collect();
+
+        // Accumulator of 5 leaves, the empty subtrees on the right being placeholders.
+        let left = node(node(leaves[0], leaves[1]), node(leaves[2], leaves[3]));
+        let right = node(
+            node(leaves[4], ACCUMULATOR_PLACEHOLDER_HASH),
+            ACCUMULATOR_PLACEHOLDER_HASH,
+        );
+        let root = node(left, right);
+
+        let proof = TransactionAccumulatorRangeProof {
+            left_siblings: vec![leaves[0]],
+            right_siblings: vec![ACCUMULATOR_PLACEHOLDER_HASH, ACCUMULATOR_PLACEHOLDER_HASH],
+        };
+
+        proof.verify(root, 1, &leaves[1..]).unwrap();
+        assert!(proof.verify(root, 0, &leaves[1..]).is_err());
+        assert!(proof.verify(root, 1, &leaves[1..4]).is_err());
+        assert!(proof.verify(root, 1, &[]).is_err());
+
+        let proof_deserialized =
+            TransactionAccumulatorRangeProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert_eq!(
+            bcs::to_bytes(&proof).unwrap(),
+            proof_deserialized.to_bytes()
+        );
+        proof_deserialized.verify(root, 1, &leaves[1..]).unwrap();
+    }
+
+    #[cfg(feature = "aptos")]
     #[test]
     fn test_bytes_conversion_transaction_accumulator_proof() {
         use crate::aptos_test_utils::wrapper::AptosWrapper;
@@ -178,6 +323,7 @@ mod test {
         assert_eq!(aptos_proof_bytes, lc_sparse_proof_bytes);
     }
 
+    #[cfg(feature = "aptos")]
     #[test]
     fn test_transaction_accumulator() {
         use crate::aptos_test_utils::wrapper::AptosWrapper;
@@ -211,4 +357,55 @@ mod test {
             .verify(expected_root_hash, element_hash, element_index)
             .unwrap()
     }
+
+    #[cfg(feature = "aptos")]
+    #[test]
+    fn test_transaction_accumulator_range() {
+        use crate::aptos_test_utils::wrapper::AptosWrapper;
+        use crate::crypto::hash::{CryptoHash, HashValue};
+        use crate::merkle::transaction_proof::TransactionAccumulatorRangeProof;
+        use crate::types::transaction::TransactionInfo;
+
+        let mut aptos_wrapper = AptosWrapper::new(40, 1, 1).unwrap();
+        aptos_wrapper.generate_traffic().unwrap();
+
+        let range_assets = aptos_wrapper.get_latest_transaction_range_proof(5).unwrap();
+
+        let aptos_proof_bytes = bcs::to_bytes(range_assets.range_proof()).unwrap();
+        let proof = TransactionAccumulatorRangeProof::from_bytes(&aptos_proof_bytes).unwrap();
+        assert_eq!(aptos_proof_bytes, proof.to_bytes());
+
+        let latest_li = aptos_wrapper.get_latest_li().unwrap();
+        let expected_root_hash = HashValue::from_slice(
+            latest_li
+                .ledger_info()
+                .transaction_accumulator_hash()
+                .as_ref(),
+        )
+        .unwrap();
+        let element_hashes: Vec<HashValue> = range_assets
+            .transactions()
+            .iter()
+            .map(|transaction| {
+                TransactionInfo::from_bytes(&bcs::to_bytes(transaction).unwrap())
+                    .unwrap()
+                    .hash()
+            })
+            .collect();
+
+        proof
+            .verify(
+                expected_root_hash,
+                *range_assets.first_transaction_version(),
+                &element_hashes,
+            )
+            .unwrap();
+        assert!(proof
+            .verify(
+                expected_root_hash,
+                *range_assets.first_transaction_version() + 1,
+                &element_hashes,
+            )
+            .is_err());
+    }
 }
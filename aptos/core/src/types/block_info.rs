@@ -15,13 +15,11 @@
 //! was proposed, and  optionally the state of the next epoch.
 
 // SPDX-License-Identifier: Apache-2.0
-use crate::crypto::hash::{HashValue, HASH_LENGTH};
-use crate::serde_error;
+use crate::crypto::hash::HashValue;
+use crate::types::codec;
 use crate::types::epoch_state::EpochState;
 use crate::types::error::TypesError;
-use crate::types::utils::U64_SIZE;
 use crate::types::{Round, Version};
-use bytes::{Buf, BufMut, BytesMut};
 use getset::{CopyGetters, Getters};
 use serde::{Deserialize, Serialize};
 
@@ -103,23 +101,7 @@ impl BlockInfo {
     ///
     /// A `Vec<u8>` representing the `BlockInfo`.
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = BytesMut::new();
-        bytes.put_u64_le(self.epoch);
-        bytes.put_u64_le(self.round);
-        bytes.put_slice(self.id.as_ref());
-        bytes.put_slice(self.executed_state_id.as_ref());
-        bytes.put_u64_le(self.version);
-        bytes.put_u64_le(self.timestamp_usecs);
-        match &self.next_epoch_state {
-            Some(state) => {
-                bytes.put_u8(1); // Indicate that there is a next_epoch_state
-                bytes.put_slice(&state.to_bytes());
-            }
-            None => {
-                bytes.put_u8(0); // Indicate that there is no next_epoch_state
-            }
-        }
-        bytes.to_vec()
+        codec::to_bytes(self)
     }
 
     /// Creates a `BlockInfo` from a byte slice.
@@ -132,96 +114,8 @@ impl BlockInfo {
     ///
     /// A `Result` which is `Ok` if the `BlockInfo` could
     /// be successfully created, and `Err` otherwise.
-    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, TypesError> {
-        let epoch_state_size = bytes.len() - 4 * U64_SIZE - 2 * HASH_LENGTH - 1;
-
-        let epoch = bytes.get_u64_le();
-        let round = bytes.get_u64_le();
-
-        let id = HashValue::from_slice(
-            bytes
-                .chunk()
-                .get(..HASH_LENGTH)
-                .ok_or_else(|| serde_error!("BlockInfo", "Not enough data for id"))?,
-        )
-        .map_err(|e| serde_error!("BlockInfo", e))?;
-
-        bytes.advance(HASH_LENGTH); // Advance the buffer by the size of HashValue
-
-        let executed_state_id =
-            HashValue::from_slice(bytes.chunk().get(..HASH_LENGTH).ok_or_else(|| {
-                serde_error!("BlockInfo", "Not enough data for executed_state_id")
-            })?)
-            .map_err(|e| serde_error!("BlockInfo", e))?;
-
-        bytes.advance(HASH_LENGTH); // Advance the buffer by the size of HashValue
-
-        let version = bytes.get_u64_le();
-        let timestamp_usecs = bytes.get_u64_le();
-
-        let next_epoch_state = match bytes.get_u8() {
-            1 => {
-                let epoch_state =
-                    EpochState::from_bytes(bytes.chunk().get(..epoch_state_size).ok_or_else(
-                        || serde_error!("BlockInfo", "Not enough data for epoch state"),
-                    )?)
-                    .map_err(|e| serde_error!("BlockInfo", e))?;
-                bytes.advance(epoch_state_size);
-
-                Some(epoch_state)
-            }
-            _ => None,
-        };
-
-        if bytes.remaining() != 0 {
-            return Err(serde_error!(
-                "BlockInfo",
-                "Unexpected data after completing deserialization"
-            ));
-        }
-
-        Ok(Self {
-            epoch,
-            round,
-            id,
-            executed_state_id,
-            version,
-            timestamp_usecs,
-            next_epoch_state,
-        })
-    }
-
-    /// Estimate the size in bytes for  `BlockInfo` from the given bytes.
-    ///
-    /// # Arguments
-    ///
-    /// * `bytes: &[u8]` - A byte slice from which to estimate the size.
-    ///
-    /// # Returns
-    ///
-    /// The estimated size in bytes for the structure.
-    ///
-    /// # Note
-    ///
-    /// The `BlockInfo` bytes should start from offset 0 of the slice.
-    pub(crate) fn estimate_size_from_bytes(bytes: &[u8]) -> Result<usize, TypesError> {
-        let fixed_size = 4 * U64_SIZE + 2 * HASH_LENGTH;
-
-        let has_epoch_state = bytes
-            .get(4 * U64_SIZE + 2 * HASH_LENGTH)
-            .ok_or_else(|| serde_error!("BlockInfo", "Not enough data for next_epoch_state"))?;
-
-        if *has_epoch_state == 0 {
-            return Ok(fixed_size + 1);
-        }
-
-        Ok(fixed_size
-            + 1
-            + EpochState::estimate_size_from_bytes(
-                bytes
-                    .get(fixed_size + 1..)
-                    .ok_or_else(|| serde_error!("BlockInfo", "Not enough data for EpochState"))?,
-            )?)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
+        codec::from_bytes("BlockInfo", bytes)
     }
 }
 
@@ -0,0 +1,248 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! # Codec Module
+//!
+//! This module provides the encoding layer shared by the core types
+//! of the Aptos Light Client.
+//!
+//! The layout of every type is declared through its `serde`
+//! implementation, which mirrors the one of the Aptos codebase. The
+//! functions of this module then encode and decode those types following
+//! the Binary Canonical Serialization (BCS) format, so that no type has
+//! to handle LEB128 lengths or enum variants by hand. The layer only relies
+//! on `serde` and `bcs`, which keeps it usable inside the zkVM programs.
+
+use crate::serde_error;
+use crate::types::error::TypesError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Encodes a value following BCS.
+///
+/// # Arguments
+///
+/// * `value: &T` - The value to encode.
+///
+/// # Returns
+///
+/// A `Vec<u8>` representing the encoded value.
+///
+/// # Panics
+///
+/// Panics if the value exceeds the limits of BCS on sequence lengths
+/// or container depth, which no type of the light client can reach.
+pub fn to_bytes<T: Serialize + ?Sized>(value: &T) -> Vec<u8> {
+    bcs::to_bytes(value).expect("to_bytes: value should be BCS serializable")
+}
+
+/// Decodes a value following BCS.
+///
+/// The whole byte slice has to be consumed for the decoding to succeed,
+/// which makes every type accept a single canonical encoding.
+///
+/// # Arguments
+///
+/// * `structure: &str` - The name of the decoded structure, used in errors.
+/// * `bytes: &[u8]` - A byte slice from which to decode the value.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` if the value could be successfully
+/// decoded, and `Err` otherwise.
+pub fn from_bytes<T: DeserializeOwned>(structure: &str, bytes: &[u8]) -> Result<T, TypesError> {
+    bcs::from_bytes(bytes).map_err(|e| serde_error!(structure, e))
+}
+
+#[cfg(all(test, feature = "aptos"))]
+mod test {
+    //! Differential tests checking that every core type shares its
+    //! encoding, byte-for-byte, with its counterpart in `aptos-types`.
+
+    use crate::crypto::sig::{AggregateSignature, PublicKey, Signature};
+    use crate::merkle::sparse_proof::SparseMerkleProof;
+    use crate::merkle::transaction_proof::TransactionAccumulatorProof;
+    use crate::types::block_info::BlockInfo;
+    use crate::types::epoch_state::EpochState;
+    use crate::types::event::{ContractEvent, EventKey};
+    use crate::types::ledger_info::{LedgerInfo, LedgerInfoWithSignatures};
+    use crate::types::state_value::StateValue;
+    use crate::types::transaction::{ExecutionStatus, TransactionInfo};
+    use crate::types::trusted_state::{EpochChangeProof, TrustedState};
+    use crate::types::type_tag::{StructTag, TypeTag};
+    use crate::types::validator::{ValidatorConsensusInfo, ValidatorVerifier};
+    use crate::types::waypoint::Waypoint;
+    use crate::types::AccountAddress;
+    use proptest::prelude::*;
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+
+    /// Checks that the bytes of an upstream value are decoded by a core
+    /// type and encoded back to the exact same bytes.
+    macro_rules! assert_roundtrip {
+        ($ty:ty, $aptos_value:expr) => {{
+            let aptos_bytes = bcs::to_bytes(&$aptos_value).unwrap();
+            let value = <$ty>::from_bytes(&aptos_bytes).unwrap_or_else(|e| {
+                panic!("{}: could not decode upstream bytes: {e}", stringify!($ty))
+            });
+            prop_assert_eq!(value.to_bytes(), aptos_bytes, "{}", stringify!($ty));
+        }};
+    }
+
+    /// Mutates one byte of the encoding of an upstream value and
+    /// truncates it, to explore the inputs around valid encodings.
+    fn mutate<A: Serialize>(
+        aptos_value: &A,
+        index: prop::sample::Index,
+        xor: u8,
+        cut: usize,
+    ) -> Vec<u8> {
+        let mut bytes = bcs::to_bytes(aptos_value).unwrap();
+        let i = index.index(bytes.len());
+        bytes[i] ^= xor;
+        bytes.truncate(bytes.len() - cut.min(bytes.len()));
+        bytes
+    }
+
+    /// Checks that a core type accepts any encoding accepted upstream,
+    /// and that every encoding it accepts is canonical.
+    fn assert_decoding_agrees<A, T, D, E>(
+        bytes: &[u8],
+        from_bytes: D,
+        to_bytes: E,
+    ) -> Result<(), TestCaseError>
+    where
+        A: DeserializeOwned,
+        D: Fn(&[u8]) -> Option<T>,
+        E: Fn(&T) -> Vec<u8>,
+    {
+        let decoded = from_bytes(bytes);
+        if bcs::from_bytes::<A>(bytes).is_ok() {
+            prop_assert!(decoded.is_some(), "upstream encoding rejected");
+        }
+        if let Some(value) = decoded {
+            prop_assert_eq!(to_bytes(&value), bytes.to_vec());
+        }
+        Ok(())
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(10))]
+
+        #[test]
+        fn test_codec_matches_aptos_ledger(
+            ledger_info_with_signatures in any::<aptos_types::ledger_info::LedgerInfoWithSignatures>(),
+            block_info in any::<aptos_types::block_info::BlockInfo>(),
+            epoch_state in any::<aptos_types::epoch_state::EpochState>(),
+            validator_verifier in any::<aptos_types::validator_verifier::ValidatorVerifier>(),
+            validator_consensus_info in any::<aptos_types::validator_verifier::ValidatorConsensusInfo>(),
+        ) {
+            let ledger_info = ledger_info_with_signatures.ledger_info();
+            let signatures = ledger_info_with_signatures.signatures();
+
+            assert_roundtrip!(LedgerInfoWithSignatures, ledger_info_with_signatures);
+            assert_roundtrip!(LedgerInfo, ledger_info);
+            assert_roundtrip!(AggregateSignature, signatures);
+            if let Some(signature) = signatures.sig() {
+                assert_roundtrip!(Signature, signature);
+            }
+            assert_roundtrip!(
+                Waypoint,
+                aptos_types::waypoint::Waypoint::new_any(ledger_info)
+            );
+            assert_roundtrip!(BlockInfo, block_info);
+            assert_roundtrip!(EpochState, epoch_state);
+            assert_roundtrip!(ValidatorVerifier, validator_verifier);
+            assert_roundtrip!(ValidatorConsensusInfo, validator_consensus_info);
+            assert_roundtrip!(PublicKey, validator_consensus_info.public_key());
+        }
+
+        #[test]
+        fn test_codec_matches_aptos_state(
+            transaction_info in any::<aptos_types::transaction::TransactionInfo>(),
+            execution_status in any::<aptos_types::transaction::ExecutionStatus>(),
+            contract_event in any::<aptos_types::contract_event::ContractEvent>(),
+            event_key in any::<aptos_types::event::EventKey>(),
+            state_value in any::<aptos_types::state_store::state_value::StateValue>(),
+            type_tag in any::<aptos_sdk::move_types::language_storage::TypeTag>(),
+            struct_tag in any::<aptos_sdk::move_types::language_storage::StructTag>(),
+            address in any::<aptos_types::account_address::AccountAddress>(),
+        ) {
+            assert_roundtrip!(TransactionInfo, transaction_info);
+            assert_roundtrip!(ExecutionStatus, execution_status);
+            assert_roundtrip!(ContractEvent, contract_event);
+            assert_roundtrip!(EventKey, event_key);
+            assert_roundtrip!(StateValue, state_value);
+            assert_roundtrip!(TypeTag, type_tag);
+            assert_roundtrip!(StructTag, struct_tag);
+            assert_roundtrip!(AccountAddress, address);
+        }
+
+        #[test]
+        fn test_codec_fuzz_against_aptos(
+            transaction_info in any::<aptos_types::transaction::TransactionInfo>(),
+            contract_event in any::<aptos_types::contract_event::ContractEvent>(),
+            state_value in any::<aptos_types::state_store::state_value::StateValue>(),
+            index in any::<prop::sample::Index>(),
+            xor in 1u8..,
+            cut in 0usize..4,
+        ) {
+            assert_decoding_agrees::<aptos_types::transaction::TransactionInfo, _, _, _>(
+                &mutate(&transaction_info, index, xor, cut),
+                |bytes| TransactionInfo::from_bytes(bytes).ok(),
+                TransactionInfo::to_bytes,
+            )?;
+            assert_decoding_agrees::<aptos_types::contract_event::ContractEvent, _, _, _>(
+                &mutate(&contract_event, index, xor, cut),
+                |bytes| ContractEvent::from_bytes(bytes).ok(),
+                ContractEvent::to_bytes,
+            )?;
+            assert_decoding_agrees::<aptos_types::state_store::state_value::StateValue, _, _, _>(
+                &mutate(&state_value, index, xor, cut),
+                |bytes| StateValue::from_bytes(bytes).ok(),
+                StateValue::to_bytes,
+            )?;
+        }
+    }
+
+    #[test]
+    fn test_codec_matches_aptos_proofs() {
+        use crate::aptos_test_utils::wrapper::AptosWrapper;
+
+        let mut aptos_wrapper = AptosWrapper::new(4, 1, 1).unwrap();
+        aptos_wrapper.generate_traffic().unwrap();
+
+        let proof_assets = aptos_wrapper.get_latest_proof_account(0).unwrap();
+        let state_proof = aptos_wrapper.new_state_proof(0).unwrap();
+
+        let sparse_merkle_proof = bcs::to_bytes(proof_assets.state_proof()).unwrap();
+        assert_eq!(
+            SparseMerkleProof::from_bytes(&sparse_merkle_proof)
+                .unwrap()
+                .to_bytes(),
+            sparse_merkle_proof
+        );
+
+        let transaction_proof = bcs::to_bytes(proof_assets.transaction_proof()).unwrap();
+        assert_eq!(
+            TransactionAccumulatorProof::from_bytes(&transaction_proof)
+                .unwrap()
+                .to_bytes(),
+            transaction_proof
+        );
+
+        let epoch_change_proof = bcs::to_bytes(state_proof.epoch_changes()).unwrap();
+        assert_eq!(
+            EpochChangeProof::from_bytes(&epoch_change_proof)
+                .unwrap()
+                .to_bytes(),
+            epoch_change_proof
+        );
+
+        let trusted_state = bcs::to_bytes(&aptos_wrapper.trusted_state()).unwrap();
+        assert_eq!(
+            TrustedState::from_bytes(&trusted_state).unwrap().to_bytes(),
+            trusted_state
+        );
+    }
+}
@@ -11,14 +11,11 @@
 //! an epoch in the blockchain, including the epoch number
 //! and the validator verifier.
 
-// SPDX-License-Identifier: Apache-2.0
-use crate::serde_error;
+use crate::types::codec;
 use crate::types::error::TypesError;
 use crate::types::ledger_info::{LedgerInfo, LedgerInfoWithSignatures};
-use crate::types::utils::U64_SIZE;
-use crate::types::validator::ValidatorVerifier;
+use crate::types::validator::{PreparedValidatorVerifier, ValidatorVerifier};
 use anyhow::ensure;
-use bytes::{Buf, BufMut, BytesMut};
 use getset::Getters;
 use serde::{Deserialize, Serialize};
 
@@ -79,16 +76,47 @@ impl EpochState {
         Ok(())
     }
 
+    /// Verifies signatures over a given `LedgerInfoWithSignatures`
+    /// with a `PreparedValidatorVerifier` of this epoch.
+    ///
+    /// # Arguments
+    ///
+    /// * `ledger_info: &LedgerInfoWithSignatures` - The ledger
+    ///   info with signatures to verify.
+    /// * `prepared_verifier: &PreparedValidatorVerifier` - The
+    ///   prepared validator set of this epoch.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the ledger info with signatures
+    /// is valid, and `Err` otherwise.
+    pub fn verify_prepared(
+        &self,
+        ledger_info: &LedgerInfoWithSignatures,
+        prepared_verifier: &PreparedValidatorVerifier,
+    ) -> anyhow::Result<()> {
+        ensure!(
+            self.epoch == ledger_info.ledger_info().epoch(),
+            "LedgerInfo has unexpected epoch {}, expected {}",
+            ledger_info.ledger_info().epoch(),
+            self.epoch
+        );
+        ensure!(
+            prepared_verifier.validator_verifier() == &self.verifier,
+            "PreparedValidatorVerifier does not match the validator set of epoch {}",
+            self.epoch
+        );
+        ledger_info.verify_prepared_signatures(prepared_verifier)?;
+        Ok(())
+    }
+
     /// Converts the `EpochState` to a byte vector.
     ///
     /// # Returns
     ///
     /// A `Vec<u8>` representing the `EpochState`.
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = BytesMut::new();
-        bytes.put_u64_le(self.epoch);
-        bytes.put_slice(&self.verifier.to_bytes());
-        bytes.to_vec()
+        codec::to_bytes(self)
     }
 
     /// Creates an `EpochState` from a byte slice.
@@ -101,51 +129,8 @@ impl EpochState {
     ///
     /// A `Result` which is `Ok` if the `EpochState` could
     /// be successfully created, and `Err` otherwise.
-    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, TypesError> {
-        let validator_verifier_size = bytes.len() - U64_SIZE;
-
-        let epoch = bytes.get_u64_le();
-
-        // Deserialize ValidatorVerifier
-        let verifier = ValidatorVerifier::from_bytes(
-            bytes
-                .chunk()
-                .get(..validator_verifier_size)
-                .ok_or_else(|| serde_error!("EpochState", "Not enough data for verifier"))?,
-        )
-        .map_err(|e| serde_error!("EpochState", e))?;
-        bytes.advance(validator_verifier_size);
-
-        if bytes.remaining() != 0 {
-            return Err(serde_error!(
-                "EpochState",
-                "Unexpected data after completing deserialization"
-            ));
-        }
-
-        Ok(Self { epoch, verifier })
-    }
-
-    /// Estimate the size in bytes for  `EpochState` from the given bytes.
-    ///
-    /// # Arguments
-    ///
-    /// * `bytes: &[u8]` - A byte slice from which to estimate the size.
-    ///
-    /// # Returns
-    ///
-    /// The estimated size in bytes for the structure.
-    ///
-    /// # Note
-    ///
-    /// The `EpochState` bytes should start from offset 0 of the slice.
-    pub(crate) fn estimate_size_from_bytes(bytes: &[u8]) -> Result<usize, TypesError> {
-        Ok(U64_SIZE
-            + ValidatorVerifier::estimate_size_from_bytes(
-                bytes
-                    .get(U64_SIZE..)
-                    .ok_or_else(|| serde_error!("EpochState", "Not enough data for verifier"))?,
-            )?)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
+        codec::from_bytes("EpochState", bytes)
     }
 }
 
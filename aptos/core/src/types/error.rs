@@ -39,6 +39,33 @@ pub enum VerifyError {
     InvalidBitVec,
     #[error("Failed to verify aggreagated signature")]
     FailedToVerifyAggregatedSignature,
+    #[error("Validator set has no voting power")]
+    /// The validator set is empty or its total voting power is zero
+    NoVotingPower,
+    #[error("Validator set has too many validators: {0}")]
+    /// The validator set cannot be indexed by a bitmask
+    TooManyValidators(usize),
+    #[error("Validator set contains a duplicate author")]
+    /// Two validators share the same address
+    DuplicateAuthor,
+    #[error("Validator set contains a duplicate public key")]
+    /// Two validators share the same public key
+    DuplicatePublicKey,
+    #[error("Voting power overflowed")]
+    /// The sum of the voting powers does not fit in the expected integer
+    VotingPowerOverflow,
+    #[error(
+        "Ledger info timestamp ({}) is more than {} usecs older than the reference time ({})",
+        timestamp_usecs,
+        max_age_usecs,
+        reference_timestamp_usecs
+    )]
+    /// The ledger info is older than the maximum age allowed by the caller
+    StaleLedgerInfo {
+        timestamp_usecs: u64,
+        reference_timestamp_usecs: u64,
+        max_age_usecs: u64,
+    },
 }
 
 /// Errors possible during type conversions.
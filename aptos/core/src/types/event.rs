@@ -0,0 +1,285 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! # Event Module
+//!
+//! This module provides the `ContractEvent` structure and
+//! associated methods for handling events emitted by transactions
+//! in the Aptos Light Client.
+//!
+//! Events are hashed as leaves of the event accumulator whose
+//! root is stored in the `TransactionInfo` of the transaction
+//! that emitted them.
+
+// SPDX-License-Identifier: Apache-2.0
+use crate::crypto::hash::{hash_data, prefixed_sha3, CryptoHash, HashValue};
+use crate::types::codec;
+use crate::types::error::TypesError;
+use crate::types::type_tag::TypeTag;
+use crate::types::utils::U64_SIZE;
+use crate::types::{AccountAddress, ACCOUNT_ADDRESS_SIZE};
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
+
+/// Length in bytes of the serialized `EventKey`.
+pub const EVENT_KEY_SIZE: usize = U64_SIZE + ACCOUNT_ADDRESS_SIZE;
+
+/// `EventKey` is a structure representing the unique
+/// identifier of a V1 event stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct EventKey {
+    creation_number: u64,
+    account_address: AccountAddress,
+}
+
+impl EventKey {
+    /// Creates a new `EventKey`.
+    ///
+    /// # Arguments
+    ///
+    /// * `creation_number: u64` - The creation number of the event stream.
+    /// * `account_address: AccountAddress` - The address of the account owning the event stream.
+    ///
+    /// # Returns
+    ///
+    /// A new `EventKey`.
+    pub const fn new(creation_number: u64, account_address: AccountAddress) -> Self {
+        Self {
+            creation_number,
+            account_address,
+        }
+    }
+
+    /// Converts the `EventKey` to a byte vector.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<u8>` representing the `EventKey`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        codec::to_bytes(self)
+    }
+
+    /// Creates an `EventKey` from a byte slice.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes: &[u8]` - A byte slice from which to create the `EventKey`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the `EventKey` could
+    /// be successfully created, and `Err` otherwise.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
+        codec::from_bytes("EventKey", bytes)
+    }
+}
+
+/// `ContractEventV1` is a structure representing an event
+/// emitted on an `EventHandle` by a transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Getters, CopyGetters)]
+pub struct ContractEventV1 {
+    #[getset(get_copy = "pub")]
+    key: EventKey,
+    #[getset(get_copy = "pub")]
+    sequence_number: u64,
+    #[getset(get = "pub")]
+    type_tag: TypeTag,
+    #[serde(with = "serde_bytes")]
+    #[getset(get = "pub")]
+    event_data: Vec<u8>,
+}
+
+impl ContractEventV1 {
+    /// Creates a new `ContractEventV1`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key: EventKey` - The key of the event stream.
+    /// * `sequence_number: u64` - The sequence number of the event in its stream.
+    /// * `type_tag: TypeTag` - The type of the event data.
+    /// * `event_data: Vec<u8>` - The BCS serialized event data.
+    ///
+    /// # Returns
+    ///
+    /// A new `ContractEventV1`.
+    pub const fn new(
+        key: EventKey,
+        sequence_number: u64,
+        type_tag: TypeTag,
+        event_data: Vec<u8>,
+    ) -> Self {
+        Self {
+            key,
+            sequence_number,
+            type_tag,
+            event_data,
+        }
+    }
+}
+
+/// `ContractEventV2` is a structure representing a module
+/// event emitted by a transaction. Contrary to V1 events,
+/// they are not attached to any event stream.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct ContractEventV2 {
+    type_tag: TypeTag,
+    #[serde(with = "serde_bytes")]
+    event_data: Vec<u8>,
+}
+
+impl ContractEventV2 {
+    /// Creates a new `ContractEventV2`.
+    ///
+    /// # Arguments
+    ///
+    /// * `type_tag: TypeTag` - The type of the event data.
+    /// * `event_data: Vec<u8>` - The BCS serialized event data.
+    ///
+    /// # Returns
+    ///
+    /// A new `ContractEventV2`.
+    pub const fn new(type_tag: TypeTag, event_data: Vec<u8>) -> Self {
+        Self {
+            type_tag,
+            event_data,
+        }
+    }
+}
+
+/// `ContractEvent` is an enum representing an event emitted
+/// by a transaction, either as a V1 or a V2 event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ContractEvent {
+    V1(ContractEventV1),
+    V2(ContractEventV2),
+}
+
+impl ContractEvent {
+    /// Returns the type of the event data.
+    ///
+    /// # Returns
+    ///
+    /// The `TypeTag` of the event.
+    pub const fn type_tag(&self) -> &TypeTag {
+        match self {
+            Self::V1(event) => &event.type_tag,
+            Self::V2(event) => &event.type_tag,
+        }
+    }
+
+    /// Returns the BCS serialized event data.
+    ///
+    /// # Returns
+    ///
+    /// The event data as a byte slice.
+    pub fn event_data(&self) -> &[u8] {
+        match self {
+            Self::V1(event) => &event.event_data,
+            Self::V2(event) => &event.event_data,
+        }
+    }
+
+    /// Converts the `ContractEvent` to a byte vector.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<u8>` representing the `ContractEvent`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        codec::to_bytes(self)
+    }
+
+    /// Creates a `ContractEvent` from a byte slice.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes: &[u8]` - A byte slice from which to create the `ContractEvent`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the `ContractEvent` could
+    /// be successfully created, and `Err` otherwise.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
+        codec::from_bytes("ContractEvent", bytes)
+    }
+}
+
+impl CryptoHash for ContractEvent {
+    fn hash(&self) -> HashValue {
+        HashValue::new(hash_data(
+            &prefixed_sha3(b"ContractEvent"),
+            vec![&self.to_bytes()],
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tiny_keccak::{Hasher, Sha3};
+
+    fn events() -> Vec<ContractEvent> {
+        vec![
+            ContractEvent::V1(ContractEventV1::new(
+                EventKey::new(2, AccountAddress::new([3; ACCOUNT_ADDRESS_SIZE])),
+                7,
+                TypeTag::Vector(Box::new(TypeTag::U64)),
+                vec![1, 2, 3, 4],
+            )),
+            ContractEvent::V2(ContractEventV2::new(TypeTag::Address, vec![5; 300])),
+        ]
+    }
+
+    #[test]
+    fn test_bytes_conversion_contract_event() {
+        for event in events() {
+            let bytes = bcs::to_bytes(&event).unwrap();
+            assert_eq!(bytes, event.to_bytes());
+
+            let event_deserialized = ContractEvent::from_bytes(&bytes).unwrap();
+            assert_eq!(event, event_deserialized);
+
+            assert!(ContractEvent::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_hash() {
+        for event in events() {
+            let expected = {
+                let mut digest = Sha3::v256();
+                digest.update(&prefixed_sha3(b"ContractEvent"));
+                digest.update(&bcs::to_bytes(&event).unwrap());
+                let mut hasher_bytes = [0u8; 32];
+                digest.finalize(&mut hasher_bytes);
+                hasher_bytes
+            };
+
+            assert_eq!(&expected, event.hash().as_ref());
+        }
+    }
+
+    #[cfg(feature = "aptos")]
+    #[test]
+    fn test_aptos_contract_event() {
+        use crate::aptos_test_utils::wrapper::AptosWrapper;
+        use aptos_crypto::hash::CryptoHash as AptosCryptoHash;
+
+        let mut aptos_wrapper = AptosWrapper::new(4, 1, 1).unwrap();
+        aptos_wrapper.generate_traffic().unwrap();
+
+        let event_assets = aptos_wrapper.get_latest_event_proof(0).unwrap();
+
+        for aptos_event in event_assets.transaction_events() {
+            let aptos_bytes = bcs::to_bytes(aptos_event).unwrap();
+
+            let event = ContractEvent::from_bytes(&aptos_bytes).unwrap();
+            assert_eq!(aptos_bytes, event.to_bytes());
+            assert_eq!(
+                event.hash().to_vec(),
+                AptosCryptoHash::hash(aptos_event).to_vec()
+            );
+        }
+    }
+}
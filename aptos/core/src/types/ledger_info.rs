@@ -12,17 +12,15 @@
 //! represent offset and length of the fields bytes.
 
 // SPDX-License-Identifier: Apache-2.0
-use crate::crypto::hash::{hash_data, prefixed_sha3, CryptoHash, HashValue, HASH_LENGTH};
+use crate::crypto::hash::{hash_data, prefixed_sha3, CryptoHash, HashValue};
 use crate::crypto::sig::AggregateSignature;
-use crate::serde_error;
 use crate::types::block_info::BlockInfo;
+use crate::types::codec;
 use crate::types::epoch_state::EpochState;
 use crate::types::error::{TypesError, VerifyError};
-use crate::types::utils::ENUM_VARIANT_LEN;
-use crate::types::validator::ValidatorVerifier;
+use crate::types::validator::{PreparedValidatorVerifier, ValidatorVerifier};
 use crate::types::Version;
-use bytes::{Buf, BufMut, BytesMut};
-use getset::Getters;
+use getset::{CopyGetters, Getters};
 use serde::{Deserialize, Serialize};
 use std::ops::Deref;
 
@@ -114,16 +112,40 @@ impl LedgerInfo {
         self.commit_info.version()
     }
 
+    /// Checks that the `LedgerInfo` is not older than allowed by
+    /// the given `MaxAge`.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_age: &MaxAge` - The reference time and maximum age to check against.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the timestamp of the `LedgerInfo` is at most
+    /// `max_age_usecs` behind `reference_timestamp_usecs`, and `Err` otherwise.
+    pub fn verify_freshness(&self, max_age: &MaxAge) -> Result<(), VerifyError> {
+        let timestamp_usecs = self.timestamp_usecs();
+        if max_age
+            .reference_timestamp_usecs
+            .saturating_sub(timestamp_usecs)
+            > max_age.max_age_usecs
+        {
+            return Err(VerifyError::StaleLedgerInfo {
+                timestamp_usecs,
+                reference_timestamp_usecs: max_age.reference_timestamp_usecs,
+                max_age_usecs: max_age.max_age_usecs,
+            });
+        }
+        Ok(())
+    }
+
     /// Converts the `LedgerInfo` to a byte vector.
     ///
     /// # Returns
     ///
     /// A `Vec<u8>` representing the `LedgerInfo`.
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = BytesMut::new();
-        bytes.put_slice(&self.commit_info.to_bytes());
-        bytes.put_slice(self.consensus_data_hash.as_ref());
-        bytes.to_vec()
+        codec::to_bytes(self)
     }
 
     /// Creates a `LedgerInfo` from a byte slice.
@@ -136,53 +158,8 @@ impl LedgerInfo {
     ///
     /// A `Result` which is `Ok` if the `LedgerInfo` could be
     /// successfully created, and `Err` otherwise.
-    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, TypesError> {
-        let commit_info = BlockInfo::from_bytes(
-            bytes
-                .chunk()
-                .get(..bytes.len() - HASH_LENGTH)
-                .ok_or_else(|| serde_error!("LedgerInfo", "Not enough data for BlockInfo"))?,
-        )
-        .map_err(|e| serde_error!("LedgerInfo", e))?;
-
-        bytes.advance(bytes.len() - HASH_LENGTH); // Advance the buffer to get the hash
-
-        let consensus_data_hash =
-            HashValue::from_slice(bytes.chunk().get(..HASH_LENGTH).ok_or_else(|| {
-                serde_error!("LedgerInfo", "Not enough data for consensus data hash")
-            })?)
-            .map_err(|e| serde_error!("LedgerInfo", e))?;
-
-        bytes.advance(HASH_LENGTH);
-
-        if bytes.remaining() != 0 {
-            return Err(serde_error!(
-                "LedgerInfo",
-                "Unexpected data after completing deserialization"
-            ));
-        }
-
-        Ok(Self {
-            commit_info,
-            consensus_data_hash,
-        })
-    }
-
-    /// Estimate the size in bytes for  `LedgerInfo` from the given bytes.
-    ///
-    /// # Arguments
-    ///
-    /// * `bytes: &[u8]` - A byte slice from which to estimate the size.
-    ///
-    /// # Returns
-    ///
-    /// The estimated size in bytes for the structure.
-    ///
-    /// # Note
-    ///
-    /// The `LedgerInfo` bytes should start from offset 0 of the slice.
-    pub(crate) fn estimate_size_from_bytes(bytes: &[u8]) -> Result<usize, TypesError> {
-        Ok(BlockInfo::estimate_size_from_bytes(bytes)? + HASH_LENGTH)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
+        codec::from_bytes("LedgerInfo", bytes)
     }
 }
 
@@ -195,6 +172,36 @@ impl CryptoHash for LedgerInfo {
     }
 }
 
+/// `MaxAge` bounds how far the timestamp of a `LedgerInfo` may lag
+/// behind a reference time supplied by the caller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct MaxAge {
+    /// Reference time, in microseconds since the Unix epoch.
+    reference_timestamp_usecs: u64,
+    /// Maximum age of the `LedgerInfo` at the reference time, in microseconds.
+    max_age_usecs: u64,
+}
+
+impl MaxAge {
+    /// Creates a new `MaxAge`.
+    ///
+    /// # Arguments
+    ///
+    /// * `reference_timestamp_usecs: u64` - The reference time, in microseconds.
+    /// * `max_age_usecs: u64` - The maximum age at the reference time, in microseconds.
+    ///
+    /// # Returns
+    ///
+    /// A new `MaxAge`.
+    pub const fn new(reference_timestamp_usecs: u64, max_age_usecs: u64) -> Self {
+        Self {
+            reference_timestamp_usecs,
+            max_age_usecs,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Getters, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LedgerInfoWithV0 {
     #[getset(get = "pub")]
@@ -213,40 +220,19 @@ impl LedgerInfoWithV0 {
         validator.verify_multi_signatures(self.ledger_info(), &self.signatures)
     }
 
-    pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = BytesMut::new();
-        bytes.put_slice(&self.ledger_info.to_bytes());
-        bytes.put_slice(&self.signatures.to_bytes());
-        bytes.to_vec()
+    pub fn verify_prepared_signatures(
+        &self,
+        validator: &PreparedValidatorVerifier,
+    ) -> anyhow::Result<(), VerifyError> {
+        validator.verify_multi_signatures(self.ledger_info(), &self.signatures)
     }
 
-    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, TypesError> {
-        let ledger_info_size = LedgerInfo::estimate_size_from_bytes(bytes)?;
-        let agg_sig_size = bytes.len() - ledger_info_size;
-
-        let ledger_info =
-            LedgerInfo::from_bytes(bytes.chunk().get(..ledger_info_size).ok_or_else(|| {
-                serde_error!("LedgerInfoWithV0", "Not enough data for LedgerInfo")
-            })?)?;
-        bytes.advance(ledger_info_size);
-
-        let signatures =
-            AggregateSignature::from_bytes(bytes.chunk().get(..agg_sig_size).ok_or_else(
-                || serde_error!("LedgerInfoWithV0", "Not enough data for AggregateSignature"),
-            )?)?;
-        bytes.advance(agg_sig_size);
-
-        if bytes.remaining() != 0 {
-            return Err(serde_error!(
-                "LedgerInfoWithV0",
-                "Unexpected data after completing deserialization"
-            ));
-        }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        codec::to_bytes(self)
+    }
 
-        Ok(Self {
-            ledger_info,
-            signatures,
-        })
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
+        codec::from_bytes("LedgerInfoWithV0", bytes)
     }
 }
 
@@ -266,14 +252,7 @@ impl LedgerInfoWithSignatures {
     ///
     /// A `Vec<u8>` representing the `LedgerInfoWithSignatures`.
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = BytesMut::new();
-        match self {
-            LedgerInfoWithSignatures::V0(ledger_info_with_v0) => {
-                bytes.put_u8(0); // 0 indicates V0
-                bytes.extend_from_slice(&ledger_info_with_v0.to_bytes());
-            }
-        }
-        bytes.to_vec()
+        codec::to_bytes(self)
     }
 
     /// Creates a `LedgerInfoWithSignatures` from a byte slice.
@@ -286,33 +265,8 @@ impl LedgerInfoWithSignatures {
     ///
     /// A `Result` which is `Ok` if the `LedgerInfoWithSignatures`
     /// could be successfully created, and `Err` otherwise.
-    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, TypesError> {
-        let ledger_v0_size = bytes.len() - ENUM_VARIANT_LEN;
-        let li_w_sig = match bytes.get_u8() {
-            0 => {
-                let ledger_info_with_v0 = LedgerInfoWithV0::from_bytes(
-                    bytes.chunk().get(..ledger_v0_size).ok_or_else(|| {
-                        serde_error!(
-                            "LedgerInfoWithSignatures",
-                            "Not enough LedgerInfoWithV0 for LedgerInfo"
-                        )
-                    })?,
-                )?;
-                bytes.advance(ledger_v0_size);
-
-                LedgerInfoWithSignatures::V0(ledger_info_with_v0)
-            }
-            _ => return Err(serde_error!("LedgerInfoWithSignatures", "Unknown variant")),
-        };
-
-        if bytes.remaining() != 0 {
-            return Err(serde_error!(
-                "LedgerInfoWithSignatures",
-                "Unexpected data after completing deserialization"
-            ));
-        }
-
-        Ok(li_w_sig)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
+        codec::from_bytes("LedgerInfoWithSignatures", bytes)
     }
 
     /// Returns the aggregated signatures of the validators
@@ -366,6 +320,41 @@ mod test {
         assert_eq!(intern_li_hash.to_vec(), aptos_li_hash.to_vec());
     }
 
+    #[test]
+    fn test_ledger_info_freshness() {
+        use super::*;
+        use crate::aptos_test_utils::wrapper::AptosWrapper;
+
+        let mut aptos_wrapper = AptosWrapper::new(2, 130, 130).unwrap();
+        aptos_wrapper.generate_traffic().unwrap();
+
+        let ledger_info = LedgerInfo::from_bytes(
+            &bcs::to_bytes(aptos_wrapper.get_latest_li().unwrap().ledger_info()).unwrap(),
+        )
+        .unwrap();
+        let timestamp_usecs = ledger_info.timestamp_usecs();
+
+        // Ledger infos at most `max_age_usecs` old, or ahead of the reference time, are fresh
+        for (reference_timestamp_usecs, max_age_usecs) in [
+            (timestamp_usecs, 0),
+            (timestamp_usecs + 10, 10),
+            (timestamp_usecs.saturating_sub(10), 0),
+        ] {
+            assert!(ledger_info
+                .verify_freshness(&MaxAge::new(reference_timestamp_usecs, max_age_usecs))
+                .is_ok());
+        }
+
+        assert_eq!(
+            ledger_info.verify_freshness(&MaxAge::new(timestamp_usecs + 11, 10)),
+            Err(VerifyError::StaleLedgerInfo {
+                timestamp_usecs,
+                reference_timestamp_usecs: timestamp_usecs + 11,
+                max_age_usecs: 10,
+            })
+        );
+    }
+
     proptest! {
         #![proptest_config(ProptestConfig::with_cases(10))]
         #[test]
@@ -10,18 +10,36 @@
 //! - `block_info`: This sub-module contains the `BlockInfo`
 //!   structure and associated methods. It is used to represent
 //!   the block information in the blockchain.
+//! - `codec`: This sub-module contains the encoding layer
+//!   shared by the core types. It is used to encode and decode
+//!   them following the Binary Canonical Serialization format.
 //! - `epoch_state`: This sub-module contains the `EpochState`
 //!   structure and associated methods. It is used to represent
 //!   the epoch state in the blockchain.
+//! - `event`: This sub-module contains the `ContractEvent`
+//!   structure and associated methods. It is used to represent
+//!   the events emitted by transactions in the blockchain.
 //! - `ledger_info`: This sub-module contains the `LedgerInfo`
 //!   structure and associated methods. It is used to represent
 //!   the ledger information from the blockchain.
+//! - `resource`: This sub-module contains the `AccountResource`
+//!   and `CoinStoreResource` structures and associated methods. They
+//!   are used to decode common Move resources stored in the state.
+//! - `state_key`: This sub-module contains the `StateKey`
+//!   structure and associated methods. It is used to derive the
+//!   keys of the leaves of the state from resources and table items.
+//! - `state_value`: This sub-module contains the `StateValue`
+//!   structure and associated methods. It is used to represent
+//!   the values of the leaves of the state in the blockchain.
 //! - `transaction`: This sub-module contains the `Transaction`
 //!   structure and associated methods. It is used to represent
 //!   the transactions in the blockchain.
 //! - `trusted_state`: This sub-module contains the `TrustedState`
 //!   structure and associated methods. It is used to represent the
 //!   trusted state for the blockchain from the Light Client perspective.
+//! - `type_tag`: This sub-module contains the `TypeTag` and
+//!   `StructTag` structures and associated methods. They are used
+//!   to represent Move types, such as the type of emitted events.
 //! - `validator`: This sub-module contains the `ValidatorConsensusInfo`
 //!   and `ValidatorVerifier` structures and associated methods. They are
 //!   used to represent the validator information from the blockchain
@@ -42,11 +60,17 @@ use anyhow::Result;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 pub mod block_info;
+pub mod codec;
 pub mod epoch_state;
 pub mod error;
+pub mod event;
 pub mod ledger_info;
+pub mod resource;
+pub mod state_key;
+pub mod state_value;
 pub mod transaction;
 pub mod trusted_state;
+pub mod type_tag;
 pub mod utils;
 pub mod validator;
 pub mod waypoint;
@@ -56,7 +80,7 @@ pub type Version = u64;
 
 pub const ACCOUNT_ADDRESS_SIZE: usize = HASH_LENGTH;
 
-#[derive(Default, Debug, Clone, PartialEq, Eq, Hash, Copy)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Copy)]
 pub struct AccountAddress([u8; ACCOUNT_ADDRESS_SIZE]);
 
 impl AccountAddress {
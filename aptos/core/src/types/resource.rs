@@ -0,0 +1,274 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! # Resource Module
+//!
+//! This module provides decoders for common Move resources
+//! stored in the state of the Aptos chain, such as
+//! `0x1::account::Account` and `0x1::coin::CoinStore<AptosCoin>`.
+//!
+//! The resources are decoded from the data of a `StateValue`,
+//! so that the fields of a proven leaf can be read directly.
+
+// SPDX-License-Identifier: Apache-2.0
+use crate::types::codec;
+use crate::types::error::TypesError;
+use crate::types::event::{EventKey, EVENT_KEY_SIZE};
+use crate::types::state_key::StateKey;
+use crate::types::type_tag::{StructTag, TypeTag};
+use crate::types::utils::U64_SIZE;
+use crate::types::{AccountAddress, ACCOUNT_ADDRESS_SIZE};
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
+
+/// Length in bytes of the serialized `EventHandle`.
+pub const EVENT_HANDLE_SIZE: usize = U64_SIZE + EVENT_KEY_SIZE;
+
+/// `ResourceKind` is an enum listing the Move resources
+/// that can be decoded from a `StateValue`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResourceKind {
+    /// `0x1::account::Account`
+    Account,
+    /// `0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>`
+    AptosCoinStore,
+}
+
+impl ResourceKind {
+    /// Returns the type of the resource.
+    ///
+    /// # Returns
+    ///
+    /// The `StructTag` of the resource.
+    pub fn struct_tag(&self) -> StructTag {
+        let framework = framework_address();
+        match self {
+            Self::Account => StructTag::new(framework, "account".into(), "Account".into(), vec![]),
+            Self::AptosCoinStore => StructTag::new(
+                framework,
+                "coin".into(),
+                "CoinStore".into(),
+                vec![TypeTag::Struct(Box::new(StructTag::new(
+                    framework,
+                    "aptos_coin".into(),
+                    "AptosCoin".into(),
+                    vec![],
+                )))],
+            ),
+        }
+    }
+
+    /// Returns the key under which the resource is stored for the given address.
+    ///
+    /// # Arguments
+    ///
+    /// * `address: AccountAddress` - The address holding the resource.
+    ///
+    /// # Returns
+    ///
+    /// The `StateKey` of the resource.
+    pub fn state_key(&self, address: AccountAddress) -> StateKey {
+        StateKey::resource(address, self.struct_tag())
+    }
+}
+
+/// Returns the `0x1` address, under which the Aptos framework is published.
+const fn framework_address() -> AccountAddress {
+    let mut address = [0u8; ACCOUNT_ADDRESS_SIZE];
+    address[ACCOUNT_ADDRESS_SIZE - 1] = 1;
+    AccountAddress::new(address)
+}
+
+/// `EventHandle` is a structure representing a V1 event
+/// stream, as stored in Move resources.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct EventHandle {
+    counter: u64,
+    key: EventKey,
+}
+
+impl EventHandle {
+    /// Converts the `EventHandle` to a byte vector.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<u8>` representing the `EventHandle`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        codec::to_bytes(self)
+    }
+}
+
+/// `AccountResource` is a structure representing the
+/// `0x1::account::Account` Move resource.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Getters, CopyGetters)]
+pub struct AccountResource {
+    #[serde(with = "serde_bytes")]
+    #[getset(get = "pub")]
+    authentication_key: Vec<u8>,
+    #[getset(get_copy = "pub")]
+    sequence_number: u64,
+    #[getset(get_copy = "pub")]
+    guid_creation_num: u64,
+    #[getset(get_copy = "pub")]
+    coin_register_events: EventHandle,
+    #[getset(get_copy = "pub")]
+    key_rotation_events: EventHandle,
+    #[getset(get_copy = "pub")]
+    rotation_capability_offer: Option<AccountAddress>,
+    #[getset(get_copy = "pub")]
+    signer_capability_offer: Option<AccountAddress>,
+}
+
+impl AccountResource {
+    /// Converts the `AccountResource` to a byte vector.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<u8>` representing the `AccountResource`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        codec::to_bytes(self)
+    }
+
+    /// Creates an `AccountResource` from a byte slice.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes: &[u8]` - A byte slice from which to create the `AccountResource`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the `AccountResource` could
+    /// be successfully created, and `Err` otherwise.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
+        codec::from_bytes("AccountResource", bytes)
+    }
+}
+
+/// `CoinStoreResource` is a structure representing the
+/// `0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>` Move resource.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct CoinStoreResource {
+    coin: u64,
+    frozen: bool,
+    deposit_events: EventHandle,
+    withdraw_events: EventHandle,
+}
+
+impl CoinStoreResource {
+    /// Returns the balance of the `CoinStoreResource`.
+    ///
+    /// # Returns
+    ///
+    /// The value of the coin held in the store.
+    pub const fn balance(&self) -> u64 {
+        self.coin
+    }
+
+    /// Converts the `CoinStoreResource` to a byte vector.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<u8>` representing the `CoinStoreResource`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        codec::to_bytes(self)
+    }
+
+    /// Creates a `CoinStoreResource` from a byte slice.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes: &[u8]` - A byte slice from which to create the `CoinStoreResource`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the `CoinStoreResource` could
+    /// be successfully created, and `Err` otherwise.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
+        codec::from_bytes("CoinStoreResource", bytes)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const fn event_handle(counter: u64, creation_number: u64) -> EventHandle {
+        EventHandle {
+            counter,
+            key: EventKey::new(
+                creation_number,
+                AccountAddress::new([7; ACCOUNT_ADDRESS_SIZE]),
+            ),
+        }
+    }
+
+    #[test]
+    fn test_bytes_conversion_resources() {
+        let account = AccountResource {
+            authentication_key: vec![3; 32],
+            sequence_number: 12,
+            guid_creation_num: 4,
+            coin_register_events: event_handle(1, 0),
+            key_rotation_events: event_handle(0, 1),
+            rotation_capability_offer: None,
+            signer_capability_offer: Some(AccountAddress::new([9; ACCOUNT_ADDRESS_SIZE])),
+        };
+        let bytes = account.to_bytes();
+        assert_eq!(bytes, bcs::to_bytes(&account).unwrap());
+        assert_eq!(AccountResource::from_bytes(&bytes).unwrap(), account);
+        assert!(AccountResource::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+        let coin_store = CoinStoreResource {
+            coin: 1_000_000,
+            frozen: false,
+            deposit_events: event_handle(3, 2),
+            withdraw_events: event_handle(2, 3),
+        };
+        let bytes = coin_store.to_bytes();
+        assert_eq!(bytes, bcs::to_bytes(&coin_store).unwrap());
+        assert_eq!(CoinStoreResource::from_bytes(&bytes).unwrap(), coin_store);
+        assert_eq!(
+            CoinStoreResource::from_bytes(&bytes).unwrap().balance(),
+            1_000_000
+        );
+        assert!(CoinStoreResource::from_bytes(&[&bytes[..], &[0]].concat()).is_err());
+    }
+
+    #[cfg(feature = "aptos")]
+    #[test]
+    fn test_decode_aptos_resources() {
+        use crate::aptos_test_utils::wrapper::AptosWrapper;
+        use crate::types::state_value::StateValue;
+
+        let mut aptos_wrapper = AptosWrapper::new(4, 1, 1).unwrap();
+        aptos_wrapper.generate_traffic().unwrap();
+
+        let proof_assets = aptos_wrapper.get_latest_proof_account(0).unwrap();
+        let state_value = StateValue::from_bytes(
+            &bcs::to_bytes(proof_assets.state_value().as_ref().unwrap()).unwrap(),
+        )
+        .unwrap();
+        let aptos_account: aptos_types::account_config::AccountResource =
+            bcs::from_bytes(state_value.data()).unwrap();
+
+        let account = AccountResource::from_bytes(state_value.data()).unwrap();
+        assert_eq!(account.to_bytes(), state_value.data());
+        assert_eq!(account.sequence_number(), aptos_account.sequence_number());
+        assert_eq!(
+            account.authentication_key(),
+            aptos_account.authentication_key()
+        );
+
+        let proof_assets = aptos_wrapper.get_latest_proof_coin_store(0).unwrap();
+        let state_value = StateValue::from_bytes(
+            &bcs::to_bytes(proof_assets.state_value().as_ref().unwrap()).unwrap(),
+        )
+        .unwrap();
+
+        let coin_store = CoinStoreResource::from_bytes(state_value.data()).unwrap();
+        assert_eq!(coin_store.to_bytes(), state_value.data());
+        assert!(coin_store.balance() > 0);
+    }
+}
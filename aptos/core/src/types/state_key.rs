@@ -0,0 +1,355 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! # State Key Module
+//!
+//! This module provides the `StateKey` structure and
+//! associated methods for handling the keys of the state
+//! of the Aptos chain.
+//!
+//! A `StateKey` identifies a leaf of the state sparse Merkle
+//! tree from human-level inputs, such as a Move resource stored
+//! at an address or an item of a table. Its hash is the key
+//! of the leaf, that is the path followed in the tree.
+
+// SPDX-License-Identifier: Apache-2.0
+use crate::crypto::hash::{hash_data, prefixed_sha3, CryptoHash, HashValue};
+use crate::types::type_tag::StructTag;
+use crate::types::utils::write_leb128;
+use crate::types::AccountAddress;
+use bytes::{BufMut, BytesMut};
+use serde::{Deserialize, Serialize};
+use tiny_keccak::{Hasher, Keccak};
+
+/// Tag prefixing the encoding of a `StateKey` pointing to an access path.
+const ACCESS_PATH_TAG: u8 = 0;
+/// Tag prefixing the encoding of a `StateKey` pointing to a table item.
+const TABLE_ITEM_TAG: u8 = 1;
+
+/// Index of the `Path::Resource` variant in the Aptos codebase.
+const RESOURCE_PATH_VARIANT: u8 = 1;
+/// Index of the `Path::ResourceGroup` variant in the Aptos codebase.
+const RESOURCE_GROUP_PATH_VARIANT: u8 = 2;
+
+/// `StateKey` is an enum representing the key of a leaf
+/// in the state of the chain.
+///
+/// Resources and resource groups are stored under an access
+/// path made of an address and a `StructTag`, while table items
+/// are stored under the handle of their table and their BCS
+/// serialized key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StateKey {
+    Resource {
+        address: AccountAddress,
+        struct_tag: StructTag,
+    },
+    ResourceGroup {
+        address: AccountAddress,
+        struct_tag: StructTag,
+    },
+    TableItem {
+        handle: AccountAddress,
+        #[serde(with = "serde_bytes")]
+        key: Vec<u8>,
+    },
+}
+
+impl StateKey {
+    /// Creates a `StateKey` for a Move resource stored at an address.
+    ///
+    /// # Arguments
+    ///
+    /// * `address: AccountAddress` - The address holding the resource.
+    /// * `struct_tag: StructTag` - The type of the resource.
+    ///
+    /// # Returns
+    ///
+    /// A new `StateKey`.
+    pub const fn resource(address: AccountAddress, struct_tag: StructTag) -> Self {
+        Self::Resource {
+            address,
+            struct_tag,
+        }
+    }
+
+    /// Creates a `StateKey` for a resource group stored at an address.
+    ///
+    /// # Arguments
+    ///
+    /// * `address: AccountAddress` - The address holding the resource group.
+    /// * `struct_tag: StructTag` - The tag of the resource group.
+    ///
+    /// # Returns
+    ///
+    /// A new `StateKey`.
+    pub const fn resource_group(address: AccountAddress, struct_tag: StructTag) -> Self {
+        Self::ResourceGroup {
+            address,
+            struct_tag,
+        }
+    }
+
+    /// Creates a `StateKey` for an item of a table.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle: AccountAddress` - The handle of the table.
+    /// * `key: Vec<u8>` - The BCS serialized key of the item in the table.
+    ///
+    /// # Returns
+    ///
+    /// A new `StateKey`.
+    pub const fn table_item(handle: AccountAddress, key: Vec<u8>) -> Self {
+        Self::TableItem { handle, key }
+    }
+
+    /// Returns the index of the variant of the `StateKey`, `0` for a resource,
+    /// `1` for a resource group and `2` for a table item.
+    ///
+    /// # Returns
+    ///
+    /// The index of the variant.
+    pub const fn variant_index(&self) -> u8 {
+        match self {
+            Self::Resource { .. } => 0,
+            Self::ResourceGroup { .. } => 1,
+            Self::TableItem { .. } => 2,
+        }
+    }
+
+    /// Returns the address holding the resource or resource group, or the
+    /// handle of the table.
+    ///
+    /// # Returns
+    ///
+    /// The `AccountAddress` the `StateKey` is stored under.
+    pub const fn address(&self) -> &AccountAddress {
+        match self {
+            Self::Resource { address, .. } | Self::ResourceGroup { address, .. } => address,
+            Self::TableItem { handle, .. } => handle,
+        }
+    }
+
+    /// Returns the Keccak-256 hash of the BCS serialized `StructTag` of a
+    /// resource or resource group, or of the key of a table item.
+    ///
+    /// Along with the address, it identifies the leaf in a way that a
+    /// contract can cheaply recompute, unlike the key of the leaf.
+    ///
+    /// # Returns
+    ///
+    /// The identifier of what is stored under the address.
+    pub fn identifier(&self) -> [u8; 32] {
+        let mut hasher = Keccak::v256();
+        match self {
+            Self::Resource { struct_tag, .. } | Self::ResourceGroup { struct_tag, .. } => {
+                hasher.update(&struct_tag.to_bytes())
+            }
+            Self::TableItem { key, .. } => hasher.update(key),
+        }
+        let mut identifier = [0u8; 32];
+        hasher.finalize(&mut identifier);
+        identifier
+    }
+
+    /// Encodes the `StateKey` as in the Aptos codebase, which is
+    /// the payload hashed to get the key of the leaf.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<u8>` representing the encoded `StateKey`.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = BytesMut::new();
+        match self {
+            Self::Resource {
+                address,
+                struct_tag,
+            } => {
+                bytes.put_u8(ACCESS_PATH_TAG);
+                bytes.put_slice(&write_access_path(
+                    address,
+                    RESOURCE_PATH_VARIANT,
+                    struct_tag,
+                ));
+            }
+            Self::ResourceGroup {
+                address,
+                struct_tag,
+            } => {
+                bytes.put_u8(ACCESS_PATH_TAG);
+                bytes.put_slice(&write_access_path(
+                    address,
+                    RESOURCE_GROUP_PATH_VARIANT,
+                    struct_tag,
+                ));
+            }
+            Self::TableItem { handle, key } => {
+                bytes.put_u8(TABLE_ITEM_TAG);
+                bytes.put_slice(&handle.to_bytes());
+                bytes.put_slice(key);
+            }
+        }
+        bytes.to_vec()
+    }
+}
+
+/// Serializes an access path as its address followed by its
+/// BCS serialized `Path`, itself serialized as a byte vector.
+fn write_access_path(
+    address: &AccountAddress,
+    path_variant: u8,
+    struct_tag: &StructTag,
+) -> Vec<u8> {
+    let mut path = vec![path_variant];
+    path.extend_from_slice(&struct_tag.to_bytes());
+
+    let mut bytes = address.to_bytes();
+    bytes.extend_from_slice(&write_leb128(path.len() as u64));
+    bytes.extend_from_slice(&path);
+    bytes
+}
+
+impl CryptoHash for StateKey {
+    fn hash(&self) -> HashValue {
+        HashValue::new(hash_data(&prefixed_sha3(b"StateKey"), vec![&self.encode()]))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::types::ACCOUNT_ADDRESS_SIZE;
+
+    #[test]
+    fn test_encode_state_key() {
+        let address = AccountAddress::new([1; ACCOUNT_ADDRESS_SIZE]);
+        let struct_tag = StructTag::new(address, "account".into(), "Account".into(), vec![]);
+        let struct_tag_bytes = struct_tag.to_bytes();
+
+        let resource = StateKey::resource(address, struct_tag.clone()).encode();
+        assert_eq!(resource[0], ACCESS_PATH_TAG);
+        assert_eq!(&resource[1..=ACCOUNT_ADDRESS_SIZE], address.to_bytes());
+        // Path length, variant and struct tag
+        assert_eq!(
+            resource[ACCOUNT_ADDRESS_SIZE + 1] as usize,
+            struct_tag_bytes.len() + 1
+        );
+        assert_eq!(resource[ACCOUNT_ADDRESS_SIZE + 2], RESOURCE_PATH_VARIANT);
+        assert_eq!(&resource[ACCOUNT_ADDRESS_SIZE + 3..], struct_tag_bytes);
+
+        let resource_group = StateKey::resource_group(address, struct_tag).encode();
+        assert_eq!(
+            resource_group[ACCOUNT_ADDRESS_SIZE + 2],
+            RESOURCE_GROUP_PATH_VARIANT
+        );
+
+        let table_item = StateKey::table_item(address, vec![4, 5, 6]).encode();
+        assert_eq!(table_item[0], TABLE_ITEM_TAG);
+        assert_eq!(&table_item[1..=ACCOUNT_ADDRESS_SIZE], address.to_bytes());
+        assert_eq!(&table_item[ACCOUNT_ADDRESS_SIZE + 1..], [4, 5, 6]);
+    }
+
+    #[test]
+    fn test_state_key_identifier() {
+        let address = AccountAddress::new([1; ACCOUNT_ADDRESS_SIZE]);
+        let struct_tag = StructTag::new(address, "account".into(), "Account".into(), vec![]);
+        // Keccak-256 of the empty input
+        let empty_hash =
+            hex::decode("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+                .unwrap();
+
+        let resource = StateKey::resource(address, struct_tag.clone());
+        let resource_group = StateKey::resource_group(address, struct_tag);
+        let table_item = StateKey::table_item(address, vec![]);
+
+        assert_eq!(resource.variant_index(), 0);
+        assert_eq!(resource_group.variant_index(), 1);
+        assert_eq!(table_item.variant_index(), 2);
+        for state_key in [&resource, &resource_group, &table_item] {
+            assert_eq!(state_key.address(), &address);
+        }
+        // Resources and resource groups of the same type share their identifier
+        assert_eq!(resource.identifier(), resource_group.identifier());
+        assert_ne!(resource.identifier().as_slice(), empty_hash);
+        assert_eq!(table_item.identifier().as_slice(), empty_hash);
+    }
+
+    #[cfg(feature = "aptos")]
+    #[test]
+    fn test_state_key_hash() {
+        use crate::aptos_test_utils::wrapper::AptosWrapper;
+        use crate::types::resource::ResourceKind;
+        use aptos_crypto::hash::CryptoHash as AptosCryptoHash;
+        use aptos_sdk::move_types::language_storage::StructTag as AptosStructTag;
+        use aptos_types::account_address::AccountAddress as AptosAccountAddress;
+        use aptos_types::state_store::state_key::StateKey as AptosStateKey;
+        use aptos_types::state_store::table::TableHandle;
+        use std::str::FromStr;
+
+        let aptos_address = AptosAccountAddress::new([3; ACCOUNT_ADDRESS_SIZE]);
+        let address = AccountAddress::new([3; ACCOUNT_ADDRESS_SIZE]);
+
+        for aptos_struct_tag in [
+            "0x1::account::Account",
+            "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>",
+            "0x1::object::ObjectGroup",
+        ] {
+            let aptos_struct_tag = AptosStructTag::from_str(aptos_struct_tag).unwrap();
+            let struct_tag =
+                StructTag::from_bytes(&bcs::to_bytes(&aptos_struct_tag).unwrap()).unwrap();
+
+            assert_eq!(
+                StateKey::resource(address, struct_tag.clone())
+                    .hash()
+                    .to_vec(),
+                AptosCryptoHash::hash(
+                    &AptosStateKey::resource(&aptos_address, &aptos_struct_tag).unwrap()
+                )
+                .to_vec()
+            );
+            assert_eq!(
+                StateKey::resource_group(address, struct_tag)
+                    .hash()
+                    .to_vec(),
+                AptosCryptoHash::hash(&AptosStateKey::resource_group(
+                    &aptos_address,
+                    &aptos_struct_tag
+                ))
+                .to_vec()
+            );
+        }
+
+        let key = bcs::to_bytes(&42u64).unwrap();
+        assert_eq!(
+            StateKey::table_item(address, key.clone()).hash().to_vec(),
+            AptosCryptoHash::hash(&AptosStateKey::table_item(
+                &TableHandle(aptos_address),
+                &key
+            ))
+            .to_vec()
+        );
+
+        // Keys derived from human-level inputs match the ones of the proofs
+        let mut aptos_wrapper = AptosWrapper::new(4, 1, 1).unwrap();
+        aptos_wrapper.generate_traffic().unwrap();
+
+        let address =
+            AccountAddress::from_bytes(aptos_wrapper.accounts()[0].address().as_ref()).unwrap();
+        for (resource_kind, proof_assets) in [
+            (
+                ResourceKind::Account,
+                aptos_wrapper.get_latest_proof_account(0).unwrap(),
+            ),
+            (
+                ResourceKind::AptosCoinStore,
+                aptos_wrapper.get_latest_proof_coin_store(0).unwrap(),
+            ),
+        ] {
+            assert_eq!(
+                resource_kind.state_key(address).hash().to_vec(),
+                proof_assets.key().to_vec()
+            );
+        }
+    }
+}
@@ -0,0 +1,186 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! # State Value Module
+//!
+//! This module provides the `StateValue` structure and
+//! associated methods for handling the values stored in the
+//! state of the Aptos chain.
+//!
+//! A `StateValue` holds the raw bytes of a leaf of the state
+//! sparse Merkle tree, such as a BCS serialized Move resource,
+//! along with its optional storage metadata. Its hash is the
+//! value hash committed in the leaf.
+
+// SPDX-License-Identifier: Apache-2.0
+use crate::crypto::hash::{hash_data, prefixed_sha3, CryptoHash, HashValue};
+use crate::types::codec;
+use crate::types::error::TypesError;
+use serde::{Deserialize, Serialize};
+
+/// `StateValueMetadata` is an enum representing the storage
+/// metadata attached to a `StateValue`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StateValueMetadata {
+    V0 {
+        deposit: u64,
+        creation_time_usecs: u64,
+    },
+    V1 {
+        slot_deposit: u64,
+        bytes_deposit: u64,
+        creation_time_usecs: u64,
+    },
+}
+
+impl StateValueMetadata {
+    /// Converts the `StateValueMetadata` to a byte vector.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<u8>` representing the `StateValueMetadata`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        codec::to_bytes(self)
+    }
+
+    /// Creates a `StateValueMetadata` from a byte slice.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes: &[u8]` - A byte slice from which to create the `StateValueMetadata`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the `StateValueMetadata` could
+    /// be successfully created, and `Err` otherwise.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
+        codec::from_bytes("StateValueMetadata", bytes)
+    }
+}
+
+/// `StateValue` is an enum representing the value of a leaf
+/// in the state of the chain, with or without metadata.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename = "StateValue")]
+pub enum StateValue {
+    V0(#[serde(with = "serde_bytes")] Vec<u8>),
+    WithMetadata {
+        #[serde(with = "serde_bytes")]
+        data: Vec<u8>,
+        metadata: StateValueMetadata,
+    },
+}
+
+impl StateValue {
+    /// Returns the raw data of the `StateValue`.
+    ///
+    /// # Returns
+    ///
+    /// The data as a byte slice, such as a BCS serialized Move resource.
+    pub fn data(&self) -> &[u8] {
+        match self {
+            Self::V0(data) | Self::WithMetadata { data, .. } => data,
+        }
+    }
+
+    /// Returns the metadata of the `StateValue`, if any.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing the `StateValueMetadata`.
+    pub const fn metadata(&self) -> Option<&StateValueMetadata> {
+        match self {
+            Self::V0(_) => None,
+            Self::WithMetadata { metadata, .. } => Some(metadata),
+        }
+    }
+
+    /// Converts the `StateValue` to a byte vector.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<u8>` representing the `StateValue`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        codec::to_bytes(self)
+    }
+
+    /// Creates a `StateValue` from a byte slice.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes: &[u8]` - A byte slice from which to create the `StateValue`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the `StateValue` could
+    /// be successfully created, and `Err` otherwise.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
+        codec::from_bytes("StateValue", bytes)
+    }
+}
+
+impl CryptoHash for StateValue {
+    fn hash(&self) -> HashValue {
+        HashValue::new(hash_data(
+            &prefixed_sha3(b"StateValue"),
+            vec![&self.to_bytes()],
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bytes_conversion_state_value() {
+        let state_values = [
+            StateValue::V0(vec![1, 2, 3]),
+            StateValue::WithMetadata {
+                data: vec![4; 200],
+                metadata: StateValueMetadata::V0 {
+                    deposit: 50,
+                    creation_time_usecs: 1_000,
+                },
+            },
+            StateValue::WithMetadata {
+                data: vec![],
+                metadata: StateValueMetadata::V1 {
+                    slot_deposit: 40,
+                    bytes_deposit: 10,
+                    creation_time_usecs: 2_000,
+                },
+            },
+        ];
+
+        for state_value in state_values {
+            let bytes = state_value.to_bytes();
+            assert_eq!(bytes, bcs::to_bytes(&state_value).unwrap());
+            assert_eq!(StateValue::from_bytes(&bytes).unwrap(), state_value);
+            assert!(StateValue::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        }
+    }
+
+    #[cfg(feature = "aptos")]
+    #[test]
+    fn test_state_value_hash() {
+        use crate::aptos_test_utils::wrapper::AptosWrapper;
+        use aptos_crypto::hash::CryptoHash as AptosCryptoHash;
+
+        let mut aptos_wrapper = AptosWrapper::new(4, 1, 1).unwrap();
+        aptos_wrapper.generate_traffic().unwrap();
+
+        let proof_assets = aptos_wrapper.get_latest_proof_account(0).unwrap();
+        let aptos_state_value = proof_assets.state_value().as_ref().unwrap();
+
+        let bytes = bcs::to_bytes(aptos_state_value).unwrap();
+        let state_value = StateValue::from_bytes(&bytes).unwrap();
+
+        assert_eq!(state_value.to_bytes(), bytes);
+        assert_eq!(state_value.data(), aptos_state_value.bytes().as_ref());
+        assert_eq!(
+            state_value.hash().to_vec(),
+            AptosCryptoHash::hash(aptos_state_value).to_vec()
+        );
+    }
+}
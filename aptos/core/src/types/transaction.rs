@@ -12,10 +12,11 @@
 
 // SPDX-License-Identifier: Apache-2.0
 use crate::crypto::hash::{hash_data, prefixed_sha3, CryptoHash, HashValue, HASH_LENGTH};
-use crate::serde_error;
+use crate::types::codec;
 use crate::types::error::TypesError;
 use crate::types::utils::{ENUM_VARIANT_LEN, U64_SIZE};
-use bytes::{Buf, BufMut, BytesMut};
+use crate::types::AccountAddress;
+use getset::Getters;
 use serde::{Deserialize, Serialize};
 
 /// `TransactionInfo` contains Information related to how
@@ -40,20 +41,36 @@ impl TransactionInfo {
         }
     }
 
+    /// Returns the execution status of the `TransactionInfo`.
+    ///
+    /// # Returns
+    ///
+    /// The `ExecutionStatus` of the transaction.
+    pub const fn status(&self) -> &ExecutionStatus {
+        match self {
+            TransactionInfo::V0(info) => &info.status,
+        }
+    }
+
+    /// Returns the root hash of the event accumulator of the `TransactionInfo`.
+    ///
+    /// # Returns
+    ///
+    /// The root hash of the accumulator storing all events
+    /// emitted by the transaction.
+    pub const fn event_root_hash(&self) -> HashValue {
+        match self {
+            TransactionInfo::V0(info) => info.event_root_hash,
+        }
+    }
+
     /// Converts the `TransactionInfo` to a byte vector.
     ///
     /// # Returns
     ///
     /// A `Vec<u8>` representing the `TransactionInfo`.
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = BytesMut::new();
-        match self {
-            TransactionInfo::V0(info) => {
-                bytes.put_u8(0);
-                bytes.put_slice(&info.to_bytes());
-            }
-        }
-        bytes.to_vec()
+        codec::to_bytes(self)
     }
 
     /// Creates a `TransactionInfo` from a byte slice.
@@ -67,28 +84,7 @@ impl TransactionInfo {
     /// A `Result` which is `Ok` if the `TransactionInfo`
     /// could be successfully created, and `Err` otherwise.
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
-        let mut buf = BytesMut::from(bytes);
-        let tx_info = match buf.get_u8() {
-            0 => {
-                let tx_info_v0 = TransactionInfoV0::from_bytes(
-                    buf.chunk().get(..TRANSACTION_INFO_V0_SIZE).ok_or_else(|| {
-                        serde_error!("TransactionInfo", "Not enough data for TransactionInfoV0")
-                    })?,
-                )?;
-                buf.advance(TRANSACTION_INFO_V0_SIZE);
-                TransactionInfo::V0(tx_info_v0)
-            }
-            _ => return Err(serde_error!("TransactionInfo", "Invalid variant")),
-        };
-
-        if buf.remaining() != 0 {
-            return Err(serde_error!(
-                "TransactionInfo",
-                "Unexpected data after completing deserialization"
-            ));
-        }
-
-        Ok(tx_info)
+        codec::from_bytes("TransactionInfo", bytes)
     }
 }
 
@@ -101,7 +97,9 @@ impl CryptoHash for TransactionInfo {
     }
 }
 
-/// Length in bytes of the serialized `TransactionInfoV0`.
+/// Length in bytes of the serialized `TransactionInfoV0` when
+/// it carries a state checkpoint hash, as for the last transaction
+/// of a block, which is always successful.
 pub const TRANSACTION_INFO_V0_SIZE: usize =
     U64_SIZE + 4 * HASH_LENGTH + 2 * ENUM_VARIANT_LEN + EXECUTION_STATUS_SIZE;
 
@@ -136,185 +134,182 @@ pub struct TransactionInfoV0 {
     state_cemetery_hash: Option<HashValue>,
 }
 
-impl TransactionInfoV0 {
-    /// Converts the `TransactionInfoV0` to a byte vector.
+/// Length in bytes of the serialized `ExecutionStatus::Success`, the status
+/// of the transactions carrying a state checkpoint.
+pub const EXECUTION_STATUS_SIZE: usize = ENUM_VARIANT_LEN;
+
+/// The status of VM execution, which contains more detailed failure info.
+///
+/// Transactions carrying a state checkpoint are always successful, but
+/// other transactions included in a block may have failed.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ExecutionStatus {
+    Success,
+    OutOfGas,
+    MoveAbort {
+        location: AbortLocation,
+        code: u64,
+        info: Option<AbortInfo>,
+    },
+    ExecutionFailure {
+        location: AbortLocation,
+        function: u16,
+        code_offset: u16,
+    },
+    /// Any other error, along with its optional VM status code.
+    MiscellaneousError(Option<u64>),
+}
+
+impl ExecutionStatus {
+    /// Returns whether the transaction was successfully executed.
     ///
     /// # Returns
     ///
-    /// A `Vec<u8>` representing the `TransactionInfoV0
-    pub(crate) fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = BytesMut::new();
-        bytes.put_u64_le(self.gas_used);
-        bytes.put_slice(&self.status.to_bytes());
-        bytes.put_slice(self.transaction_hash.as_ref());
-        bytes.put_slice(self.event_root_hash.as_ref());
-        bytes.put_slice(self.state_change_hash.as_ref());
-        match &self.state_checkpoint_hash {
-            Some(hash) => {
-                bytes.put_u8(1);
-                bytes.put_slice(hash.as_ref());
-            }
-            None => bytes.put_u8(0),
+    /// `true` if the status is `ExecutionStatus::Success`, `false` otherwise.
+    pub const fn is_success(&self) -> bool {
+        matches!(self, ExecutionStatus::Success)
+    }
+
+    /// Returns the index of the variant of the `ExecutionStatus`, as
+    /// serialized by BCS.
+    ///
+    /// # Returns
+    ///
+    /// `0` for `Success`, `1` for `OutOfGas`, `2` for `MoveAbort`, `3` for
+    /// `ExecutionFailure` and `4` for `MiscellaneousError`.
+    pub const fn variant_index(&self) -> u8 {
+        match self {
+            ExecutionStatus::Success => 0,
+            ExecutionStatus::OutOfGas => 1,
+            ExecutionStatus::MoveAbort { .. } => 2,
+            ExecutionStatus::ExecutionFailure { .. } => 3,
+            ExecutionStatus::MiscellaneousError(_) => 4,
         }
-        match &self.state_cemetery_hash {
-            Some(hash) => {
-                bytes.put_u8(1);
-                bytes.put_slice(hash.as_ref());
-            }
-            None => bytes.put_u8(0),
+    }
+
+    /// Returns the abort code of the transaction.
+    ///
+    /// # Returns
+    ///
+    /// The code of a `MoveAbort`, `None` for any other status.
+    pub const fn abort_code(&self) -> Option<u64> {
+        match self {
+            ExecutionStatus::MoveAbort { code, .. } => Some(*code),
+            _ => None,
         }
-        bytes.to_vec()
     }
 
-    /// Creates a `TransactionInfoV0` from a byte slice.
+    /// Converts the `ExecutionStatus` to a byte vector.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<u8>` representing the `ExecutionStatus`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        codec::to_bytes(self)
+    }
+
+    /// Creates a `ExecutionStatus` from a byte slice.
     ///
     /// # Arguments
     ///
-    /// * `bytes: &[u8]` - A byte slice from which to create the `TransactionInfoV0`.
+    /// * `bytes: &[u8]` - A byte slice from which to create the `ExecutionStatus`.
     ///
     /// # Returns
     ///
-    /// A `Result` which is `Ok` if the `TransactionInfoV0`
+    /// A `Result` which is `Ok` if the `ExecutionStatus`
     /// could be successfully created, and `Err` otherwise.
-    pub(crate) fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
-        let mut buf = BytesMut::from(bytes);
-
-        let gas_used = buf.get_u64_le();
-
-        let status =
-            ExecutionStatus::from_bytes(buf.chunk().get(..EXECUTION_STATUS_SIZE).ok_or_else(
-                || serde_error!("TransactionInfo0", "Not enough data for ExecutionStatus"),
-            )?)?;
-        buf.advance(EXECUTION_STATUS_SIZE);
-
-        let transaction_hash =
-            HashValue::from_slice(buf.chunk().get(..HASH_LENGTH).ok_or_else(|| {
-                serde_error!("TransactionInfo0", "Not enough data for transaction hash")
-            })?)
-            .unwrap();
-        buf.advance(HASH_LENGTH);
-
-        let event_root_hash =
-            HashValue::from_slice(buf.chunk().get(..HASH_LENGTH).ok_or_else(|| {
-                serde_error!("TransactionInfo0", "Not enough data for event root hash")
-            })?)
-            .unwrap();
-        buf.advance(HASH_LENGTH);
-
-        let state_change_hash =
-            HashValue::from_slice(buf.chunk().get(..HASH_LENGTH).ok_or_else(|| {
-                serde_error!("TransactionInfo0", "Not enough data for statechange hash")
-            })?)
-            .unwrap();
-        buf.advance(HASH_LENGTH);
-
-        let state_checkpoint_hash = if buf.get_u8() == 1 {
-            Some(
-                HashValue::from_slice(buf.chunk().get(..HASH_LENGTH).ok_or_else(|| {
-                    serde_error!(
-                        "TransactionInfo0",
-                        "Not enough data for state checkpoint hash"
-                    )
-                })?)
-                .unwrap(),
-            )
-        } else {
-            None
-        };
-        buf.advance(HASH_LENGTH);
-
-        let state_cemetery_hash = if buf.get_u8() == 1 {
-            let hash_value =
-                HashValue::from_slice(buf.chunk().get(..HASH_LENGTH).ok_or_else(|| {
-                    serde_error!(
-                        "TransactionInfo0",
-                        "Not enough data for state cemetery hash"
-                    )
-                })?)
-                .unwrap();
-            buf.advance(HASH_LENGTH);
-            Some(hash_value)
-        } else {
-            None
-        };
-
-        if buf.remaining() != 0 {
-            return Err(serde_error!(
-                "TransactionInfoV0",
-                "Unexpected data after completing deserialization"
-            ));
-        }
-
-        Ok(Self {
-            gas_used,
-            status,
-            transaction_hash,
-            event_root_hash,
-            state_change_hash,
-            state_checkpoint_hash,
-            state_cemetery_hash,
-        })
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
+        codec::from_bytes("ExecutionStatus", bytes)
     }
 }
 
-/// Length in bytes of the serialized `ExecutionStatus`. We only
-/// expect to receive successful transactions included  in the block to
-/// prove account inclusion.
-pub const EXECUTION_STATUS_SIZE: usize = ENUM_VARIANT_LEN;
-
-/// The status of VM execution, which contains more detailed failure info.
-/// We only expect to handle successful transactions in the light client,
-/// as we use it for state checkpoint.
+/// `AbortLocation` represents where a Move abort occurred,
+/// either in a published module or in a script.
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub enum ExecutionStatus {
-    Success,
+pub enum AbortLocation {
+    Module(ModuleId),
+    Script,
 }
 
-impl ExecutionStatus {
-    /// Converts the `ExecutionStatus` to a byte vector.
+impl AbortLocation {
+    /// Converts the `AbortLocation` to a byte vector.
     ///
     /// # Returns
     ///
-    /// A `Vec<u8>` representing the `ExecutionStatus
-    pub(crate) fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = BytesMut::new();
-        match self {
-            ExecutionStatus::Success => {
-                bytes.put_u8(0);
-            }
-        }
-        bytes.to_vec()
+    /// A `Vec<u8>` representing the `AbortLocation`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        codec::to_bytes(self)
     }
-    /// Creates a `ExecutionStatus` from a byte slice.
+}
+
+/// `ModuleId` identifies a Move module by the address
+/// it is published at and its name.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct ModuleId {
+    address: AccountAddress,
+    name: String,
+}
+
+impl ModuleId {
+    /// Creates a new `ModuleId`.
     ///
     /// # Arguments
     ///
-    /// * `bytes: &[u8]` - A byte slice from which to create the `ExecutionStatus`.
+    /// * `address: AccountAddress` - The address the module is published at.
+    /// * `name: String` - The name of the module.
     ///
     /// # Returns
     ///
-    /// A `Result` which is `Ok` if the `ExecutionStatus`
-    /// could be successfully created, and `Err` otherwise.
-    pub(crate) fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
-        let mut buf = BytesMut::from(bytes);
-        let execution_status = match buf.get_u8() {
-            0 => ExecutionStatus::Success,
-            _ => {
-                return Err(serde_error!(
-                    "ExecutionStatus",
-                    "Invalid variant, expected only success"
-                ))
-            }
-        };
-
-        if buf.remaining() != 0 {
-            return Err(serde_error!(
-                "ExecutionStatus",
-                "Unexpected data after completing deserialization"
-            ));
+    /// A new `ModuleId`.
+    pub const fn new(address: AccountAddress, name: String) -> Self {
+        Self { address, name }
+    }
+
+    /// Converts the `ModuleId` to a byte vector.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<u8>` representing the `ModuleId`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        codec::to_bytes(self)
+    }
+}
+
+/// `AbortInfo` contains the human readable reason of a Move
+/// abort, as found in the error map of the aborting module.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct AbortInfo {
+    reason_name: String,
+    description: String,
+}
+
+impl AbortInfo {
+    /// Creates a new `AbortInfo`.
+    ///
+    /// # Arguments
+    ///
+    /// * `reason_name: String` - The name of the abort reason.
+    /// * `description: String` - The description of the abort reason.
+    ///
+    /// # Returns
+    ///
+    /// A new `AbortInfo`.
+    pub const fn new(reason_name: String, description: String) -> Self {
+        Self {
+            reason_name,
+            description,
         }
+    }
 
-        Ok(execution_status)
+    /// Converts the `AbortInfo` to a byte vector.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<u8>` representing the `AbortInfo`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        codec::to_bytes(self)
     }
 }
 
@@ -322,19 +317,127 @@ impl ExecutionStatus {
 mod test {
     #[test]
     fn test_bytes_conversion_execution_status() {
-        use crate::types::transaction::ExecutionStatus;
-
-        // Test ExecutionStatus::Success
-        let execution_status = ExecutionStatus::Success;
-        let execution_status_ser_bcs = bcs::to_bytes(&execution_status).unwrap();
-        let execution_status_from_bcs =
-            ExecutionStatus::from_bytes(&execution_status_ser_bcs).unwrap();
-
-        assert_eq!(execution_status, execution_status_from_bcs);
+        use crate::types::transaction::{AbortInfo, AbortLocation, ExecutionStatus, ModuleId};
+        use crate::types::AccountAddress;
+
+        let module_location = AbortLocation::Module(ModuleId::new(
+            AccountAddress::new([1; 32]),
+            "coin".to_string(),
+        ));
+        let execution_statuses = [
+            ExecutionStatus::Success,
+            ExecutionStatus::OutOfGas,
+            ExecutionStatus::MoveAbort {
+                location: module_location.clone(),
+                code: 65542,
+                info: Some(AbortInfo::new(
+                    "EINSUFFICIENT_BALANCE".to_string(),
+                    "Not enough coins to complete transaction".to_string(),
+                )),
+            },
+            ExecutionStatus::MoveAbort {
+                location: AbortLocation::Script,
+                code: 1,
+                info: None,
+            },
+            ExecutionStatus::ExecutionFailure {
+                location: module_location,
+                function: 3,
+                code_offset: 42,
+            },
+            ExecutionStatus::MiscellaneousError(Some(4016)),
+            ExecutionStatus::MiscellaneousError(None),
+        ];
+
+        for execution_status in execution_statuses {
+            let execution_status_ser_bcs = bcs::to_bytes(&execution_status).unwrap();
+            let execution_status_from_bcs =
+                ExecutionStatus::from_bytes(&execution_status_ser_bcs).unwrap();
+
+            assert_eq!(execution_status, execution_status_from_bcs);
+            assert_eq!(
+                execution_status_ser_bcs[0],
+                execution_status.variant_index()
+            );
+
+            let execution_status_to_bytes = execution_status_from_bcs.to_bytes();
+
+            assert_eq!(execution_status_ser_bcs, execution_status_to_bytes);
+            assert!(ExecutionStatus::from_bytes(
+                &execution_status_ser_bcs[..execution_status_ser_bcs.len() - 1]
+            )
+            .is_err());
+        }
+    }
 
-        let execution_status_to_bytes = execution_status_from_bcs.to_bytes();
+    #[cfg(feature = "aptos")]
+    #[test]
+    fn test_bytes_conversion_aptos_execution_status() {
+        use crate::types::transaction::ExecutionStatus;
+        use aptos_sdk::move_types::identifier::Identifier;
+        use aptos_sdk::move_types::language_storage::ModuleId;
+        use aptos_sdk::move_types::vm_status::{AbortLocation, StatusCode};
+        use aptos_types::transaction::{AbortInfo, ExecutionStatus as AptosExecutionStatus};
+
+        let module_location = AbortLocation::Module(ModuleId::new(
+            aptos_types::account_address::AccountAddress::ONE,
+            Identifier::new("coin").unwrap(),
+        ));
+        let aptos_execution_statuses = [
+            AptosExecutionStatus::Success,
+            AptosExecutionStatus::OutOfGas,
+            AptosExecutionStatus::MoveAbort {
+                location: module_location.clone(),
+                code: 65542,
+                info: Some(AbortInfo {
+                    reason_name: "EINSUFFICIENT_BALANCE".to_string(),
+                    description: "Not enough coins to complete transaction".to_string(),
+                }),
+            },
+            AptosExecutionStatus::ExecutionFailure {
+                location: module_location,
+                function: 3,
+                code_offset: 42,
+            },
+            AptosExecutionStatus::MiscellaneousError(Some(StatusCode::TYPE_MISMATCH)),
+        ];
+
+        for aptos_execution_status in aptos_execution_statuses {
+            let aptos_bytes = bcs::to_bytes(&aptos_execution_status).unwrap();
+            let execution_status = ExecutionStatus::from_bytes(&aptos_bytes).unwrap();
+
+            assert_eq!(
+                execution_status.is_success(),
+                aptos_execution_status.is_success()
+            );
+            assert_eq!(aptos_bytes, execution_status.to_bytes());
+        }
+    }
 
-        assert_eq!(execution_status_ser_bcs, execution_status_to_bytes);
+    #[test]
+    fn test_bytes_conversion_transaction_info_without_checkpoint() {
+        use crate::crypto::hash::HashValue;
+        use crate::types::transaction::{ExecutionStatus, TransactionInfo, TransactionInfoV0};
+
+        let transaction_info = TransactionInfo::V0(TransactionInfoV0 {
+            gas_used: 42,
+            status: ExecutionStatus::Success,
+            transaction_hash: HashValue::new([1; 32]),
+            event_root_hash: HashValue::new([2; 32]),
+            state_change_hash: HashValue::new([3; 32]),
+            state_checkpoint_hash: None,
+            state_cemetery_hash: None,
+        });
+
+        let transaction_info_bcs = bcs::to_bytes(&transaction_info).unwrap();
+        let transaction_info_from_bcs = TransactionInfo::from_bytes(&transaction_info_bcs).unwrap();
+
+        assert_eq!(transaction_info, transaction_info_from_bcs);
+        assert_eq!(transaction_info_bcs, transaction_info_from_bcs.to_bytes());
+        assert_eq!(
+            transaction_info_from_bcs.event_root_hash(),
+            HashValue::new([2; 32])
+        );
     }
 
     #[cfg(feature = "aptos")]
@@ -8,17 +8,15 @@
 //! It can be leveraged alongside an`EpochChangeProof`
 //! to "ratchet" our view forward to a newer state.
 
-// SPDX-License-Identifier: Apache-2.0
 use crate::crypto::hash::{hash_data, prefixed_sha3, CryptoHash, HashValue};
-use crate::serde_error;
+use crate::types::codec;
 use crate::types::epoch_state::EpochState;
 use crate::types::error::TypesError;
 use crate::types::ledger_info::{LedgerInfo, LedgerInfoWithSignatures};
-use crate::types::utils::{read_leb128, write_leb128};
-use crate::types::waypoint::{Waypoint, WAYPOINT_SIZE};
+use crate::types::validator::PreparedValidatorVerifier;
+use crate::types::waypoint::Waypoint;
 use crate::types::Version;
 use anyhow::{bail, ensure, format_err};
-use bytes::{Buf, BufMut, BytesMut};
 use serde::{Deserialize, Serialize};
 
 /// `TrustedState` keeps track of our light clients' latest,
@@ -69,12 +67,9 @@ impl TrustedState {
     /// # Returns
     ///
     /// The waypoint of the `TrustedState`.
-    pub fn waypoint(&self) -> Waypoint {
+    pub const fn waypoint(&self) -> Waypoint {
         match self {
-            Self::EpochWaypoint(_waypoint) => {
-                unimplemented!("This LC doesn't support epoch waypoints")
-            }
-            Self::EpochState { waypoint, .. } => *waypoint,
+            Self::EpochWaypoint(waypoint) | Self::EpochState { waypoint, .. } => *waypoint,
         }
     }
     /// Checks if epoch change verification is required. This
@@ -87,11 +82,10 @@ impl TrustedState {
     /// # Returns
     ///
     /// A boolean indicating whether epoch change verification is required.
-    fn epoch_change_verification_required(&self, epoch: u64) -> bool {
+    const fn epoch_change_verification_required(&self, epoch: u64) -> bool {
         match self {
-            Self::EpochWaypoint(_waypoint) => {
-                unimplemented!("This LC doesn't support epoch waypoints")
-            }
+            // A waypoint always needs an epoch change ledger info to be verified against.
+            Self::EpochWaypoint(_) => true,
             Self::EpochState { epoch_state, .. } => {
                 epoch_state.epoch_change_verification_required(epoch)
             }
@@ -110,39 +104,55 @@ impl TrustedState {
     /// A boolean indicating whether the ledger info is stale.
     fn is_ledger_info_stale(&self, ledger_info: &LedgerInfo) -> bool {
         match self {
-            Self::EpochWaypoint(_waypoint) => {
-                unimplemented!("This LC doesn't support epoch waypoints")
-            }
+            Self::EpochWaypoint(waypoint) => ledger_info.version() < waypoint.version(),
             Self::EpochState { epoch_state, .. } => epoch_state.is_ledger_info_stale(ledger_info),
         }
     }
     /// Verifies a ledger info with signatures against  the current trusted state.
-    /// This will verify the signatures and the epoch number.
+    /// This will verify the signatures and the epoch number, or check the ledger
+    /// info against the waypoint if the trusted state is an epoch waypoint.
+    ///
+    /// When a `PreparedValidatorVerifier` is given, it is used
+    /// to verify the signatures in place of the validator set.
     ///
     /// # Arguments
     ///
     /// * `ledger_info: &LedgerInfoWithSignatures` - The
     ///   ledger info with signatures to verify.
+    /// * `prepared_verifier: Option<&PreparedValidatorVerifier>` - The
+    ///   prepared validator set of the trusted epoch, if any.
     ///
     /// # Returns
     ///
     /// A `Result` which is `Ok` if the ledger info with
     /// signatures is valid, and `Err` otherwise.
-    fn verify(&self, ledger_info: &LedgerInfoWithSignatures) -> anyhow::Result<()> {
-        match self {
-            Self::EpochWaypoint(_waypoint) => {
-                unimplemented!("This LC doesn't support epoch waypoints")
+    fn verify(
+        &self,
+        ledger_info: &LedgerInfoWithSignatures,
+        prepared_verifier: Option<&PreparedValidatorVerifier>,
+    ) -> anyhow::Result<()> {
+        match (self, prepared_verifier) {
+            (Self::EpochWaypoint(waypoint), _) => waypoint.verify(ledger_info.ledger_info()),
+            (Self::EpochState { epoch_state, .. }, Some(prepared_verifier)) => {
+                epoch_state.verify_prepared(ledger_info, prepared_verifier)
             }
-            Self::EpochState { epoch_state, .. } => epoch_state.verify(ledger_info),
+            (Self::EpochState { epoch_state, .. }, None) => epoch_state.verify(ledger_info),
         }
     }
 
     /// The main LC method that verifies and ratchets the
     /// trusted state. Expects to receive an `EpochChangeProof`
-    /// containing one `LedgerInfoWithSignatures` that
-    /// represents an epoch transition from
-    /// trusted_state.epoch -> trusted_state.epoch +1, and
-    /// verifies it.
+    /// containing a chain of `LedgerInfoWithSignatures`, each
+    /// one representing an epoch transition from
+    /// epoch -> epoch + 1, starting at trusted_state.epoch.
+    /// Every transition is verified with the validator set of
+    /// the epoch it ends, and the returned state is the one
+    /// started by the last `LedgerInfoWithSignatures`.
+    ///
+    /// When the trusted state is an epoch waypoint, the first
+    /// `LedgerInfoWithSignatures` is instead checked against the
+    /// waypoint, and the trusted state transitions into the
+    /// epoch it starts.
     ///
     /// # Arguments
     ///
@@ -155,6 +165,28 @@ impl TrustedState {
     pub fn verify_and_ratchet_inner<'a>(
         &self,
         epoch_change_proof: &'a EpochChangeProof,
+    ) -> anyhow::Result<TrustedStateChange<'a>> {
+        self.verify_and_ratchet_prepared(epoch_change_proof, None)
+    }
+
+    /// Verifies and ratchets the trusted state as
+    /// [`TrustedState::verify_and_ratchet_inner`], verifying the
+    /// first epoch transition with a `PreparedValidatorVerifier`
+    /// of the trusted epoch when one is given.
+    ///
+    /// # Arguments
+    ///
+    /// * `epoch_change_proof: &'a EpochChangeProof` - The epoch change proof to verify.
+    /// * `prepared_verifier: Option<&PreparedValidatorVerifier>` - The
+    ///   prepared validator set of the trusted epoch, if any.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the trusted state could be successfully verified and ratcheted, and `Err` otherwise.
+    pub fn verify_and_ratchet_prepared<'a>(
+        &self,
+        epoch_change_proof: &'a EpochChangeProof,
+        prepared_verifier: Option<&PreparedValidatorVerifier>,
     ) -> anyhow::Result<TrustedStateChange<'a>> {
         // Abort early if the response is stale.
         let curr_version = self.version();
@@ -171,7 +203,7 @@ impl TrustedState {
 
         if self.epoch_change_verification_required(latest_li.ledger_info().next_block_epoch()) {
             // Verify the EpochChangeProof to move us into the latest epoch.
-            let epoch_change_li = epoch_change_proof.verify(self)?;
+            let epoch_change_li = epoch_change_proof.verify_prepared(self, prepared_verifier)?;
             let new_epoch_state = epoch_change_li
                 .ledger_info()
                 .next_epoch_state()
@@ -208,24 +240,7 @@ impl TrustedState {
     ///
     /// A `Vec<u8>` representing the `TrustedState`.
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = BytesMut::new();
-
-        match self {
-            TrustedState::EpochWaypoint(waypoint) => {
-                bytes.put_u8(0); // 0 indicates EpochWaypoint
-                bytes.put_slice(&waypoint.to_bytes());
-            }
-            TrustedState::EpochState {
-                waypoint,
-                epoch_state,
-            } => {
-                bytes.put_u8(1); // 1 indicates EpochState
-                bytes.put_slice(&waypoint.to_bytes());
-                bytes.put_slice(&epoch_state.to_bytes());
-            }
-        }
-
-        bytes.to_vec()
+        codec::to_bytes(self)
     }
 
     /// Creates a `TrustedState` from a byte slice.
@@ -238,45 +253,8 @@ impl TrustedState {
     ///
     /// A `Result` which is `Ok` if the `TrustedState` could
     /// be successfully created, and `Err` otherwise.
-    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, TypesError> {
-        let epoch_state_size = bytes.len() - WAYPOINT_SIZE - 1;
-
-        let trusted_state = match bytes.get_u8() {
-            0 => {
-                let waypoint =
-                    Waypoint::from_bytes(bytes.chunk().get(..WAYPOINT_SIZE).ok_or_else(|| {
-                        serde_error!("TrustedState", "Not enough data for Waypoint")
-                    })?)?;
-                TrustedState::EpochWaypoint(waypoint)
-            }
-            1 => {
-                let waypoint =
-                    Waypoint::from_bytes(bytes.chunk().get(..WAYPOINT_SIZE).ok_or_else(|| {
-                        serde_error!("TrustedState", "Not enough data for Waypoint")
-                    })?)?;
-                bytes.advance(WAYPOINT_SIZE);
-
-                let epoch_state =
-                    EpochState::from_bytes(bytes.chunk().get(..epoch_state_size).ok_or_else(
-                        || serde_error!("TrustedState", "Not enough data for epoch state"),
-                    )?)?;
-                bytes.advance(epoch_state_size);
-                TrustedState::EpochState {
-                    waypoint,
-                    epoch_state,
-                }
-            }
-            _ => return Err(serde_error!("TrustedState", "Unknown variant")),
-        };
-
-        if bytes.remaining() != 0 {
-            return Err(serde_error!(
-                "LedgerInfo",
-                "Unexpected data after completing deserialization"
-            ));
-        }
-
-        Ok(trusted_state)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
+        codec::from_bytes("TrustedState", bytes)
     }
 }
 
@@ -334,6 +312,26 @@ impl EpochChangeProof {
     ///
     /// The latest [`LedgerInfoWithSignatures`] in the [`EpochChangeProof`].
     pub fn verify(&self, verifier: &TrustedState) -> anyhow::Result<&LedgerInfoWithSignatures> {
+        self.verify_prepared(verifier, None)
+    }
+
+    /// Verify the proof as [`EpochChangeProof::verify`], using a
+    /// `PreparedValidatorVerifier` of the trusted epoch to verify the
+    /// first epoch change when one is given.
+    ///
+    /// # Arguments
+    ///
+    /// * `verifier` - The [`TrustedState`] to verify the [`EpochChangeProof`] against.
+    /// * `prepared_verifier` - The prepared validator set of the trusted epoch, if any.
+    ///
+    /// # Returns
+    ///
+    /// The latest [`LedgerInfoWithSignatures`] in the [`EpochChangeProof`].
+    pub fn verify_prepared(
+        &self,
+        verifier: &TrustedState,
+        mut prepared_verifier: Option<&PreparedValidatorVerifier>,
+    ) -> anyhow::Result<&LedgerInfoWithSignatures> {
         ensure!(
             !self.ledger_info_with_sigs.is_empty(),
             "The EpochChangeProof is empty"
@@ -374,7 +372,9 @@ impl EpochChangeProof {
             })
         {
             // Try to verify each (epoch -> epoch + 1) jump in the EpochChangeProof.
-            trusted_state.verify(ledger_info_with_sigs)?;
+            // Only the first jump is signed by the trusted epoch, which the
+            // prepared validator set belongs to.
+            trusted_state.verify(ledger_info_with_sigs, prepared_verifier.take())?;
             // While the original verification could've been via waypoints,
             // all the next epoch changes are verified using the (already
             // trusted) validator sets.
@@ -401,20 +401,7 @@ impl EpochChangeProof {
     ///
     /// A `Vec<u8>` representing the `EpochChangeProof`.
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = BytesMut::new();
-
-        // Write the length of the vector as LEB128
-        bytes.extend_from_slice(&write_leb128(self.ledger_info_with_sigs.len() as u64));
-
-        // Write each LedgerInfoWithSignatures to bytes
-        for ledger_info_with_sig in &self.ledger_info_with_sigs {
-            bytes.extend_from_slice(&ledger_info_with_sig.to_bytes());
-        }
-
-        // Write the `more` field
-        bytes.put_u8(u8::from(self.more));
-
-        bytes.to_vec()
+        codec::to_bytes(self)
     }
 
     /// Creates an `EpochChangeProof` from a byte slice.
@@ -427,55 +414,8 @@ impl EpochChangeProof {
     ///
     /// A `Result` which is `Ok` if the `EpochChangeProof`
     /// could be successfully created, and `Err` otherwise.
-    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, TypesError> {
-        // Read the length of the vector from LEB128
-        let (len, bytes_read) = read_leb128(bytes)
-            .map_err(|_| serde_error!("EpochChangeProof", "Not enough data for length"))?;
-        bytes.advance(bytes_read);
-
-        // Total length for LedgerInfoWithSignatures
-        let total_len = bytes.remaining() - 1;
-
-        if total_len % len as usize != 0 {
-            return Err(serde_error!(
-                "EpochChangeProof",
-                "Invalid data length for ledger_info_with_sigs"
-            ));
-        }
-
-        // Read each LedgerInfoWithSignatures from bytes
-        let mut ledger_info_with_sigs = Vec::new();
-        for i in 0..len {
-            let ledger_info_with_sig = LedgerInfoWithSignatures::from_bytes(
-                bytes
-                    .chunk()
-                    .get(..total_len / len as usize)
-                    .ok_or_else(|| {
-                        serde_error!(
-                            "EpochChangeProof",
-                            format!("Not enough data for LedgerInfoWithSignatures at index {i}")
-                        )
-                    })?,
-            )?;
-
-            ledger_info_with_sigs.push(ledger_info_with_sig);
-            bytes.advance(total_len / len as usize);
-        }
-
-        // Read the `more` field
-        let more = bytes.get_u8() != 0;
-
-        if bytes.remaining() != 0 {
-            return Err(serde_error!(
-                "EpochChangeProof",
-                "Unexpected data after completing deserialization"
-            ));
-        }
-
-        Ok(EpochChangeProof {
-            ledger_info_with_sigs,
-            more,
-        })
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
+        codec::from_bytes("EpochChangeProof", bytes)
     }
 }
 
@@ -574,4 +514,123 @@ mod test {
             aptos_trusted_state_hash.to_vec()
         );
     }
+
+    #[test]
+    fn test_verify_and_ratchet_multiple_epochs() {
+        use super::*;
+        use crate::aptos_test_utils::wrapper::AptosWrapper;
+
+        const EPOCH_CHANGES: usize = 3;
+
+        let mut aptos_wrapper = AptosWrapper::new(2, 130, 95).unwrap();
+
+        aptos_wrapper.generate_traffic().unwrap();
+        let trusted_state =
+            TrustedState::from_bytes(&bcs::to_bytes(aptos_wrapper.trusted_state()).unwrap())
+                .unwrap();
+
+        for _ in 0..EPOCH_CHANGES - 1 {
+            aptos_wrapper.commit_new_epoch().unwrap();
+        }
+        let state_proof = aptos_wrapper
+            .new_state_proof(trusted_state.version())
+            .unwrap();
+
+        let bytes = bcs::to_bytes(state_proof.epoch_changes()).unwrap();
+        let epoch_change_proof = EpochChangeProof::from_bytes(&bytes).unwrap();
+        assert_eq!(bytes, epoch_change_proof.to_bytes());
+        assert_eq!(
+            epoch_change_proof.ledger_info_with_sigs.len(),
+            EPOCH_CHANGES
+        );
+
+        match trusted_state
+            .verify_and_ratchet_inner(&epoch_change_proof)
+            .unwrap()
+        {
+            TrustedStateChange::Epoch { new_state, .. } => {
+                assert_eq!(
+                    new_state.epoch().unwrap(),
+                    trusted_state.epoch().unwrap() + EPOCH_CHANGES as u64
+                );
+            }
+            _ => panic!("Expected epoch change"),
+        }
+
+        // Dropping a link of the chain breaks the verification
+        let mut broken_epoch_change_proof = epoch_change_proof.clone();
+        broken_epoch_change_proof.ledger_info_with_sigs.remove(1);
+        assert!(trusted_state
+            .verify_and_ratchet_inner(&broken_epoch_change_proof)
+            .is_err());
+    }
+
+    #[cfg(feature = "waypoint")]
+    #[test]
+    fn test_verify_and_ratchet_from_waypoint() {
+        use super::*;
+        use crate::aptos_test_utils::wrapper::AptosWrapper;
+
+        let mut aptos_wrapper = AptosWrapper::new(2, 130, 95).unwrap();
+
+        let trusted_state_version = *aptos_wrapper.current_version();
+        aptos_wrapper.generate_traffic().unwrap();
+        aptos_wrapper.commit_new_epoch().unwrap();
+
+        let state_proof = aptos_wrapper
+            .new_state_proof(trusted_state_version)
+            .unwrap();
+        let aptos_epoch_change_li = state_proof
+            .epoch_changes()
+            .ledger_info_with_sigs
+            .first()
+            .unwrap()
+            .ledger_info();
+        let epoch_change_proof =
+            EpochChangeProof::from_bytes(&bcs::to_bytes(state_proof.epoch_changes()).unwrap())
+                .unwrap();
+        let epoch_change_li = epoch_change_proof
+            .ledger_info_with_sigs
+            .first()
+            .unwrap()
+            .ledger_info();
+
+        // The waypoint is the same as the one Aptos publishes for this epoch change
+        let waypoint = Waypoint::new_any(epoch_change_li);
+        assert_eq!(
+            bcs::to_bytes(&aptos_types::waypoint::Waypoint::new_any(
+                aptos_epoch_change_li
+            ))
+            .unwrap(),
+            waypoint.to_bytes()
+        );
+
+        let trusted_state = TrustedState::EpochWaypoint(waypoint);
+        assess_equality(&trusted_state.to_bytes());
+
+        match trusted_state
+            .verify_and_ratchet_inner(&epoch_change_proof)
+            .unwrap()
+        {
+            TrustedStateChange::Epoch { new_state, .. } => {
+                assert_eq!(
+                    new_state.epoch(),
+                    epoch_change_li
+                        .next_epoch_state()
+                        .map(|epoch_state| epoch_state.epoch)
+                );
+                assert_eq!(new_state.version(), epoch_change_li.version());
+            }
+            _ => panic!("Expected epoch change"),
+        }
+
+        // A waypoint committing to another ledger info is rejected
+        let mut wrong_waypoint_bytes = waypoint.to_bytes();
+        *wrong_waypoint_bytes.last_mut().unwrap() ^= 1;
+        let wrong_trusted_state =
+            TrustedState::EpochWaypoint(Waypoint::from_bytes(&wrong_waypoint_bytes).unwrap());
+        assert!(wrong_trusted_state
+            .verify_and_ratchet_inner(&epoch_change_proof)
+            .is_err());
+    }
 }
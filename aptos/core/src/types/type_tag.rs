@@ -0,0 +1,200 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! # Type Tag Module
+//!
+//! This module provides the `TypeTag` and `StructTag` structures
+//! and associated methods for handling Move type tags in the
+//! Aptos Light Client.
+//!
+//! Type tags are used in the Aptos chain to identify the type of
+//! emitted events or of resources stored in the state.
+
+// SPDX-License-Identifier: Apache-2.0
+use crate::types::codec;
+use crate::types::error::TypesError;
+use crate::types::AccountAddress;
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+
+/// `TypeTag` is an enum representing a Move type.
+///
+/// The order of the variants matches the one of the Aptos
+/// codebase, as their index is used for serialization.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TypeTag {
+    #[serde(rename = "bool", alias = "Bool")]
+    Bool,
+    #[serde(rename = "u8", alias = "U8")]
+    U8,
+    #[serde(rename = "u64", alias = "U64")]
+    U64,
+    #[serde(rename = "u128", alias = "U128")]
+    U128,
+    #[serde(rename = "address", alias = "Address")]
+    Address,
+    #[serde(rename = "signer", alias = "Signer")]
+    Signer,
+    #[serde(rename = "vector", alias = "Vector")]
+    Vector(Box<TypeTag>),
+    #[serde(rename = "struct", alias = "Struct")]
+    Struct(Box<StructTag>),
+    #[serde(rename = "u16", alias = "U16")]
+    U16,
+    #[serde(rename = "u32", alias = "U32")]
+    U32,
+    #[serde(rename = "u256", alias = "U256")]
+    U256,
+}
+
+impl TypeTag {
+    /// Converts the `TypeTag` to a byte vector.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<u8>` representing the `TypeTag`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        codec::to_bytes(self)
+    }
+
+    /// Creates a `TypeTag` from a byte slice.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes: &[u8]` - A byte slice from which to create the `TypeTag`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the `TypeTag` could
+    /// be successfully created, and `Err` otherwise.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
+        codec::from_bytes("TypeTag", bytes)
+    }
+}
+
+/// `StructTag` is a structure representing a Move struct type,
+/// identified by the address and module it is declared in, its
+/// name and its type arguments.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct StructTag {
+    address: AccountAddress,
+    module: String,
+    name: String,
+    #[serde(rename = "type_args", alias = "type_params")]
+    type_args: Vec<TypeTag>,
+}
+
+impl StructTag {
+    /// Creates a new `StructTag`.
+    ///
+    /// # Arguments
+    ///
+    /// * `address: AccountAddress` - The address of the account publishing the module.
+    /// * `module: String` - The name of the module declaring the struct.
+    /// * `name: String` - The name of the struct.
+    /// * `type_args: Vec<TypeTag>` - The type arguments of the struct.
+    ///
+    /// # Returns
+    ///
+    /// A new `StructTag`.
+    pub const fn new(
+        address: AccountAddress,
+        module: String,
+        name: String,
+        type_args: Vec<TypeTag>,
+    ) -> Self {
+        Self {
+            address,
+            module,
+            name,
+            type_args,
+        }
+    }
+
+    /// Converts the `StructTag` to a byte vector.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<u8>` representing the `StructTag`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        codec::to_bytes(self)
+    }
+
+    /// Creates a `StructTag` from a byte slice.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes: &[u8]` - A byte slice from which to create the `StructTag`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the `StructTag` could
+    /// be successfully created, and `Err` otherwise.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
+        codec::from_bytes("StructTag", bytes)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::types::ACCOUNT_ADDRESS_SIZE;
+
+    fn coin_store_tag() -> TypeTag {
+        let mut aptos_framework = [0u8; ACCOUNT_ADDRESS_SIZE];
+        aptos_framework[ACCOUNT_ADDRESS_SIZE - 1] = 1;
+
+        TypeTag::Struct(Box::new(StructTag::new(
+            AccountAddress::new(aptos_framework),
+            "coin".into(),
+            "CoinStore".into(),
+            vec![
+                TypeTag::Struct(Box::new(StructTag::new(
+                    AccountAddress::new(aptos_framework),
+                    "aptos_coin".into(),
+                    "AptosCoin".into(),
+                    vec![],
+                ))),
+                TypeTag::Vector(Box::new(TypeTag::U8)),
+            ],
+        )))
+    }
+
+    #[test]
+    fn test_bytes_conversion_type_tag() {
+        let type_tag = coin_store_tag();
+
+        let bytes = bcs::to_bytes(&type_tag).unwrap();
+        assert_eq!(bytes, type_tag.to_bytes());
+
+        let type_tag_deserialized = TypeTag::from_bytes(&bytes).unwrap();
+        assert_eq!(type_tag, type_tag_deserialized);
+
+        // Trailing and missing data
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(TypeTag::from_bytes(&trailing).is_err());
+        assert!(TypeTag::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[cfg(feature = "aptos")]
+    #[test]
+    fn test_bytes_conversion_aptos_type_tag() {
+        use aptos_sdk::move_types::language_storage::TypeTag as AptosTypeTag;
+        use std::str::FromStr;
+
+        for aptos_type_tag in [
+            "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>",
+            "0x1::object::ObjectGroup",
+            "vector<vector<u256>>",
+            "address",
+        ] {
+            let aptos_type_tag = AptosTypeTag::from_str(aptos_type_tag).unwrap();
+            let aptos_bytes = bcs::to_bytes(&aptos_type_tag).unwrap();
+
+            let type_tag = TypeTag::from_bytes(&aptos_bytes).unwrap();
+            assert_eq!(aptos_bytes, type_tag.to_bytes());
+        }
+    }
+}
@@ -6,6 +6,9 @@ use anyhow::{anyhow, Result};
 /// Size in bytes for an enum variant representation.
 pub const ENUM_VARIANT_LEN: usize = 1;
 
+/// Size of a u16 representation in bytes.
+pub const U16_SIZE: usize = 2;
+
 /// Size of a u64 representation in bytes.
 pub const U64_SIZE: usize = 8;
 
@@ -15,6 +18,38 @@ pub const VOTING_POWER_OFFSET_INCR: usize = U64_SIZE;
 /// Size of the LEB128 representation of a public key vector length.
 pub const LEB128_PUBKEY_LEN: usize = 1;
 
+/// Root of trust committed by the epoch change program when it ratchets from the prepared
+/// validator verifier of a trusted epoch state.
+pub const EPOCH_CHANGE_FROM_VALIDATOR_VERIFIER: u8 = 0;
+
+/// Root of trust committed by the epoch change program when it bootstraps from a waypoint,
+/// the previous validator verifier hash slot then holding the waypoint value.
+pub const EPOCH_CHANGE_FROM_WAYPOINT: u8 = 1;
+
+/// Flag committed by the inclusion program when the `MaxAge` the ledger info was checked
+/// against is part of its public values.
+pub const INCLUSION_MAX_AGE_FLAG: u8 = 1;
+
+/// Flag committed by the inclusion program when the execution status of the transaction is
+/// part of its public values.
+pub const INCLUSION_STATUS_FLAG: u8 = 1 << 1;
+
+/// Flag committed by the inclusion program when the fields of a decoded `0x1::account::Account`
+/// are part of its public values.
+pub const INCLUSION_ACCOUNT_FLAG: u8 = 1 << 2;
+
+/// Flag committed by the inclusion program when the fields of a decoded
+/// `0x1::coin::CoinStore<AptosCoin>` are part of its public values.
+pub const INCLUSION_COIN_STORE_FLAG: u8 = 1 << 3;
+
+/// Flag committed by the inclusion programs when the proven key is present in the state, rather
+/// than absent from it.
+pub const INCLUSION_PRESENT_FLAG: u8 = 1 << 4;
+
+/// Flag committed by the inclusion programs when the `StateKey` the proven key is derived from
+/// is part of their public values, as the index of its variant, its address and its identifier.
+pub const INCLUSION_STATE_KEY_FLAG: u8 = 1 << 5;
+
 /// Reads a LEB128 encoded number from a byte slice.
 ///
 /// This function takes a byte slice as input and returns a tuple of the parsed number and the number of bytes read.
@@ -3,16 +3,16 @@
 
 // SPDX-License-Identifier: Apache-2.0
 use crate::crypto::hash::{hash_data, prefixed_sha3, CryptoHash, HashValue};
-use crate::crypto::sig::{AggregateSignature, BitVec, PublicKey, PUB_KEY_LEN};
-use crate::serde_error;
+use crate::crypto::sig::{AggregateSignature, BitVec, PreparedPublicKey, PublicKey, PUB_KEY_LEN};
+use crate::types::codec;
 use crate::types::error::{TypesError, VerifyError};
 use crate::types::ledger_info::LedgerInfo;
-use crate::types::utils::{read_leb128, write_leb128, LEB128_PUBKEY_LEN, VOTING_POWER_OFFSET_INCR};
+use crate::types::utils::{LEB128_PUBKEY_LEN, VOTING_POWER_OFFSET_INCR};
 use crate::types::{AccountAddress, ACCOUNT_ADDRESS_SIZE};
 use anyhow::Result;
-use bytes::{Buf, BufMut, BytesMut};
 use getset::Getters;
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// Size in bytes for a `ValidatorConsensusInfo`
 pub const VALIDATOR_CONSENSUS_INFO_SIZE: usize =
@@ -54,11 +54,7 @@ impl ValidatorConsensusInfo {
     ///
     /// A `Vec<u8>` representing the `ValidatorConsensusInfo`.
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = BytesMut::new();
-        bytes.put_slice(&self.address.to_bytes());
-        bytes.put_slice(&self.public_key.to_bytes());
-        bytes.put_u64_le(self.voting_power);
-        bytes.to_vec()
+        codec::to_bytes(self)
     }
 
     /// Creates a `ValidatorConsensusInfo` from a byte slice.
@@ -71,49 +67,27 @@ impl ValidatorConsensusInfo {
     ///
     /// A `Result` which is `Ok` if the `ValidatorConsensusInfo`
     /// could be successfully created, and `Err` otherwise.
-    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, TypesError> {
-        let address = AccountAddress::from_bytes(
-            bytes.chunk().get(..ACCOUNT_ADDRESS_SIZE).ok_or_else(|| {
-                serde_error!(
-                    "ValidatorConsensusInfo",
-                    "Not enough data for AccountAddress"
-                )
-            })?,
-        )
-        .map_err(|e| serde_error!("ValidatorConsensusInfo", e))?;
-        bytes.advance(ACCOUNT_ADDRESS_SIZE); // Advance the buffer by the size of AccountAddress
-
-        let (slice_len, bytes_read) = read_leb128(bytes).map_err(|e| {
-            serde_error!(
-                "ValidatorConsensusInfo",
-                format!("Failed to read length of public_key: {e}")
-            )
-        })?;
-        bytes.advance(bytes_read);
-
-        let public_key =
-            PublicKey::from_bytes(bytes.chunk().get(..slice_len as usize).ok_or_else(|| {
-                serde_error!("ValidatorConsensusInfo", "Not enough data for PublicKey")
-            })?)
-            .map_err(|e| serde_error!("ValidatorConsensusInfo", e))?;
-        bytes.advance(slice_len as usize); // Advance the buffer by the size of PublicKey
-        let voting_power = bytes.get_u64_le();
-
-        if bytes.remaining() != 0 {
-            return Err(serde_error!(
-                "LedgerInfo",
-                "Unexpected data after completing deserialization"
-            ));
-        }
-
-        Ok(Self {
-            address,
-            public_key,
-            voting_power,
-        })
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
+        codec::from_bytes("ValidatorConsensusInfo", bytes)
     }
 }
 
+/// `VerificationMode` selects the checks run when verifying
+/// multi-signatures with a `ValidatorVerifier`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerificationMode {
+    /// Rejects malformed validator sets, such as sets without voting
+    /// power or with duplicate validators, and never skips the
+    /// signature verification.
+    #[default]
+    Strict,
+    /// Mirrors the Aptos verifier, which accepts any signature when
+    /// the quorum voting power is zero. Only available for tests and
+    /// with the `test-utils` feature.
+    #[cfg(any(test, feature = "test-utils"))]
+    Permissive,
+}
+
 /// `ValidatorVerifier` represents a list of validators, most
 /// of the time related to a given epoch.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Getters, Serialize)]
@@ -156,6 +130,50 @@ impl ValidatorVerifier {
         self.validator_infos.is_empty()
     }
 
+    /// Checks that the validator set is well-formed.
+    ///
+    /// The set must have a non-zero total voting power that fits in
+    /// a `u64`, no more validators than a bitmask can index, and no
+    /// two validators sharing an address or a public key.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the validator set is well-formed, and `Err` otherwise.
+    pub fn validate(&self) -> std::result::Result<(), VerifyError> {
+        self.num_validators()?;
+
+        let total_voting_power = self
+            .validator_infos
+            .iter()
+            .try_fold(0u64, |sum, info| sum.checked_add(info.voting_power))
+            .ok_or(VerifyError::VotingPowerOverflow)?;
+        if total_voting_power == 0 {
+            return Err(VerifyError::NoVotingPower);
+        }
+
+        let mut addresses: Vec<_> = self
+            .validator_infos
+            .iter()
+            .map(|info| &info.address)
+            .collect();
+        addresses.sort_unstable();
+        if addresses.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(VerifyError::DuplicateAuthor);
+        }
+
+        let mut public_keys: Vec<_> = self
+            .validator_infos
+            .iter()
+            .map(|info| info.public_key.compressed_pubkey())
+            .collect();
+        public_keys.sort_unstable();
+        if public_keys.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(VerifyError::DuplicatePublicKey);
+        }
+
+        Ok(())
+    }
+
     /// Ensure there are not more than the maximum expected voters (all possible signatures).
     ///
     /// # Arguments
@@ -186,21 +204,25 @@ impl ValidatorVerifier {
     ///
     /// # Returns
     ///
-    /// The total voting power of the `ValidatorVerifier`.
-    fn s_voting_power(address_to_validator_info: &[ValidatorConsensusInfo]) -> u128 {
-        address_to_validator_info.iter().fold(0, |sum, x| {
-            sum.checked_add(u128::from(x.voting_power))
-                .expect("sum of all voting power is greater than u64::max")
-        })
+    /// A `Result` which is `Ok` with the total voting power of the
+    /// `ValidatorVerifier`, and `Err` if it overflows.
+    fn s_voting_power(
+        address_to_validator_info: &[ValidatorConsensusInfo],
+    ) -> std::result::Result<u128, VerifyError> {
+        address_to_validator_info
+            .iter()
+            .try_fold(0u128, |sum, x| sum.checked_add(u128::from(x.voting_power)))
+            .ok_or(VerifyError::VotingPowerOverflow)
     }
 
     /// Returns the total voting power of the `ValidatorVerifier`.
     ///
     /// # Returns
     ///
-    /// The total voting power of the `ValidatorVerifier`.
+    /// A `Result` which is `Ok` with the total voting power of the
+    /// `ValidatorVerifier`, and `Err` if it overflows.
     // TODO: Make this more efficient
-    pub fn total_voting_power(&self) -> u128 {
+    pub fn total_voting_power(&self) -> std::result::Result<u128, VerifyError> {
         Self::s_voting_power(&self.validator_infos[..])
     }
 
@@ -209,15 +231,26 @@ impl ValidatorVerifier {
     ///
     /// # Returns
     ///
-    /// The quorum voting power of the `ValidatorVerifier`.
-    pub fn quorum_voting_power(&self) -> u128 {
+    /// A `Result` which is `Ok` with the quorum voting power of the
+    /// `ValidatorVerifier`, and `Err` if the total voting power overflows.
+    pub fn quorum_voting_power(&self) -> std::result::Result<u128, VerifyError> {
         if self.validator_infos.is_empty() {
-            0
+            Ok(0)
         } else {
-            self.total_voting_power() * 2 / 3 + 1
+            Ok(self.total_voting_power()? * 2 / 3 + 1)
         }
     }
 
+    /// Returns the number of validators, as indexed by a bitmask.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` with the number of validators, and `Err`
+    /// if a bitmask cannot index them all.
+    fn num_validators(&self) -> std::result::Result<u16, VerifyError> {
+        u16::try_from(self.len()).map_err(|_| VerifyError::TooManyValidators(self.len()))
+    }
+
     /// Returns the voting power for this address.
     ///
     /// # Arguments
@@ -250,10 +283,14 @@ impl ValidatorVerifier {
         &self,
         authors: impl Iterator<Item = &'a AccountAddress>,
     ) -> std::result::Result<u128, VerifyError> {
-        let mut aggregated_voting_power = 0;
+        let mut aggregated_voting_power: u128 = 0;
         for account_address in authors {
             match self.get_voting_power(account_address) {
-                Some(voting_power) => aggregated_voting_power += u128::from(voting_power),
+                Some(voting_power) => {
+                    aggregated_voting_power = aggregated_voting_power
+                        .checked_add(u128::from(voting_power))
+                        .ok_or(VerifyError::VotingPowerOverflow)?
+                }
                 None => return Err(VerifyError::UnknownAuthor),
             }
         }
@@ -280,9 +317,11 @@ impl ValidatorVerifier {
         let aggregated_voting_power = self.sum_voting_power(authors)?;
 
         let target = if check_super_majority {
-            self.quorum_voting_power()
+            self.quorum_voting_power()?
         } else {
-            self.total_voting_power() - self.quorum_voting_power() + 1
+            (self.total_voting_power()? + 1)
+                .checked_sub(self.quorum_voting_power()?)
+                .ok_or(VerifyError::VotingPowerOverflow)?
         };
 
         if aggregated_voting_power < target {
@@ -296,7 +335,7 @@ impl ValidatorVerifier {
 
     /// Verifies the multi-signatures of a given `LedgerInfo`
     /// with the provided `AggregateSignature` from the
-    /// `ValidatorVerifier`.
+    /// `ValidatorVerifier`, in strict mode.
     ///
     /// # Arguments
     ///
@@ -311,8 +350,34 @@ impl ValidatorVerifier {
         message: &LedgerInfo,
         multi_signature: &AggregateSignature,
     ) -> std::result::Result<(), VerifyError> {
+        self.verify_multi_signatures_with_mode(message, multi_signature, VerificationMode::Strict)
+    }
+
+    /// Verifies the multi-signatures of a given `LedgerInfo`
+    /// with the provided `AggregateSignature` from the
+    /// `ValidatorVerifier`, using the given `VerificationMode`.
+    ///
+    /// # Arguments
+    ///
+    /// * `message: &LedgerInfo` - The ledger info.
+    /// * `multi_signature: &AggregateSignature` - The aggregate signature.
+    /// * `mode: VerificationMode` - The checks to run on the validator set.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the multi-signatures are valid, and `Err` otherwise.
+    pub fn verify_multi_signatures_with_mode(
+        &self,
+        message: &LedgerInfo,
+        multi_signature: &AggregateSignature,
+        mode: VerificationMode,
+    ) -> std::result::Result<(), VerifyError> {
+        if mode == VerificationMode::Strict {
+            self.validate()?;
+        }
+
         // Verify the number of signature is not greater than expected.
-        Self::check_num_of_voters(self.len() as u16, multi_signature.validator_bitmask())?;
+        Self::check_num_of_voters(self.num_validators()?, multi_signature.validator_bitmask())?;
         let mut pub_keys = vec![];
         let mut authors = vec![];
         for index in multi_signature.validator_bitmask().iter_ones() {
@@ -326,7 +391,8 @@ impl ValidatorVerifier {
 
         // Verify the quorum voting power of the authors
         self.check_voting_power(authors.into_iter(), true)?;
-        if self.quorum_voting_power() == 0 {
+        #[cfg(any(test, feature = "test-utils"))]
+        if mode == VerificationMode::Permissive && self.quorum_voting_power()? == 0 {
             // This should happen only in case of tests.
             // TODO(skedia): Clean up the test behaviors to not rely on empty signature
             // verification
@@ -343,12 +409,8 @@ impl ValidatorVerifier {
         let aggregated_key =
             PublicKey::aggregate(&pub_keys).map_err(|_| VerifyError::FailedToAggregatePubKey)?;
 
-        // see aptos_crypto::unit_tests::cryptohasher
-        let mut bytes = prefixed_sha3(b"LedgerInfo").to_vec();
-        bytes.extend_from_slice(&message.to_bytes());
-
         multi_sig
-            .verify(&bytes, &aggregated_key)
+            .verify(&signing_message(message), &aggregated_key)
             .map_err(|_| VerifyError::InvalidMultiSignature)?;
         Ok(())
     }
@@ -359,12 +421,7 @@ impl ValidatorVerifier {
     ///
     /// A `Vec<u8>` representing the `ValidatorVerifier`.
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = BytesMut::new();
-        bytes.put_slice(&write_leb128(self.validator_infos.len() as u64));
-        for info in &self.validator_infos {
-            bytes.put_slice(&info.to_bytes());
-        }
-        bytes.to_vec()
+        codec::to_bytes(self)
     }
 
     /// Creates a `ValidatorVerifier` from a byte slice.
@@ -377,90 +434,279 @@ impl ValidatorVerifier {
     ///
     /// A `Result` which is `Ok` if the `ValidatorVerifier`
     /// could be successfully created, and `Err` otherwise.
-    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, TypesError> {
-        let mut validator_infos = Vec::new();
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
+        codec::from_bytes("ValidatorVerifier", bytes)
+    }
+}
 
-        let (slice_len, bytes_read) = read_leb128(bytes).map_err(|_| {
-            serde_error!(
-                "ValidatorVerifier",
-                "Failed to read length of validator_infos"
-            )
-        })?;
-
-        bytes.advance(bytes_read);
-
-        for _ in 0..slice_len {
-            let info_bytes = bytes
-                .chunk()
-                .get(..VALIDATOR_CONSENSUS_INFO_SIZE)
-                .ok_or_else(|| {
-                    serde_error!(
-                        "ValidatorVerifier",
-                        "Not enough data for ValidatorConsensusInfo"
-                    )
-                })?;
-            validator_infos.push(
-                ValidatorConsensusInfo::from_bytes(info_bytes)
-                    .map_err(|e| serde_error!("ValidatorVerifier", e))?,
-            );
-            bytes.advance(VALIDATOR_CONSENSUS_INFO_SIZE);
-        }
+impl CryptoHash for ValidatorVerifier {
+    fn hash(&self) -> HashValue {
+        HashValue::new(hash_data(
+            &prefixed_sha3(b"ValidatorVerifier"),
+            vec![&self.to_bytes()],
+        ))
+    }
+}
 
-        if bytes.remaining() != 0 {
-            return Err(serde_error!(
-                "ValidatorVerifier",
-                "Unexpected data after completing deserialization"
-            ));
+/// Reconstruct fields from the raw data upon deserialization.
+impl<'de> Deserialize<'de> for ValidatorVerifier {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(rename = "ValidatorVerifier")]
+        struct RawValidatorVerifier {
+            validator_infos: Vec<ValidatorConsensusInfo>,
         }
 
-        Ok(Self { validator_infos })
+        let RawValidatorVerifier { validator_infos } =
+            RawValidatorVerifier::deserialize(deserializer)?;
+
+        Ok(ValidatorVerifier::new(validator_infos))
+    }
+}
+
+/// Returns the message signed by the validators for a given `LedgerInfo`.
+///
+/// # Arguments
+///
+/// * `message: &LedgerInfo` - The ledger info.
+///
+/// # Returns
+///
+/// A `Vec<u8>` representing the signed message.
+fn signing_message(message: &LedgerInfo) -> Vec<u8> {
+    // see aptos_crypto::unit_tests::cryptohasher
+    let mut bytes = prefixed_sha3(b"LedgerInfo").to_vec();
+    bytes.extend_from_slice(&message.to_bytes());
+    bytes
+}
+
+/// `PreparedValidatorVerifier` is a `ValidatorVerifier` prepared
+/// for the verification of many multi-signatures during an epoch.
+///
+/// It stores the decompressed public keys of the validators along
+/// with their aggregate, so that verifying a multi-signature neither
+/// decompresses any key nor adds more than half of them. Its hash,
+/// which covers the prepared keys, is the identity of the validator
+/// set committed by the programs.
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+pub struct PreparedValidatorVerifier {
+    /// The validator set, with public keys compressed from the prepared ones.
+    #[getset(get = "pub")]
+    validator_verifier: ValidatorVerifier,
+    /// The prepared public keys of the validators, in the order of the set.
+    public_keys: Vec<PreparedPublicKey>,
+    /// The aggregate of all the prepared public keys.
+    aggregated_public_key: PreparedPublicKey,
+}
+
+impl PreparedValidatorVerifier {
+    /// Creates a new `PreparedValidatorVerifier`, decompressing the
+    /// public keys of the validators after checking that the
+    /// validator set is well-formed.
+    ///
+    /// # Arguments
+    ///
+    /// * `validator_verifier: ValidatorVerifier` - The validator set to prepare.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` with the `PreparedValidatorVerifier`
+    /// if the validator set is well-formed, and `Err` otherwise.
+    pub fn new(validator_verifier: ValidatorVerifier) -> std::result::Result<Self, VerifyError> {
+        validator_verifier.validate()?;
+
+        let public_keys: Vec<_> = validator_verifier
+            .validator_infos
+            .iter()
+            .map(|info| PreparedPublicKey::from(&info.public_key))
+            .collect();
+        let aggregated_public_key = PreparedPublicKey::aggregate(&public_keys);
+
+        Ok(Self {
+            validator_verifier,
+            public_keys,
+            aggregated_public_key,
+        })
+    }
+
+    /// Aggregates the prepared public keys of the validators set in a
+    /// bitmask. When more than half of the validators signed, the keys
+    /// of the non-signers are subtracted from the aggregate of the whole
+    /// set instead, which takes fewer curve operations.
+    ///
+    /// # Arguments
+    ///
+    /// * `bitmask: &BitVec` - The bitmask of the signers.
+    /// * `num_signers: usize` - The number of bits set in the bitmask.
+    ///
+    /// # Returns
+    ///
+    /// The aggregated `PreparedPublicKey` of the signers.
+    fn aggregate_signers(&self, bitmask: &BitVec, num_signers: usize) -> PreparedPublicKey {
+        if num_signers * 2 > self.public_keys.len() {
+            self.aggregated_public_key.subtract(
+                self.public_keys
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| !bitmask.is_set(*index as u16))
+                    .map(|(_, public_key)| public_key),
+            )
+        } else {
+            PreparedPublicKey::aggregate(bitmask.iter_ones().map(|index| &self.public_keys[index]))
+        }
     }
 
-    /// Estimate the size in bytes for  `ValidatorVerifier` from the given bytes.
+    /// Verifies the multi-signatures of a given `LedgerInfo`
+    /// with the provided `AggregateSignature` from the
+    /// `PreparedValidatorVerifier`.
+    ///
+    /// The validator set is checked to be well-formed when preparing
+    /// it, so this verification is always in strict mode.
     ///
     /// # Arguments
     ///
-    /// * `bytes: &[u8]` - A byte slice from which to estimate the size.
+    /// * `message: &LedgerInfo` - The ledger info.
+    /// * `multi_signature: &AggregateSignature` - The aggregate signature.
     ///
     /// # Returns
     ///
-    /// The estimated size in bytes for the structure.
+    /// A `Result` which is `Ok` if the multi-signatures are valid, and `Err` otherwise.
+    pub fn verify_multi_signatures(
+        &self,
+        message: &LedgerInfo,
+        multi_signature: &AggregateSignature,
+    ) -> std::result::Result<(), VerifyError> {
+        let validator_verifier = &self.validator_verifier;
+
+        // Verify the number of signature is not greater than expected.
+        ValidatorVerifier::check_num_of_voters(
+            validator_verifier.num_validators()?,
+            multi_signature.validator_bitmask(),
+        )?;
+        let authors = multi_signature
+            .validator_bitmask()
+            .iter_ones()
+            .map(|index| {
+                validator_verifier
+                    .validator_infos
+                    .get(index)
+                    .map(|validator| &validator.address)
+                    .ok_or(VerifyError::UnknownAuthor)
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        // Verify the quorum voting power of the authors
+        validator_verifier.check_voting_power(authors.iter().copied(), true)?;
+
+        // Verify empty multi signature
+        let multi_sig = multi_signature
+            .sig()
+            .as_ref()
+            .ok_or(VerifyError::EmptySignature)?;
+
+        // Verify the optimistically aggregated signature.
+        let aggregated_key =
+            self.aggregate_signers(multi_signature.validator_bitmask(), authors.len());
+
+        multi_sig
+            .verify_prepared(&signing_message(message), &aggregated_key)
+            .map_err(|_| VerifyError::InvalidMultiSignature)?;
+        Ok(())
+    }
+
+    /// Converts the `PreparedValidatorVerifier` to a byte vector.
     ///
-    /// # Note
+    /// # Returns
     ///
-    /// The `ValidatorVerifier` bytes should start from offset 0 of the slice.
-    pub(crate) fn estimate_size_from_bytes(bytes: &[u8]) -> Result<usize, TypesError> {
-        let (slice_len, bytes_read) =
-            read_leb128(bytes).map_err(|e| serde_error!("ValidatorVerifier", e))?;
-        Ok(slice_len as usize * VALIDATOR_CONSENSUS_INFO_SIZE + bytes_read)
+    /// A `Vec<u8>` representing the `PreparedValidatorVerifier`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        codec::to_bytes(self)
+    }
+
+    /// Creates a `PreparedValidatorVerifier` from a byte slice.
+    ///
+    /// The prepared public keys are trusted as they are, so the
+    /// hash of the resulting `PreparedValidatorVerifier` has to be
+    /// checked against a trusted one before relying on it.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes: &[u8]` - A byte slice from which to create the `PreparedValidatorVerifier`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the `PreparedValidatorVerifier`
+    /// could be successfully created, and `Err` otherwise.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
+        codec::from_bytes("PreparedValidatorVerifier", bytes)
     }
 }
 
-impl CryptoHash for ValidatorVerifier {
+impl CryptoHash for PreparedValidatorVerifier {
     fn hash(&self) -> HashValue {
         HashValue::new(hash_data(
-            &prefixed_sha3(b"ValidatorVerifier"),
+            &prefixed_sha3(b"PreparedValidatorVerifier"),
             vec![&self.to_bytes()],
         ))
     }
 }
 
+impl Serialize for PreparedValidatorVerifier {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let validator_infos: Vec<_> = self
+            .validator_verifier
+            .validator_infos
+            .iter()
+            .zip(&self.public_keys)
+            .map(|(info, public_key)| (info.address, public_key, info.voting_power))
+            .collect();
+
+        let mut state = serializer.serialize_struct("PreparedValidatorVerifier", 2)?;
+        state.serialize_field("validator_infos", &validator_infos)?;
+        state.serialize_field("aggregated_public_key", &self.aggregated_public_key)?;
+        state.end()
+    }
+}
+
 /// Reconstruct fields from the raw data upon deserialization.
-impl<'de> Deserialize<'de> for ValidatorVerifier {
+impl<'de> Deserialize<'de> for PreparedValidatorVerifier {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
         #[derive(Deserialize)]
-        #[serde(rename = "ValidatorVerifier")]
-        struct RawValidatorVerifier {
-            validator_infos: Vec<ValidatorConsensusInfo>,
+        #[serde(rename = "PreparedValidatorVerifier")]
+        struct RawPreparedValidatorVerifier {
+            validator_infos: Vec<(AccountAddress, PreparedPublicKey, u64)>,
+            aggregated_public_key: PreparedPublicKey,
         }
 
-        let RawValidatorVerifier { validator_infos } =
-            RawValidatorVerifier::deserialize(deserializer)?;
+        let RawPreparedValidatorVerifier {
+            validator_infos,
+            aggregated_public_key,
+        } = RawPreparedValidatorVerifier::deserialize(deserializer)?;
+
+        let (validator_infos, public_keys) = validator_infos
+            .into_iter()
+            .map(|(address, public_key, voting_power)| {
+                (
+                    ValidatorConsensusInfo::new(address, public_key.compress(), voting_power),
+                    public_key,
+                )
+            })
+            .unzip();
 
-        Ok(ValidatorVerifier::new(validator_infos))
+        Ok(PreparedValidatorVerifier {
+            validator_verifier: ValidatorVerifier::new(validator_infos),
+            public_keys,
+            aggregated_public_key,
+        })
     }
 }
 
@@ -500,6 +746,196 @@ mod test {
         assert_eq!(bytes, validator_to_bytes);
     }
 
+    /// Returns the latest epoch change `LedgerInfoWithSignatures`
+    /// along with its next `ValidatorVerifier`, which signed it.
+    fn signed_ledger_info(
+        validators: usize,
+    ) -> (
+        crate::types::ledger_info::LedgerInfoWithSignatures,
+        crate::types::validator::ValidatorVerifier,
+    ) {
+        use crate::aptos_test_utils::wrapper::AptosWrapper;
+        use crate::types::ledger_info::LedgerInfoWithSignatures;
+
+        let mut aptos_wrapper = AptosWrapper::new(2, validators, validators).unwrap();
+        aptos_wrapper.generate_traffic().unwrap();
+        aptos_wrapper.commit_new_epoch().unwrap();
+
+        let li: LedgerInfoWithSignatures =
+            bcs::from_bytes(&aptos_wrapper.get_latest_li_bytes().unwrap()).unwrap();
+        let validator_verifier = li
+            .ledger_info()
+            .next_epoch_state()
+            .unwrap()
+            .verifier
+            .clone();
+
+        (li, validator_verifier)
+    }
+
+    #[test]
+    fn test_strict_verification_adversarial() {
+        use crate::crypto::sig::AggregateSignature;
+        use crate::types::error::VerifyError;
+        use crate::types::validator::{
+            ValidatorConsensusInfo, ValidatorVerifier, VerificationMode,
+        };
+
+        let (li, validator_verifier) = signed_ledger_info(10);
+        let message = li.ledger_info();
+        let signatures = li.signatures();
+        let validator_infos = validator_verifier.validator_infos();
+
+        let verify = |validator_verifier: &ValidatorVerifier,
+                      signatures: &AggregateSignature,
+                      mode: VerificationMode| {
+            validator_verifier.verify_multi_signatures_with_mode(message, signatures, mode)
+        };
+
+        // Honest validator set
+        assert!(verify(&validator_verifier, signatures, VerificationMode::Strict).is_ok());
+        assert!(li.verify_signatures(&validator_verifier).is_ok());
+
+        // Empty validator set, only accepted through the permissive bypass
+        let empty_verifier = ValidatorVerifier::new(vec![]);
+        let empty_signature = AggregateSignature::from_bytes(&[0, 0]).unwrap();
+        assert_eq!(
+            verify(&empty_verifier, &empty_signature, VerificationMode::Strict),
+            Err(VerifyError::NoVotingPower)
+        );
+        assert!(verify(
+            &empty_verifier,
+            &empty_signature,
+            VerificationMode::Permissive
+        )
+        .is_ok());
+
+        // Zero voting power
+        let zero_power_verifier = ValidatorVerifier::new(
+            validator_infos
+                .iter()
+                .map(|info| ValidatorConsensusInfo::new(info.address, info.public_key.clone(), 0))
+                .collect(),
+        );
+        assert_eq!(
+            verify(&zero_power_verifier, signatures, VerificationMode::Strict),
+            Err(VerifyError::NoVotingPower)
+        );
+
+        // Duplicate author, which still carries a valid signature
+        let mut duplicate_author_infos = validator_infos.clone();
+        duplicate_author_infos[1].address = duplicate_author_infos[0].address;
+        let duplicate_author_verifier = ValidatorVerifier::new(duplicate_author_infos);
+        assert_eq!(
+            verify(
+                &duplicate_author_verifier,
+                signatures,
+                VerificationMode::Strict
+            ),
+            Err(VerifyError::DuplicateAuthor)
+        );
+        assert!(verify(
+            &duplicate_author_verifier,
+            signatures,
+            VerificationMode::Permissive
+        )
+        .is_ok());
+
+        // Duplicate public key
+        let mut duplicate_key_infos = validator_infos.clone();
+        duplicate_key_infos[1].public_key = duplicate_key_infos[0].public_key.clone();
+        assert_eq!(
+            verify(
+                &ValidatorVerifier::new(duplicate_key_infos),
+                signatures,
+                VerificationMode::Strict
+            ),
+            Err(VerifyError::DuplicatePublicKey)
+        );
+
+        // Voting power overflow
+        let mut overflow_infos = validator_infos.clone();
+        overflow_infos[0].voting_power = u64::MAX;
+        assert_eq!(
+            verify(
+                &ValidatorVerifier::new(overflow_infos),
+                signatures,
+                VerificationMode::Strict
+            ),
+            Err(VerifyError::VotingPowerOverflow)
+        );
+
+        // Validator set too large to be indexed by a bitmask, instead of truncating its length
+        let too_many_verifier =
+            ValidatorVerifier::new(vec![validator_infos[0].clone(); usize::from(u16::MAX) + 1]);
+        for mode in [VerificationMode::Strict, VerificationMode::Permissive] {
+            assert_eq!(
+                verify(&too_many_verifier, signatures, mode),
+                Err(VerifyError::TooManyValidators(usize::from(u16::MAX) + 1))
+            );
+        }
+
+        // Bitmask longer than the validator set
+        let bitmask_len = signatures.validator_bitmask().to_bytes().len();
+        let mut long_signature_bytes = signatures.to_bytes();
+        long_signature_bytes[0] += 1;
+        long_signature_bytes.insert(bitmask_len, 0b1000_0000);
+        let long_signature = AggregateSignature::from_bytes(&long_signature_bytes).unwrap();
+        for mode in [VerificationMode::Strict, VerificationMode::Permissive] {
+            assert_eq!(
+                verify(&validator_verifier, &long_signature, mode),
+                Err(VerifyError::InvalidBitVec)
+            );
+        }
+    }
+
+    #[test]
+    fn test_prepared_validator_verifier() {
+        use crate::crypto::hash::CryptoHash;
+        use crate::crypto::sig::{BitVec, PreparedPublicKey};
+        use crate::types::error::VerifyError;
+        use crate::types::validator::{PreparedValidatorVerifier, ValidatorVerifier};
+
+        let (li, validator_verifier) = signed_ledger_info(10);
+        let prepared_verifier = PreparedValidatorVerifier::new(validator_verifier.clone()).unwrap();
+
+        // Verifies the same signatures as the validator set it was prepared from
+        assert!(prepared_verifier
+            .verify_multi_signatures(li.ledger_info(), li.signatures())
+            .is_ok());
+        assert!(li.verify_prepared_signatures(&prepared_verifier).is_ok());
+
+        // The prepared keys survive the conversion to bytes, and so does the identity
+        let prepared_from_bytes =
+            PreparedValidatorVerifier::from_bytes(&prepared_verifier.to_bytes()).unwrap();
+        assert_eq!(prepared_from_bytes, prepared_verifier);
+        assert_eq!(
+            prepared_from_bytes.validator_verifier(),
+            &validator_verifier
+        );
+        assert_eq!(prepared_from_bytes.hash(), prepared_verifier.hash());
+
+        // Subtracting the non-signers gives the aggregate of the signers
+        for bitmask in [[0b1100_0000, 0b0000_0000], [0b1111_1110, 0b0100_0000]] {
+            let bitmask = BitVec::from_bytes(&bitmask);
+            let num_signers = bitmask.iter_ones().count();
+            assert_eq!(
+                prepared_verifier.aggregate_signers(&bitmask, num_signers),
+                PreparedPublicKey::aggregate(
+                    bitmask
+                        .iter_ones()
+                        .map(|index| &prepared_verifier.public_keys[index])
+                )
+            );
+        }
+
+        // Malformed validator sets cannot be prepared
+        assert_eq!(
+            PreparedValidatorVerifier::new(ValidatorVerifier::new(vec![])),
+            Err(VerifyError::NoVotingPower)
+        );
+    }
+
     proptest! {
         #![proptest_config(ProptestConfig::with_cases(10))]
         #[test]
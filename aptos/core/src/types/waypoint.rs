@@ -10,17 +10,20 @@
 //! The `Waypoint` structure represents a waypoint on the Aptos chain,
 //! which is a value that clients can use to bootstrap securely to a ledger.
 
-// SPDX-License-Identifier: Apache-2.0
 use crate::crypto::hash::{hash_data, prefixed_sha3, CryptoHash, HashValue, HASH_LENGTH};
 use crate::serde_error;
+use crate::types::codec;
 use crate::types::epoch_state::EpochState;
 use crate::types::error::TypesError;
 use crate::types::ledger_info::LedgerInfo;
 use crate::types::utils::U64_SIZE;
 use crate::types::Version;
-use bytes::{Buf, BufMut, BytesMut};
+use anyhow::ensure;
 use getset::CopyGetters;
+use serde::de::Error;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
 
 /// Length in bytes of the serialized `Waypoint`.
 pub const WAYPOINT_SIZE: usize = U64_SIZE + HASH_LENGTH;
@@ -31,6 +34,7 @@ pub struct Waypoint {
     #[getset(get_copy = "pub")]
     version: Version,
     /// The hash of the chosen fields of LedgerInfo.
+    #[getset(get_copy = "pub")]
     value: HashValue,
 }
 
@@ -67,49 +71,111 @@ impl Waypoint {
         }
     }
 
-    /// Creates a `Waypoint` from a byte slice.
+    /// Verifies that the given `LedgerInfo` is the one committed by the `Waypoint`.
     ///
     /// # Arguments
     ///
-    /// * `bytes: &[u8]` - A byte slice from which to create the `Waypoint`.
+    /// * `ledger_info: &LedgerInfo` - The ledger info to verify.
     ///
     /// # Returns
     ///
-    /// A `Result` which is `Ok` if the `Waypoint` could
-    /// be successfully created, and `Err` otherwise.
-    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, TypesError> {
-        let version = bytes.get_u64_le();
+    /// A `Result` which is `Ok` if the version and the hash of the
+    /// ledger info match the waypoint, and `Err` otherwise.
+    pub fn verify(&self, ledger_info: &LedgerInfo) -> anyhow::Result<()> {
+        ensure!(
+            ledger_info.version() == self.version,
+            "Waypoint version mismatch: waypoint version = {}, given version = {}",
+            self.version,
+            ledger_info.version()
+        );
+        let converter = Ledger2WaypointConverter::new(ledger_info);
+        ensure!(
+            converter.hash() == self.value,
+            "Waypoint value mismatch: waypoint value = {:x}, given value = {:x}",
+            self.value,
+            converter.hash()
+        );
 
-        let value = HashValue::from_slice(
-            bytes
-                .chunk()
-                .get(..HASH_LENGTH)
-                .ok_or_else(|| serde_error!("Waypoint", "Not enough data for value"))?,
-        )
-        .map_err(|e| serde_error!("Waypoint", e))?;
+        Ok(())
+    }
 
-        bytes.advance(HASH_LENGTH);
+    /// Checks whether the `Waypoint` value is the dummy one,
+    /// produced by `Waypoint::new_any` when the `waypoint`
+    /// feature is not enabled.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the value of the `Waypoint` is the dummy one, `false` otherwise.
+    pub fn is_dummy(&self) -> bool {
+        self.value == HashValue::default()
+    }
 
-        if bytes.remaining() != 0 {
+    /// Validates that the `Waypoint` can be used as a root of trust.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Err` if the `waypoint` feature is enabled
+    /// and the `Waypoint` carries a dummy value, and `Ok` otherwise.
+    pub fn validate(&self) -> Result<(), TypesError> {
+        if cfg!(feature = "waypoint") && self.is_dummy() {
             return Err(serde_error!(
-                "TransactionInfo",
-                "Unexpected data after completing deserialization"
+                "Waypoint",
+                format!("Dummy value for waypoint at version {}", self.version)
             ));
         }
 
-        Ok(Self { version, value })
+        Ok(())
     }
 
-    ///. Converts the `Waypoint` to a byte vector.
+    /// Creates a `Waypoint` from a byte slice.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes: &[u8]` - A byte slice from which to create the `Waypoint`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the `Waypoint` could
+    /// be successfully created, and `Err` otherwise.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypesError> {
+        codec::from_bytes("Waypoint", bytes)
+    }
+
+    /// Converts the `Waypoint` to a byte vector.
     ///
     /// # Returns
     ///
     /// A `Vec<u8>` representing the `Waypoint`.
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = BytesMut::new();
-        bytes.put_u64_le(self.version);
-        bytes.put_slice(self.value.as_ref());
-        bytes.to_vec()
+        codec::to_bytes(self)
+    }
+}
+
+impl fmt::Display for Waypoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{:x}", self.version, self.value)
+    }
+}
+
+impl FromStr for Waypoint {
+    type Err = TypesError;
+
+    /// Parses a `Waypoint` from its canonical `version:hex_hash` representation,
+    /// as published by the Aptos operators.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (version, value) = s.split_once(':').ok_or_else(|| {
+            serde_error!("Waypoint", "Expected a waypoint formatted as version:hash")
+        })?;
+
+        let version = Version::from_str(version).map_err(|e| serde_error!("Waypoint", e))?;
+        let value =
+            HashValue::from_slice(hex::decode(value).map_err(|e| serde_error!("Waypoint", e))?)
+                .map_err(|e| serde_error!("Waypoint", e))?;
+
+        let waypoint = Self { version, value };
+        waypoint.validate()?;
+
+        Ok(waypoint)
     }
 }
 
@@ -118,6 +184,11 @@ impl<'de> Deserialize<'de> for Waypoint {
     where
         D: Deserializer<'de>,
     {
+        if deserializer.is_human_readable() {
+            let s = <String>::deserialize(deserializer)?;
+            return Waypoint::from_str(&s).map_err(D::Error::custom);
+        }
+
         #[derive(::serde::Deserialize)]
         #[serde(rename = "Waypoint")]
         struct Value(Version, HashValue);
@@ -135,6 +206,10 @@ impl Serialize for Waypoint {
     where
         S: Serializer,
     {
+        if serializer.is_human_readable() {
+            return serializer.serialize_str(&self.to_string());
+        }
+
         serializer.serialize_newtype_struct("Waypoint", &(self.version, self.value))
     }
 }
@@ -159,7 +234,6 @@ impl Ledger2WaypointConverter {
     /// # Returns
     ///
     /// A new `Ledger2WaypointConverter`.
-    #[allow(dead_code)]
     pub(crate) fn new(ledger_info: &LedgerInfo) -> Self {
         Self {
             epoch: ledger_info.epoch(),
@@ -176,21 +250,7 @@ impl Ledger2WaypointConverter {
     ///
     /// A `Vec<u8>` representing the `Ledger2WaypointConverter`.
     fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = BytesMut::new();
-        bytes.put_u64_le(self.epoch);
-        bytes.put_slice(self.root_hash.as_ref());
-        bytes.put_u64_le(self.version);
-        bytes.put_u64_le(self.timestamp_usecs);
-        match &self.next_epoch_state {
-            Some(state) => {
-                bytes.put_u8(1); // Indicate that there is a next_epoch_state
-                bytes.put_slice(&state.to_bytes());
-            }
-            None => {
-                bytes.put_u8(0); // Indicate that there is no next_epoch_state
-            }
-        }
-        bytes.to_vec()
+        codec::to_bytes(self)
     }
 }
 
@@ -226,6 +286,43 @@ mod test {
         assert_eq!(expected, actual.hash());
     }
 
+    #[test]
+    fn test_waypoint_str_conversion() {
+        use serde::de::value::{Error as ValueError, StrDeserializer};
+        use serde::de::IntoDeserializer;
+
+        let waypoint = Waypoint {
+            version: 42,
+            value: HashValue::new([0xab; HASH_LENGTH]),
+        };
+        let waypoint_str = format!("42:{}", "ab".repeat(HASH_LENGTH));
+
+        assert_eq!(waypoint.to_string(), waypoint_str);
+        assert_eq!(Waypoint::from_str(&waypoint_str).unwrap(), waypoint);
+
+        let deserializer: StrDeserializer<'_, ValueError> =
+            waypoint_str.as_str().into_deserializer();
+        assert_eq!(Waypoint::deserialize(deserializer).unwrap(), waypoint);
+
+        for invalid in [
+            "42",
+            "version:abab",
+            "42:abab",
+            &format!("42:{}", "zz".repeat(HASH_LENGTH)),
+        ] {
+            assert!(Waypoint::from_str(invalid).is_err());
+        }
+
+        let dummy_waypoint_str = format!("42:{}", "00".repeat(HASH_LENGTH));
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "waypoint")] {
+                assert!(Waypoint::from_str(&dummy_waypoint_str).is_err());
+            } else {
+                assert!(Waypoint::from_str(&dummy_waypoint_str).unwrap().is_dummy());
+            }
+        }
+    }
+
     #[cfg(feature = "aptos")]
     mod aptos {
         use proptest::prelude::ProptestConfig;
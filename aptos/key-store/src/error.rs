@@ -0,0 +1,21 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+use thiserror::Error;
+
+/// The error type for errors being thrown while storing or loading program keys.
+#[derive(Debug, Error)]
+pub enum KeyStoreError {
+    #[error("Error while accessing keys at {path}: {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: Box<dyn std::error::Error + Sync + Send>,
+    },
+    #[error("Error while serializing keys at {path}: {source}")]
+    Serialization {
+        path: String,
+        #[source]
+        source: Box<dyn std::error::Error + Sync + Send>,
+    },
+}
@@ -0,0 +1,190 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! # Key store
+//!
+//! On-disk store for the proving and verifying keys of the Sphinx programs of
+//! the light clients, shared by their proof servers. Keys are stored under the
+//! hash of the ELF of their program and the version of their format, so that
+//! they are only generated again when the program or the prover changes.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::{info, warn};
+use sha2::{Digest, Sha256};
+use sphinx_prover::types::HashableKey;
+use sphinx_sdk::{ProverClient, SphinxProvingKey, SphinxVerifyingKey};
+
+pub use crate::error::KeyStoreError;
+
+mod error;
+
+/// Extension of the files holding the keys of a program.
+const KEYS_FILE_EXTENSION: &str = "keys";
+
+/// Version of the format of the stored keys, to be bumped whenever the Sphinx
+/// prover is upgraded, as its keys can differ for the same ELF.
+pub const KEYS_FORMAT_VERSION: u32 = 1;
+
+/// On-disk store of the proving and verifying keys of the programs.
+#[derive(Debug, Clone)]
+pub struct KeyStore {
+    dir: PathBuf,
+}
+
+impl KeyStore {
+    /// Creates a new `KeyStore`, creating its directory if needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory the keys are stored in.
+    ///
+    /// # Returns
+    ///
+    /// A new `KeyStore`.
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self, KeyStoreError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).map_err(|err| KeyStoreError::Io {
+            path: dir.display().to_string(),
+            source: err.into(),
+        })?;
+
+        Ok(Self { dir })
+    }
+
+    /// Returns the keys of a program, loading them from the store when
+    /// they were already generated for its ELF, and generating and storing
+    /// them otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client used to generate the keys.
+    /// * `program` - The ELF of the program.
+    ///
+    /// # Returns
+    ///
+    /// The proving and verifying keys of the program.
+    pub fn setup(
+        &self,
+        client: &ProverClient,
+        program: &[u8],
+    ) -> Result<(SphinxProvingKey, SphinxVerifyingKey), KeyStoreError> {
+        let path = self.keys_path(program);
+
+        if path.exists() {
+            match Self::load(&path) {
+                Ok(keys) => {
+                    info!("Loaded program keys from {}", path.display());
+                    return Ok(keys);
+                }
+                Err(err) => warn!("Regenerating program keys, could not load them: {err}"),
+            }
+        }
+
+        info!("Generating program keys for {}", path.display());
+        let keys = client.setup(program);
+        Self::store(&path, &keys)?;
+
+        Ok(keys)
+    }
+
+    /// Returns the path of the keys of a program, named after the version of
+    /// their format and the hash of its ELF.
+    fn keys_path(&self, program: &[u8]) -> PathBuf {
+        let elf_hash = hex::encode(Sha256::digest(program));
+        self.dir
+            .join(format!("v{KEYS_FORMAT_VERSION}-{elf_hash}"))
+            .with_extension(KEYS_FILE_EXTENSION)
+    }
+
+    fn load(path: &Path) -> Result<(SphinxProvingKey, SphinxVerifyingKey), KeyStoreError> {
+        let bytes = fs::read(path).map_err(|err| KeyStoreError::Io {
+            path: path.display().to_string(),
+            source: err.into(),
+        })?;
+
+        bincode::deserialize(&bytes).map_err(|err| KeyStoreError::Serialization {
+            path: path.display().to_string(),
+            source: err.into(),
+        })
+    }
+
+    fn store(
+        path: &Path,
+        keys: &(SphinxProvingKey, SphinxVerifyingKey),
+    ) -> Result<(), KeyStoreError> {
+        let bytes = bincode::serialize(keys).map_err(|err| KeyStoreError::Serialization {
+            path: path.display().to_string(),
+            source: err.into(),
+        })?;
+
+        // Write to a temporary file first so that concurrent servers never load partial keys
+        let tmp_path = path.with_extension(format!("{KEYS_FILE_EXTENSION}.{}", std::process::id()));
+        fs::write(&tmp_path, bytes)
+            .and_then(|_| fs::rename(&tmp_path, path))
+            .map_err(|err| KeyStoreError::Io {
+                path: path.display().to_string(),
+                source: err.into(),
+            })
+    }
+}
+
+/// Returns the hash of a verifying key, as expected by the verifier contracts.
+///
+/// # Arguments
+///
+/// * `vk` - The verifying key.
+///
+/// # Returns
+///
+/// The hex encoded hash of the verifying key.
+pub fn verifying_key_hash(vk: &SphinxVerifyingKey) -> String {
+    vk.bytes32()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_keys_path() {
+        let key_store = KeyStore {
+            dir: PathBuf::from("keys"),
+        };
+
+        let path = key_store.keys_path(b"program");
+        assert_eq!(path.parent(), Some(Path::new("keys")));
+        assert_eq!(path.extension().unwrap(), KEYS_FILE_EXTENSION);
+        // Keys are named after the ELF, so that another program never loads them
+        assert_eq!(key_store.keys_path(b"program"), path);
+        assert_ne!(key_store.keys_path(b"other program"), path);
+        // Keys of another format are never loaded either
+        assert!(path
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with(&format!("v{KEYS_FORMAT_VERSION}-")));
+    }
+
+    #[test]
+    fn test_load_invalid_keys() {
+        let dir = std::env::temp_dir().join(format!("lc-key-store-{}", std::process::id()));
+        let key_store = KeyStore::new(&dir).unwrap();
+        assert!(dir.is_dir());
+
+        let path = key_store.keys_path(b"program");
+        assert!(matches!(
+            KeyStore::load(&path),
+            Err(KeyStoreError::Io { .. })
+        ));
+
+        fs::write(&path, b"not keys").unwrap();
+        assert!(matches!(
+            KeyStore::load(&path),
+            Err(KeyStoreError::Serialization { .. })
+        ));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
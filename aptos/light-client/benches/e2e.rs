@@ -18,18 +18,19 @@
 //!
 //! For more information on the Light Client design, its programs and the predicates used in this
 //! benchmark, please refer to the [documentation](../../docs/src/benchmark/overview.md).
-use crate::ProofType::{Snark, Stark};
+use aptos_lc::epoch_change::{EpochChangeIn, EpochChangeOut, EpochChangeProver};
 use aptos_lc::inclusion::{
-    SparseMerkleProofAssets, TransactionProofAssets, ValidatorVerifierAssets,
+    InclusionIn, InclusionOut, InclusionProver, SparseMerkleProofAssets, TransactionProofAssets,
+    ValidatorVerifierAssets,
 };
+use aptos_lc::prover::{Prover, ProvingMode};
 use aptos_lc_core::aptos_test_utils::wrapper::{AptosWrapper, ExecuteBlockArgs};
 use aptos_lc_core::crypto::hash::{CryptoHash, HashValue};
 use aptos_lc_core::types::trusted_state::{EpochChangeProof, TrustedState, TrustedStateChange};
-use aptos_lc_core::types::validator::ValidatorVerifier;
+use aptos_lc_core::types::validator::{PreparedValidatorVerifier, ValidatorVerifier};
 use serde::Serialize;
 use sphinx_sdk::artifacts::try_install_plonk_bn254_artifacts;
 use sphinx_sdk::utils::setup_logger;
-use sphinx_sdk::{ProverClient, SphinxProofWithPublicValues, SphinxStdin};
 use std::env;
 use std::time::Instant;
 
@@ -49,12 +50,6 @@ struct BenchmarkResults {
     inclusion_proof: Timings,
 }
 
-enum ProofType {
-    #[allow(dead_code)]
-    Snark(SphinxProofWithPublicValues),
-    Stark(SphinxProofWithPublicValues),
-}
-
 fn main() {
     // First we set the stark environment.
     set_stark_environment();
@@ -69,7 +64,9 @@ fn main() {
         TrustedState::EpochState { epoch_state, .. } => epoch_state.verifier().clone(),
         _ => panic!("expected epoch state"),
     };
-    let validator_verifier_hash = validator_verifier.hash();
+    let validator_verifier_hash = PreparedValidatorVerifier::new(validator_verifier)
+        .unwrap()
+        .hash();
     let trusted_state_version = *aptos_wrapper.current_version();
 
     // Simulate traffic to generate a new block.
@@ -79,45 +76,39 @@ fn main() {
     let state_proof = aptos_wrapper
         .new_state_proof(trusted_state_version)
         .unwrap();
-    let aptos_epoch_change_proof = &bcs::to_bytes(state_proof.epoch_changes()).unwrap();
+    let aptos_epoch_change_proof = bcs::to_bytes(state_proof.epoch_changes()).unwrap();
+
+    // Instantiate the provers, owning the keys of their program.
+    let epoch_change_prover = EpochChangeProver::new();
+    let inclusion_prover = InclusionProver::new();
 
-    // Instantiate prover client.
-    let prover_client = ProverClient::new();
+    let (validator_verifier, expected_hash) =
+        verify_and_ratchet_with_hash(&trusted_state, &aptos_epoch_change_proof);
+    let epoch_change_inputs = EpochChangeIn::new(trusted_state, aptos_epoch_change_proof);
 
     // Execute stark generation for epoch change.
+    setup_logger();
     let start_epoch_change_stark_proving = Instant::now();
-    let mut epoch_change_proof = if let Stark(epoch_change_proof) = prove_epoch_change(
-        &prover_client,
-        &trusted_state,
-        aptos_epoch_change_proof,
-        false,
-    ) {
-        epoch_change_proof
-    } else {
-        panic!("Expected Stark proof for epoch change")
-    };
+    let epoch_change_proof = epoch_change_prover
+        .prove(&epoch_change_inputs, ProvingMode::STARK)
+        .unwrap();
     let epoch_change_stark_proving_time = start_epoch_change_stark_proving.elapsed();
 
-    let prev_validator_verifier_hash: [u8; 32] = epoch_change_proof.public_values.read();
+    let epoch_change_output = EpochChangeOut::from(&mut epoch_change_proof.public_values());
 
     // Verify that the epoch change program produces the expected validator verifier hash.
     // This verifies validator consistency required by P2.
     assert_eq!(
-        &prev_validator_verifier_hash,
-        validator_verifier_hash.as_ref(),
+        epoch_change_output.prev_validator_verifier_hash(),
+        validator_verifier_hash,
         "The output for the previous validator verifier hash is not the expected one for the Epoch Change program."
     );
 
-    let new_validator_verifier_hash: [u8; 32] = epoch_change_proof.public_values.read();
-
-    let (validator_verifier, expected_hash) =
-        verify_and_ratchet_with_hash(&trusted_state, aptos_epoch_change_proof);
-
     // Assert the correct validator verifier hash against out-of-circuit computation
     // after ratcheting.
     assert_eq!(
-        &new_validator_verifier_hash,
-        expected_hash.as_ref(),
+        epoch_change_output.new_validator_verifier_hash(),
+        expected_hash,
         "Validator verifier hash mismatch with previously known one"
     );
 
@@ -134,14 +125,14 @@ fn main() {
     // Serialize and prepare merkle and accumulator proofs for the transaction and its inclusion in the ledger
     let sparse_merkle_proof = bcs::to_bytes(proof_assets.state_proof()).unwrap();
     let key: [u8; 32] = *proof_assets.key().as_ref();
-    let element_hash: [u8; 32] = *proof_assets.state_value_hash().unwrap().as_ref();
+    let element_value = proof_assets.state_value_bytes().unwrap();
 
     let transaction = bcs::to_bytes(&proof_assets.transaction()).unwrap();
     let transaction_proof = bcs::to_bytes(&proof_assets.transaction_proof()).unwrap();
     let latest_li = aptos_wrapper.get_latest_li_bytes().unwrap();
 
     let sparse_merkle_proof_assets =
-        SparseMerkleProofAssets::new(sparse_merkle_proof, key, element_hash);
+        SparseMerkleProofAssets::new(sparse_merkle_proof, key, None, element_value);
 
     let transaction_proof_assets = TransactionProofAssets::new(
         transaction,
@@ -150,43 +141,48 @@ fn main() {
         latest_li,
     );
 
-    let validator_verifier_assets = ValidatorVerifierAssets::new(validator_verifier.to_bytes());
+    let validator_verifier_assets = ValidatorVerifierAssets::new(
+        PreparedValidatorVerifier::new(validator_verifier)
+            .unwrap()
+            .to_bytes(),
+    );
+
+    // Do not commit the execution status, decode the leaf value nor check the ledger info
+    // freshness.
+    let inclusion_inputs = InclusionIn::new(
+        sparse_merkle_proof_assets,
+        transaction_proof_assets,
+        validator_verifier_assets,
+        false,
+        None,
+        None,
+    );
 
     // Execute stark generation for an account being included
     // in the state.
     // The verification of the proofs in the program ensures the
     // account inclusion required by P3.
     let start_inclusion_stark_proving = Instant::now();
-    let mut inclusion_proof = if let Stark(inclusion_proof) = prove_inclusion(
-        &prover_client,
-        &sparse_merkle_proof_assets,
-        &transaction_proof_assets,
-        &validator_verifier_assets,
-        false,
-    ) {
-        inclusion_proof
-    } else {
-        panic!("Expected Stark proof for inclusion")
-    };
-
+    let inclusion_proof = inclusion_prover
+        .prove(&inclusion_inputs, ProvingMode::STARK)
+        .unwrap();
     let inclusion_stark_proving_time = start_inclusion_stark_proving.elapsed();
-    let output_validator_hash: [u8; 32] = inclusion_proof.public_values.read();
+
+    let inclusion_output = InclusionOut::from(&mut inclusion_proof.public_values());
 
     // Verify the consistency of the validator verifier hash post-merkle proof.
     // This verifies the validator consistency required by P1.
     assert_eq!(
-        output_validator_hash,
-        new_validator_verifier_hash,
+        inclusion_output.validator_verifier_hash(),
+        &epoch_change_output.new_validator_verifier_hash(),
         "The output for the validator verifier hash is not the expected one for the Merkle program."
     );
 
-    let merkle_root_slice: [u8; 32] = inclusion_proof.public_values.read();
-
     // Verify the consistency of the final merkle root hash computed
     // by the program against the expected one.
     // This verifies P3 out-of-circuit.
     assert_eq!(
-        &merkle_root_slice,
+        inclusion_output.state_hash().as_ref(),
         proof_assets
             .transaction()
             .ensure_state_checkpoint_hash()
@@ -203,23 +199,12 @@ fn main() {
 
     // Execute snark generation for epoch change.
     let start_epoch_change_snark_proving = Instant::now();
-    let _ = prove_epoch_change(
-        &prover_client,
-        &trusted_state,
-        aptos_epoch_change_proof,
-        true,
-    );
+    let _ = epoch_change_prover.prove(&epoch_change_inputs, ProvingMode::SNARK);
     let epoch_change_snark_proving_time = start_epoch_change_snark_proving.elapsed();
 
     // Execute snark generation for an account being included in the state.
     let start_inclusion_snark_proving = Instant::now();
-    let _ = prove_inclusion(
-        &prover_client,
-        &sparse_merkle_proof_assets,
-        &transaction_proof_assets,
-        &validator_verifier_assets,
-        true,
-    );
+    let _ = inclusion_prover.prove(&inclusion_inputs, ProvingMode::SNARK);
     let inclusion_snark_proving_time = start_inclusion_snark_proving.elapsed();
 
     // Serialize and print the timing results for both proofs.
@@ -238,62 +223,6 @@ fn main() {
     println!("{}", json_output);
 }
 
-fn prove_epoch_change(
-    client: &ProverClient,
-    trusted_state: &[u8],
-    epoch_change_proof: &[u8],
-    snark: bool,
-) -> ProofType {
-    let mut stdin = SphinxStdin::new();
-
-    setup_logger();
-
-    stdin.write(&trusted_state);
-    stdin.write(&epoch_change_proof);
-
-    let (pk, _) = client.setup(aptos_programs::EPOCH_CHANGE_PROGRAM);
-
-    if snark {
-        Snark(client.prove(&pk, stdin).plonk().run().unwrap())
-    } else {
-        Stark(client.prove(&pk, stdin).run().unwrap())
-    }
-}
-
-fn prove_inclusion(
-    client: &ProverClient,
-    sparse_merkle_proof_assets: &SparseMerkleProofAssets,
-    transaction_proof_assets: &TransactionProofAssets,
-    validator_verifier_assets: &ValidatorVerifierAssets,
-    snark: bool,
-) -> ProofType {
-    let mut stdin = SphinxStdin::new();
-
-    setup_logger();
-
-    // Account inclusion input
-    stdin.write(sparse_merkle_proof_assets.sparse_merkle_proof());
-    stdin.write(sparse_merkle_proof_assets.leaf_key());
-    stdin.write(sparse_merkle_proof_assets.leaf_hash());
-
-    // Tx inclusion input
-    stdin.write(transaction_proof_assets.transaction());
-    stdin.write(transaction_proof_assets.transaction_index());
-    stdin.write(transaction_proof_assets.transaction_proof());
-    stdin.write(transaction_proof_assets.latest_li());
-
-    // Validator verifier
-    stdin.write(validator_verifier_assets.validator_verifier());
-
-    let (pk, _) = client.setup(aptos_programs::INCLUSION_PROGRAM);
-
-    if snark {
-        Snark(client.prove(&pk, stdin).plonk().run().unwrap())
-    } else {
-        Stark(client.prove(&pk, stdin).run().unwrap())
-    }
-}
-
 fn verify_and_ratchet_with_hash(
     trusted_state: &[u8],
     epoch_change_proof: &[u8],
@@ -317,7 +246,10 @@ fn verify_and_ratchet_with_hash(
                 .expect("Expected epoch state")
                 .verifier();
 
-            (validator_verifier.clone(), validator_verifier.hash())
+            let prepared_verifier = PreparedValidatorVerifier::new(validator_verifier.clone())
+                .expect("PreparedValidatorVerifier::new: could not prepare validator verifier");
+
+            (validator_verifier.clone(), prepared_verifier.hash())
         }
         _ => panic!("Expected epoch change"),
     }
@@ -16,50 +16,20 @@
 //! This benchmark aims to identify potential optimizations in the proving and verification process of epoch transitions
 //! within the Aptos blockchain.
 
-use anyhow::anyhow;
+use aptos_lc::epoch_change::{prepared_verifier, EpochChangeIn, EpochChangeOut, EpochChangeProver};
+use aptos_lc::prover::{ProofType, Prover, ProvingMode};
 use aptos_lc_core::aptos_test_utils::wrapper::AptosWrapper;
 use aptos_lc_core::crypto::hash::CryptoHash;
-use aptos_lc_core::types::trusted_state::TrustedState;
 use serde::Serialize;
-use sphinx_sdk::utils::setup_logger;
-use sphinx_sdk::{ProverClient, SphinxProofWithPublicValues, SphinxStdin};
 use std::env;
 use std::hint::black_box;
 use std::time::Instant;
 
 struct ProvingAssets {
     mode: ProvingMode,
-    client: ProverClient,
-    trusted_state: Vec<u8>,
+    prover: EpochChangeProver,
     validator_verifier_hash: Vec<u8>,
-    epoch_change_proof: Vec<u8>,
-}
-
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
-pub enum ProvingMode {
-    STARK,
-    SNARK,
-}
-
-impl From<ProvingMode> for String {
-    fn from(mode: ProvingMode) -> String {
-        match mode {
-            ProvingMode::STARK => "STARK".to_string(),
-            ProvingMode::SNARK => "SNARK".to_string(),
-        }
-    }
-}
-
-impl TryFrom<&str> for ProvingMode {
-    type Error = anyhow::Error;
-
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value {
-            "STARK" => Ok(ProvingMode::STARK),
-            "SNARK" => Ok(ProvingMode::SNARK),
-            _ => Err(anyhow!("Invalid proving mode")),
-        }
-    }
+    inputs: EpochChangeIn,
 }
 
 const NBR_VALIDATORS: usize = 130;
@@ -71,10 +41,10 @@ impl ProvingAssets {
         let mut aptos_wrapper = AptosWrapper::new(2, NBR_VALIDATORS, AVERAGE_SIGNERS_NBR).unwrap();
 
         let trusted_state = bcs::to_bytes(aptos_wrapper.trusted_state()).unwrap();
-        let validator_verifier_hash = match TrustedState::from_bytes(&trusted_state).unwrap() {
-            TrustedState::EpochState { epoch_state, .. } => epoch_state.verifier().hash().to_vec(),
-            _ => panic!("Expected epoch change for current trusted state"),
-        };
+        let validator_verifier_hash = prepared_verifier(&trusted_state)
+            .expect("Expected epoch change for current trusted state")
+            .hash()
+            .to_vec();
         let trusted_state_version = *aptos_wrapper.current_version();
 
         aptos_wrapper.generate_traffic().unwrap();
@@ -83,38 +53,26 @@ impl ProvingAssets {
             .new_state_proof(trusted_state_version)
             .unwrap();
 
-        let epoch_change_proof = &bcs::to_bytes(state_proof.epoch_changes()).unwrap();
+        let epoch_change_proof = bcs::to_bytes(state_proof.epoch_changes()).unwrap();
 
-        let client = ProverClient::new();
+        let prover = EpochChangeProver::new();
 
         Self {
             mode,
-            client,
-            trusted_state,
+            prover,
             validator_verifier_hash,
-            epoch_change_proof: epoch_change_proof.clone(),
+            inputs: EpochChangeIn::new(trusted_state, epoch_change_proof),
         }
     }
 
-    fn prove(&self) -> SphinxProofWithPublicValues {
-        let mut stdin = SphinxStdin::new();
-
-        setup_logger();
+    fn prove(&self) -> ProofType {
+        sphinx_sdk::utils::setup_logger();
 
-        stdin.write(&self.trusted_state);
-        stdin.write(&self.epoch_change_proof);
-
-        let (pk, _) = self.client.setup(aptos_programs::EPOCH_CHANGE_PROGRAM);
-
-        match self.mode {
-            ProvingMode::STARK => self.client.prove(&pk, stdin).run().unwrap(),
-            ProvingMode::SNARK => self.client.prove(&pk, stdin).plonk().run().unwrap(),
-        }
+        self.prover.prove(&self.inputs, self.mode).unwrap()
     }
 
-    fn verify(&self, proof: &SphinxProofWithPublicValues) {
-        let (_, vk) = self.client.setup(aptos_programs::EPOCH_CHANGE_PROGRAM);
-        self.client.verify(proof, &vk).expect("Verification failed");
+    fn verify(&self, proof: &ProofType) {
+        self.prover.verify(proof).expect("Verification failed");
     }
 }
 
@@ -132,15 +90,15 @@ fn main() {
     let proving_assets = ProvingAssets::new(mode);
 
     let start_proving = Instant::now();
-    let mut epoch_change_proof = proving_assets.prove();
+    let epoch_change_proof = proving_assets.prove();
     let proving_time = start_proving.elapsed();
 
     // Verify that the computed hash matches the expected validator verifier hash.
-    let prev_validator_verifier_hash = epoch_change_proof.public_values.read::<[u8; 32]>();
+    let output = EpochChangeOut::from(&mut epoch_change_proof.clone().public_values());
     // This verifies predicate consistency required by P2.
     assert_eq!(
-        prev_validator_verifier_hash,
-        proving_assets.validator_verifier_hash.as_slice()
+        output.prev_validator_verifier_hash().to_vec(),
+        proving_assets.validator_verifier_hash
     );
 
     // Benchmark the verification process.
@@ -0,0 +1,415 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! # Aggregation module
+//!
+//! This module contains the prover of the aggregation program, which
+//! verifies an epoch change proof and an inclusion proof in a single proof.
+//! The inclusion proof has to be signed by the validators of the epoch the
+//! epoch change proof ratchets to.
+
+use anyhow::Result;
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+use sphinx_prover::types::HashableKey;
+use sphinx_sdk::{
+    ProverClient, SphinxProof, SphinxProofWithPublicValues, SphinxProvingKey, SphinxPublicValues,
+    SphinxStdin, SphinxVerifyingKey,
+};
+
+use crate::epoch_change::{EpochChangeIn, EpochChangeOut, EpochChangeProver};
+use crate::error::{KeyStoreError, ProverError};
+use crate::inclusion::{InclusionIn, InclusionOut, InclusionProver};
+use crate::key_store::KeyStore;
+use crate::prover::{ProofType, Prover, ProvingMode};
+
+#[cfg(feature = "aptos")]
+pub fn setup_assets() -> (EpochChangeIn, InclusionIn) {
+    use crate::inclusion::{account_address, assets_from_wrapper};
+    use aptos_lc_core::aptos_test_utils::wrapper::AptosWrapper;
+    use aptos_lc_core::types::resource::ResourceKind;
+
+    const NBR_VALIDATORS: usize = 130;
+    const AVERAGE_SIGNERS_NBR: usize = 95;
+
+    let mut aptos_wrapper = AptosWrapper::new(500, NBR_VALIDATORS, AVERAGE_SIGNERS_NBR).unwrap();
+
+    let trusted_state = bcs::to_bytes(aptos_wrapper.trusted_state()).unwrap();
+    let trusted_state_version = *aptos_wrapper.current_version();
+
+    aptos_wrapper.generate_traffic().unwrap();
+
+    let state_proof = aptos_wrapper
+        .new_state_proof(trusted_state_version)
+        .unwrap();
+    let epoch_change_proof = bcs::to_bytes(state_proof.epoch_changes()).unwrap();
+
+    // The latest ledger info is signed by the validators of the new epoch
+    let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+        assets_from_wrapper(&aptos_wrapper, |aptos_wrapper| {
+            (
+                aptos_wrapper.get_latest_proof_account(400).unwrap(),
+                ResourceKind::Account.state_key(account_address(aptos_wrapper, 400)),
+            )
+        });
+
+    (
+        EpochChangeIn::new(trusted_state, epoch_change_proof),
+        InclusionIn::new(
+            sparse_merkle_proof_assets,
+            transaction_proof_assets,
+            validator_verifier_assets,
+            false,
+            None,
+            None,
+        ),
+    )
+}
+
+/// Generates the input of the aggregation program.
+///
+/// Both proofs have to be compressed STARK proofs. The program reads the
+/// digest of the verifying key and the public values of each proof, and
+/// verifies them against the proofs written to the proof stream.
+///
+/// # Arguments
+///
+/// * `epoch_change_proof` - The compressed epoch change proof.
+/// * `epoch_change_vk` - The verifying key of the epoch change program.
+/// * `inclusion_proof` - The compressed inclusion proof.
+/// * `inclusion_vk` - The verifying key of the inclusion program.
+///
+/// # Returns
+///
+/// The Sphinx stdin of the aggregation program.
+pub fn generate_stdin(
+    epoch_change_proof: &SphinxProofWithPublicValues,
+    epoch_change_vk: &SphinxVerifyingKey,
+    inclusion_proof: &SphinxProofWithPublicValues,
+    inclusion_vk: &SphinxVerifyingKey,
+) -> Result<SphinxStdin, ProverError> {
+    let mut stdin = SphinxStdin::new();
+
+    for (proof, vk) in [
+        (epoch_change_proof, epoch_change_vk),
+        (inclusion_proof, inclusion_vk),
+    ] {
+        let SphinxProof::Compressed(compressed_proof) = &proof.proof else {
+            return Err(ProverError::SphinxInput {
+                source: "aggregated proofs must be compressed".into(),
+            });
+        };
+
+        stdin.write(&vk.hash_u32());
+        stdin.write(&proof.public_values.to_vec());
+        stdin.write_proof(compressed_proof.clone(), vk.vk.clone());
+    }
+
+    Ok(stdin)
+}
+
+#[inline]
+pub fn generate_keys(client: &ProverClient) -> (SphinxProvingKey, SphinxVerifyingKey) {
+    client.setup(aptos_programs::AGGREGATION_PROGRAM)
+}
+
+/// The prover for the aggregation program.
+///
+/// It holds the provers of the epoch change and inclusion programs, used
+/// to generate the compressed proofs that are then aggregated.
+pub struct AggregationProver {
+    client: ProverClient,
+    keys: (SphinxProvingKey, SphinxVerifyingKey),
+    epoch_change_prover: EpochChangeProver,
+    inclusion_prover: InclusionProver,
+}
+
+impl Default for AggregationProver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AggregationProver {
+    /// Create a new `AggregationProver`.
+    ///
+    /// # Returns
+    ///
+    /// A new `AggregationProver`.
+    pub fn new() -> Self {
+        let client = ProverClient::new();
+        let keys = generate_keys(&client);
+
+        Self {
+            client,
+            keys,
+            epoch_change_prover: EpochChangeProver::new(),
+            inclusion_prover: InclusionProver::new(),
+        }
+    }
+
+    /// Create a new `AggregationProver`, loading the keys of all the programs
+    /// it proves from a `KeyStore`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_store` - The store holding the keys, generated and stored if missing.
+    ///
+    /// # Returns
+    ///
+    /// A new `AggregationProver`.
+    pub fn with_key_store(key_store: &KeyStore) -> Result<Self, KeyStoreError> {
+        let client = ProverClient::new();
+        let keys = key_store.setup(&client, aptos_programs::AGGREGATION_PROGRAM)?;
+
+        Ok(Self {
+            client,
+            keys,
+            epoch_change_prover: EpochChangeProver::with_key_store(key_store)?,
+            inclusion_prover: InclusionProver::with_key_store(key_store)?,
+        })
+    }
+
+    /// Gets a `SphinxVerifyingKey`.
+    ///
+    /// # Returns
+    ///
+    /// A `SphinxVerifyingKey` that can be used for verifying the aggregated proof.
+    pub const fn get_vk(&self) -> &SphinxVerifyingKey {
+        &self.keys.1
+    }
+}
+
+/// The input for the aggregated proof.
+#[derive(Debug, Clone, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct AggregationIn {
+    epoch_change_in: EpochChangeIn,
+    /// Input of an inclusion proof signed by the validators of the new epoch.
+    inclusion_in: InclusionIn,
+}
+
+impl AggregationIn {
+    /// Create a new `AggregationIn`.
+    ///
+    /// # Arguments
+    ///
+    /// * `epoch_change_in` - The input of the epoch change proof.
+    /// * `inclusion_in` - The input of the inclusion proof, for the epoch ratcheted to.
+    ///
+    /// # Returns
+    ///
+    /// A new `AggregationIn`.
+    pub const fn new(epoch_change_in: EpochChangeIn, inclusion_in: InclusionIn) -> Self {
+        Self {
+            epoch_change_in,
+            inclusion_in,
+        }
+    }
+}
+
+/// The output for the aggregated proof.
+#[derive(Debug, Clone, Eq, PartialEq, Getters)]
+#[getset(get = "pub")]
+pub struct AggregationOut {
+    /// Digest of the verifying key of the epoch change program.
+    epoch_change_vk_digest: [u32; 8],
+    /// Digest of the verifying key of the inclusion program.
+    inclusion_vk_digest: [u32; 8],
+    epoch_change: EpochChangeOut,
+    inclusion: InclusionOut,
+}
+
+impl From<&mut SphinxPublicValues> for AggregationOut {
+    fn from(public_values: &mut SphinxPublicValues) -> Self {
+        let epoch_change_vk_digest = public_values.read::<[u32; 8]>();
+        let inclusion_vk_digest = public_values.read::<[u32; 8]>();
+        let epoch_change = EpochChangeOut::from(&mut *public_values);
+        let inclusion = InclusionOut::from(&mut *public_values);
+
+        Self {
+            epoch_change_vk_digest,
+            inclusion_vk_digest,
+            epoch_change,
+            inclusion,
+        }
+    }
+}
+
+impl Prover for AggregationProver {
+    const PROGRAM: &'static [u8] = aptos_programs::AGGREGATION_PROGRAM;
+    type Error = ProverError;
+    type StdIn = AggregationIn;
+    type StdOut = AggregationOut;
+
+    /// Generates the compressed epoch change and inclusion proofs, and the
+    /// Sphinx stdin aggregating them.
+    fn generate_sphinx_stdin(&self, inputs: &Self::StdIn) -> Result<SphinxStdin, Self::Error> {
+        let epoch_change_proof = self
+            .epoch_change_prover
+            .prove_compressed(&inputs.epoch_change_in)?;
+        let inclusion_proof = self
+            .inclusion_prover
+            .prove_compressed(&inputs.inclusion_in)?;
+
+        generate_stdin(
+            &epoch_change_proof,
+            self.epoch_change_prover.get_vk(),
+            &inclusion_proof,
+            self.inclusion_prover.get_vk(),
+        )
+    }
+
+    fn execute(&self, inputs: &Self::StdIn) -> Result<Self::StdOut, Self::Error> {
+        sphinx_sdk::utils::setup_logger();
+
+        let stdin = self.generate_sphinx_stdin(inputs)?;
+
+        let (mut public_values, _) = self
+            .client
+            .execute(Self::PROGRAM, stdin)
+            .run()
+            .map_err(|err| ProverError::Execution { source: err.into() })?;
+
+        Ok(AggregationOut::from(&mut public_values))
+    }
+
+    fn prove(&self, inputs: &Self::StdIn, mode: ProvingMode) -> Result<ProofType, Self::Error> {
+        let stdin = self.generate_sphinx_stdin(inputs)?;
+
+        match mode {
+            ProvingMode::STARK => self
+                .client
+                .prove(&self.keys.0, stdin)
+                .run()
+                .map_err(|err| ProverError::Proving {
+                    proof_type: mode.into(),
+                    source: err.into(),
+                })
+                .map(ProofType::STARK),
+            ProvingMode::SNARK => self
+                .client
+                .prove(&self.keys.0, stdin)
+                .plonk()
+                .run()
+                .map_err(|err| ProverError::Proving {
+                    proof_type: mode.into(),
+                    source: err.into(),
+                })
+                .map(ProofType::SNARK),
+        }
+    }
+
+    fn verify(&self, proof: &ProofType) -> Result<(), Self::Error> {
+        self.client
+            .verify(proof.proof(), &self.keys.1)
+            .map_err(|err| ProverError::Verification { source: err.into() })
+    }
+}
+
+#[cfg(all(test, feature = "aptos"))]
+mod test {
+    use crate::aggregation::{setup_assets, AggregationIn, AggregationOut, AggregationProver};
+    use crate::prover::{Prover, ProvingMode};
+    use sphinx_prover::types::HashableKey;
+    use sphinx_sdk::artifacts::try_install_plonk_bn254_artifacts;
+
+    #[test]
+    #[ignore = "This test is too slow for CI"]
+    fn test_execute_aggregation() {
+        use std::time::Instant;
+
+        let (epoch_change_in, inclusion_in) = setup_assets();
+        let inputs = AggregationIn::new(epoch_change_in, inclusion_in);
+
+        let prover = AggregationProver::new();
+
+        println!("Starting execution of aggregation...");
+        let start = Instant::now();
+        let output = prover.execute(&inputs).unwrap();
+        println!("Execution took {:?}", start.elapsed());
+
+        assert_eq!(
+            output.epoch_change().new_validator_verifier_hash(),
+            *output.inclusion().validator_verifier_hash()
+        );
+        assert_eq!(output.epoch_change().epoch(), *output.inclusion().epoch());
+        assert_eq!(
+            *output.epoch_change_vk_digest(),
+            prover.epoch_change_prover.get_vk().hash_u32()
+        );
+        assert_eq!(
+            *output.inclusion_vk_digest(),
+            prover.inclusion_prover.get_vk().hash_u32()
+        );
+    }
+
+    #[test]
+    #[ignore = "This test is too slow for CI"]
+    fn test_execute_aggregation_rejects_tampered_inputs() {
+        use crate::epoch_change::EpochChangeIn;
+        use crate::inclusion::{account_address, assets_from_wrapper, InclusionIn};
+        use aptos_lc_core::aptos_test_utils::scenario::ScenarioBuilder;
+        use aptos_lc_core::types::resource::ResourceKind;
+
+        let mut scenario = ScenarioBuilder::new(4, 10).unwrap();
+        scenario.generate_traffic().unwrap();
+
+        // The inclusion proof is signed by the validators of the trusted epoch,
+        // not by the grown validator set the epoch change proof ratchets to
+        let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            assets_from_wrapper(scenario.aptos_wrapper(), |aptos_wrapper| {
+                (
+                    aptos_wrapper.get_latest_proof_account(0).unwrap(),
+                    ResourceKind::Account.state_key(account_address(aptos_wrapper, 0)),
+                )
+            });
+        scenario.add_validators(2, 10);
+        let (trusted_state, epoch_change_proof) = scenario.commit_epoch_changes(1).unwrap();
+
+        let inputs = AggregationIn::new(
+            EpochChangeIn::new(trusted_state, epoch_change_proof),
+            InclusionIn::new(
+                sparse_merkle_proof_assets,
+                transaction_proof_assets,
+                validator_verifier_assets,
+                false,
+                None,
+                None,
+            ),
+        );
+
+        let prover = AggregationProver::new();
+        assert!(prover.execute(&inputs).is_err());
+    }
+
+    #[test]
+    #[ignore = "This test is too slow for CI"]
+    fn test_snark_prove_aggregation() {
+        use std::time::Instant;
+
+        let (epoch_change_in, inclusion_in) = setup_assets();
+        let inputs = AggregationIn::new(epoch_change_in, inclusion_in);
+
+        let prover = AggregationProver::new();
+
+        // Install PLONK artifacts.
+        try_install_plonk_bn254_artifacts(false);
+
+        let start = Instant::now();
+        println!("Starting generation of aggregation proof...");
+        let snark_proof = prover.prove(&inputs, ProvingMode::SNARK).unwrap();
+        println!("Proving took {:?}", start.elapsed());
+
+        let start = Instant::now();
+        println!("Starting verification of aggregation proof...");
+        prover.verify(&snark_proof).unwrap();
+        println!("Verification took {:?}", start.elapsed());
+
+        let output = AggregationOut::from(&mut snark_proof.public_values());
+        assert_eq!(
+            output.epoch_change().new_validator_verifier_hash(),
+            *output.inclusion().validator_verifier_hash()
+        );
+    }
+}
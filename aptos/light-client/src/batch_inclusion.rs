@@ -0,0 +1,388 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use sphinx_sdk::{
+    ProverClient, SphinxProofWithPublicValues, SphinxProvingKey, SphinxStdin, SphinxVerifyingKey,
+};
+
+use crate::error::LightClientError;
+use crate::inclusion::{SparseMerkleProofAssets, TransactionProofAssets, ValidatorVerifierAssets};
+
+#[cfg(feature = "aptos")]
+pub fn setup_assets(
+    nbr_leaves: usize,
+) -> (
+    Vec<SparseMerkleProofAssets>,
+    TransactionProofAssets,
+    ValidatorVerifierAssets,
+) {
+    use aptos_lc_core::aptos_test_utils::wrapper::AptosWrapper;
+    use aptos_lc_core::types::resource::ResourceKind;
+    use aptos_lc_core::types::trusted_state::TrustedState;
+    use aptos_lc_core::types::validator::PreparedValidatorVerifier;
+    use aptos_lc_core::types::AccountAddress;
+
+    const NBR_VALIDATORS: usize = 130;
+    const AVERAGE_SIGNERS_NBR: usize = 95;
+
+    let mut aptos_wrapper = AptosWrapper::new(500, NBR_VALIDATORS, AVERAGE_SIGNERS_NBR).unwrap();
+    aptos_wrapper.generate_traffic().unwrap();
+
+    // All the proofs are generated for the latest version, so they share the same state checkpoint
+    let proofs_assets: Vec<_> = (0..nbr_leaves)
+        .map(|account_idx| {
+            let address = AccountAddress::from_bytes(
+                aptos_wrapper.accounts()[account_idx].address().as_ref(),
+            )
+            .unwrap();
+            (
+                aptos_wrapper.get_latest_proof_account(account_idx).unwrap(),
+                ResourceKind::Account.state_key(address),
+            )
+        })
+        .collect();
+
+    let sparse_merkle_proofs_assets = proofs_assets
+        .iter()
+        .map(|(proof_assets, state_key)| {
+            SparseMerkleProofAssets::new(
+                bcs::to_bytes(proof_assets.state_proof()).unwrap(),
+                *proof_assets.key().as_ref(),
+                Some(state_key.clone()),
+                proof_assets.state_value_bytes().unwrap(),
+            )
+        })
+        .collect();
+
+    let (proof_assets, _) = &proofs_assets[0];
+    let transaction = bcs::to_bytes(&proof_assets.transaction()).unwrap();
+    let transaction_proof = bcs::to_bytes(&proof_assets.transaction_proof()).unwrap();
+
+    let latest_li = aptos_wrapper.get_latest_li_bytes().unwrap();
+
+    let validator_verifier =
+        match TrustedState::from_bytes(&bcs::to_bytes(&aptos_wrapper.trusted_state()).unwrap())
+            .unwrap()
+        {
+            TrustedState::EpochState { epoch_state, .. } => epoch_state.verifier().clone(),
+            _ => panic!("expected epoch state"),
+        };
+
+    let transaction_proof_assets = TransactionProofAssets::new(
+        transaction,
+        *proof_assets.transaction_version(),
+        transaction_proof,
+        latest_li,
+    );
+
+    let validator_verifier_assets = ValidatorVerifierAssets::new(
+        PreparedValidatorVerifier::new(validator_verifier)
+            .unwrap()
+            .to_bytes(),
+    );
+
+    (
+        sparse_merkle_proofs_assets,
+        transaction_proof_assets,
+        validator_verifier_assets,
+    )
+}
+
+/// Generates the input of the batch inclusion program.
+///
+/// All the leaves are proven against the state checkpoint of the
+/// transaction in `transaction_proof_assets`, so that the transaction
+/// and signature verifications are only done once.
+pub fn generate_stdin(
+    sparse_merkle_proofs_assets: &[SparseMerkleProofAssets],
+    transaction_proof_assets: &TransactionProofAssets,
+    validator_verifier_assets: &ValidatorVerifierAssets,
+) -> SphinxStdin {
+    let mut stdin = SphinxStdin::new();
+    // Accounts inclusion input
+    let leaves: Vec<_> = sparse_merkle_proofs_assets
+        .iter()
+        .map(|sparse_merkle_proof_assets| {
+            (
+                sparse_merkle_proof_assets.sparse_merkle_proof(),
+                sparse_merkle_proof_assets.leaf_key(),
+                sparse_merkle_proof_assets.state_key(),
+                sparse_merkle_proof_assets.leaf_value(),
+            )
+        })
+        .collect();
+    stdin.write(&leaves);
+
+    // Tx inclusion input
+    stdin.write(transaction_proof_assets.transaction());
+    stdin.write(transaction_proof_assets.transaction_index());
+    stdin.write(transaction_proof_assets.transaction_proof());
+    stdin.write(transaction_proof_assets.latest_li());
+
+    // Validator verifier
+    stdin.write(validator_verifier_assets.validator_verifier());
+
+    stdin
+}
+
+#[inline]
+pub fn generate_keys(client: &ProverClient) -> (SphinxProvingKey, SphinxVerifyingKey) {
+    client.setup(aptos_programs::BATCH_INCLUSION_PROGRAM)
+}
+
+#[allow(dead_code)]
+struct BatchInclusionOutput {
+    validator_verifier_hash: [u8; 32],
+    state_hash: [u8; 32],
+    block_hash: [u8; 32],
+    nbr_leaves: u64,
+    /// Hash of the concatenated keys, flags, value hashes and state keys of the proven leaves.
+    leaves_hash: [u8; 32],
+}
+
+#[allow(dead_code)]
+fn prove_batch_inclusion(
+    client: &ProverClient,
+    sparse_merkle_proofs_assets: &[SparseMerkleProofAssets],
+    transaction_proof_assets: &TransactionProofAssets,
+    validator_verifier_assets: &ValidatorVerifierAssets,
+) -> Result<(SphinxProofWithPublicValues, BatchInclusionOutput), LightClientError> {
+    sphinx_sdk::utils::setup_logger();
+
+    let stdin = generate_stdin(
+        sparse_merkle_proofs_assets,
+        transaction_proof_assets,
+        validator_verifier_assets,
+    );
+    let (pk, _) = generate_keys(client);
+
+    let mut proof =
+        client
+            .prove(&pk, stdin)
+            .run()
+            .map_err(|err| LightClientError::ProvingError {
+                program: "prove-batch-inclusion".to_string(),
+                source: err.into(),
+            })?;
+
+    // Read output.
+    let validator_verifier_hash = proof.public_values.read::<[u8; 32]>();
+    let state_hash = proof.public_values.read::<[u8; 32]>();
+    let block_hash = proof.public_values.read::<[u8; 32]>();
+    let nbr_leaves = proof.public_values.read::<u64>();
+    let leaves_hash = proof.public_values.read::<[u8; 32]>();
+
+    Ok((
+        proof,
+        BatchInclusionOutput {
+            validator_verifier_hash,
+            state_hash,
+            block_hash,
+            nbr_leaves,
+            leaves_hash,
+        },
+    ))
+}
+
+#[cfg(all(test, feature = "aptos"))]
+mod test {
+    use crate::batch_inclusion::{generate_stdin, setup_assets};
+    use crate::error::LightClientError;
+    use crate::inclusion::SparseMerkleProofAssets;
+    use aptos_lc_core::crypto::hash::{hash_data, CryptoHash};
+    use aptos_lc_core::types::state_value::StateValue;
+    use aptos_lc_core::types::utils::{INCLUSION_PRESENT_FLAG, INCLUSION_STATE_KEY_FLAG};
+    use sphinx_sdk::utils::setup_logger;
+    use sphinx_sdk::ProverClient;
+
+    const NBR_LEAVES: usize = 10;
+
+    fn leaves_hash(sparse_merkle_proofs_assets: &[SparseMerkleProofAssets]) -> [u8; 32] {
+        let leaves: Vec<([u8; 32], [u8; 1], [u8; 32], Vec<u8>)> = sparse_merkle_proofs_assets
+            .iter()
+            .map(|sparse_merkle_proof_assets| {
+                let value_hash = sparse_merkle_proof_assets
+                    .leaf_value()
+                    .as_ref()
+                    .map(|value| *StateValue::from_bytes(value).unwrap().hash().as_ref());
+                let mut flags = 0;
+                if value_hash.is_some() {
+                    flags |= INCLUSION_PRESENT_FLAG;
+                }
+                let mut state_key_bytes = Vec::new();
+                if let Some(state_key) = sparse_merkle_proof_assets.state_key() {
+                    flags |= INCLUSION_STATE_KEY_FLAG;
+                    state_key_bytes.push(state_key.variant_index());
+                    state_key_bytes.extend_from_slice(&state_key.address().to_bytes());
+                    state_key_bytes.extend_from_slice(&state_key.identifier());
+                }
+                (
+                    *sparse_merkle_proof_assets.leaf_key(),
+                    [flags],
+                    value_hash.unwrap_or_default(),
+                    state_key_bytes,
+                )
+            })
+            .collect();
+
+        hash_data(
+            &[],
+            leaves
+                .iter()
+                .flat_map(|(key, flags, value_hash, state_key_bytes)| {
+                    [
+                        key.as_slice(),
+                        flags.as_slice(),
+                        value_hash.as_slice(),
+                        state_key_bytes.as_slice(),
+                    ]
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_execute_batch_inclusion() {
+        use std::time::Instant;
+
+        setup_logger();
+
+        let (sparse_merkle_proofs_assets, transaction_proof_assets, validator_verifier_assets) =
+            setup_assets(NBR_LEAVES);
+
+        let stdin = generate_stdin(
+            &sparse_merkle_proofs_assets,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+        );
+
+        println!("Starting execution of batch inclusion...");
+        let start = Instant::now();
+        let client = ProverClient::new();
+        let (mut public_values, _) = client
+            .execute(aptos_programs::BATCH_INCLUSION_PROGRAM, stdin)
+            .run()
+            .map_err(|err| LightClientError::ProvingError {
+                program: "prove-batch-inclusion".to_string(),
+                source: err.into(),
+            })
+            .unwrap();
+        println!("Execution took {:?}", start.elapsed());
+
+        // Skip the validator verifier hash, state hash and block hash
+        for _ in 0..3 {
+            public_values.read::<[u8; 32]>();
+        }
+        assert_eq!(public_values.read::<u64>(), NBR_LEAVES as u64);
+        assert_eq!(
+            public_values.read::<[u8; 32]>(),
+            leaves_hash(&sparse_merkle_proofs_assets)
+        );
+    }
+
+    #[test]
+    fn test_execute_batch_inclusion_rejects_tampered_inputs() {
+        use crate::inclusion::TransactionProofAssets;
+        use aptos_lc_core::aptos_test_utils::scenario::{
+            forge_ledger_info, truncate_sparse_merkle_proof, LedgerInfoForgery,
+        };
+
+        let (sparse_merkle_proofs_assets, transaction_proof_assets, validator_verifier_assets) =
+            setup_assets(NBR_LEAVES);
+
+        // Only the last leaf of the batch is tampered with
+        let mut truncated_sparse_merkle_proofs_assets = sparse_merkle_proofs_assets.clone();
+        let last_leaf = truncated_sparse_merkle_proofs_assets.pop().unwrap();
+        truncated_sparse_merkle_proofs_assets.push(SparseMerkleProofAssets::new(
+            truncate_sparse_merkle_proof(last_leaf.sparse_merkle_proof()).unwrap(),
+            *last_leaf.leaf_key(),
+            last_leaf.state_key().clone(),
+            last_leaf.leaf_value().clone(),
+        ));
+
+        let forged_transaction_proof_assets = TransactionProofAssets::new(
+            transaction_proof_assets.transaction().clone(),
+            *transaction_proof_assets.transaction_index(),
+            transaction_proof_assets.transaction_proof().clone(),
+            forge_ledger_info(
+                transaction_proof_assets.latest_li(),
+                LedgerInfoForgery::Timestamp,
+            )
+            .unwrap(),
+        );
+        let wrong_index_transaction_proof_assets = TransactionProofAssets::new(
+            transaction_proof_assets.transaction().clone(),
+            *transaction_proof_assets.transaction_index() - 1,
+            transaction_proof_assets.transaction_proof().clone(),
+            transaction_proof_assets.latest_li().clone(),
+        );
+
+        let client = ProverClient::new();
+        for (case, sparse_merkle_proofs_assets, transaction_proof_assets) in [
+            (
+                "truncated sparse merkle proof",
+                &truncated_sparse_merkle_proofs_assets,
+                &transaction_proof_assets,
+            ),
+            (
+                "forged ledger info",
+                &sparse_merkle_proofs_assets,
+                &forged_transaction_proof_assets,
+            ),
+            (
+                "wrong transaction index",
+                &sparse_merkle_proofs_assets,
+                &wrong_index_transaction_proof_assets,
+            ),
+        ] {
+            let stdin = generate_stdin(
+                sparse_merkle_proofs_assets,
+                transaction_proof_assets,
+                &validator_verifier_assets,
+            );
+            assert!(
+                client
+                    .execute(aptos_programs::BATCH_INCLUSION_PROGRAM, stdin)
+                    .run()
+                    .is_err(),
+                "{case} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    #[ignore = "This test is too slow for CI"]
+    fn test_prove_batch_inclusion() {
+        use super::*;
+        use std::time::Instant;
+
+        let client = ProverClient::new();
+
+        let (sparse_merkle_proofs_assets, transaction_proof_assets, validator_verifier_assets) =
+            setup_assets(NBR_LEAVES);
+
+        let start = Instant::now();
+        println!("Starting generation of batch inclusion proof...");
+        let (proof, output) = prove_batch_inclusion(
+            &client,
+            &sparse_merkle_proofs_assets,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+        )
+        .unwrap();
+        println!("Proving took {:?}", start.elapsed());
+
+        assert_eq!(output.nbr_leaves, NBR_LEAVES as u64);
+        assert_eq!(
+            output.leaves_hash,
+            leaves_hash(&sparse_merkle_proofs_assets)
+        );
+
+        let (_, vk) = generate_keys(&client);
+        let start = Instant::now();
+        println!("Starting verification of batch inclusion proof...");
+        client.verify(&proof, &vk).unwrap();
+        println!("Verification took {:?}", start.elapsed());
+    }
+}
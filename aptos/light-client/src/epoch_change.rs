@@ -2,20 +2,30 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::Result;
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
 use sphinx_sdk::{
-    ProverClient, SphinxProofWithPublicValues, SphinxProvingKey, SphinxStdin, SphinxVerifyingKey,
+    ProverClient, SphinxProofWithPublicValues, SphinxProvingKey, SphinxPublicValues, SphinxStdin,
+    SphinxVerifyingKey,
 };
 
-use crate::error::LightClientError;
+use crate::error::{KeyStoreError, ProverError};
+use crate::key_store::KeyStore;
+use crate::prover::{ProofType, Prover, ProvingMode, COMPRESSED_PROOF_TYPE};
+use aptos_lc_core::crypto::hash::HashValue;
+use aptos_lc_core::types::trusted_state::TrustedState;
+use aptos_lc_core::types::utils::EPOCH_CHANGE_FROM_WAYPOINT;
+use aptos_lc_core::types::validator::PreparedValidatorVerifier;
 
-#[allow(dead_code)]
-struct EpochChangeOutput {
-    prev_validator_verifier_hash: [u8; 32],
-    new_validator_verifier_hash: [u8; 32],
+#[cfg(feature = "aptos")]
+pub fn setup_assets() -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+    setup_assets_for(1)
 }
 
+/// Generates the assets for an epoch change proof walking `epoch_changes`
+/// consecutive epoch transitions from the current trusted state.
 #[cfg(feature = "aptos")]
-pub fn setup_assets() -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+pub fn setup_assets_for(epoch_changes: usize) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
     use aptos_lc_core::aptos_test_utils::wrapper::AptosWrapper;
     use aptos_lc_core::crypto::hash::CryptoHash;
 
@@ -25,17 +35,16 @@ pub fn setup_assets() -> (Vec<u8>, Vec<u8>, Vec<u8>) {
     let mut aptos_wrapper = AptosWrapper::new(20000, NBR_VALIDATORS, AVERAGE_SIGNERS_NBR).unwrap();
 
     let trusted_state = bcs::to_bytes(aptos_wrapper.trusted_state()).unwrap();
-    let validator_verifier_hash =
-        match aptos_lc_core::types::trusted_state::TrustedState::from_bytes(&trusted_state).unwrap()
-        {
-            aptos_lc_core::types::trusted_state::TrustedState::EpochState {
-                epoch_state, ..
-            } => epoch_state.verifier().hash().to_vec(),
-            _ => panic!("Expected epoch change for current trusted state"),
-        };
+    let validator_verifier_hash = prepared_verifier(&trusted_state)
+        .expect("Expected epoch change for current trusted state")
+        .hash()
+        .to_vec();
     let trusted_state_version = *aptos_wrapper.current_version();
 
     aptos_wrapper.generate_traffic().unwrap();
+    for _ in 1..epoch_changes {
+        aptos_wrapper.commit_new_epoch().unwrap();
+    }
 
     let state_proof = aptos_wrapper
         .new_state_proof(trusted_state_version)
@@ -46,10 +55,74 @@ pub fn setup_assets() -> (Vec<u8>, Vec<u8>, Vec<u8>) {
     (trusted_state, epoch_change_proof, validator_verifier_hash)
 }
 
+#[cfg(all(feature = "aptos", feature = "waypoint"))]
+pub fn setup_waypoint_assets() -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+    use aptos_lc_core::aptos_test_utils::wrapper::AptosWrapper;
+    use aptos_lc_core::types::trusted_state::EpochChangeProof;
+    use aptos_lc_core::types::waypoint::Waypoint;
+
+    const NBR_VALIDATORS: usize = 130;
+    const AVERAGE_SIGNERS_NBR: usize = 95;
+
+    let mut aptos_wrapper = AptosWrapper::new(20000, NBR_VALIDATORS, AVERAGE_SIGNERS_NBR).unwrap();
+
+    let trusted_state_version = *aptos_wrapper.current_version();
+
+    aptos_wrapper.generate_traffic().unwrap();
+
+    let state_proof = aptos_wrapper
+        .new_state_proof(trusted_state_version)
+        .unwrap();
+
+    let epoch_change_proof = bcs::to_bytes(state_proof.epoch_changes()).unwrap();
+
+    // Bootstrap from the waypoint committing to the first epoch change ledger info
+    let waypoint = Waypoint::new_any(
+        EpochChangeProof::from_bytes(&epoch_change_proof)
+            .unwrap()
+            .ledger_info_with_sigs
+            .first()
+            .expect("Expected an epoch change ledger info")
+            .ledger_info(),
+    );
+    let trusted_state = TrustedState::EpochWaypoint(waypoint).to_bytes();
+
+    (trusted_state, epoch_change_proof, waypoint.value().to_vec())
+}
+
+/// Prepares the validator verifier of a trusted state, when the
+/// trusted state is an epoch state.
+///
+/// # Arguments
+///
+/// * `trusted_state` - The bytes of the trusted state.
+///
+/// # Returns
+///
+/// The `PreparedValidatorVerifier` of the trusted epoch, or `None` if the
+/// trusted state is an epoch waypoint or could not be prepared.
+pub fn prepared_verifier(trusted_state: &[u8]) -> Option<PreparedValidatorVerifier> {
+    match TrustedState::from_bytes(trusted_state).ok()? {
+        TrustedState::EpochState { epoch_state, .. } => {
+            PreparedValidatorVerifier::new(epoch_state.verifier().clone()).ok()
+        }
+        TrustedState::EpochWaypoint(_) => None,
+    }
+}
+
+/// Generates the input of the epoch change program.
+///
+/// The validator verifier of the trusted epoch is prepared outside of
+/// the program, which then verifies the first epoch change without
+/// decompressing any public key.
 pub fn generate_stdin(current_trusted_state: &[u8], epoch_change_proof: &[u8]) -> SphinxStdin {
     let mut stdin = SphinxStdin::new();
     stdin.write(&current_trusted_state);
     stdin.write(&epoch_change_proof);
+    stdin.write(
+        &prepared_verifier(current_trusted_state)
+            .map(|prepared_verifier| prepared_verifier.to_bytes()),
+    );
     stdin
 }
 
@@ -58,138 +131,415 @@ pub fn generate_keys(client: &ProverClient) -> (SphinxProvingKey, SphinxVerifyin
     client.setup(aptos_programs::EPOCH_CHANGE_PROGRAM)
 }
 
-#[allow(dead_code)]
-fn prove_epoch_change(
-    client: &ProverClient,
-    current_trusted_state: &[u8],
-    epoch_change_proof: &[u8],
-) -> Result<(SphinxProofWithPublicValues, EpochChangeOutput), LightClientError> {
-    sphinx_sdk::utils::setup_logger();
+/// The prover for the epoch change program.
+pub struct EpochChangeProver {
+    client: ProverClient,
+    keys: (SphinxProvingKey, SphinxVerifyingKey),
+}
 
-    let stdin = generate_stdin(current_trusted_state, epoch_change_proof);
-    let (pk, _) = generate_keys(client);
+impl Default for EpochChangeProver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EpochChangeProver {
+    /// Create a new `EpochChangeProver`.
+    ///
+    /// # Returns
+    ///
+    /// A new `EpochChangeProver`.
+    pub fn new() -> Self {
+        let client = ProverClient::new();
+        let keys = generate_keys(&client);
+
+        Self { client, keys }
+    }
+
+    /// Create a new `EpochChangeProver`, loading its keys from a `KeyStore`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_store` - The store holding the keys, generated and stored if missing.
+    ///
+    /// # Returns
+    ///
+    /// A new `EpochChangeProver`.
+    pub fn with_key_store(key_store: &KeyStore) -> Result<Self, KeyStoreError> {
+        let client = ProverClient::new();
+        let keys = key_store.setup(&client, aptos_programs::EPOCH_CHANGE_PROGRAM)?;
+
+        Ok(Self { client, keys })
+    }
 
-    let mut proof =
-        client
-            .prove(&pk, stdin)
+    /// Gets a `SphinxVerifyingKey`.
+    ///
+    /// # Returns
+    ///
+    /// A `SphinxVerifyingKey` that can be used for verifying the epoch change proof.
+    pub const fn get_vk(&self) -> &SphinxVerifyingKey {
+        &self.keys.1
+    }
+
+    /// Generate a compressed STARK proof of the epoch change program, that can be
+    /// verified by the aggregation program.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - The input for the prover.
+    ///
+    /// # Returns
+    ///
+    /// The compressed proof.
+    pub fn prove_compressed(
+        &self,
+        inputs: &EpochChangeIn,
+    ) -> Result<SphinxProofWithPublicValues, ProverError> {
+        let stdin = self.generate_sphinx_stdin(inputs)?;
+
+        self.client
+            .prove(&self.keys.0, stdin)
+            .compressed()
             .run()
-            .map_err(|err| LightClientError::ProvingError {
-                program: "prove-epoch-change".to_string(),
+            .map_err(|err| ProverError::Proving {
+                proof_type: COMPRESSED_PROOF_TYPE.into(),
                 source: err.into(),
-            })?;
+            })
+    }
+}
 
-    // Read output.
-    let prev_validator_verifier_hash = proof.public_values.read::<[u8; 32]>();
-    let new_validator_verifier_hash = proof.public_values.read::<[u8; 32]>();
+/// The input for the epoch change proof.
+#[derive(Debug, Clone, Eq, PartialEq, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct EpochChangeIn {
+    trusted_state: Vec<u8>,
+    epoch_change_proof: Vec<u8>,
+}
+
+impl EpochChangeIn {
+    /// Create a new `EpochChangeIn`.
+    ///
+    /// # Arguments
+    ///
+    /// * `trusted_state` - The bytes of the current trusted state.
+    /// * `epoch_change_proof` - The bytes of the `EpochChangeProof` to ratchet the trusted state with.
+    ///
+    /// # Returns
+    ///
+    /// A new `EpochChangeIn`.
+    pub const fn new(trusted_state: Vec<u8>, epoch_change_proof: Vec<u8>) -> Self {
+        Self {
+            trusted_state,
+            epoch_change_proof,
+        }
+    }
+}
+
+/// The output for the epoch change proof.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct EpochChangeOut {
+    /// Hash of the `PreparedValidatorVerifier` of the trusted epoch, or
+    /// the waypoint value when bootstrapping from a waypoint.
+    prev_validator_verifier_hash: HashValue,
+    /// Hash of the `PreparedValidatorVerifier` of the new epoch.
+    new_validator_verifier_hash: HashValue,
+    /// The new epoch.
+    epoch: u64,
+    /// Whether `prev_validator_verifier_hash` holds a waypoint value.
+    from_waypoint: bool,
+}
+
+impl From<&mut SphinxPublicValues> for EpochChangeOut {
+    fn from(public_values: &mut SphinxPublicValues) -> Self {
+        let prev_validator_verifier_hash = HashValue::new(public_values.read::<[u8; 32]>());
+        let new_validator_verifier_hash = HashValue::new(public_values.read::<[u8; 32]>());
+        let epoch = public_values.read::<u64>();
+        let from_waypoint = public_values.read::<u8>() == EPOCH_CHANGE_FROM_WAYPOINT;
 
-    Ok((
-        proof,
-        EpochChangeOutput {
+        Self {
             prev_validator_verifier_hash,
             new_validator_verifier_hash,
-        },
-    ))
+            epoch,
+            from_waypoint,
+        }
+    }
+}
+
+impl Prover for EpochChangeProver {
+    const PROGRAM: &'static [u8] = aptos_programs::EPOCH_CHANGE_PROGRAM;
+    type Error = ProverError;
+    type StdIn = EpochChangeIn;
+    type StdOut = EpochChangeOut;
+
+    fn generate_sphinx_stdin(&self, inputs: &Self::StdIn) -> Result<SphinxStdin, Self::Error> {
+        Ok(generate_stdin(
+            &inputs.trusted_state,
+            &inputs.epoch_change_proof,
+        ))
+    }
+
+    fn execute(&self, inputs: &Self::StdIn) -> Result<Self::StdOut, Self::Error> {
+        sphinx_sdk::utils::setup_logger();
+
+        let stdin = self.generate_sphinx_stdin(inputs)?;
+
+        let (mut public_values, _) = self
+            .client
+            .execute(Self::PROGRAM, stdin)
+            .run()
+            .map_err(|err| ProverError::Execution { source: err.into() })?;
+
+        Ok(EpochChangeOut::from(&mut public_values))
+    }
+
+    fn prove(&self, inputs: &Self::StdIn, mode: ProvingMode) -> Result<ProofType, Self::Error> {
+        let stdin = self.generate_sphinx_stdin(inputs)?;
+
+        match mode {
+            ProvingMode::STARK => self
+                .client
+                .prove(&self.keys.0, stdin)
+                .run()
+                .map_err(|err| ProverError::Proving {
+                    proof_type: mode.into(),
+                    source: err.into(),
+                })
+                .map(ProofType::STARK),
+            ProvingMode::SNARK => self
+                .client
+                .prove(&self.keys.0, stdin)
+                .plonk()
+                .run()
+                .map_err(|err| ProverError::Proving {
+                    proof_type: mode.into(),
+                    source: err.into(),
+                })
+                .map(ProofType::SNARK),
+        }
+    }
+
+    fn verify(&self, proof: &ProofType) -> Result<(), Self::Error> {
+        self.client
+            .verify(proof.proof(), &self.keys.1)
+            .map_err(|err| ProverError::Verification { source: err.into() })
+    }
 }
 
 #[cfg(all(test, feature = "aptos"))]
 mod test {
-    use crate::epoch_change::setup_assets;
-    use crate::error::LightClientError;
+    use crate::epoch_change::{setup_assets, EpochChangeIn, EpochChangeOut, EpochChangeProver};
+    use crate::prover::{Prover, ProvingMode};
     use sphinx_sdk::artifacts::try_install_plonk_bn254_artifacts;
-    use sphinx_sdk::utils::setup_logger;
-    use sphinx_sdk::{ProverClient, SphinxStdin};
 
-    fn execute_epoch_change(
-        current_trusted_state: &[u8],
-        epoch_change_proof: &[u8],
-    ) -> Result<(), LightClientError> {
-        setup_logger();
+    #[test]
+    fn test_execute_epoch_change() {
+        use std::time::Instant;
 
-        let mut stdin = SphinxStdin::new();
+        let (trusted_state, epoch_change_proof, validator_verifier_hash) = setup_assets();
 
-        stdin.write(&current_trusted_state);
-        stdin.write(&epoch_change_proof);
+        let prover = EpochChangeProver::new();
 
-        let client = ProverClient::new();
-        client
-            .execute(aptos_programs::EPOCH_CHANGE_PROGRAM, stdin)
-            .run()
-            .map_err(|err| LightClientError::ProvingError {
-                program: "prove-epoch-change".to_string(),
-                source: err.into(),
-            })?;
+        println!("Starting execution of prove_epoch_change...");
+        let start = Instant::now();
+        let output = prover
+            .execute(&EpochChangeIn::new(trusted_state, epoch_change_proof))
+            .unwrap();
+        println!("Execution took {:?}", start.elapsed());
 
-        Ok(())
+        assert_eq!(
+            output.prev_validator_verifier_hash().to_vec(),
+            validator_verifier_hash
+        );
     }
 
     #[test]
-    fn test_execute_epoch_change() {
+    fn test_execute_multi_epoch_change() {
+        use crate::epoch_change::setup_assets_for;
+        use aptos_lc_core::crypto::hash::CryptoHash;
+        use aptos_lc_core::types::trusted_state::EpochChangeProof;
+        use aptos_lc_core::types::validator::PreparedValidatorVerifier;
         use std::time::Instant;
 
-        let (trusted_state, epoch_change_proof, _) = setup_assets();
+        const EPOCH_CHANGES: usize = 3;
 
-        println!("Starting execution of prove_epoch_change...");
+        let (trusted_state, epoch_change_proof, validator_verifier_hash) =
+            setup_assets_for(EPOCH_CHANGES);
+
+        let final_epoch_state = EpochChangeProof::from_bytes(&epoch_change_proof)
+            .unwrap()
+            .ledger_info_with_sigs
+            .last()
+            .and_then(|li| li.ledger_info().next_epoch_state().cloned())
+            .expect("Expected a final epoch state");
+
+        let prover = EpochChangeProver::new();
+
+        println!("Starting execution of prove_epoch_change over {EPOCH_CHANGES} epochs...");
         let start = Instant::now();
-        execute_epoch_change(&trusted_state, &epoch_change_proof).unwrap();
+        let output = prover
+            .execute(&EpochChangeIn::new(trusted_state, epoch_change_proof))
+            .unwrap();
         println!("Execution took {:?}", start.elapsed());
+
+        assert_eq!(
+            output.prev_validator_verifier_hash().to_vec(),
+            validator_verifier_hash
+        );
+        assert_eq!(
+            output.new_validator_verifier_hash(),
+            PreparedValidatorVerifier::new(final_epoch_state.verifier().clone())
+                .unwrap()
+                .hash()
+        );
+        assert_eq!(output.epoch(), final_epoch_state.epoch);
+        assert!(!output.from_waypoint());
+    }
+
+    #[test]
+    fn test_execute_epoch_change_rejects_tampered_inputs() {
+        use aptos_lc_core::aptos_test_utils::scenario::{
+            forge_ledger_info, truncate_epoch_change_proof, LedgerInfoForgery, ScenarioBuilder,
+            SignerSelection,
+        };
+        use aptos_lc_core::types::ledger_info::LedgerInfoWithSignatures;
+        use aptos_lc_core::types::trusted_state::EpochChangeProof;
+
+        let mut scenario = ScenarioBuilder::new(20, 10).unwrap();
+        scenario.generate_traffic().unwrap();
+
+        // Change the stake of a validator and grow the validator set
+        scenario
+            .set_voting_power(0, 1)
+            .unwrap()
+            .add_validators(2, 10);
+        let (trusted_state, epoch_change_proof) = scenario.commit_epoch_changes(2).unwrap();
+
+        let prover = EpochChangeProver::new();
+        prover
+            .execute(&EpochChangeIn::new(
+                trusted_state.clone(),
+                epoch_change_proof.clone(),
+            ))
+            .unwrap();
+
+        let truncated_proof = truncate_epoch_change_proof(&epoch_change_proof).unwrap();
+
+        let first_li = EpochChangeProof::from_bytes(&epoch_change_proof)
+            .unwrap()
+            .ledger_info_with_sigs[0]
+            .to_bytes();
+        let forged_proof = EpochChangeProof {
+            ledger_info_with_sigs: vec![LedgerInfoWithSignatures::from_bytes(
+                &forge_ledger_info(&first_li, LedgerInfoForgery::NextEpochState).unwrap(),
+            )
+            .unwrap()],
+            more: false,
+        }
+        .to_bytes();
+
+        scenario.with_signers(SignerSelection::BelowQuorum);
+        let (below_quorum_trusted_state, below_quorum_proof) =
+            scenario.commit_epoch_changes(1).unwrap();
+
+        for (case, inputs) in [
+            (
+                "truncated epoch change proof",
+                EpochChangeIn::new(trusted_state.clone(), truncated_proof),
+            ),
+            (
+                "forged next epoch state",
+                EpochChangeIn::new(trusted_state, forged_proof),
+            ),
+            (
+                "signers below quorum",
+                EpochChangeIn::new(below_quorum_trusted_state, below_quorum_proof),
+            ),
+        ] {
+            assert!(
+                prover.execute(&inputs).is_err(),
+                "{case} should be rejected"
+            );
+        }
+    }
+
+    #[cfg(feature = "waypoint")]
+    #[test]
+    fn test_execute_epoch_change_from_waypoint() {
+        use crate::epoch_change::setup_waypoint_assets;
+        use std::time::Instant;
+
+        let (trusted_state, epoch_change_proof, waypoint) = setup_waypoint_assets();
+
+        let prover = EpochChangeProver::new();
+
+        println!("Starting execution of prove_epoch_change from waypoint...");
+        let start = Instant::now();
+        let output = prover
+            .execute(&EpochChangeIn::new(trusted_state, epoch_change_proof))
+            .unwrap();
+        println!("Execution took {:?}", start.elapsed());
+
+        assert_eq!(output.prev_validator_verifier_hash().to_vec(), waypoint);
+        assert!(output.from_waypoint());
     }
 
     #[test]
     #[ignore = "This test is too slow for CI"]
     fn test_prove_epoch_change() {
-        use super::*;
-        use sphinx_sdk::ProverClient;
         use std::time::Instant;
 
         let (trusted_state, epoch_change_proof, validator_verifier_hash) = setup_assets();
 
-        let client = ProverClient::new();
+        let prover = EpochChangeProver::new();
 
         let start = Instant::now();
         println!("Starting generation of prove_epoch_change proof...");
-        let (proof, output) =
-            prove_epoch_change(&client, &trusted_state, &epoch_change_proof).unwrap();
+        let proof = prover
+            .prove(
+                &EpochChangeIn::new(trusted_state, epoch_change_proof),
+                ProvingMode::STARK,
+            )
+            .unwrap();
         println!("Proving took {:?}", start.elapsed());
 
-        assert_eq!(
-            output.prev_validator_verifier_hash,
-            validator_verifier_hash.as_slice()
-        );
-
-        let (_, vk) = client.setup(aptos_programs::EPOCH_CHANGE_PROGRAM);
         let start = Instant::now();
         println!("Starting verification of prove_epoch_change proof...");
-        client.verify(&proof, &vk).unwrap();
+        prover.verify(&proof).unwrap();
         println!("Verification took {:?}", start.elapsed());
+
+        let output = EpochChangeOut::from(&mut proof.public_values());
+        assert_eq!(
+            output.prev_validator_verifier_hash().to_vec(),
+            validator_verifier_hash
+        );
     }
 
     #[test]
     #[ignore = "This test is too slow for CI"]
     fn test_snark_prove_epoch_change() {
-        use super::*;
-        use sphinx_sdk::ProverClient;
         use std::time::Instant;
 
-        setup_logger();
-
         let (trusted_state, epoch_change_proof, _) = setup_assets();
 
-        let client = ProverClient::new();
-        let (pk, vk) = client.setup(aptos_programs::EPOCH_CHANGE_PROGRAM);
-
-        let stdin = generate_stdin(trusted_state.as_slice(), &epoch_change_proof);
+        let prover = EpochChangeProver::new();
 
         // Install PLONK artifacts.
         try_install_plonk_bn254_artifacts(false);
 
         let start = Instant::now();
         println!("Starting generation of prove_epoch_change proof...");
-        let snark_proof = client.prove(&pk, stdin).plonk().run().unwrap();
+        let snark_proof = prover
+            .prove(
+                &EpochChangeIn::new(trusted_state, epoch_change_proof),
+                ProvingMode::SNARK,
+            )
+            .unwrap();
         println!("Proving took {:?}", start.elapsed());
 
         let start = Instant::now();
         println!("Starting verification of prove_epoch_change proof...");
-        client.verify(&snark_proof, &vk).unwrap();
+        prover.verify(&snark_proof).unwrap();
         println!("Verification took {:?}", start.elapsed());
     }
 }
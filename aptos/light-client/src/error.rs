@@ -1,6 +1,7 @@
 // Copyright (c) Argument Computer Corporation
 // SPDX-License-Identifier: Apache-2.0
 
+pub use lc_key_store::KeyStoreError;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -12,3 +13,29 @@ pub(crate) enum LightClientError {
         source: Box<dyn std::error::Error + Sync + Send>,
     },
 }
+
+/// The error type for errors being thrown while proving program execution.
+#[derive(Debug, Error)]
+pub enum ProverError {
+    #[error("Error while generating Sphinx input: {source}")]
+    SphinxInput {
+        #[source]
+        source: Box<dyn std::error::Error + Sync + Send>,
+    },
+    #[error("Error while executing the program: {source}")]
+    Execution {
+        #[source]
+        source: Box<dyn std::error::Error + Sync + Send>,
+    },
+    #[error("Error while generating {proof_type} proof: {source}")]
+    Proving {
+        proof_type: String,
+        #[source]
+        source: Box<dyn std::error::Error + Sync + Send>,
+    },
+    #[error("Error while verifying the proof: {source}")]
+    Verification {
+        #[source]
+        source: Box<dyn std::error::Error + Sync + Send>,
+    },
+}
@@ -0,0 +1,324 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+use sphinx_sdk::{
+    ProverClient, SphinxProofWithPublicValues, SphinxProvingKey, SphinxStdin, SphinxVerifyingKey,
+};
+
+use crate::error::LightClientError;
+use crate::inclusion::{TransactionProofAssets, ValidatorVerifierAssets};
+
+#[derive(Clone, Debug, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct EventProofAssets {
+    event: Vec<u8>,
+    event_index: u64,
+    event_proof: Vec<u8>,
+}
+
+impl EventProofAssets {
+    pub const fn new(event: Vec<u8>, event_index: u64, event_proof: Vec<u8>) -> EventProofAssets {
+        EventProofAssets {
+            event,
+            event_index,
+            event_proof,
+        }
+    }
+}
+
+#[cfg(feature = "aptos")]
+pub fn setup_assets() -> (
+    EventProofAssets,
+    TransactionProofAssets,
+    ValidatorVerifierAssets,
+) {
+    use aptos_lc_core::aptos_test_utils::wrapper::AptosWrapper;
+    use aptos_lc_core::types::trusted_state::TrustedState;
+    use aptos_lc_core::types::validator::PreparedValidatorVerifier;
+
+    const NBR_VALIDATORS: usize = 130;
+    const AVERAGE_SIGNERS_NBR: usize = 95;
+
+    let mut aptos_wrapper = AptosWrapper::new(500, NBR_VALIDATORS, AVERAGE_SIGNERS_NBR).unwrap();
+    aptos_wrapper.generate_traffic().unwrap();
+
+    let proof_assets = aptos_wrapper.get_latest_event_proof(0).unwrap();
+
+    let event = bcs::to_bytes(proof_assets.event()).unwrap();
+    let event_proof = bcs::to_bytes(proof_assets.event_proof()).unwrap();
+
+    let transaction = bcs::to_bytes(&proof_assets.transaction()).unwrap();
+    let transaction_proof = bcs::to_bytes(&proof_assets.transaction_proof()).unwrap();
+
+    let latest_li = aptos_wrapper.get_latest_li_bytes().unwrap();
+
+    let validator_verifier =
+        match TrustedState::from_bytes(&bcs::to_bytes(&aptos_wrapper.trusted_state()).unwrap())
+            .unwrap()
+        {
+            TrustedState::EpochState { epoch_state, .. } => epoch_state.verifier().clone(),
+            _ => panic!("expected epoch state"),
+        };
+
+    let event_proof_assets = EventProofAssets {
+        event,
+        event_index: *proof_assets.event_index(),
+        event_proof,
+    };
+
+    let transaction_proof_assets = TransactionProofAssets::new(
+        transaction,
+        *proof_assets.transaction_version(),
+        transaction_proof,
+        latest_li,
+    );
+
+    let validator_verifier_assets = ValidatorVerifierAssets::new(
+        PreparedValidatorVerifier::new(validator_verifier)
+            .unwrap()
+            .to_bytes(),
+    );
+
+    (
+        event_proof_assets,
+        transaction_proof_assets,
+        validator_verifier_assets,
+    )
+}
+
+pub fn generate_stdin(
+    event_proof_assets: &EventProofAssets,
+    transaction_proof_assets: &TransactionProofAssets,
+    validator_verifier_assets: &ValidatorVerifierAssets,
+) -> SphinxStdin {
+    let mut stdin = SphinxStdin::new();
+    // Event inclusion input
+    stdin.write(&event_proof_assets.event);
+    stdin.write(&event_proof_assets.event_index);
+    stdin.write(&event_proof_assets.event_proof);
+
+    // Tx inclusion input
+    stdin.write(transaction_proof_assets.transaction());
+    stdin.write(transaction_proof_assets.transaction_index());
+    stdin.write(transaction_proof_assets.transaction_proof());
+    stdin.write(transaction_proof_assets.latest_li());
+
+    // Validator verifier
+    stdin.write(validator_verifier_assets.validator_verifier());
+
+    stdin
+}
+
+#[inline]
+pub fn generate_keys(client: &ProverClient) -> (SphinxProvingKey, SphinxVerifyingKey) {
+    client.setup(aptos_programs::EVENT_PROGRAM)
+}
+
+#[allow(dead_code)]
+struct EventOutput {
+    validator_verifier_hash: [u8; 32],
+    block_hash: [u8; 32],
+    transaction_version: u64,
+    event_hash: [u8; 32],
+    type_tag_hash: [u8; 32],
+}
+
+#[allow(dead_code)]
+fn prove_event(
+    client: &ProverClient,
+    event_proof_assets: &EventProofAssets,
+    transaction_proof_assets: &TransactionProofAssets,
+    validator_verifier_assets: &ValidatorVerifierAssets,
+) -> Result<(SphinxProofWithPublicValues, EventOutput), LightClientError> {
+    sphinx_sdk::utils::setup_logger();
+
+    let stdin = generate_stdin(
+        event_proof_assets,
+        transaction_proof_assets,
+        validator_verifier_assets,
+    );
+    let (pk, _) = generate_keys(client);
+
+    let mut proof =
+        client
+            .prove(&pk, stdin)
+            .run()
+            .map_err(|err| LightClientError::ProvingError {
+                program: "prove-event-inclusion".to_string(),
+                source: err.into(),
+            })?;
+
+    // Read output.
+    let validator_verifier_hash = proof.public_values.read::<[u8; 32]>();
+    let block_hash = proof.public_values.read::<[u8; 32]>();
+    let transaction_version = proof.public_values.read::<u64>();
+    let event_hash = proof.public_values.read::<[u8; 32]>();
+    let type_tag_hash = proof.public_values.read::<[u8; 32]>();
+
+    Ok((
+        proof,
+        EventOutput {
+            validator_verifier_hash,
+            block_hash,
+            transaction_version,
+            event_hash,
+            type_tag_hash,
+        },
+    ))
+}
+
+#[cfg(all(test, feature = "aptos"))]
+mod test {
+    use crate::error::LightClientError;
+    use crate::event::{generate_stdin, setup_assets};
+    use sphinx_sdk::utils::setup_logger;
+    use sphinx_sdk::ProverClient;
+
+    #[test]
+    fn test_execute_event() {
+        use std::time::Instant;
+
+        setup_logger();
+
+        let (event_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            setup_assets();
+
+        let stdin = generate_stdin(
+            &event_proof_assets,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+        );
+
+        println!("Starting execution of event inclusion...");
+        let start = Instant::now();
+        let client = ProverClient::new();
+        client
+            .execute(aptos_programs::EVENT_PROGRAM, stdin)
+            .run()
+            .map_err(|err| LightClientError::ProvingError {
+                program: "prove-event-inclusion".to_string(),
+                source: err.into(),
+            })
+            .unwrap();
+        println!("Execution took {:?}", start.elapsed());
+    }
+
+    #[test]
+    fn test_execute_event_rejects_tampered_inputs() {
+        use crate::event::EventProofAssets;
+        use crate::inclusion::TransactionProofAssets;
+        use aptos_lc_core::aptos_test_utils::scenario::{
+            forge_ledger_info, truncate_accumulator_proof, LedgerInfoForgery,
+        };
+
+        let (event_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            setup_assets();
+
+        let wrong_index_event_proof_assets = EventProofAssets::new(
+            event_proof_assets.event().clone(),
+            *event_proof_assets.event_index() + 1,
+            event_proof_assets.event_proof().clone(),
+        );
+        let truncated_transaction_proof_assets = TransactionProofAssets::new(
+            transaction_proof_assets.transaction().clone(),
+            *transaction_proof_assets.transaction_index(),
+            truncate_accumulator_proof(transaction_proof_assets.transaction_proof()).unwrap(),
+            transaction_proof_assets.latest_li().clone(),
+        );
+        let forged_transaction_proof_assets = TransactionProofAssets::new(
+            transaction_proof_assets.transaction().clone(),
+            *transaction_proof_assets.transaction_index(),
+            transaction_proof_assets.transaction_proof().clone(),
+            forge_ledger_info(
+                transaction_proof_assets.latest_li(),
+                LedgerInfoForgery::Timestamp,
+            )
+            .unwrap(),
+        );
+
+        let client = ProverClient::new();
+        for (case, event_proof_assets, transaction_proof_assets) in [
+            (
+                "wrong event index",
+                &wrong_index_event_proof_assets,
+                &transaction_proof_assets,
+            ),
+            (
+                "truncated transaction proof",
+                &event_proof_assets,
+                &truncated_transaction_proof_assets,
+            ),
+            (
+                "forged ledger info",
+                &event_proof_assets,
+                &forged_transaction_proof_assets,
+            ),
+        ] {
+            let stdin = generate_stdin(
+                event_proof_assets,
+                transaction_proof_assets,
+                &validator_verifier_assets,
+            );
+            assert!(
+                client
+                    .execute(aptos_programs::EVENT_PROGRAM, stdin)
+                    .run()
+                    .is_err(),
+                "{case} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    #[ignore = "This test is too slow for CI"]
+    fn test_prove_event() {
+        use super::*;
+        use aptos_lc_core::crypto::hash::{hash_data, CryptoHash};
+        use aptos_lc_core::types::event::ContractEvent;
+        use aptos_lc_core::types::validator::PreparedValidatorVerifier;
+        use std::time::Instant;
+
+        let client = ProverClient::new();
+
+        let (event_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            setup_assets();
+
+        let start = Instant::now();
+        println!("Starting generation of event inclusion proof...");
+        let (proof, output) = prove_event(
+            &client,
+            &event_proof_assets,
+            &transaction_proof_assets,
+            &validator_verifier_assets,
+        )
+        .unwrap();
+        println!("Proving took {:?}", start.elapsed());
+
+        assert_eq!(
+            &output.validator_verifier_hash,
+            PreparedValidatorVerifier::from_bytes(validator_verifier_assets.validator_verifier())
+                .unwrap()
+                .hash()
+                .as_ref()
+        );
+        let event = ContractEvent::from_bytes(event_proof_assets.event()).unwrap();
+        assert_eq!(&output.event_hash, event.hash().as_ref());
+        assert_eq!(
+            output.type_tag_hash,
+            hash_data(&[], vec![&event.type_tag().to_bytes()])
+        );
+        assert_eq!(
+            &output.transaction_version,
+            transaction_proof_assets.transaction_index()
+        );
+
+        let (_, vk) = generate_keys(&client);
+        let start = Instant::now();
+        println!("Starting verification of event inclusion proof...");
+        client.verify(&proof, &vk).unwrap();
+        println!("Verification took {:?}", start.elapsed());
+    }
+}
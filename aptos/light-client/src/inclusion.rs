@@ -5,29 +5,47 @@ use anyhow::Result;
 use getset::Getters;
 use serde::{Deserialize, Serialize};
 use sphinx_sdk::{
-    ProverClient, SphinxProofWithPublicValues, SphinxProvingKey, SphinxStdin, SphinxVerifyingKey,
+    ProverClient, SphinxProofWithPublicValues, SphinxProvingKey, SphinxPublicValues, SphinxStdin,
+    SphinxVerifyingKey,
 };
 
-use crate::error::LightClientError;
+use crate::error::{KeyStoreError, ProverError};
+use crate::key_store::KeyStore;
+use crate::prover::{ProofType, Prover, ProvingMode, COMPRESSED_PROOF_TYPE};
+#[cfg(feature = "aptos")]
+use aptos_lc_core::aptos_test_utils::wrapper::{self, AptosWrapper};
+use aptos_lc_core::crypto::hash::HashValue;
+use aptos_lc_core::types::ledger_info::MaxAge;
+use aptos_lc_core::types::resource::ResourceKind;
+use aptos_lc_core::types::state_key::StateKey;
+use aptos_lc_core::types::utils::{
+    INCLUSION_ACCOUNT_FLAG, INCLUSION_COIN_STORE_FLAG, INCLUSION_MAX_AGE_FLAG,
+    INCLUSION_PRESENT_FLAG, INCLUSION_STATE_KEY_FLAG, INCLUSION_STATUS_FLAG,
+};
 
 #[derive(Clone, Debug, Getters, Serialize, Deserialize)]
 #[getset(get = "pub")]
 pub struct SparseMerkleProofAssets {
     sparse_merkle_proof: Vec<u8>,
     leaf_key: [u8; 32],
-    leaf_hash: [u8; 32],
+    /// Resource or table item `leaf_key` is derived from, checked by the program when set.
+    state_key: Option<StateKey>,
+    /// BCS serialized `StateValue` of the leaf, `None` to prove that `leaf_key` is absent from the state.
+    leaf_value: Option<Vec<u8>>,
 }
 
 impl SparseMerkleProofAssets {
     pub const fn new(
         sparse_merkle_proof: Vec<u8>,
         leaf_key: [u8; 32],
-        leaf_hash: [u8; 32],
+        state_key: Option<StateKey>,
+        leaf_value: Option<Vec<u8>>,
     ) -> SparseMerkleProofAssets {
         SparseMerkleProofAssets {
             sparse_merkle_proof,
             leaf_key,
-            leaf_hash,
+            state_key,
+            leaf_value,
         }
     }
 }
@@ -60,6 +78,7 @@ impl TransactionProofAssets {
 #[derive(Clone, Debug, Getters, Serialize, Deserialize)]
 #[getset(get = "pub")]
 pub struct ValidatorVerifierAssets {
+    /// The bytes of the `PreparedValidatorVerifier` verifying the signatures.
     validator_verifier: Vec<u8>,
 }
 
@@ -75,20 +94,91 @@ pub fn setup_assets() -> (
     TransactionProofAssets,
     ValidatorVerifierAssets,
 ) {
-    use aptos_lc_core::aptos_test_utils::wrapper::AptosWrapper;
-    use aptos_lc_core::types::trusted_state::TrustedState;
+    setup_assets_for(|aptos_wrapper| {
+        (
+            aptos_wrapper.get_latest_proof_account(400).unwrap(),
+            ResourceKind::Account.state_key(account_address(aptos_wrapper, 400)),
+        )
+    })
+}
+
+#[cfg(feature = "aptos")]
+pub fn setup_non_inclusion_assets() -> (
+    SparseMerkleProofAssets,
+    TransactionProofAssets,
+    ValidatorVerifierAssets,
+) {
+    use aptos_lc_core::types::AccountAddress;
+
+    setup_assets_for(|aptos_wrapper| {
+        (
+            aptos_wrapper.get_latest_proof_absent_account().unwrap(),
+            ResourceKind::Account.state_key(AccountAddress::new([0xab; 32])),
+        )
+    })
+}
 
+#[cfg(feature = "aptos")]
+pub fn setup_coin_store_assets() -> (
+    SparseMerkleProofAssets,
+    TransactionProofAssets,
+    ValidatorVerifierAssets,
+) {
+    setup_assets_for(|aptos_wrapper| {
+        (
+            aptos_wrapper.get_latest_proof_coin_store(400).unwrap(),
+            ResourceKind::AptosCoinStore.state_key(account_address(aptos_wrapper, 400)),
+        )
+    })
+}
+
+#[cfg(feature = "aptos")]
+pub(crate) fn account_address(
+    aptos_wrapper: &AptosWrapper,
+    account_idx: usize,
+) -> aptos_lc_core::types::AccountAddress {
+    aptos_lc_core::types::AccountAddress::from_bytes(
+        aptos_wrapper.accounts()[account_idx].address().as_ref(),
+    )
+    .unwrap()
+}
+
+#[cfg(feature = "aptos")]
+fn setup_assets_for(
+    get_proof_assets: impl FnOnce(&AptosWrapper) -> (wrapper::SparseMerkleProofAssets, StateKey),
+) -> (
+    SparseMerkleProofAssets,
+    TransactionProofAssets,
+    ValidatorVerifierAssets,
+) {
     const NBR_VALIDATORS: usize = 130;
     const AVERAGE_SIGNERS_NBR: usize = 95;
 
     let mut aptos_wrapper = AptosWrapper::new(500, NBR_VALIDATORS, AVERAGE_SIGNERS_NBR).unwrap();
     aptos_wrapper.generate_traffic().unwrap();
 
-    let proof_assets = aptos_wrapper.get_latest_proof_account(400).unwrap();
+    assets_from_wrapper(&aptos_wrapper, get_proof_assets)
+}
+
+/// Generates the assets for an inclusion proof against the latest
+/// `LedgerInfo` of an already running `AptosWrapper`.
+#[cfg(feature = "aptos")]
+pub(crate) fn assets_from_wrapper(
+    aptos_wrapper: &AptosWrapper,
+    get_proof_assets: impl FnOnce(&AptosWrapper) -> (wrapper::SparseMerkleProofAssets, StateKey),
+) -> (
+    SparseMerkleProofAssets,
+    TransactionProofAssets,
+    ValidatorVerifierAssets,
+) {
+    use aptos_lc_core::types::trusted_state::TrustedState;
+    use aptos_lc_core::types::validator::PreparedValidatorVerifier;
+
+    let (proof_assets, state_key) = get_proof_assets(aptos_wrapper);
 
     let sparse_merkle_proof = bcs::to_bytes(proof_assets.state_proof()).unwrap();
     let key: [u8; 32] = *proof_assets.key().as_ref();
-    let element_hash: [u8; 32] = *proof_assets.state_value_hash().unwrap().as_ref();
+    let element_value = proof_assets.state_value_bytes().unwrap();
 
     let transaction = bcs::to_bytes(&proof_assets.transaction()).unwrap();
     let transaction_proof = bcs::to_bytes(&proof_assets.transaction_proof()).unwrap();
@@ -106,7 +196,8 @@ pub fn setup_assets() -> (
     let sparse_merkle_proof_assets = SparseMerkleProofAssets {
         sparse_merkle_proof,
         leaf_key: key,
-        leaf_hash: element_hash,
+        state_key: Some(state_key),
+        leaf_value: element_value,
     };
 
     let transaction_proof_assets = TransactionProofAssets {
@@ -117,7 +208,9 @@ pub fn setup_assets() -> (
     };
 
     let validator_verifier_assets = ValidatorVerifierAssets {
-        validator_verifier: validator_verifier.to_bytes(),
+        validator_verifier: PreparedValidatorVerifier::new(validator_verifier)
+            .unwrap()
+            .to_bytes(),
     };
 
     (
@@ -127,16 +220,34 @@ pub fn setup_assets() -> (
     )
 }
 
+/// Generates the input of the inclusion program.
+///
+/// The ledger info epoch, version and timestamp are followed by a flags byte
+/// telling whether the key is present in the state, and which of the optional
+/// public values are committed next, in order.
+/// When `max_age` is set, the program fails if the timestamp of the latest
+/// `LedgerInfo` is older than allowed, and commits the `MaxAge` it checked
+/// against. When
+/// `commit_status` is set, the variant of the `ExecutionStatus` of the proven
+/// transaction and its abort code are committed after the other public values. When
+/// the `StateKey` of the leaf is given, its variant, address and identifier are
+/// committed next, binding the proof to what is stored at which address. When
+/// `decode_resource` is set, the leaf value is decoded as the given Move
+/// resource and its fields are committed last.
 pub fn generate_stdin(
     sparse_merkle_proof_assets: &SparseMerkleProofAssets,
     transaction_proof_assets: &TransactionProofAssets,
     validator_verifier_assets: &ValidatorVerifierAssets,
+    commit_status: bool,
+    decode_resource: Option<ResourceKind>,
+    max_age: Option<MaxAge>,
 ) -> SphinxStdin {
     let mut stdin = SphinxStdin::new();
     // Account inclusion input
     stdin.write(&sparse_merkle_proof_assets.sparse_merkle_proof);
     stdin.write(&sparse_merkle_proof_assets.leaf_key);
-    stdin.write(&sparse_merkle_proof_assets.leaf_hash);
+    stdin.write(&sparse_merkle_proof_assets.state_key);
+    stdin.write(&sparse_merkle_proof_assets.leaf_value);
 
     // Tx inclusion input
     stdin.write(&transaction_proof_assets.transaction);
@@ -147,6 +258,15 @@ pub fn generate_stdin(
     // Validator verifier
     stdin.write(&validator_verifier_assets.validator_verifier);
 
+    // Execution status
+    stdin.write(&commit_status);
+
+    // Decoded resource
+    stdin.write(&decode_resource);
+
+    // Ledger info freshness
+    stdin.write(&max_age);
+
     stdin
 }
 
@@ -155,194 +275,897 @@ pub fn generate_keys(client: &ProverClient) -> (SphinxProvingKey, SphinxVerifyin
     client.setup(aptos_programs::INCLUSION_PROGRAM)
 }
 
-#[allow(dead_code)]
-struct InclusionOutput {
-    validator_verifier_hash: [u8; 32],
-    state_hash: [u8; 32],
-    block_hash: [u8; 32],
-    key: [u8; 32],
-    value: [u8; 32],
+/// The prover for the inclusion program.
+pub struct InclusionProver {
+    client: ProverClient,
+    keys: (SphinxProvingKey, SphinxVerifyingKey),
 }
 
-#[allow(dead_code)]
-fn prove_inclusion(
-    client: &ProverClient,
-    sparse_merkle_proof_assets: &SparseMerkleProofAssets,
-    transaction_proof_assets: &TransactionProofAssets,
-    validator_verifier_assets: &ValidatorVerifierAssets,
-) -> Result<(SphinxProofWithPublicValues, InclusionOutput), LightClientError> {
-    sphinx_sdk::utils::setup_logger();
+impl Default for InclusionProver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-    let stdin = generate_stdin(
-        sparse_merkle_proof_assets,
-        transaction_proof_assets,
-        validator_verifier_assets,
-    );
-    let (pk, _) = generate_keys(client);
+impl InclusionProver {
+    /// Create a new `InclusionProver`.
+    ///
+    /// # Returns
+    ///
+    /// A new `InclusionProver`.
+    pub fn new() -> Self {
+        let client = ProverClient::new();
+        let keys = generate_keys(&client);
+
+        Self { client, keys }
+    }
+
+    /// Create a new `InclusionProver`, loading its keys from a `KeyStore`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_store` - The store holding the keys, generated and stored if missing.
+    ///
+    /// # Returns
+    ///
+    /// A new `InclusionProver`.
+    pub fn with_key_store(key_store: &KeyStore) -> Result<Self, KeyStoreError> {
+        let client = ProverClient::new();
+        let keys = key_store.setup(&client, aptos_programs::INCLUSION_PROGRAM)?;
+
+        Ok(Self { client, keys })
+    }
+
+    /// Gets a `SphinxVerifyingKey`.
+    ///
+    /// # Returns
+    ///
+    /// A `SphinxVerifyingKey` that can be used for verifying the inclusion proof.
+    pub const fn get_vk(&self) -> &SphinxVerifyingKey {
+        &self.keys.1
+    }
+
+    /// Generate a compressed STARK proof of the inclusion program, that can be
+    /// verified by the aggregation program.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - The input for the prover.
+    ///
+    /// # Returns
+    ///
+    /// The compressed proof.
+    pub fn prove_compressed(
+        &self,
+        inputs: &InclusionIn,
+    ) -> Result<SphinxProofWithPublicValues, ProverError> {
+        let stdin = self.generate_sphinx_stdin(inputs)?;
 
-    let mut proof =
-        client
-            .prove(&pk, stdin)
+        self.client
+            .prove(&self.keys.0, stdin)
+            .compressed()
             .run()
-            .map_err(|err| LightClientError::ProvingError {
-                program: "prove-merkle-inclusion".to_string(),
+            .map_err(|err| ProverError::Proving {
+                proof_type: COMPRESSED_PROOF_TYPE.into(),
                 source: err.into(),
-            })?;
-
-    // Read output.
-    let validator_verifier_hash = proof.public_values.read::<[u8; 32]>();
-    let state_hash = proof.public_values.read::<[u8; 32]>();
-    let block_hash = proof.public_values.read::<[u8; 32]>();
-    let key = proof.public_values.read::<[u8; 32]>();
-    let value = proof.public_values.read::<[u8; 32]>();
-
-    Ok((
-        proof,
-        InclusionOutput {
+            })
+    }
+}
+
+/// The input for the inclusion proof.
+#[derive(Debug, Clone, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct InclusionIn {
+    sparse_merkle_proof_assets: SparseMerkleProofAssets,
+    transaction_proof_assets: TransactionProofAssets,
+    validator_verifier_assets: ValidatorVerifierAssets,
+    /// Whether to commit the `ExecutionStatus` of the transaction.
+    commit_status: bool,
+    /// Move resource to decode from the leaf value, if any.
+    decode_resource: Option<ResourceKind>,
+    /// Bound on the age of the latest `LedgerInfo`, if any.
+    max_age: Option<MaxAge>,
+}
+
+impl InclusionIn {
+    /// Create a new `InclusionIn`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sparse_merkle_proof_assets` - The assets proving the leaf in the state.
+    /// * `transaction_proof_assets` - The assets proving the transaction in the ledger.
+    /// * `validator_verifier_assets` - The validator verifier of the current epoch.
+    /// * `commit_status` - Whether to commit the execution status of the transaction.
+    /// * `decode_resource` - Move resource to decode from the leaf value, if any.
+    /// * `max_age` - Bound on the age of the latest `LedgerInfo`, if any.
+    ///
+    /// # Returns
+    ///
+    /// A new `InclusionIn`.
+    pub const fn new(
+        sparse_merkle_proof_assets: SparseMerkleProofAssets,
+        transaction_proof_assets: TransactionProofAssets,
+        validator_verifier_assets: ValidatorVerifierAssets,
+        commit_status: bool,
+        decode_resource: Option<ResourceKind>,
+        max_age: Option<MaxAge>,
+    ) -> Self {
+        Self {
+            sparse_merkle_proof_assets,
+            transaction_proof_assets,
+            validator_verifier_assets,
+            commit_status,
+            decode_resource,
+            max_age,
+        }
+    }
+}
+
+/// The output for the inclusion proof.
+#[derive(Debug, Clone, Eq, PartialEq, Getters)]
+#[getset(get = "pub")]
+pub struct InclusionOut {
+    validator_verifier_hash: HashValue,
+    state_hash: HashValue,
+    block_hash: HashValue,
+    key: HashValue,
+    /// Hash of the leaf value, all zeroes if the key is absent under `state_hash`.
+    value: HashValue,
+    /// Whether the key is present under `state_hash`, rather than absent from it.
+    present: bool,
+    epoch: u64,
+    version: u64,
+    timestamp_usecs: u64,
+    /// Bound the ledger info timestamp was checked against, if it was requested.
+    max_age: Option<MaxAge>,
+    /// `ExecutionStatus` of the transaction, if it was requested.
+    status: Option<StatusOut>,
+    /// `StateKey` the key is derived from, if it was given.
+    state_key: Option<StateKeyOut>,
+    /// Fields decoded from the leaf value, if they were requested.
+    resource: Option<ResourceOut>,
+}
+
+/// The `ExecutionStatus` of a transaction, as committed by the inclusion program.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct StatusOut {
+    /// Index of the variant of the status, `0` for `Success`.
+    pub variant_index: u8,
+    /// Code of a `MoveAbort`, `0` for any other status.
+    pub abort_code: u64,
+}
+
+impl StatusOut {
+    /// Returns whether the transaction was successfully executed.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the status is `ExecutionStatus::Success`, `false` otherwise.
+    pub const fn is_success(&self) -> bool {
+        self.variant_index == 0
+    }
+}
+
+/// The `StateKey` the proven key is derived from, as committed by the inclusion program.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct StateKeyOut {
+    /// Index of the variant of the `StateKey`, `0` for a resource.
+    pub variant_index: u8,
+    /// Address holding the resource, or handle of the table.
+    pub address: [u8; 32],
+    /// Keccak-256 hash of the `StructTag` of the resource, or of the key of the table item.
+    pub identifier: [u8; 32],
+}
+
+impl From<&StateKey> for StateKeyOut {
+    fn from(state_key: &StateKey) -> Self {
+        Self {
+            variant_index: state_key.variant_index(),
+            address: state_key
+                .address()
+                .to_bytes()
+                .try_into()
+                .expect("address: expected 32 bytes"),
+            identifier: state_key.identifier(),
+        }
+    }
+}
+
+/// The fields of a Move resource decoded by the inclusion program, along
+/// with the address holding the resource.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ResourceOut {
+    Account {
+        address: [u8; 32],
+        sequence_number: u64,
+        authentication_key: [u8; 32],
+    },
+    AptosCoinStore {
+        address: [u8; 32],
+        balance: u64,
+    },
+}
+
+impl From<&mut SphinxPublicValues> for InclusionOut {
+    fn from(public_values: &mut SphinxPublicValues) -> Self {
+        let validator_verifier_hash = HashValue::new(public_values.read::<[u8; 32]>());
+        let state_hash = HashValue::new(public_values.read::<[u8; 32]>());
+        let block_hash = HashValue::new(public_values.read::<[u8; 32]>());
+        let key = HashValue::new(public_values.read::<[u8; 32]>());
+        let value = HashValue::new(public_values.read::<[u8; 32]>());
+        let epoch = public_values.read::<u64>();
+        let version = public_values.read::<u64>();
+        let timestamp_usecs = public_values.read::<u64>();
+        // The flags tell which of the optional values were committed
+        let flags = public_values.read::<u8>();
+        let present = flags & INCLUSION_PRESENT_FLAG != 0;
+        let max_age = (flags & INCLUSION_MAX_AGE_FLAG != 0)
+            .then(|| MaxAge::new(public_values.read::<u64>(), public_values.read::<u64>()));
+        let status = (flags & INCLUSION_STATUS_FLAG != 0).then(|| StatusOut {
+            variant_index: public_values.read::<u8>(),
+            abort_code: public_values.read::<u64>(),
+        });
+        let state_key = (flags & INCLUSION_STATE_KEY_FLAG != 0).then(|| StateKeyOut {
+            variant_index: public_values.read::<u8>(),
+            address: public_values.read::<[u8; 32]>(),
+            identifier: public_values.read::<[u8; 32]>(),
+        });
+        // Decoded fields always come with the state key of the resource, holding its address
+        let resource_address = || {
+            state_key
+                .expect("resource: decoded fields are committed without their state key")
+                .address
+        };
+        let resource = if flags & INCLUSION_ACCOUNT_FLAG != 0 {
+            Some(ResourceOut::Account {
+                address: resource_address(),
+                sequence_number: public_values.read::<u64>(),
+                authentication_key: public_values.read::<[u8; 32]>(),
+            })
+        } else if flags & INCLUSION_COIN_STORE_FLAG != 0 {
+            Some(ResourceOut::AptosCoinStore {
+                address: resource_address(),
+                balance: public_values.read::<u64>(),
+            })
+        } else {
+            None
+        };
+
+        Self {
             validator_verifier_hash,
             state_hash,
             block_hash,
             key,
             value,
-        },
-    ))
+            present,
+            epoch,
+            version,
+            timestamp_usecs,
+            max_age,
+            status,
+            state_key,
+            resource,
+        }
+    }
 }
 
-#[cfg(all(test, feature = "aptos"))]
-mod test {
-    use crate::error::LightClientError;
-    use crate::inclusion::{
-        setup_assets, SparseMerkleProofAssets, TransactionProofAssets, ValidatorVerifierAssets,
-    };
-    use aptos_lc_core::types::validator::ValidatorVerifier;
-    use sphinx_sdk::artifacts::try_install_plonk_bn254_artifacts;
-    use sphinx_sdk::utils::setup_logger;
-    use sphinx_sdk::{ProverClient, SphinxStdin};
+impl Prover for InclusionProver {
+    const PROGRAM: &'static [u8] = aptos_programs::INCLUSION_PROGRAM;
+    type Error = ProverError;
+    type StdIn = InclusionIn;
+    type StdOut = InclusionOut;
 
-    fn execute_inclusion(
-        sparse_merkle_proof_assets: &SparseMerkleProofAssets,
-        transaction_proof_assets: &TransactionProofAssets,
-        validator_verifier_assets: &ValidatorVerifierAssets,
-    ) -> Result<(), LightClientError> {
-        setup_logger();
+    fn generate_sphinx_stdin(&self, inputs: &Self::StdIn) -> Result<SphinxStdin, Self::Error> {
+        Ok(generate_stdin(
+            &inputs.sparse_merkle_proof_assets,
+            &inputs.transaction_proof_assets,
+            &inputs.validator_verifier_assets,
+            inputs.commit_status,
+            inputs.decode_resource,
+            inputs.max_age,
+        ))
+    }
 
-        let mut stdin = SphinxStdin::new();
+    fn execute(&self, inputs: &Self::StdIn) -> Result<Self::StdOut, Self::Error> {
+        sphinx_sdk::utils::setup_logger();
 
-        // Account inclusion input
-        stdin.write(&sparse_merkle_proof_assets.sparse_merkle_proof);
-        stdin.write(&sparse_merkle_proof_assets.leaf_key);
-        stdin.write(&sparse_merkle_proof_assets.leaf_hash);
+        let stdin = self.generate_sphinx_stdin(inputs)?;
 
-        // Tx inclusion input
-        stdin.write(&transaction_proof_assets.transaction);
-        stdin.write(&transaction_proof_assets.transaction_index);
-        stdin.write(&transaction_proof_assets.transaction_proof);
-        stdin.write(&transaction_proof_assets.latest_li);
+        let (mut public_values, _) = self
+            .client
+            .execute(Self::PROGRAM, stdin)
+            .run()
+            .map_err(|err| ProverError::Execution { source: err.into() })?;
 
-        // Validator verifier
-        stdin.write(&validator_verifier_assets.validator_verifier);
+        Ok(InclusionOut::from(&mut public_values))
+    }
 
-        let client = ProverClient::new();
-        client
-            .execute(aptos_programs::INCLUSION_PROGRAM, stdin)
-            .run()
-            .map_err(|err| LightClientError::ProvingError {
-                program: "prove-merkle-inclusion".to_string(),
-                source: err.into(),
-            })?;
+    fn prove(&self, inputs: &Self::StdIn, mode: ProvingMode) -> Result<ProofType, Self::Error> {
+        let stdin = self.generate_sphinx_stdin(inputs)?;
+
+        match mode {
+            ProvingMode::STARK => self
+                .client
+                .prove(&self.keys.0, stdin)
+                .run()
+                .map_err(|err| ProverError::Proving {
+                    proof_type: mode.into(),
+                    source: err.into(),
+                })
+                .map(ProofType::STARK),
+            ProvingMode::SNARK => self
+                .client
+                .prove(&self.keys.0, stdin)
+                .plonk()
+                .run()
+                .map_err(|err| ProverError::Proving {
+                    proof_type: mode.into(),
+                    source: err.into(),
+                })
+                .map(ProofType::SNARK),
+        }
+    }
 
-        Ok(())
+    fn verify(&self, proof: &ProofType) -> Result<(), Self::Error> {
+        self.client
+            .verify(proof.proof(), &self.keys.1)
+            .map_err(|err| ProverError::Verification { source: err.into() })
     }
+}
+
+#[cfg(all(test, feature = "aptos"))]
+mod test {
+    use crate::inclusion::{
+        setup_assets, setup_coin_store_assets, setup_non_inclusion_assets, InclusionIn,
+        InclusionOut, InclusionProver, ResourceOut, SparseMerkleProofAssets,
+    };
+    use crate::prover::{Prover, ProvingMode};
+    use aptos_lc_core::crypto::hash::CryptoHash;
+    use aptos_lc_core::types::ledger_info::{LedgerInfoWithSignatures, MaxAge};
+    use aptos_lc_core::types::resource::ResourceKind;
+    use aptos_lc_core::types::state_key::StateKey;
+    use aptos_lc_core::types::validator::PreparedValidatorVerifier;
+    use sphinx_sdk::artifacts::try_install_plonk_bn254_artifacts;
 
     #[test]
     fn test_execute_inclusion() {
+        use crate::inclusion::{StateKeyOut, StatusOut};
+        use aptos_lc_core::types::state_value::StateValue;
+        use aptos_lc_core::types::transaction::TransactionInfo;
         use std::time::Instant;
 
         let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
             setup_assets();
+        let inputs = InclusionIn::new(
+            sparse_merkle_proof_assets,
+            transaction_proof_assets,
+            validator_verifier_assets,
+            true,
+            None,
+            None,
+        );
+
+        let prover = InclusionProver::new();
 
         println!("Starting execution of inclusion...");
         let start = Instant::now();
-        execute_inclusion(
-            &sparse_merkle_proof_assets,
-            &transaction_proof_assets,
-            &validator_verifier_assets,
+        let output = prover.execute(&inputs).unwrap();
+        println!("Execution took {:?}", start.elapsed());
+
+        assert_eq!(
+            output.validator_verifier_hash(),
+            &PreparedValidatorVerifier::from_bytes(
+                inputs.validator_verifier_assets().validator_verifier()
+            )
+            .unwrap()
+            .hash()
+        );
+        let transaction =
+            TransactionInfo::from_bytes(inputs.transaction_proof_assets().transaction()).unwrap();
+        assert_eq!(
+            output.state_hash(),
+            &transaction.state_checkpoint().unwrap()
+        );
+        let latest_li =
+            LedgerInfoWithSignatures::from_bytes(inputs.transaction_proof_assets().latest_li())
+                .unwrap();
+        assert_eq!(output.block_hash(), &latest_li.ledger_info().block_id());
+        assert_eq!(
+            output.key().as_ref(),
+            inputs.sparse_merkle_proof_assets().leaf_key()
+        );
+        let leaf_value = StateValue::from_bytes(
+            inputs
+                .sparse_merkle_proof_assets()
+                .leaf_value()
+                .as_ref()
+                .unwrap(),
         )
         .unwrap();
-        println!("Execution took {:?}", start.elapsed());
+        assert_eq!(output.value(), &leaf_value.hash());
+        assert!(output.present());
+        assert_eq!(output.epoch(), &latest_li.ledger_info().epoch());
+        assert_eq!(output.version(), &latest_li.ledger_info().version());
+        assert_eq!(
+            output.timestamp_usecs(),
+            &latest_li.ledger_info().timestamp_usecs()
+        );
+        assert!(output.max_age().is_none());
+        assert_eq!(
+            output.status(),
+            &Some(StatusOut {
+                variant_index: transaction.status().variant_index(),
+                abort_code: 0,
+            })
+        );
+        assert!(output.status().unwrap().is_success());
+        assert_eq!(
+            output.state_key(),
+            &inputs
+                .sparse_merkle_proof_assets()
+                .state_key()
+                .as_ref()
+                .map(StateKeyOut::from)
+        );
+        assert!(output.resource().is_none());
     }
 
     #[test]
-    #[ignore = "This test is too slow for CI"]
-    fn test_prove_inclusion() {
-        use super::*;
-        use aptos_lc_core::crypto::hash::CryptoHash;
-        use sphinx_sdk::ProverClient;
-        use std::time::Instant;
-        let client = ProverClient::new();
+    fn test_execute_inclusion_rejects_tampered_inputs() {
+        use crate::inclusion::{account_address, assets_from_wrapper, TransactionProofAssets};
+        use aptos_lc_core::aptos_test_utils::scenario::{
+            forge_ledger_info, truncate_accumulator_proof, truncate_sparse_merkle_proof,
+            LedgerInfoForgery, ScenarioBuilder, SignerSelection,
+        };
+
+        let mut scenario = ScenarioBuilder::new(4, 10).unwrap();
+        scenario.generate_traffic().unwrap();
 
         let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
-            setup_assets();
+            assets_from_wrapper(scenario.aptos_wrapper(), |aptos_wrapper| {
+                (
+                    aptos_wrapper.get_latest_proof_account(0).unwrap(),
+                    ResourceKind::Account.state_key(account_address(aptos_wrapper, 0)),
+                )
+            });
+        let inputs = |sparse_merkle_proof_assets: &SparseMerkleProofAssets,
+                      transaction_proof_assets: &TransactionProofAssets| {
+            InclusionIn::new(
+                sparse_merkle_proof_assets.clone(),
+                transaction_proof_assets.clone(),
+                validator_verifier_assets.clone(),
+                false,
+                None,
+                None,
+            )
+        };
+        let with_latest_li = |latest_li: Vec<u8>| {
+            TransactionProofAssets::new(
+                transaction_proof_assets.transaction().clone(),
+                *transaction_proof_assets.transaction_index(),
+                transaction_proof_assets.transaction_proof().clone(),
+                latest_li,
+            )
+        };
 
-        let start = Instant::now();
-        println!("Starting generation of inclusion proof...");
-        let (proof, output) = prove_inclusion(
-            &client,
-            &sparse_merkle_proof_assets,
-            &transaction_proof_assets,
-            &validator_verifier_assets,
+        let prover = InclusionProver::new();
+        prover
+            .execute(&inputs(
+                &sparse_merkle_proof_assets,
+                &transaction_proof_assets,
+            ))
+            .unwrap();
+
+        let forged_li = forge_ledger_info(
+            transaction_proof_assets.latest_li(),
+            LedgerInfoForgery::Version,
         )
         .unwrap();
+        let impersonated_li = scenario
+            .impersonate_signers(transaction_proof_assets.latest_li())
+            .unwrap();
+        let below_quorum_li = scenario
+            .with_signers(SignerSelection::BelowQuorum)
+            .latest_li_bytes()
+            .unwrap();
+
+        let truncated_sparse_merkle_proof_assets = SparseMerkleProofAssets::new(
+            truncate_sparse_merkle_proof(sparse_merkle_proof_assets.sparse_merkle_proof()).unwrap(),
+            *sparse_merkle_proof_assets.leaf_key(),
+            sparse_merkle_proof_assets.state_key().clone(),
+            sparse_merkle_proof_assets.leaf_value().clone(),
+        );
+        let truncated_transaction_proof_assets = TransactionProofAssets::new(
+            transaction_proof_assets.transaction().clone(),
+            *transaction_proof_assets.transaction_index(),
+            truncate_accumulator_proof(transaction_proof_assets.transaction_proof()).unwrap(),
+            transaction_proof_assets.latest_li().clone(),
+        );
+        let wrong_index_transaction_proof_assets = TransactionProofAssets::new(
+            transaction_proof_assets.transaction().clone(),
+            *transaction_proof_assets.transaction_index() - 1,
+            transaction_proof_assets.transaction_proof().clone(),
+            transaction_proof_assets.latest_li().clone(),
+        );
+
+        for (case, inputs) in [
+            (
+                "forged ledger info",
+                inputs(&sparse_merkle_proof_assets, &with_latest_li(forged_li)),
+            ),
+            (
+                "impersonated signers",
+                inputs(
+                    &sparse_merkle_proof_assets,
+                    &with_latest_li(impersonated_li),
+                ),
+            ),
+            (
+                "signers below quorum",
+                inputs(
+                    &sparse_merkle_proof_assets,
+                    &with_latest_li(below_quorum_li),
+                ),
+            ),
+            (
+                "truncated sparse merkle proof",
+                inputs(
+                    &truncated_sparse_merkle_proof_assets,
+                    &transaction_proof_assets,
+                ),
+            ),
+            (
+                "truncated transaction proof",
+                inputs(
+                    &sparse_merkle_proof_assets,
+                    &truncated_transaction_proof_assets,
+                ),
+            ),
+            (
+                "wrong transaction index",
+                inputs(
+                    &sparse_merkle_proof_assets,
+                    &wrong_index_transaction_proof_assets,
+                ),
+            ),
+        ] {
+            assert!(
+                prover.execute(&inputs).is_err(),
+                "{case} should be rejected"
+            );
+        }
+    }
 
+    #[test]
+    fn test_inclusion_out_optional_values() {
+        use crate::inclusion::{StateKeyOut, StatusOut};
+        use aptos_lc_core::types::utils::{
+            INCLUSION_COIN_STORE_FLAG, INCLUSION_PRESENT_FLAG, INCLUSION_STATE_KEY_FLAG,
+            INCLUSION_STATUS_FLAG,
+        };
+        use sphinx_sdk::SphinxPublicValues;
+
+        let mut public_values = SphinxPublicValues::new();
+        for hash in [[1u8; 32], [2; 32], [3; 32], [4; 32], [5; 32]] {
+            public_values.write(&hash);
+        }
+        for value in [6u64, 7, 8] {
+            public_values.write(&value);
+        }
+        public_values.write(
+            &(INCLUSION_PRESENT_FLAG
+                | INCLUSION_STATUS_FLAG
+                | INCLUSION_STATE_KEY_FLAG
+                | INCLUSION_COIN_STORE_FLAG),
+        );
+        public_values.write(&2u8);
+        public_values.write(&65542u64);
+        public_values.write(&0u8);
+        public_values.write(&[9u8; 32]);
+        public_values.write(&[10u8; 32]);
+        public_values.write(&1000u64);
+
+        let output = InclusionOut::from(&mut public_values);
+
+        assert!(output.present());
+        assert_eq!(output.timestamp_usecs(), &8);
+        assert!(output.max_age().is_none());
         assert_eq!(
-            &output.validator_verifier_hash,
-            ValidatorVerifier::from_bytes(validator_verifier_assets.validator_verifier())
+            output.status(),
+            &Some(StatusOut {
+                variant_index: 2,
+                abort_code: 65542,
+            })
+        );
+        assert!(!output.status().unwrap().is_success());
+        assert_eq!(
+            output.state_key(),
+            &Some(StateKeyOut {
+                variant_index: 0,
+                address: [9; 32],
+                identifier: [10; 32],
+            })
+        );
+        assert_eq!(
+            output.resource(),
+            &Some(ResourceOut::AptosCoinStore {
+                address: [9; 32],
+                balance: 1000
+            })
+        );
+    }
+
+    #[test]
+    fn test_inclusion_prover_key_store() {
+        use crate::key_store::{verifying_key_hash, KeyStore};
+
+        let dir = std::env::temp_dir().join(format!("aptos-lc-key-store-{}", std::process::id()));
+        let key_store = KeyStore::new(&dir).unwrap();
+
+        let prover = InclusionProver::with_key_store(&key_store).unwrap();
+        // Keys are loaded back from the store
+        let cached_prover = InclusionProver::with_key_store(&key_store).unwrap();
+        assert_eq!(
+            verifying_key_hash(cached_prover.get_vk()),
+            verifying_key_hash(prover.get_vk())
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_execute_non_inclusion() {
+        use std::time::Instant;
+
+        let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            setup_non_inclusion_assets();
+        assert!(sparse_merkle_proof_assets.leaf_value().is_none());
+
+        let prover = InclusionProver::new();
+
+        println!("Starting execution of non-inclusion...");
+        let start = Instant::now();
+        let output = prover
+            .execute(&InclusionIn::new(
+                sparse_merkle_proof_assets,
+                transaction_proof_assets,
+                validator_verifier_assets,
+                false,
+                None,
+                None,
+            ))
+            .unwrap();
+        println!("Execution took {:?}", start.elapsed());
+
+        assert_eq!(output.value().as_ref(), &[0; 32]);
+        assert!(!output.present());
+    }
+
+    #[test]
+    fn test_execute_inclusion_max_age() {
+        let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            setup_assets();
+        let timestamp_usecs =
+            LedgerInfoWithSignatures::from_bytes(transaction_proof_assets.latest_li())
                 .unwrap()
-                .hash()
-                .as_ref()
+                .ledger_info()
+                .timestamp_usecs();
+
+        let prover = InclusionProver::new();
+
+        let max_age = MaxAge::new(timestamp_usecs + 1_000_000, 1_000_000);
+        let output = prover
+            .execute(&InclusionIn::new(
+                sparse_merkle_proof_assets.clone(),
+                transaction_proof_assets.clone(),
+                validator_verifier_assets.clone(),
+                false,
+                None,
+                Some(max_age),
+            ))
+            .unwrap();
+
+        assert_eq!(output.timestamp_usecs(), &timestamp_usecs);
+        assert_eq!(output.max_age(), &Some(max_age));
+
+        // A ledger info older than the maximum age is rejected
+        assert!(prover
+            .execute(&InclusionIn::new(
+                sparse_merkle_proof_assets,
+                transaction_proof_assets,
+                validator_verifier_assets,
+                false,
+                None,
+                Some(MaxAge::new(timestamp_usecs + 1_000_001, 1_000_000)),
+            ))
+            .is_err());
+    }
+
+    #[test]
+    fn test_execute_inclusion_wrong_state_key() {
+        let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            setup_assets();
+
+        // The key of the account resource cannot be derived from another resource
+        let Some(StateKey::Resource { address, .. }) = sparse_merkle_proof_assets.state_key()
+        else {
+            panic!("expected a resource state key");
+        };
+        let sparse_merkle_proof_assets = SparseMerkleProofAssets::new(
+            sparse_merkle_proof_assets.sparse_merkle_proof().clone(),
+            *sparse_merkle_proof_assets.leaf_key(),
+            Some(ResourceKind::AptosCoinStore.state_key(*address)),
+            sparse_merkle_proof_assets.leaf_value().clone(),
         );
 
+        let prover = InclusionProver::new();
+
+        assert!(prover
+            .execute(&InclusionIn::new(
+                sparse_merkle_proof_assets,
+                transaction_proof_assets,
+                validator_verifier_assets,
+                false,
+                None,
+                None,
+            ))
+            .is_err());
+    }
+
+    #[test]
+    fn test_execute_inclusion_decode_resources() {
+        use aptos_lc_core::types::resource::{AccountResource, CoinStoreResource};
+        use aptos_lc_core::types::state_value::StateValue;
+
+        let prover = InclusionProver::new();
+
+        let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            setup_assets();
+        let state_value =
+            StateValue::from_bytes(sparse_merkle_proof_assets.leaf_value().as_ref().unwrap())
+                .unwrap();
+        let account = AccountResource::from_bytes(state_value.data()).unwrap();
+        let account_address = sparse_merkle_proof_assets
+            .state_key()
+            .as_ref()
+            .unwrap()
+            .address()
+            .to_bytes();
+
+        let output = prover
+            .execute(&InclusionIn::new(
+                sparse_merkle_proof_assets,
+                transaction_proof_assets,
+                validator_verifier_assets,
+                false,
+                Some(ResourceKind::Account),
+                None,
+            ))
+            .unwrap();
+
+        let Some(ResourceOut::Account {
+            address,
+            sequence_number,
+            authentication_key,
+        }) = output.resource()
+        else {
+            panic!("expected decoded account fields");
+        };
+        assert_eq!(address.as_slice(), account_address);
+        assert_eq!(*sequence_number, account.sequence_number());
+        assert_eq!(
+            authentication_key.as_slice(),
+            account.authentication_key().as_slice()
+        );
+
+        let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            setup_coin_store_assets();
+        let state_value =
+            StateValue::from_bytes(sparse_merkle_proof_assets.leaf_value().as_ref().unwrap())
+                .unwrap();
+        let coin_store = CoinStoreResource::from_bytes(state_value.data()).unwrap();
+        let coin_store_address = sparse_merkle_proof_assets
+            .state_key()
+            .as_ref()
+            .unwrap()
+            .address()
+            .to_bytes();
+
+        let output = prover
+            .execute(&InclusionIn::new(
+                sparse_merkle_proof_assets,
+                transaction_proof_assets,
+                validator_verifier_assets,
+                false,
+                Some(ResourceKind::AptosCoinStore),
+                None,
+            ))
+            .unwrap();
+
+        assert_eq!(
+            output.resource(),
+            &Some(ResourceOut::AptosCoinStore {
+                address: coin_store_address.try_into().unwrap(),
+                balance: coin_store.balance()
+            })
+        );
+    }
+
+    #[test]
+    #[ignore = "This test is too slow for CI"]
+    fn test_prove_inclusion() {
+        use std::time::Instant;
+
+        let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
+            setup_assets();
+        let inputs = InclusionIn::new(
+            sparse_merkle_proof_assets,
+            transaction_proof_assets,
+            validator_verifier_assets,
+            false,
+            None,
+            None,
+        );
+
+        let prover = InclusionProver::new();
+
+        let start = Instant::now();
+        println!("Starting generation of inclusion proof...");
+        let proof = prover.prove(&inputs, ProvingMode::STARK).unwrap();
         println!("Proving took {:?}", start.elapsed());
 
-        let (_, vk) = client.setup(aptos_programs::INCLUSION_PROGRAM);
         let start = Instant::now();
         println!("Starting verification of inclusion proof...");
-        client.verify(&proof, &vk).unwrap();
+        prover.verify(&proof).unwrap();
         println!("Verification took {:?}", start.elapsed());
+
+        let output = InclusionOut::from(&mut proof.public_values());
+        assert!(output.max_age().is_none());
+        assert!(output.status().is_none());
+        assert!(output.resource().is_none());
+        assert_eq!(
+            output.validator_verifier_hash(),
+            &PreparedValidatorVerifier::from_bytes(
+                inputs.validator_verifier_assets().validator_verifier()
+            )
+            .unwrap()
+            .hash()
+        );
     }
 
     #[test]
     #[ignore = "This test is too slow for CI"]
     fn test_snark_prove_inclusion() {
-        use super::*;
-        use sphinx_sdk::ProverClient;
         use std::time::Instant;
 
-        setup_logger();
-
-        let client = ProverClient::new();
-        let (pk, vk) = client.setup(aptos_programs::INCLUSION_PROGRAM);
-
         let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
             setup_assets();
 
-        let stdin = generate_stdin(
-            &sparse_merkle_proof_assets,
-            &transaction_proof_assets,
-            &validator_verifier_assets,
-        );
+        let prover = InclusionProver::new();
 
         // Install PLONK artifacts.
         try_install_plonk_bn254_artifacts(false);
 
         let start = Instant::now();
         println!("Starting generation of inclusion proof...");
-        let snark_proof = client.prove(&pk, stdin).plonk().run().unwrap();
+        let snark_proof = prover
+            .prove(
+                &InclusionIn::new(
+                    sparse_merkle_proof_assets,
+                    transaction_proof_assets,
+                    validator_verifier_assets,
+                    false,
+                    None,
+                    None,
+                ),
+                ProvingMode::SNARK,
+            )
+            .unwrap();
         println!("Proving took {:?}", start.elapsed());
 
         let start = Instant::now();
         println!("Starting verification of inclusion proof...");
-        client.verify(&snark_proof, &vk).unwrap();
+        prover.verify(&snark_proof).unwrap();
         println!("Verification took {:?}", start.elapsed());
     }
 }
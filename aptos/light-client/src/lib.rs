@@ -1,7 +1,14 @@
 // Copyright (c) Argument Computer Corporation
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod aggregation;
+pub mod batch_inclusion;
 pub mod epoch_change;
-mod error;
+pub mod error;
+pub mod event;
 pub mod inclusion;
+pub mod prover;
 mod sig;
+pub mod transaction_range;
+
+pub use lc_key_store as key_store;
@@ -0,0 +1,212 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! # Prover module
+//!
+//! This module contains the `Prover` trait implemented by the provers of the
+//! light client programs, along with the proving mode and proof types they
+//! share.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use sphinx_sdk::{SphinxProofWithPublicValues, SphinxPublicValues, SphinxStdin};
+
+/// Name of the compressed STARK proofs verified recursively by the aggregation program.
+pub const COMPRESSED_PROOF_TYPE: &str = "compressed STARK";
+
+/// The proving mode for the prover.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ProvingMode {
+    STARK,
+    SNARK,
+}
+
+impl ProvingMode {
+    /// Returns a boolean indicating if the proving mode is STARK.
+    ///
+    /// # Returns
+    ///
+    /// A boolean indicating if the proving mode is STARK.
+    pub const fn is_stark(&self) -> bool {
+        matches!(self, ProvingMode::STARK)
+    }
+
+    /// Returns a serialized representation of the enum.
+    ///
+    /// # Returns
+    ///
+    /// A u8 representing the enum.
+    pub const fn to_bytes(&self) -> u8 {
+        match self {
+            ProvingMode::STARK => 0,
+            ProvingMode::SNARK => 1,
+        }
+    }
+
+    /// Returns a ProvingMode from a serialized representation.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The serialized representation of the enum.
+    ///
+    /// # Returns
+    ///
+    /// The ProvingMode, or an error if the bytes are empty or hold an unknown mode.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        match bytes.first() {
+            Some(0) => Ok(ProvingMode::STARK),
+            Some(1) => Ok(ProvingMode::SNARK),
+            Some(_) => Err(anyhow!("Invalid proving mode")),
+            None => Err(anyhow!("Missing proving mode")),
+        }
+    }
+}
+
+impl From<ProvingMode> for String {
+    fn from(mode: ProvingMode) -> String {
+        match mode {
+            ProvingMode::STARK => "STARK".to_string(),
+            ProvingMode::SNARK => "SNARK".to_string(),
+        }
+    }
+}
+
+impl TryFrom<&str> for ProvingMode {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
+        match value {
+            "STARK" => Ok(ProvingMode::STARK),
+            "SNARK" => Ok(ProvingMode::SNARK),
+            _ => Err(anyhow!("Invalid proving mode")),
+        }
+    }
+}
+
+/// The proof type generated by the prover.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ProofType {
+    STARK(SphinxProofWithPublicValues),
+    SNARK(SphinxProofWithPublicValues),
+}
+
+impl ProofType {
+    /// Returns a boolean indicating if the proof type is STARK.
+    ///
+    /// # Returns
+    ///
+    /// A boolean indicating if the proof type is STARK.
+    pub const fn is_stark(&self) -> bool {
+        matches!(self, ProofType::STARK(_))
+    }
+
+    /// Returns the underlying Sphinx proof.
+    ///
+    /// # Returns
+    ///
+    /// The Sphinx proof, along with its public values.
+    pub const fn proof(&self) -> &SphinxProofWithPublicValues {
+        match self {
+            ProofType::STARK(proof) | ProofType::SNARK(proof) => proof,
+        }
+    }
+
+    /// Returns the public values of the proof.
+    ///
+    /// # Returns
+    ///
+    /// The public values of the proof.
+    pub fn public_values(self) -> SphinxPublicValues {
+        match self {
+            ProofType::STARK(proof) | ProofType::SNARK(proof) => proof.public_values,
+        }
+    }
+}
+
+/// `Prover` is a trait that embodies a prover in the context of our Light Client. It is dedicated to
+/// a given program and has a specific input and output type.
+pub trait Prover {
+    /// The program to prove and verify.
+    const PROGRAM: &'static [u8];
+
+    /// The error type for the prover.
+    type Error;
+
+    /// The input type for the prover.
+    type StdIn;
+
+    /// The output type for the prover.
+    type StdOut;
+
+    /// Generate the Sphinx stdin for the prover.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - The input for the prover.
+    ///
+    /// # Returns
+    ///
+    /// The Sphinx stdin.
+    fn generate_sphinx_stdin(&self, inputs: &Self::StdIn) -> Result<SphinxStdin, Self::Error>;
+
+    /// Execute the program, useful to get the cycles that the program will take.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - The input for the prover.
+    ///
+    /// # Returns
+    ///
+    /// The output of the prover.
+    fn execute(&self, inputs: &Self::StdIn) -> Result<Self::StdOut, Self::Error>;
+
+    /// Generate a proof for the program. The proof can either be a STARK or a SNARK proof.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - The input for the prover.
+    /// * `mode` - The proof type to generate.
+    ///
+    /// # Returns
+    ///
+    /// The proof.
+    fn prove(&self, inputs: &Self::StdIn, mode: ProvingMode) -> Result<ProofType, Self::Error>;
+
+    /// Verify a proof for the program.
+    ///
+    /// # Arguments
+    ///
+    /// * `proof` - The proof to verify.
+    ///
+    /// # Returns
+    ///
+    /// An empty result if the proof is valid.
+    fn verify(&self, proof: &ProofType) -> Result<(), Self::Error>;
+}
+
+// The proving mode does not depend on the Aptos types, so its tests run without
+// the `aptos` feature
+#[cfg(test)]
+mod test {
+    use crate::prover::ProvingMode;
+
+    #[test]
+    fn test_proving_mode_bytes_conversion() {
+        for mode in [ProvingMode::STARK, ProvingMode::SNARK] {
+            assert_eq!(ProvingMode::from_bytes(&[mode.to_bytes()]).unwrap(), mode);
+        }
+
+        assert!(ProvingMode::from_bytes(&[2]).is_err());
+        assert!(ProvingMode::from_bytes(&[]).is_err());
+    }
+
+    #[test]
+    fn test_proving_mode_string_conversion() {
+        for mode in [ProvingMode::STARK, ProvingMode::SNARK] {
+            let name = String::from(mode);
+            assert_eq!(ProvingMode::try_from(name.as_str()).unwrap(), mode);
+        }
+
+        assert!(ProvingMode::try_from("stark").is_err());
+    }
+}
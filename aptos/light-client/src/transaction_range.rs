@@ -0,0 +1,307 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+use sphinx_sdk::{
+    ProverClient, SphinxProofWithPublicValues, SphinxProvingKey, SphinxStdin, SphinxVerifyingKey,
+};
+
+use crate::error::LightClientError;
+use crate::inclusion::ValidatorVerifierAssets;
+
+#[derive(Clone, Debug, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct TransactionRangeProofAssets {
+    transactions: Vec<Vec<u8>>,
+    first_transaction_version: u64,
+    range_proof: Vec<u8>,
+    latest_li: Vec<u8>,
+}
+
+impl TransactionRangeProofAssets {
+    pub const fn new(
+        transactions: Vec<Vec<u8>>,
+        first_transaction_version: u64,
+        range_proof: Vec<u8>,
+        latest_li: Vec<u8>,
+    ) -> TransactionRangeProofAssets {
+        TransactionRangeProofAssets {
+            transactions,
+            first_transaction_version,
+            range_proof,
+            latest_li,
+        }
+    }
+}
+
+#[cfg(feature = "aptos")]
+pub fn setup_assets(
+    num_transactions: u64,
+) -> (TransactionRangeProofAssets, ValidatorVerifierAssets) {
+    use aptos_lc_core::aptos_test_utils::wrapper::AptosWrapper;
+    use aptos_lc_core::types::trusted_state::TrustedState;
+    use aptos_lc_core::types::validator::PreparedValidatorVerifier;
+
+    const NBR_VALIDATORS: usize = 130;
+    const AVERAGE_SIGNERS_NBR: usize = 95;
+
+    let mut aptos_wrapper = AptosWrapper::new(500, NBR_VALIDATORS, AVERAGE_SIGNERS_NBR).unwrap();
+    aptos_wrapper.generate_traffic().unwrap();
+
+    let proof_assets = aptos_wrapper
+        .get_latest_transaction_range_proof(num_transactions)
+        .unwrap();
+
+    let transactions = proof_assets
+        .transactions()
+        .iter()
+        .map(|transaction| bcs::to_bytes(transaction).unwrap())
+        .collect();
+    let range_proof = bcs::to_bytes(proof_assets.range_proof()).unwrap();
+
+    let latest_li = aptos_wrapper.get_latest_li_bytes().unwrap();
+
+    let validator_verifier =
+        match TrustedState::from_bytes(&bcs::to_bytes(&aptos_wrapper.trusted_state()).unwrap())
+            .unwrap()
+        {
+            TrustedState::EpochState { epoch_state, .. } => epoch_state.verifier().clone(),
+            _ => panic!("expected epoch state"),
+        };
+
+    let transaction_range_proof_assets = TransactionRangeProofAssets {
+        transactions,
+        first_transaction_version: *proof_assets.first_transaction_version(),
+        range_proof,
+        latest_li,
+    };
+
+    let validator_verifier_assets = ValidatorVerifierAssets::new(
+        PreparedValidatorVerifier::new(validator_verifier)
+            .unwrap()
+            .to_bytes(),
+    );
+
+    (transaction_range_proof_assets, validator_verifier_assets)
+}
+
+pub fn generate_stdin(
+    transaction_range_proof_assets: &TransactionRangeProofAssets,
+    validator_verifier_assets: &ValidatorVerifierAssets,
+) -> SphinxStdin {
+    let mut stdin = SphinxStdin::new();
+    // Txs range inclusion input
+    stdin.write(&transaction_range_proof_assets.transactions);
+    stdin.write(&transaction_range_proof_assets.first_transaction_version);
+    stdin.write(&transaction_range_proof_assets.range_proof);
+    stdin.write(&transaction_range_proof_assets.latest_li);
+
+    // Validator verifier
+    stdin.write(validator_verifier_assets.validator_verifier());
+
+    stdin
+}
+
+#[inline]
+pub fn generate_keys(client: &ProverClient) -> (SphinxProvingKey, SphinxVerifyingKey) {
+    client.setup(aptos_programs::TRANSACTION_RANGE_PROGRAM)
+}
+
+#[allow(dead_code)]
+struct TransactionRangeOutput {
+    validator_verifier_hash: [u8; 32],
+    block_hash: [u8; 32],
+    first_transaction_version: u64,
+    last_transaction_version: u64,
+    /// Hash of the concatenated hashes of the proven `TransactionInfo`s.
+    transactions_hash: [u8; 32],
+}
+
+#[allow(dead_code)]
+fn prove_transaction_range(
+    client: &ProverClient,
+    transaction_range_proof_assets: &TransactionRangeProofAssets,
+    validator_verifier_assets: &ValidatorVerifierAssets,
+) -> Result<(SphinxProofWithPublicValues, TransactionRangeOutput), LightClientError> {
+    sphinx_sdk::utils::setup_logger();
+
+    let stdin = generate_stdin(transaction_range_proof_assets, validator_verifier_assets);
+    let (pk, _) = generate_keys(client);
+
+    let mut proof =
+        client
+            .prove(&pk, stdin)
+            .run()
+            .map_err(|err| LightClientError::ProvingError {
+                program: "prove-transaction-range".to_string(),
+                source: err.into(),
+            })?;
+
+    // Read output.
+    let validator_verifier_hash = proof.public_values.read::<[u8; 32]>();
+    let block_hash = proof.public_values.read::<[u8; 32]>();
+    let first_transaction_version = proof.public_values.read::<u64>();
+    let last_transaction_version = proof.public_values.read::<u64>();
+    let transactions_hash = proof.public_values.read::<[u8; 32]>();
+
+    Ok((
+        proof,
+        TransactionRangeOutput {
+            validator_verifier_hash,
+            block_hash,
+            first_transaction_version,
+            last_transaction_version,
+            transactions_hash,
+        },
+    ))
+}
+
+#[cfg(all(test, feature = "aptos"))]
+mod test {
+    use crate::error::LightClientError;
+    use crate::transaction_range::{generate_stdin, setup_assets};
+    use sphinx_sdk::utils::setup_logger;
+    use sphinx_sdk::ProverClient;
+
+    const NBR_TRANSACTIONS: u64 = 10;
+
+    #[test]
+    fn test_execute_transaction_range() {
+        use std::time::Instant;
+
+        setup_logger();
+
+        let (transaction_range_proof_assets, validator_verifier_assets) =
+            setup_assets(NBR_TRANSACTIONS);
+
+        let stdin = generate_stdin(&transaction_range_proof_assets, &validator_verifier_assets);
+
+        println!("Starting execution of transaction range...");
+        let start = Instant::now();
+        let client = ProverClient::new();
+        client
+            .execute(aptos_programs::TRANSACTION_RANGE_PROGRAM, stdin)
+            .run()
+            .map_err(|err| LightClientError::ProvingError {
+                program: "prove-transaction-range".to_string(),
+                source: err.into(),
+            })
+            .unwrap();
+        println!("Execution took {:?}", start.elapsed());
+    }
+
+    #[test]
+    fn test_execute_transaction_range_rejects_tampered_inputs() {
+        use crate::transaction_range::TransactionRangeProofAssets;
+        use aptos_lc_core::aptos_test_utils::scenario::{
+            forge_ledger_info, truncate_range_proof, LedgerInfoForgery,
+        };
+
+        let (transaction_range_proof_assets, validator_verifier_assets) =
+            setup_assets(NBR_TRANSACTIONS);
+        let with = |first_transaction_version: u64, range_proof: Vec<u8>, latest_li: Vec<u8>| {
+            TransactionRangeProofAssets::new(
+                transaction_range_proof_assets.transactions().clone(),
+                first_transaction_version,
+                range_proof,
+                latest_li,
+            )
+        };
+        let first_transaction_version = *transaction_range_proof_assets.first_transaction_version();
+        let range_proof = transaction_range_proof_assets.range_proof();
+        let latest_li = transaction_range_proof_assets.latest_li();
+
+        let client = ProverClient::new();
+        for (case, transaction_range_proof_assets) in [
+            (
+                "truncated range proof",
+                with(
+                    first_transaction_version,
+                    truncate_range_proof(range_proof).unwrap(),
+                    latest_li.clone(),
+                ),
+            ),
+            (
+                "wrong first transaction version",
+                with(
+                    first_transaction_version - 1,
+                    range_proof.clone(),
+                    latest_li.clone(),
+                ),
+            ),
+            (
+                "forged ledger info",
+                with(
+                    first_transaction_version,
+                    range_proof.clone(),
+                    forge_ledger_info(latest_li, LedgerInfoForgery::Timestamp).unwrap(),
+                ),
+            ),
+        ] {
+            let stdin = generate_stdin(&transaction_range_proof_assets, &validator_verifier_assets);
+            assert!(
+                client
+                    .execute(aptos_programs::TRANSACTION_RANGE_PROGRAM, stdin)
+                    .run()
+                    .is_err(),
+                "{case} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    #[ignore = "This test is too slow for CI"]
+    fn test_prove_transaction_range() {
+        use super::*;
+        use aptos_lc_core::crypto::hash::{hash_data, CryptoHash};
+        use aptos_lc_core::types::transaction::TransactionInfo;
+        use std::time::Instant;
+
+        let client = ProverClient::new();
+
+        let (transaction_range_proof_assets, validator_verifier_assets) =
+            setup_assets(NBR_TRANSACTIONS);
+
+        let start = Instant::now();
+        println!("Starting generation of transaction range proof...");
+        let (proof, output) = prove_transaction_range(
+            &client,
+            &transaction_range_proof_assets,
+            &validator_verifier_assets,
+        )
+        .unwrap();
+        println!("Proving took {:?}", start.elapsed());
+
+        assert_eq!(
+            output.first_transaction_version,
+            *transaction_range_proof_assets.first_transaction_version()
+        );
+        assert_eq!(
+            output.last_transaction_version,
+            output.first_transaction_version + NBR_TRANSACTIONS - 1
+        );
+        let transaction_hashes: Vec<_> = transaction_range_proof_assets
+            .transactions()
+            .iter()
+            .map(|transaction| TransactionInfo::from_bytes(transaction).unwrap().hash())
+            .collect();
+        assert_eq!(
+            output.transactions_hash,
+            hash_data(
+                &[],
+                transaction_hashes
+                    .iter()
+                    .map(|hash| hash.as_ref().as_slice())
+                    .collect()
+            )
+        );
+
+        let (_, vk) = generate_keys(&client);
+        let start = Instant::now();
+        println!("Starting verification of transaction range proof...");
+        client.verify(&proof, &vk).unwrap();
+        println!("Verification took {:?}", start.elapsed());
+    }
+}
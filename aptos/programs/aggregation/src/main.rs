@@ -0,0 +1,70 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+#![no_main]
+
+use aptos_lc_core::crypto::hash::HASH_LENGTH;
+use sha2::{Digest, Sha256};
+
+sphinx_zkvm::entrypoint!(main);
+
+pub fn main() {
+    sphinx_zkvm::precompiles::unconstrained! {
+                println!("cycle-tracker-start: read_inputs");
+    }
+    // Verifying key digest and public values of the epoch change proof
+    let epoch_change_vk_digest = sphinx_zkvm::io::read::<[u32; 8]>();
+    let epoch_change_public_values = sphinx_zkvm::io::read::<Vec<u8>>();
+
+    // Verifying key digest and public values of the inclusion proof
+    let inclusion_vk_digest = sphinx_zkvm::io::read::<[u32; 8]>();
+    let inclusion_public_values = sphinx_zkvm::io::read::<Vec<u8>>();
+    sphinx_zkvm::precompiles::unconstrained! {
+                println!("cycle-tracker-end: read_inputs");
+    }
+
+    // Verify both proofs, read from the proof stream in the same order
+    sphinx_zkvm::precompiles::unconstrained! {
+                println!("cycle-tracker-start: verify_proofs");
+    }
+    sphinx_zkvm::precompiles::verify::verify_sphinx_proof(
+        &epoch_change_vk_digest,
+        &Sha256::digest(&epoch_change_public_values).into(),
+    );
+    sphinx_zkvm::precompiles::verify::verify_sphinx_proof(
+        &inclusion_vk_digest,
+        &Sha256::digest(&inclusion_public_values).into(),
+    );
+    sphinx_zkvm::precompiles::unconstrained! {
+                println!("cycle-tracker-end: verify_proofs");
+    }
+
+    // The epoch change program commits the previous and the new prepared validator
+    // verifier hashes first, and the inclusion program the prepared validator verifier
+    // hash it verified the signature with
+    sphinx_zkvm::precompiles::unconstrained! {
+                println!("cycle-tracker-start: check_validator_verifier");
+    }
+    let new_validator_verifier_hash = epoch_change_public_values
+        .get(HASH_LENGTH..2 * HASH_LENGTH)
+        .expect("epoch_change_public_values: missing new validator verifier hash");
+    let inclusion_validator_verifier_hash = inclusion_public_values
+        .get(..HASH_LENGTH)
+        .expect("inclusion_public_values: missing validator verifier hash");
+    assert_eq!(
+        new_validator_verifier_hash, inclusion_validator_verifier_hash,
+        "inclusion: validator verifier hash does not match the new epoch"
+    );
+    sphinx_zkvm::precompiles::unconstrained! {
+                println!("cycle-tracker-end: check_validator_verifier");
+    }
+
+    // Commit the verifying key digests, so that the verifier can check which programs
+    // the aggregated proofs were generated for
+    sphinx_zkvm::io::commit(&epoch_change_vk_digest);
+    sphinx_zkvm::io::commit(&inclusion_vk_digest);
+
+    // Commit the public values of both proofs, as committed by their program
+    sphinx_zkvm::io::commit_slice(&epoch_change_public_values);
+    sphinx_zkvm::io::commit_slice(&inclusion_public_values);
+}
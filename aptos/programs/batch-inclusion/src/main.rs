@@ -0,0 +1,166 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+#![no_main]
+
+use aptos_lc_core::crypto::hash::{hash_data, CryptoHash, HashValue};
+use aptos_lc_core::merkle::sparse_proof::SparseMerkleProof;
+use aptos_lc_core::merkle::transaction_proof::TransactionAccumulatorProof;
+use aptos_lc_core::types::ledger_info::LedgerInfoWithSignatures;
+use aptos_lc_core::types::state_key::StateKey;
+use aptos_lc_core::types::state_value::StateValue;
+use aptos_lc_core::types::transaction::TransactionInfo;
+use aptos_lc_core::types::utils::{INCLUSION_PRESENT_FLAG, INCLUSION_STATE_KEY_FLAG};
+use aptos_lc_core::types::validator::PreparedValidatorVerifier;
+
+sphinx_zkvm::entrypoint!(main);
+
+pub fn main() {
+    sphinx_zkvm::precompiles::unconstrained! {
+                println!("cycle-tracker-start: read_inputs");
+    }
+    // Get inputs for accounts inclusion, as (proof, key, state key, raw `StateValue`) tuples
+    let leaves =
+        sphinx_zkvm::io::read::<Vec<(Vec<u8>, [u8; 32], Option<StateKey>, Option<Vec<u8>>)>>();
+
+    // Get inputs for tx inclusion
+    let transaction_bytes = sphinx_zkvm::io::read::<Vec<u8>>();
+    let transaction_index = sphinx_zkvm::io::read::<u64>();
+    let transaction_proof = sphinx_zkvm::io::read::<Vec<u8>>();
+    let ledger_info_bytes = sphinx_zkvm::io::read::<Vec<u8>>();
+
+    // Latest verified prepared validator verifier
+    let verified_validator_verifier = sphinx_zkvm::io::read::<Vec<u8>>();
+
+    sphinx_zkvm::precompiles::unconstrained! {
+                println!("cycle-tracker-end: read_inputs");
+    }
+
+    assert!(!leaves.is_empty(), "leaves: expected at least one leaf");
+
+    // Deserialize Prepared Validator Verifier
+    let validator_verifier = PreparedValidatorVerifier::from_bytes(&verified_validator_verifier)
+        .expect("validator_verifier: could not create PreparedValidatorVerifier from bytes");
+
+    // Verify transaction inclusion in the LedgerInfoWithSignatures
+    let transaction = TransactionInfo::from_bytes(&transaction_bytes)
+        .expect("from_bytes: could not deserialize TransactionInfo");
+    let transaction_hash = transaction.hash();
+    let transaction_proof = TransactionAccumulatorProof::from_bytes(&transaction_proof)
+        .expect("from_bytes: could not deserialize TransactionAccumulatorProof");
+    let latest_li = LedgerInfoWithSignatures::from_bytes(&ledger_info_bytes)
+        .expect("from_bytes: could not deserialize LedgerInfo");
+
+    sphinx_zkvm::precompiles::unconstrained! {
+                println!("cycle-tracker-start: verify_transaction_inclusion");
+    }
+
+    let expected_root_hash = latest_li.ledger_info().transaction_accumulator_hash();
+
+    transaction_proof
+        .verify(expected_root_hash, transaction_hash, transaction_index)
+        .expect("verify: could not verify proof");
+    sphinx_zkvm::precompiles::unconstrained! {
+                println!("cycle-tracker-end: verify_transaction_inclusion");
+    }
+
+    // Check signature
+    sphinx_zkvm::precompiles::unconstrained! {
+                println!("cycle-tracker-start: verify_signature");
+    }
+    latest_li
+        .verify_prepared_signatures(&validator_verifier)
+        .expect("verify_prepared_signatures: could not verify signatures");
+    sphinx_zkvm::precompiles::unconstrained! {
+                    println!("cycle-tracker-end: verify_signature");
+    }
+
+    // Verify every account inclusion against the same state checkpoint
+    sphinx_zkvm::precompiles::unconstrained! {
+                println!("cycle-tracker-start: verify_merkle_proofs");
+    }
+    let sparse_expected_root_hash = transaction
+        .state_checkpoint()
+        .expect("state_checkpoint: could not get state checkpoint");
+    let mut proven_leaves = Vec::with_capacity(leaves.len());
+    for (sparse_merkle_proof_bytes, key, state_key, leaf_value_bytes) in leaves {
+        let sparse_merkle_proof = SparseMerkleProof::from_bytes(&sparse_merkle_proof_bytes)
+            .expect("from_bytes: could not deserialize SparseMerkleProof");
+        let key = HashValue::from_slice(key).expect("key: could not use input to create HashValue");
+        if let Some(state_key) = &state_key {
+            assert_eq!(
+                state_key.hash(),
+                key,
+                "state_key: hash does not match the leaf key"
+            );
+        }
+        let leaf_value_hash = leaf_value_bytes.map(|bytes| {
+            StateValue::from_bytes(&bytes)
+                .expect("from_bytes: could not deserialize StateValue")
+                .hash()
+        });
+        match leaf_value_hash {
+            Some(leaf_value_hash) => sparse_merkle_proof
+                .verify_by_hash(sparse_expected_root_hash, key, leaf_value_hash)
+                .expect("verify_by_hash: could not verify proof"),
+            None => sparse_merkle_proof
+                .verify_non_inclusion(sparse_expected_root_hash, key)
+                .expect("verify_non_inclusion: could not verify proof"),
+        };
+
+        // Tell present and absent keys apart, rather than relying on an all zeroes value hash
+        let mut flags = 0;
+        if leaf_value_hash.is_some() {
+            flags |= INCLUSION_PRESENT_FLAG;
+        }
+        // Bind the leaf to the resource or table item its key is derived from, if known
+        let mut state_key_bytes = Vec::new();
+        if let Some(state_key) = &state_key {
+            flags |= INCLUSION_STATE_KEY_FLAG;
+            state_key_bytes.push(state_key.variant_index());
+            state_key_bytes.extend_from_slice(&state_key.address().to_bytes());
+            state_key_bytes.extend_from_slice(&state_key.identifier());
+        }
+        proven_leaves.push((
+            key,
+            [flags],
+            leaf_value_hash.unwrap_or_default(),
+            state_key_bytes,
+        ));
+    }
+    sphinx_zkvm::precompiles::unconstrained! {
+                println!("cycle-tracker-end: verify_merkle_proofs");
+    }
+
+    // Commit the prepared validator verifier hash
+    sphinx_zkvm::io::commit(validator_verifier.hash().as_ref());
+
+    // Commit the state root hash
+    sphinx_zkvm::io::commit(sparse_expected_root_hash.as_ref());
+
+    // Commit current block id
+    let block_hash = latest_li.ledger_info().block_id();
+    sphinx_zkvm::io::commit(block_hash.as_ref());
+
+    // Commit the number of proven leaves
+    sphinx_zkvm::io::commit(&(proven_leaves.len() as u64));
+
+    // Commit the hash of the concatenated keys, flags, value hashes and state keys, the flags
+    // telling whether each key is present or absent under the state root, and whether its state
+    // key follows
+    let leaves_hash = hash_data(
+        &[],
+        proven_leaves
+            .iter()
+            .flat_map(|(key, flags, value_hash, state_key_bytes)| {
+                [
+                    key.as_ref().as_slice(),
+                    flags.as_slice(),
+                    value_hash.as_ref().as_slice(),
+                    state_key_bytes.as_slice(),
+                ]
+            })
+            .collect(),
+    );
+    sphinx_zkvm::io::commit(&leaves_hash);
+}
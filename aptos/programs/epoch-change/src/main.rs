@@ -5,6 +5,10 @@
 
 use aptos_lc_core::crypto::hash::CryptoHash;
 use aptos_lc_core::types::trusted_state::{EpochChangeProof, TrustedState, TrustedStateChange};
+use aptos_lc_core::types::utils::{
+    EPOCH_CHANGE_FROM_VALIDATOR_VERIFIER, EPOCH_CHANGE_FROM_WAYPOINT,
+};
+use aptos_lc_core::types::validator::PreparedValidatorVerifier;
 
 sphinx_zkvm::entrypoint!(main);
 
@@ -14,6 +18,8 @@ pub fn main() {
     }
     let trusted_state_bytes = sphinx_zkvm::io::read::<Vec<u8>>();
     let epoch_change_proof = sphinx_zkvm::io::read::<Vec<u8>>();
+    // Prepared validator verifier of the trusted epoch, if the trusted state is an epoch state
+    let prev_prepared_verifier = sphinx_zkvm::io::read::<Option<Vec<u8>>>();
     sphinx_zkvm::precompiles::unconstrained! {
                 println!("cycle-tracker-end: read_inputs");
     }
@@ -35,51 +41,80 @@ pub fn main() {
     sphinx_zkvm::precompiles::unconstrained! {
                 println!("cycle-tracker-end: deserialize_epoch_change_proof");
     }
+    sphinx_zkvm::precompiles::unconstrained! {
+                println!("cycle-tracker-start: deserialize_prepared_verifier");
+    }
+    let prev_prepared_verifier = prev_prepared_verifier.map(|bytes| {
+        PreparedValidatorVerifier::from_bytes(&bytes)
+            .expect("PreparedValidatorVerifier::from_bytes: could not create prepared verifier")
+    });
+    sphinx_zkvm::precompiles::unconstrained! {
+                println!("cycle-tracker-end: deserialize_prepared_verifier");
+    }
 
     // Verify and ratchet the trusted state
     sphinx_zkvm::precompiles::unconstrained! {
                 println!("cycle-tracker-start: verify_and_ratchet");
     }
     let trusted_state_change = trusted_state
-        .verify_and_ratchet_inner(&epoch_change_proof)
-        .expect("TrustedState::verify_and_ratchet_inner: could not ratchet");
+        .verify_and_ratchet_prepared(&epoch_change_proof, prev_prepared_verifier.as_ref())
+        .expect("TrustedState::verify_and_ratchet_prepared: could not ratchet");
     sphinx_zkvm::precompiles::unconstrained! {
                 println!("cycle-tracker-end: verify_and_ratchet");
     }
 
-    // Extract new trusted state
+    // Extract the epoch state started by the last ledger info of the chain, and
+    // prepare its validator verifier for the proofs of the coming epoch
     sphinx_zkvm::precompiles::unconstrained! {
                 println!("cycle-tracker-start: validator_verifier_hash");
     }
-    let validator_verifier_hash = match trusted_state_change {
+    let (validator_verifier_hash, epoch) = match trusted_state_change {
         TrustedStateChange::Epoch {
             latest_epoch_change_li,
             ..
-        } => latest_epoch_change_li
-            .ledger_info()
-            .next_epoch_state()
-            .expect("Expected epoch state")
-            .verifier()
-            .hash(),
+        } => {
+            let epoch_state = latest_epoch_change_li
+                .ledger_info()
+                .next_epoch_state()
+                .expect("Expected epoch state");
+            let prepared_verifier = PreparedValidatorVerifier::new(epoch_state.verifier().clone())
+                .expect("PreparedValidatorVerifier::new: could not prepare validator verifier");
+            (prepared_verifier.hash(), epoch_state.epoch)
+        }
         _ => panic!("Expected epoch change"),
     };
     sphinx_zkvm::precompiles::unconstrained! {
                 println!("cycle-tracker-end: validator_verifier_hash");
     }
 
-    // Compute previous epoch validator verifier hash and commit it
+    // Compute starting epoch prepared validator verifier hash and commit it. When bootstrapping
+    // from a waypoint, the waypoint value is committed instead as the root of trust, and a flag
+    // committed last tells which of the two the slot holds.
     sphinx_zkvm::precompiles::unconstrained! {
                 println!("cycle-tracker-start: hash_prev_validator");
     }
-    let prev_epoch_validator_verifier_hash = match &trusted_state {
-        TrustedState::EpochState { epoch_state, .. } => epoch_state.verifier().hash(),
-        _ => panic!("Expected epoch change for current trusted state"),
+    let (prev_epoch_validator_verifier_hash, root_of_trust) = match &trusted_state {
+        TrustedState::EpochState { epoch_state, .. } => {
+            let hash = match &prev_prepared_verifier {
+                Some(prepared_verifier) => prepared_verifier.hash(),
+                None => PreparedValidatorVerifier::new(epoch_state.verifier().clone())
+                    .expect("PreparedValidatorVerifier::new: could not prepare validator verifier")
+                    .hash(),
+            };
+            (hash, EPOCH_CHANGE_FROM_VALIDATOR_VERIFIER)
+        }
+        TrustedState::EpochWaypoint(waypoint) => (waypoint.value(), EPOCH_CHANGE_FROM_WAYPOINT),
     };
     sphinx_zkvm::io::commit(prev_epoch_validator_verifier_hash.as_ref());
     sphinx_zkvm::precompiles::unconstrained! {
                 println!("cycle-tracker-end: hash_prev_validator");
     }
 
-    // Hash new validator verifier and pass the hash as the now trusted state
+    // Hash the final prepared validator verifier and pass the hash as the now trusted state,
+    // along with the epoch it belongs to. Intermediate epochs are not committed.
     sphinx_zkvm::io::commit(validator_verifier_hash.as_ref());
+    sphinx_zkvm::io::commit(&epoch);
+
+    // Commit the root of trust, so that a waypoint is never mistaken for a validator verifier hash
+    sphinx_zkvm::io::commit(&root_of_trust);
 }
@@ -6,9 +6,16 @@
 use aptos_lc_core::crypto::hash::{CryptoHash, HashValue};
 use aptos_lc_core::merkle::sparse_proof::SparseMerkleProof;
 use aptos_lc_core::merkle::transaction_proof::TransactionAccumulatorProof;
-use aptos_lc_core::types::ledger_info::LedgerInfoWithSignatures;
+use aptos_lc_core::types::ledger_info::{LedgerInfoWithSignatures, MaxAge};
+use aptos_lc_core::types::resource::{AccountResource, CoinStoreResource, ResourceKind};
+use aptos_lc_core::types::state_key::StateKey;
+use aptos_lc_core::types::state_value::StateValue;
 use aptos_lc_core::types::transaction::TransactionInfo;
-use aptos_lc_core::types::validator::ValidatorVerifier;
+use aptos_lc_core::types::utils::{
+    INCLUSION_ACCOUNT_FLAG, INCLUSION_COIN_STORE_FLAG, INCLUSION_MAX_AGE_FLAG,
+    INCLUSION_PRESENT_FLAG, INCLUSION_STATE_KEY_FLAG, INCLUSION_STATUS_FLAG,
+};
+use aptos_lc_core::types::validator::PreparedValidatorVerifier;
 
 sphinx_zkvm::entrypoint!(main);
 
@@ -19,7 +26,10 @@ pub fn main() {
     // Get inputs for account inclusion
     let sparse_merkle_proof_bytes = sphinx_zkvm::io::read::<Vec<u8>>();
     let key = sphinx_zkvm::io::read::<[u8; 32]>();
-    let leaf_value_hash = sphinx_zkvm::io::read::<[u8; 32]>();
+    // Resource or table item the key is derived from, if known
+    let state_key = sphinx_zkvm::io::read::<Option<StateKey>>();
+    // Raw `StateValue` bytes, `None` if the key is expected to be absent from the state
+    let leaf_value_bytes = sphinx_zkvm::io::read::<Option<Vec<u8>>>();
 
     // Get inputs for tx inclusion
     let transaction_bytes = sphinx_zkvm::io::read::<Vec<u8>>();
@@ -27,16 +37,25 @@ pub fn main() {
     let transaction_proof = sphinx_zkvm::io::read::<Vec<u8>>();
     let ledger_info_bytes = sphinx_zkvm::io::read::<Vec<u8>>();
 
-    // Latest verified validator verifier &  hash
+    // Latest verified prepared validator verifier
     let verified_validator_verifier = sphinx_zkvm::io::read::<Vec<u8>>();
 
+    // Whether to also commit the execution status of the transaction
+    let commit_status = sphinx_zkvm::io::read::<bool>();
+
+    // Move resource to decode from the leaf value and whose fields should be committed
+    let decode_resource = sphinx_zkvm::io::read::<Option<ResourceKind>>();
+
+    // Reference time and maximum age the ledger info timestamp is checked against, if any
+    let max_age = sphinx_zkvm::io::read::<Option<MaxAge>>();
+
     sphinx_zkvm::precompiles::unconstrained! {
                 println!("cycle-tracker-end: read_inputs");
     }
 
-    // Deserialize Validator Verifier
-    let validator_verifier = ValidatorVerifier::from_bytes(&verified_validator_verifier)
-        .expect("validator_verifier: could not create ValidatorVerifier from bytes");
+    // Deserialize Prepared Validator Verifier
+    let validator_verifier = PreparedValidatorVerifier::from_bytes(&verified_validator_verifier)
+        .expect("validator_verifier: could not create PreparedValidatorVerifier from bytes");
 
     // Verify transaction inclusion in the LedgerInfoWithSignatures
     let transaction = TransactionInfo::from_bytes(&transaction_bytes)
@@ -65,11 +84,20 @@ pub fn main() {
                 println!("cycle-tracker-start: verify_signature");
     }
     latest_li
-        .verify_signatures(&validator_verifier)
-        .expect("verify_signatures: could not verify signatures");
+        .verify_prepared_signatures(&validator_verifier)
+        .expect("verify_prepared_signatures: could not verify signatures");
     sphinx_zkvm::precompiles::unconstrained! {
                     println!("cycle-tracker-end: verify_signature");
     }
+
+    // Reject stale ledger infos
+    if let Some(max_age) = &max_age {
+        latest_li
+            .ledger_info()
+            .verify_freshness(max_age)
+            .expect("verify_freshness: ledger info is too old");
+    }
+
     // Verify account inclusion in the SparseMerkleTree
     let sparse_merkle_proof = SparseMerkleProof::from_bytes(&sparse_merkle_proof_bytes)
         .expect("from_bytes: could not deserialize SparseMerkleProof");
@@ -80,19 +108,31 @@ pub fn main() {
     let sparse_expected_root_hash = transaction
         .state_checkpoint()
         .expect("state_checkpoint: could not get state checkpoint");
-    let reconstructed_root_hash = sparse_merkle_proof
-        .verify_by_hash(
-            sparse_expected_root_hash,
-            HashValue::from_slice(key).expect("key: could not use input to create HashValue"),
-            HashValue::from_slice(leaf_value_hash)
-                .expect("leaf_value_hash: could not use input to create HashValue"),
-        )
-        .expect("verify_by_hash: could not verify proof");
+    let key = HashValue::from_slice(key).expect("key: could not use input to create HashValue");
+    if let Some(state_key) = &state_key {
+        assert_eq!(
+            state_key.hash(),
+            key,
+            "state_key: hash does not match the leaf key"
+        );
+    }
+    let leaf_value = leaf_value_bytes.map(|bytes| {
+        StateValue::from_bytes(&bytes).expect("from_bytes: could not deserialize StateValue")
+    });
+    let leaf_value_hash = leaf_value.as_ref().map(CryptoHash::hash);
+    let reconstructed_root_hash = match leaf_value_hash {
+        Some(leaf_value_hash) => sparse_merkle_proof
+            .verify_by_hash(sparse_expected_root_hash, key, leaf_value_hash)
+            .expect("verify_by_hash: could not verify proof"),
+        None => sparse_merkle_proof
+            .verify_non_inclusion(sparse_expected_root_hash, key)
+            .expect("verify_non_inclusion: could not verify proof"),
+    };
     sphinx_zkvm::precompiles::unconstrained! {
                 println!("cycle-tracker-end: verify_merkle_proof");
     }
 
-    // Commit the validator verifier hash
+    // Commit the prepared validator verifier hash
     sphinx_zkvm::io::commit(validator_verifier.hash().as_ref());
 
     // Commit the state root hash
@@ -103,8 +143,98 @@ pub fn main() {
     sphinx_zkvm::io::commit(block_hash.as_ref());
 
     // Commit key
-    sphinx_zkvm::io::commit(&key);
+    sphinx_zkvm::io::commit(key.as_ref());
+
+    // Commit leaf value hash, all zeroes for an absent key
+    sphinx_zkvm::io::commit(leaf_value_hash.unwrap_or_default().as_ref());
+
+    // Commit the epoch, version and timestamp of the ledger info
+    sphinx_zkvm::io::commit(&latest_li.ledger_info().epoch());
+    sphinx_zkvm::io::commit(&latest_li.ledger_info().version());
+    sphinx_zkvm::io::commit(&latest_li.ledger_info().timestamp_usecs());
+
+    // Commit which optional values follow, so that the public values can be read without knowing
+    // the inputs of the program, and whether the key is present or absent under the state root
+    let mut flags = 0;
+    if leaf_value_hash.is_some() {
+        flags |= INCLUSION_PRESENT_FLAG;
+    }
+    if max_age.is_some() {
+        flags |= INCLUSION_MAX_AGE_FLAG;
+    }
+    if commit_status {
+        flags |= INCLUSION_STATUS_FLAG;
+    }
+    if state_key.is_some() {
+        flags |= INCLUSION_STATE_KEY_FLAG;
+    }
+    match decode_resource {
+        Some(ResourceKind::Account) => flags |= INCLUSION_ACCOUNT_FLAG,
+        Some(ResourceKind::AptosCoinStore) => flags |= INCLUSION_COIN_STORE_FLAG,
+        None => (),
+    }
+    sphinx_zkvm::io::commit(&flags);
+
+    // Commit the reference time and maximum age the ledger info was checked against, if requested
+    if let Some(max_age) = &max_age {
+        sphinx_zkvm::io::commit(&max_age.reference_timestamp_usecs());
+        sphinx_zkvm::io::commit(&max_age.max_age_usecs());
+    }
 
-    // Commit leaf value hash
-    sphinx_zkvm::io::commit(&leaf_value_hash);
+    // Commit the execution status of the transaction, if requested: its variant, and the abort
+    // code of a `MoveAbort` (zero otherwise)
+    if commit_status {
+        let status = transaction.status();
+        sphinx_zkvm::io::commit(&status.variant_index());
+        sphinx_zkvm::io::commit(&status.abort_code().unwrap_or_default());
+    }
+
+    // Commit the resource or table item the key is derived from, if known, as the key hash alone
+    // does not tell a contract what is stored at which address
+    if let Some(state_key) = &state_key {
+        let address: [u8; 32] = state_key
+            .address()
+            .to_bytes()
+            .try_into()
+            .expect("address: expected 32 bytes");
+        sphinx_zkvm::io::commit(&state_key.variant_index());
+        sphinx_zkvm::io::commit(&address);
+        sphinx_zkvm::io::commit(&state_key.identifier());
+    }
+
+    // Commit the decoded fields of the leaf value, if requested, right after the state key holding
+    // the address of the resource they belong to
+    if let Some(resource_kind) = decode_resource {
+        // Only decode leaves stored under the path of the requested resource, so that the state key
+        // was committed above
+        assert!(
+            matches!(
+                &state_key,
+                Some(StateKey::Resource { struct_tag, .. }) if *struct_tag == resource_kind.struct_tag()
+            ),
+            "decode_resource: leaf key is not derived from the requested resource"
+        );
+        let data = leaf_value
+            .as_ref()
+            .expect("decode_resource: no leaf value to decode")
+            .data();
+        match resource_kind {
+            ResourceKind::Account => {
+                let account = AccountResource::from_bytes(data)
+                    .expect("from_bytes: could not deserialize AccountResource");
+                let authentication_key: [u8; 32] = account
+                    .authentication_key()
+                    .as_slice()
+                    .try_into()
+                    .expect("authentication_key: expected 32 bytes");
+                sphinx_zkvm::io::commit(&account.sequence_number());
+                sphinx_zkvm::io::commit(&authentication_key);
+            }
+            ResourceKind::AptosCoinStore => {
+                let coin_store = CoinStoreResource::from_bytes(data)
+                    .expect("from_bytes: could not deserialize CoinStoreResource");
+                sphinx_zkvm::io::commit(&coin_store.balance());
+            }
+        }
+    }
 }
@@ -0,0 +1,98 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+#![no_main]
+
+use aptos_lc_core::crypto::hash::{hash_data, CryptoHash, HashValue};
+use aptos_lc_core::merkle::transaction_proof::TransactionAccumulatorRangeProof;
+use aptos_lc_core::types::ledger_info::LedgerInfoWithSignatures;
+use aptos_lc_core::types::transaction::TransactionInfo;
+use aptos_lc_core::types::validator::PreparedValidatorVerifier;
+
+sphinx_zkvm::entrypoint!(main);
+
+pub fn main() {
+    sphinx_zkvm::precompiles::unconstrained! {
+                println!("cycle-tracker-start: read_inputs");
+    }
+    // Get inputs for txs range inclusion
+    let transactions_bytes = sphinx_zkvm::io::read::<Vec<Vec<u8>>>();
+    let first_transaction_version = sphinx_zkvm::io::read::<u64>();
+    let range_proof = sphinx_zkvm::io::read::<Vec<u8>>();
+    let ledger_info_bytes = sphinx_zkvm::io::read::<Vec<u8>>();
+
+    // Latest verified prepared validator verifier
+    let verified_validator_verifier = sphinx_zkvm::io::read::<Vec<u8>>();
+
+    sphinx_zkvm::precompiles::unconstrained! {
+                println!("cycle-tracker-end: read_inputs");
+    }
+
+    // Deserialize Prepared Validator Verifier
+    let validator_verifier = PreparedValidatorVerifier::from_bytes(&verified_validator_verifier)
+        .expect("validator_verifier: could not create PreparedValidatorVerifier from bytes");
+
+    // Verify transactions inclusion in the LedgerInfoWithSignatures
+    let transaction_hashes: Vec<HashValue> = transactions_bytes
+        .iter()
+        .map(|transaction_bytes| {
+            TransactionInfo::from_bytes(transaction_bytes)
+                .expect("from_bytes: could not deserialize TransactionInfo")
+                .hash()
+        })
+        .collect();
+    let range_proof = TransactionAccumulatorRangeProof::from_bytes(&range_proof)
+        .expect("from_bytes: could not deserialize TransactionAccumulatorRangeProof");
+    let latest_li = LedgerInfoWithSignatures::from_bytes(&ledger_info_bytes)
+        .expect("from_bytes: could not deserialize LedgerInfo");
+
+    sphinx_zkvm::precompiles::unconstrained! {
+                println!("cycle-tracker-start: verify_transactions_range_inclusion");
+    }
+
+    let expected_root_hash = latest_li.ledger_info().transaction_accumulator_hash();
+
+    range_proof
+        .verify(
+            expected_root_hash,
+            first_transaction_version,
+            &transaction_hashes,
+        )
+        .expect("verify: could not verify range proof");
+    sphinx_zkvm::precompiles::unconstrained! {
+                println!("cycle-tracker-end: verify_transactions_range_inclusion");
+    }
+
+    // Check signature
+    sphinx_zkvm::precompiles::unconstrained! {
+                println!("cycle-tracker-start: verify_signature");
+    }
+    latest_li
+        .verify_prepared_signatures(&validator_verifier)
+        .expect("verify_prepared_signatures: could not verify signatures");
+    sphinx_zkvm::precompiles::unconstrained! {
+                    println!("cycle-tracker-end: verify_signature");
+    }
+
+    // Commit the prepared validator verifier hash
+    sphinx_zkvm::io::commit(validator_verifier.hash().as_ref());
+
+    // Commit current block id
+    let block_hash = latest_li.ledger_info().block_id();
+    sphinx_zkvm::io::commit(block_hash.as_ref());
+
+    // Commit the first and last versions of the range
+    let last_transaction_version = first_transaction_version + transaction_hashes.len() as u64 - 1;
+    sphinx_zkvm::io::commit(&first_transaction_version);
+    sphinx_zkvm::io::commit(&last_transaction_version);
+
+    // Commit the hash of the concatenated transaction info hashes
+    let transactions_hash = hash_data(
+        &[],
+        transaction_hashes
+            .iter()
+            .map(|hash| hash.as_ref().as_slice())
+            .collect(),
+    );
+    sphinx_zkvm::io::commit(&transactions_hash);
+}
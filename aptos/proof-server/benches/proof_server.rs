@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::anyhow;
+use aptos_lc_core::types::error::VerifyError;
 use bcs::from_bytes;
 use proof_server::error::ClientError;
 use proof_server::types::aptos::{AccountInclusionProofResponse, EpochChangeProofResponse};
@@ -352,7 +353,9 @@ async fn bench_proving_inclusion(final_snark: bool) -> Result<ProofData, anyhow:
         from_bytes(&buffer).map_err(|e| anyhow!(e))?;
 
     // Convert the AccountInclusionProofResponse structure into an InclusionData structure
-    let inclusion_data: InclusionData = account_inclusion_proof_response.into();
+    let inclusion_data: InclusionData = account_inclusion_proof_response
+        .try_into()
+        .map_err(|e: VerifyError| anyhow!(e))?;
 
     // Send the InclusionData as a request payload to the primary server
     let proving_type = if final_snark {
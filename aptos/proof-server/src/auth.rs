@@ -0,0 +1,699 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! # Auth
+//!
+//! Authentication and rate limiting of the requests sent to the proof server.
+//! Clients are identified by an API key declared in a keys file, and either
+//! send the secret of their key along with each request, or sign each request
+//! with an HMAC-SHA256 keyed by that secret. Signed requests carry a nonce, so
+//! that they cannot be replayed.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use axum::http::HeaderMap;
+use clap::ValueEnum;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::error::AuthError;
+
+/// Header carrying the identifier of the API key of a request.
+pub const API_KEY_ID_HEADER: &str = "x-api-key-id";
+/// Header carrying the secret of the API key of a request, in API key mode.
+pub const API_KEY_HEADER: &str = "x-api-key";
+/// Header carrying the UNIX timestamp, in seconds, at which a request was signed, in HMAC mode.
+pub const TIMESTAMP_HEADER: &str = "x-timestamp";
+/// Header carrying the unique nonce of a request, in HMAC mode.
+pub const NONCE_HEADER: &str = "x-nonce";
+/// Header carrying the hex encoded signature of a request, in HMAC mode.
+pub const SIGNATURE_HEADER: &str = "x-signature";
+/// Maximum difference, in seconds, between the timestamp of a signed request and the server time.
+pub const MAX_TIMESTAMP_SKEW_SECS: u64 = 300;
+
+/// Environment variable holding the identifier of the API key used by a client.
+const API_KEY_ID_ENV: &str = "PROOF_SERVER_API_KEY_ID";
+/// Environment variable holding the secret of the API key used by a client.
+const API_KEY_ENV: &str = "PROOF_SERVER_API_KEY";
+/// Environment variable holding the authentication mode used by a client.
+const AUTH_MODE_ENV: &str = "PROOF_SERVER_AUTH_MODE";
+
+/// Authentication mode of the requests.
+#[derive(ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AuthMode {
+    /// Requests carry the secret of their API key.
+    ApiKey,
+    /// Requests carry an HMAC-SHA256 signature of their method, path and query,
+    /// timestamp, nonce and body, keyed by the secret of their API key.
+    Hmac,
+}
+
+/// Identifier of the API key of an authenticated request, added to the
+/// extensions of the request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApiKeyId(pub String);
+
+/// Authenticates the requests against the API keys of a keys file, and
+/// enforces their rate limits.
+#[derive(Debug)]
+pub struct Authenticator {
+    mode: AuthMode,
+    keys: HashMap<String, ApiKey>,
+    buckets: Mutex<HashMap<String, Bucket>>,
+    /// Nonces of the signed requests accepted within the timestamp skew,
+    /// along with their timestamp.
+    nonces: Mutex<HashMap<(String, String), u64>>,
+}
+
+#[derive(Debug)]
+struct ApiKey {
+    secret: String,
+    rate_limit: Option<u32>,
+}
+
+/// Token bucket of an API key, refilled continuously up to its rate limit.
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl Authenticator {
+    /// Loads the API keys of a keys file. Each non-empty line that does not
+    /// start with `#` declares a key as `<key-id> <secret> [rate-limit]`, the
+    /// rate limit being a number of proving requests per minute.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - The authentication mode of the requests.
+    /// * `path` - The path of the keys file.
+    /// * `default_rate_limit` - The rate limit of the keys that do not declare one.
+    ///
+    /// # Returns
+    ///
+    /// The `Authenticator`.
+    pub fn load(
+        mode: AuthMode,
+        path: &Path,
+        default_rate_limit: Option<u32>,
+    ) -> Result<Self, AuthError> {
+        let content = fs::read_to_string(path).map_err(|err| AuthError::KeysFile {
+            path: path.display().to_string(),
+            source: err.into(),
+        })?;
+
+        let mut keys = HashMap::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid_entry = || AuthError::InvalidKeysEntry {
+                path: path.display().to_string(),
+                line: index + 1,
+            };
+            let mut fields = line.split_whitespace();
+            let (Some(key_id), Some(secret)) = (fields.next(), fields.next()) else {
+                return Err(invalid_entry());
+            };
+            let rate_limit = match fields.next() {
+                Some(rate_limit) => Some(rate_limit.parse().map_err(|_| invalid_entry())?),
+                None => default_rate_limit,
+            };
+            if fields.next().is_some() {
+                return Err(invalid_entry());
+            }
+
+            keys.insert(
+                key_id.to_string(),
+                ApiKey {
+                    secret: secret.to_string(),
+                    rate_limit,
+                },
+            );
+        }
+
+        Ok(Self {
+            mode,
+            keys,
+            buckets: Mutex::new(HashMap::new()),
+            nonces: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Returns the authentication mode of the requests.
+    ///
+    /// # Returns
+    ///
+    /// The authentication mode.
+    pub const fn mode(&self) -> AuthMode {
+        self.mode
+    }
+
+    /// Authenticates a request from its headers, before its body is read. In
+    /// HMAC mode, the signature of the request must then be checked with
+    /// `verify_signature`.
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - The headers of the request.
+    ///
+    /// # Returns
+    ///
+    /// The identifier of the API key of the request.
+    pub fn authenticate(&self, headers: &HeaderMap) -> Result<String, AuthError> {
+        let key_id = header(headers, API_KEY_ID_HEADER)?;
+        let key = self
+            .keys
+            .get(key_id)
+            .ok_or_else(|| AuthError::UnknownKey(key_id.to_string()))?;
+
+        match self.mode {
+            AuthMode::ApiKey => {
+                let secret = header(headers, API_KEY_HEADER)?;
+                if !constant_time_eq(secret.as_bytes(), key.secret.as_bytes()) {
+                    return Err(AuthError::InvalidCredentials(key_id.to_string()));
+                }
+            }
+            AuthMode::Hmac => {
+                let timestamp = timestamp(headers, key_id)?;
+                if unix_timestamp().abs_diff(timestamp) > MAX_TIMESTAMP_SKEW_SECS {
+                    return Err(AuthError::StaleTimestamp(timestamp));
+                }
+                header(headers, NONCE_HEADER)?;
+                header(headers, SIGNATURE_HEADER)?;
+            }
+        }
+
+        Ok(key_id.to_string())
+    }
+
+    /// Verifies the signature of a request authenticated in HMAC mode, and
+    /// records its nonce so that the request cannot be replayed.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_id` - The identifier of the API key of the request.
+    /// * `method` - The HTTP method of the request.
+    /// * `path_and_query` - The path of the request, along with its query if any.
+    /// * `headers` - The headers of the request.
+    /// * `body` - The body of the request.
+    ///
+    /// # Returns
+    ///
+    /// An error if the signature is invalid or the request was already received.
+    pub fn verify_signature(
+        &self,
+        key_id: &str,
+        method: &str,
+        path_and_query: &str,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Result<(), AuthError> {
+        let key = self
+            .keys
+            .get(key_id)
+            .ok_or_else(|| AuthError::UnknownKey(key_id.to_string()))?;
+        let timestamp = timestamp(headers, key_id)?;
+        let nonce = header(headers, NONCE_HEADER)?;
+        let signature = hex::decode(header(headers, SIGNATURE_HEADER)?)
+            .map_err(|_| AuthError::InvalidCredentials(key_id.to_string()))?;
+
+        request_mac(&key.secret, method, path_and_query, timestamp, nonce, body)
+            .verify_slice(&signature)
+            .map_err(|_| AuthError::InvalidCredentials(key_id.to_string()))?;
+
+        // Nonces only need to be remembered as long as their timestamp is accepted
+        let now = unix_timestamp();
+        let mut nonces = self
+            .nonces
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        nonces.retain(|_, timestamp| now.abs_diff(*timestamp) <= MAX_TIMESTAMP_SKEW_SECS);
+        if nonces
+            .insert((key_id.to_string(), nonce.to_string()), timestamp)
+            .is_some()
+        {
+            return Err(AuthError::ReplayedRequest(key_id.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Consumes one request from the rate limit of an API key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_id` - The identifier of the API key.
+    ///
+    /// # Returns
+    ///
+    /// An error if the rate limit of the key is exceeded.
+    pub fn check_rate_limit(&self, key_id: &str) -> Result<(), AuthError> {
+        let Some(rate_limit) = self.keys.get(key_id).and_then(|key| key.rate_limit) else {
+            return Ok(());
+        };
+
+        let capacity = f64::from(rate_limit);
+        let now = Instant::now();
+        let mut buckets = self.lock();
+        let bucket = buckets.entry(key_id.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            updated_at: now,
+        });
+
+        let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * capacity / 60.0).min(capacity);
+        bucket.updated_at = now;
+
+        if bucket.tokens < 1.0 {
+            return Err(AuthError::RateLimited(key_id.to_string()));
+        }
+        bucket.tokens -= 1.0;
+
+        Ok(())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, Bucket>> {
+        // Buckets are always left consistent, so a poisoned lock can be recovered
+        self.buckets
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Credentials used by a client to authenticate its requests to the proof server.
+#[derive(Clone, Debug)]
+pub struct Credentials {
+    mode: AuthMode,
+    key_id: String,
+    secret: String,
+}
+
+impl Credentials {
+    /// Creates new `Credentials`.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - The authentication mode of the proof server.
+    /// * `key_id` - The identifier of the API key.
+    /// * `secret` - The secret of the API key.
+    ///
+    /// # Returns
+    ///
+    /// The `Credentials`.
+    pub const fn new(mode: AuthMode, key_id: String, secret: String) -> Self {
+        Self {
+            mode,
+            key_id,
+            secret,
+        }
+    }
+
+    /// Reads the credentials from the `PROOF_SERVER_API_KEY_ID`,
+    /// `PROOF_SERVER_API_KEY` and `PROOF_SERVER_AUTH_MODE` environment
+    /// variables, the mode defaulting to `api-key`.
+    ///
+    /// # Returns
+    ///
+    /// The `Credentials`, or `None` if no API key is set.
+    pub fn from_env() -> Result<Option<Self>, AuthError> {
+        let (Ok(key_id), Ok(secret)) = (std::env::var(API_KEY_ID_ENV), std::env::var(API_KEY_ENV))
+        else {
+            return Ok(None);
+        };
+        let mode = match std::env::var(AUTH_MODE_ENV) {
+            Ok(mode) => AuthMode::from_str(&mode, true).map_err(AuthError::InvalidAuthMode)?,
+            Err(_) => AuthMode::ApiKey,
+        };
+
+        Ok(Some(Self::new(mode, key_id, secret)))
+    }
+
+    /// Returns the authentication headers of a request.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The HTTP method of the request.
+    /// * `path_and_query` - The path of the request, along with its query if any.
+    /// * `body` - The body of the request.
+    ///
+    /// # Returns
+    ///
+    /// The names and values of the headers to add to the request.
+    pub fn headers(
+        &self,
+        method: &str,
+        path_and_query: &str,
+        body: &[u8],
+    ) -> Vec<(&'static str, String)> {
+        let mut headers = vec![(API_KEY_ID_HEADER, self.key_id.clone())];
+        match self.mode {
+            AuthMode::ApiKey => headers.push((API_KEY_HEADER, self.secret.clone())),
+            AuthMode::Hmac => {
+                let timestamp = unix_timestamp();
+                let nonce = hex::encode(rand::random::<[u8; 16]>());
+                let signature = sign_request(
+                    &self.secret,
+                    method,
+                    path_and_query,
+                    timestamp,
+                    &nonce,
+                    body,
+                );
+                headers.push((TIMESTAMP_HEADER, timestamp.to_string()));
+                headers.push((NONCE_HEADER, nonce));
+                headers.push((SIGNATURE_HEADER, signature));
+            }
+        }
+
+        headers
+    }
+}
+
+/// Signs a request for the HMAC authentication mode.
+///
+/// # Arguments
+///
+/// * `secret` - The secret of the API key.
+/// * `method` - The HTTP method of the request.
+/// * `path_and_query` - The path of the request, along with its query if any.
+/// * `timestamp` - The UNIX timestamp, in seconds, of the signature.
+/// * `nonce` - The unique nonce of the request.
+/// * `body` - The body of the request.
+///
+/// # Returns
+///
+/// The hex encoded signature of the request.
+pub fn sign_request(
+    secret: &str,
+    method: &str,
+    path_and_query: &str,
+    timestamp: u64,
+    nonce: &str,
+    body: &[u8],
+) -> String {
+    hex::encode(
+        request_mac(secret, method, path_and_query, timestamp, nonce, body)
+            .finalize()
+            .into_bytes(),
+    )
+}
+
+fn request_mac(
+    secret: &str,
+    method: &str,
+    path_and_query: &str,
+    timestamp: u64,
+    nonce: &str,
+    body: &[u8],
+) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(format!("{method}\n{path_and_query}\n{timestamp}\n{nonce}\n").as_bytes());
+    mac.update(body);
+    mac
+}
+
+fn timestamp(headers: &HeaderMap, key_id: &str) -> Result<u64, AuthError> {
+    header(headers, TIMESTAMP_HEADER)?
+        .parse()
+        .map_err(|_| AuthError::InvalidCredentials(key_id.to_string()))
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &'static str) -> Result<&'a str, AuthError> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .ok_or(AuthError::MissingCredentials(name))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    fn authenticator(name: &str, mode: AuthMode, default_rate_limit: Option<u32>) -> Authenticator {
+        let path = std::env::temp_dir().join(format!("{name}-{}", std::process::id()));
+        fs::write(
+            &path,
+            "# Test keys\nlimited secret-a 2\n\nunlimited secret-b\n",
+        )
+        .unwrap();
+        let authenticator = Authenticator::load(mode, &path, default_rate_limit).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        authenticator
+    }
+
+    fn header_map(headers: Vec<(&'static str, String)>) -> HeaderMap {
+        headers
+            .into_iter()
+            .map(|(name, value)| {
+                (
+                    name.parse().unwrap(),
+                    HeaderValue::from_str(&value).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    fn signed_headers(timestamp: u64, nonce: &str, path_and_query: &str, body: &[u8]) -> HeaderMap {
+        header_map(vec![
+            (API_KEY_ID_HEADER, "limited".to_string()),
+            (TIMESTAMP_HEADER, timestamp.to_string()),
+            (NONCE_HEADER, nonce.to_string()),
+            (
+                SIGNATURE_HEADER,
+                sign_request("secret-a", "POST", path_and_query, timestamp, nonce, body),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_load_invalid_keys_file() {
+        let path = std::env::temp_dir().join(format!(
+            "test_load_invalid_keys_file-{}",
+            std::process::id()
+        ));
+        fs::write(&path, "valid secret\ninvalid\n").unwrap();
+
+        assert!(matches!(
+            Authenticator::load(AuthMode::ApiKey, &path, None),
+            Err(AuthError::InvalidKeysEntry { line: 2, .. })
+        ));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_api_key() {
+        let authenticator = authenticator("test_api_key", AuthMode::ApiKey, None);
+        let credentials = Credentials::new(
+            AuthMode::ApiKey,
+            "limited".to_string(),
+            "secret-a".to_string(),
+        );
+
+        let headers = header_map(credentials.headers("POST", "/epoch/proof", b"body"));
+        assert_eq!(authenticator.authenticate(&headers).unwrap(), "limited");
+
+        let headers = header_map(vec![
+            (API_KEY_ID_HEADER, "limited".to_string()),
+            (API_KEY_HEADER, "secret-b".to_string()),
+        ]);
+        assert!(matches!(
+            authenticator.authenticate(&headers),
+            Err(AuthError::InvalidCredentials(_))
+        ));
+
+        let headers = header_map(vec![
+            (API_KEY_ID_HEADER, "unknown".to_string()),
+            (API_KEY_HEADER, "secret-a".to_string()),
+        ]);
+        assert!(matches!(
+            authenticator.authenticate(&headers),
+            Err(AuthError::UnknownKey(_))
+        ));
+
+        let headers = header_map(vec![(API_KEY_ID_HEADER, "limited".to_string())]);
+        assert!(matches!(
+            authenticator.authenticate(&headers),
+            Err(AuthError::MissingCredentials(API_KEY_HEADER))
+        ));
+    }
+
+    #[test]
+    fn test_signature() {
+        let authenticator = authenticator("test_signature", AuthMode::Hmac, None);
+        let credentials = Credentials::new(
+            AuthMode::Hmac,
+            "limited".to_string(),
+            "secret-a".to_string(),
+        );
+
+        let headers = header_map(credentials.headers("POST", "/jobs?priority=1", b"body"));
+        let key_id = authenticator.authenticate(&headers).unwrap();
+        authenticator
+            .verify_signature(&key_id, "POST", "/jobs?priority=1", &headers, b"body")
+            .unwrap();
+
+        // The method, path, query and body are all signed
+        for (method, path_and_query, body) in [
+            ("GET", "/jobs?priority=1", b"body".as_slice()),
+            ("POST", "/epoch/proof?priority=1", b"body".as_slice()),
+            ("POST", "/jobs?priority=2", b"body".as_slice()),
+            ("POST", "/jobs?priority=1", b"tampered".as_slice()),
+        ] {
+            let headers = header_map(credentials.headers("POST", "/jobs?priority=1", b"body"));
+            assert!(matches!(
+                authenticator.verify_signature(&key_id, method, path_and_query, &headers, body),
+                Err(AuthError::InvalidCredentials(_))
+            ));
+        }
+
+        // The signature is keyed by the secret of the API key
+        let headers = header_map(
+            Credentials::new(
+                AuthMode::Hmac,
+                "limited".to_string(),
+                "secret-b".to_string(),
+            )
+            .headers("POST", "/jobs", b"body"),
+        );
+        assert!(matches!(
+            authenticator.verify_signature(&key_id, "POST", "/jobs", &headers, b"body"),
+            Err(AuthError::InvalidCredentials(_))
+        ));
+
+        // Signed requests need their nonce
+        let mut headers = header_map(credentials.headers("POST", "/jobs", b"body"));
+        headers.remove(NONCE_HEADER);
+        assert!(matches!(
+            authenticator.authenticate(&headers),
+            Err(AuthError::MissingCredentials(NONCE_HEADER))
+        ));
+    }
+
+    #[test]
+    fn test_timestamp_skew() {
+        let authenticator = authenticator("test_timestamp_skew", AuthMode::Hmac, None);
+        let now = unix_timestamp();
+
+        for timestamp in [
+            now - MAX_TIMESTAMP_SKEW_SECS + 1,
+            now + MAX_TIMESTAMP_SKEW_SECS - 1,
+        ] {
+            let headers = signed_headers(timestamp, &timestamp.to_string(), "/jobs", b"body");
+            authenticator.authenticate(&headers).unwrap();
+        }
+        for timestamp in [
+            now - MAX_TIMESTAMP_SKEW_SECS - 10,
+            now + MAX_TIMESTAMP_SKEW_SECS + 10,
+        ] {
+            let headers = signed_headers(timestamp, &timestamp.to_string(), "/jobs", b"body");
+            assert!(matches!(
+                authenticator.authenticate(&headers),
+                Err(AuthError::StaleTimestamp(stale)) if stale == timestamp
+            ));
+        }
+    }
+
+    #[test]
+    fn test_replayed_request() {
+        let authenticator = authenticator("test_replayed_request", AuthMode::Hmac, None);
+        let now = unix_timestamp();
+
+        let headers = signed_headers(now, "nonce", "/jobs", b"body");
+        authenticator
+            .verify_signature("limited", "POST", "/jobs", &headers, b"body")
+            .unwrap();
+        assert!(matches!(
+            authenticator.verify_signature("limited", "POST", "/jobs", &headers, b"body"),
+            Err(AuthError::ReplayedRequest(_))
+        ));
+
+        // A request with an invalid signature does not consume its nonce
+        let headers = signed_headers(now, "other-nonce", "/jobs", b"body");
+        assert!(authenticator
+            .verify_signature("limited", "POST", "/jobs", &headers, b"tampered")
+            .is_err());
+        authenticator
+            .verify_signature("limited", "POST", "/jobs", &headers, b"body")
+            .unwrap();
+
+        // Nonces are forgotten once their timestamp is no longer accepted
+        let old = now - MAX_TIMESTAMP_SKEW_SECS - 10;
+        authenticator
+            .nonces
+            .lock()
+            .unwrap()
+            .insert(("limited".to_string(), "expired-nonce".to_string()), old);
+        let headers = signed_headers(now, "new-nonce", "/jobs", b"body");
+        authenticator
+            .verify_signature("limited", "POST", "/jobs", &headers, b"body")
+            .unwrap();
+        assert!(!authenticator
+            .nonces
+            .lock()
+            .unwrap()
+            .contains_key(&("limited".to_string(), "expired-nonce".to_string())));
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let authenticator = authenticator("test_rate_limit", AuthMode::ApiKey, None);
+
+        authenticator.check_rate_limit("limited").unwrap();
+        authenticator.check_rate_limit("limited").unwrap();
+        assert!(matches!(
+            authenticator.check_rate_limit("limited"),
+            Err(AuthError::RateLimited(_))
+        ));
+
+        // Keys without a rate limit are never limited
+        for _ in 0..10 {
+            authenticator.check_rate_limit("unlimited").unwrap();
+        }
+
+        // The bucket refills with time, up to the rate limit
+        authenticator.lock().get_mut("limited").unwrap().updated_at -= Duration::from_secs(30);
+        authenticator.check_rate_limit("limited").unwrap();
+        assert!(authenticator.check_rate_limit("limited").is_err());
+
+        authenticator.lock().get_mut("limited").unwrap().updated_at -= Duration::from_secs(600);
+        authenticator.check_rate_limit("limited").unwrap();
+        authenticator.check_rate_limit("limited").unwrap();
+        assert!(authenticator.check_rate_limit("limited").is_err());
+    }
+
+    #[test]
+    fn test_default_rate_limit() {
+        let authenticator = authenticator("test_default_rate_limit", AuthMode::ApiKey, Some(1));
+
+        // The rate limit declared by a key takes precedence over the default one
+        authenticator.check_rate_limit("limited").unwrap();
+        authenticator.check_rate_limit("limited").unwrap();
+        authenticator.check_rate_limit("unlimited").unwrap();
+        assert!(authenticator.check_rate_limit("unlimited").is_err());
+    }
+}
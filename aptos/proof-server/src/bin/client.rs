@@ -28,6 +28,9 @@
 //! - **Epoch Change Proof**: This proof is generated when the epoch changes in the Aptos node.
 //! - **Inclusion Proof**: This proof is generated when an account is included in the ledger.
 //!
+//! The accounts proven are read from an optional watch list, each of them being proven at its own
+//! cadence, and the verified inclusion proofs are published to the destinations of the watch list.
+//!
 //! The client is responsible for verifying these proofs and updating the verifier state accordingly.
 //! The worst-case scenario that can happen in the client is that we receive the need to generate both
 //! proofs at the same time. In this case, the client will send both requests to the proof server, that
@@ -36,24 +39,34 @@
 #![allow(clippy::needless_return)]
 use anyhow::{anyhow, Result};
 use aptos_lc_core::crypto::hash::{CryptoHash, HashValue};
+use aptos_lc_core::types::error::VerifyError;
 use aptos_lc_core::types::trusted_state::TrustedState;
+use aptos_lc_core::types::utils::{
+    EPOCH_CHANGE_FROM_VALIDATOR_VERIFIER, EPOCH_CHANGE_FROM_WAYPOINT,
+};
+use aptos_lc_core::types::validator::PreparedValidatorVerifier;
 use aptos_lc_core::types::waypoint::Waypoint;
+use aptos_lc_core::types::Version;
 use backoff::ExponentialBackoff;
 use clap::Parser;
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
+use proof_server::auth::Credentials;
 use proof_server::error::ClientError;
+use proof_server::state_file::{PersistedClientState, StateFile};
 use proof_server::types::aptos::{
     AccountInclusionProofResponse, EpochChangeProofResponse, LedgerInfoResponse,
 };
-use proof_server::types::proof_server::ProvingMode;
+use proof_server::types::proof_server::{EpochChangeData, ProvingMode};
 use proof_server::utils::validate_and_format_url;
+use proof_server::watch::{ProofSink, Trigger, WatchEntry, WatchList};
 use proof_server::{
-    aptos_inclusion_proof_endpoint, types::proof_server::Request,
-    APTOS_EPOCH_CHANGE_PROOF_ENDPOINT, APTOS_LEDGER_INFO_ENDPOINT,
+    types::proof_server::Request, APTOS_EPOCH_CHANGE_PROOF_ENDPOINT, APTOS_LEDGER_INFO_ENDPOINT,
 };
 use sphinx_sdk::SphinxProofWithPublicValues;
+use std::collections::BTreeMap;
 use std::env;
 use std::fmt::Display;
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
@@ -61,7 +74,7 @@ use tokio::net::TcpStream;
 use tokio::sync::{mpsc, Mutex, OwnedSemaphorePermit, Semaphore};
 use tokio::task::JoinHandle;
 
-/// Address that will be used to generate the inclusion proof.
+/// Address that will be used to generate the inclusion proof when no watch list is given.
 const ACCOUNT: &str = "0x2d91309b5b07a8be428ccd75d0443e81542ffcd059d0ab380cefc552229b1a";
 
 /// A client displaying how one can make requests to the proof server and
@@ -78,6 +91,22 @@ struct Cli {
     /// The URL of the Aptos node.
     #[arg(short, long)]
     aptos_node_url: String,
+
+    /// A waypoint, formatted as `version:hash`, to bootstrap from instead of
+    /// trusting the validator set served by the Aptos node.
+    #[arg(short, long)]
+    waypoint: Option<Waypoint>,
+
+    /// File persisting the latest verified state of the client. The client
+    /// resumes from it on startup, and never moves backwards from it.
+    #[arg(long, default_value = "client-state")]
+    state_file: PathBuf,
+
+    /// JSON file listing the accounts to prove the inclusion of, along with
+    /// their cadence and where their proofs are published. A single account
+    /// is proven every 10 seconds if none is given.
+    #[arg(long)]
+    watch_list: Option<PathBuf>,
 }
 
 /// `ClientState` is a structure meant to hold the state maintained by
@@ -99,6 +128,9 @@ enum ProofType {
     Inclusion {
         task: JoinHandle<Result<SphinxProofWithPublicValues, ClientError>>,
         permit: OwnedSemaphorePermit,
+        entry: Arc<WatchEntry>,
+        /// Identifier of the block of the ledger info the proof was requested against.
+        block_id: HashValue,
     },
 }
 
@@ -106,7 +138,7 @@ impl Display for &ProofType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ProofType::EpochChange { .. } => write!(f, "Epoch Change"),
-            ProofType::Inclusion { .. } => write!(f, "Inclusion"),
+            ProofType::Inclusion { entry, .. } => write!(f, "Inclusion of {}", entry.name),
         }
     }
 }
@@ -117,7 +149,9 @@ async fn main() -> Result<()> {
     let Cli {
         proof_server_address,
         aptos_node_url,
-        ..
+        waypoint,
+        state_file,
+        watch_list,
     } = Cli::parse();
 
     // Initialize the logger
@@ -133,17 +167,50 @@ async fn main() -> Result<()> {
     let proof_server_address = Arc::new(proof_server_address);
     let aptos_node_url = Arc::new(aptos_node_url);
 
+    let watch_list = match watch_list {
+        Some(path) => WatchList::load(&path)?,
+        None => WatchList::single(ACCOUNT),
+    };
+    let proof_sink = ProofSink::new(&watch_list)?;
+    let entries: Vec<Arc<WatchEntry>> = watch_list.accounts.into_iter().map(Arc::new).collect();
+
     debug!("Initializing client");
     // Try to connect to proof server.
     connect_to_proof_server(&proof_server_address).await?;
-    // Initialize the client.
-    let (client_state, verififer_state) = init(&proof_server_address, &aptos_node_url).await?;
+    // Resume the client from its persisted state, or initialize it.
+    let (mut state_file, persisted_state) = StateFile::open(state_file)?;
+    let persisted_state = match persisted_state {
+        Some(persisted_state) => {
+            if waypoint.is_some() {
+                warn!(
+                    "Ignoring the waypoint, the client resumes from its state file {}",
+                    state_file.path().display()
+                );
+            }
+            info!(
+                "Resuming client from persisted (epoch, version): {:?}",
+                persisted_state.position()
+            );
+            persisted_state
+        }
+        None => {
+            let persisted_state = init(
+                &proof_server_address,
+                &aptos_node_url,
+                waypoint,
+                &entries[0],
+            )
+            .await?;
+            state_file.save(&persisted_state)?;
+            persisted_state
+        }
+    };
     debug!("Client initialized successfully");
 
-    let client_state: Arc<Mutex<ClientState>> = Arc::new(Mutex::new(client_state));
+    let client_state: Arc<Mutex<ClientState>> =
+        Arc::new(Mutex::new(persisted_state.trusted_state.clone()));
 
     // Create a Semaphore with only one permit for the proofs process.
-    let inclusion_semaphore = Arc::new(Semaphore::new(1));
     let epoch_change_semaphore = Arc::new(Semaphore::new(1));
 
     debug!("Spawn verifier task");
@@ -153,11 +220,23 @@ async fn main() -> Result<()> {
     tokio::spawn(verifier_task(
         task_receiver,
         proof_server_address.clone(),
-        verififer_state,
+        persisted_state,
+        state_file,
         client_state.clone(),
+        proof_sink,
     ));
 
-    // Start the main loop to listen for Aptos data every 10 seconds.
+    // Spawn a watcher task proving the inclusion of each watched account at its own cadence.
+    for entry in entries {
+        tokio::spawn(watcher_task(
+            entry,
+            proof_server_address.clone(),
+            aptos_node_url.clone(),
+            task_sender.clone(),
+        ));
+    }
+
+    // Start the main loop to listen for epoch changes every 10 seconds.
     let mut interval = tokio::time::interval(Duration::from_secs(10));
 
     debug!("Start listening for Aptos data");
@@ -183,8 +262,17 @@ async fn main() -> Result<()> {
             })?
         };
 
+        // The client never moves backwards, even if the Aptos node does.
+        if aptos_epoch < client_state_epoch {
+            error!(
+                "Aptos node is at epoch {}, behind the client at epoch {}",
+                aptos_epoch, client_state_epoch
+            );
+            continue;
+        }
+
         // Check if epoch changed and ig the epoch changed semaphore has a permit available.
-        if aptos_epoch != client_state_epoch && epoch_change_semaphore.available_permits() > 0 {
+        if aptos_epoch > client_state_epoch && epoch_change_semaphore.available_permits() > 0 {
             // Acquire a permit from the semaphore before starting the inclusion task.
             let permit = epoch_change_semaphore
                 .clone()
@@ -197,6 +285,7 @@ async fn main() -> Result<()> {
                 proof_server_address.clone(),
                 aptos_node_url.clone(),
                 aptos_epoch,
+                None,
             ));
             task_sender
                 .send(ProofType::EpochChange { task, permit })
@@ -209,24 +298,83 @@ async fn main() -> Result<()> {
                     .into(),
                 })?;
         }
+    }
+}
 
-        // Check if the inclusion semaphore has a permit available.
-        if inclusion_semaphore.available_permits() > 0 {
-            // Acquire a permit from the semaphore before starting the inclusion task.
-            let permit = inclusion_semaphore.clone().acquire_owned().await.unwrap();
+/// This method watches an account, and sends a proving task for its inclusion to the verifier
+/// task on every tick of its cadence. Accounts triggered on version changes are only proven when
+/// their state value differs from the one of their last proving task. A single proving task is
+/// in progress for an account at any time.
+///
+/// # Arguments
+///
+/// * `entry` - The watched account.
+/// * `proof_server_address` - The address of the proof server.
+/// * `aptos_node_url` - The URL of the Aptos node.
+/// * `task_sender` - The sender channel for the tasks of the verifier.
+async fn watcher_task(
+    entry: Arc<WatchEntry>,
+    proof_server_address: Arc<String>,
+    aptos_node_url: Arc<String>,
+    task_sender: mpsc::Sender<ProofType>,
+) {
+    let semaphore = Arc::new(Semaphore::new(1));
+    let mut last_value: Option<Option<Vec<u8>>> = None;
+    let mut interval = tokio::time::interval(entry.interval());
 
-            // Spawn proving task for inclusion proof.
-            let task = tokio::spawn(inclusion_proving_task(
-                proof_server_address.clone(),
-                aptos_node_url.clone(),
-                ACCOUNT.into(),
-            ));
+    debug!("Start watching {}", entry.name);
+    loop {
+        interval.tick().await;
 
-            // Send the task and the permit to the verifier.
-            task_sender
-                .send(ProofType::Inclusion { task, permit })
-                .await
-                .unwrap();
+        // Check if the semaphore of the account has a permit available.
+        if semaphore.available_permits() == 0 {
+            continue;
+        }
+
+        let inclusion_proof_data = match fetch_inclusion_proof_data(&aptos_node_url, &entry).await {
+            Ok(inclusion_proof_data) => inclusion_proof_data,
+            Err(err) => {
+                error!(
+                    "Failed to fetch the inclusion proof data of {}: {err}",
+                    entry.name
+                );
+                continue;
+            }
+        };
+
+        if entry.trigger == Trigger::VersionChange {
+            let value = inclusion_proof_data
+                .element_value()
+                .map(|value| value.to_bytes());
+            if last_value.as_ref() == Some(&value) {
+                debug!("State of {} unchanged, skipping proof", entry.name);
+                continue;
+            }
+            last_value = Some(value);
+        }
+
+        // Acquire a permit from the semaphore before starting the inclusion task.
+        let permit = semaphore.clone().acquire_owned().await.unwrap();
+
+        let block_id = inclusion_proof_data.ledger_info().ledger_info().block_id();
+
+        // Spawn proving task for inclusion proof.
+        let task = tokio::spawn(inclusion_proving_task(
+            proof_server_address.clone(),
+            inclusion_proof_data,
+            entry.clone(),
+        ));
+
+        // Send the task and the permit to the verifier.
+        let proof_type = ProofType::Inclusion {
+            task,
+            permit,
+            entry: entry.clone(),
+            block_id,
+        };
+        if task_sender.send(proof_type).await.is_err() {
+            error!("Verifier task stopped, no longer watching {}", entry.name);
+            return;
         }
     }
 }
@@ -260,14 +408,19 @@ async fn connect_to_proof_server(proof_server_address: &str) -> Result<(), Clien
 ///
 /// * `proof_server_address` - The address of the proof server.
 /// * `aptos_node_url` - The URL of the Aptos node.
+/// * `waypoint` - An optional waypoint to bootstrap from, committing to the latest epoch change.
+/// * `entry` - The watched account to generate the initial inclusion proof for.
 ///
 /// # Returns
 ///
-/// A tuple containing the client state and the verifier state.
+/// The verified state of the client, holding the client state, the verifier state and the
+/// verified proofs.
 async fn init(
     proof_server_address: &Arc<String>,
     aptos_node_url: &Arc<String>,
-) -> Result<(ClientState, VerifierState), ClientError> {
+    waypoint: Option<Waypoint>,
+    entry: &Arc<WatchEntry>,
+) -> Result<PersistedClientState, ClientError> {
     info!("Initializing client");
 
     let ledger_info_request = format!("{}{APTOS_LEDGER_INFO_ENDPOINT}", aptos_node_url);
@@ -280,17 +433,21 @@ async fn init(
         })?;
 
     // Spawn epoch change proving task and inclusion proving task.
+    let from_waypoint = waypoint.is_some();
     let epoch_change_task = tokio::spawn(epoch_change_proving_task(
         proof_server_address.clone(),
         aptos_node_url.clone(),
         u64::from_str(&ledger_info.epoch())
             .map_err(|err| ClientError::Internal { source: err.into() })?,
+        waypoint,
     ));
 
+    let inclusion_proof_data = fetch_inclusion_proof_data(aptos_node_url, entry).await?;
+    let block_id = inclusion_proof_data.ledger_info().ledger_info().block_id();
     let inclusion_task = tokio::spawn(inclusion_proving_task(
         proof_server_address.clone(),
-        aptos_node_url.clone(),
-        ACCOUNT.into(),
+        inclusion_proof_data,
+        entry.clone(),
     ));
 
     // Await for both tasks to end.
@@ -302,6 +459,9 @@ async fn init(
     let (ratcheted_trusted_state, validator_verifier_hash, mut epoch_change_proof) =
         epoch_change_payload?;
     let mut inclusion_proof = inclusion_payload?;
+    // The proofs are persisted before their public values are read by the verification
+    let (verified_epoch_change_proof, verified_inclusion_proof) =
+        (epoch_change_proof.clone(), inclusion_proof.clone());
 
     let verifier_state = (validator_verifier_hash, HashValue::default());
 
@@ -309,18 +469,28 @@ async fn init(
         proof_server_address.clone(),
         &mut epoch_change_proof,
         verifier_state,
+        from_waypoint,
     )
     .await?;
 
     // Verify inclusion proof.
-    let verifier_state = inclusion_verifying_task(
+    let (verifier_state, inclusion_version) = inclusion_verifying_task(
         proof_server_address.clone(),
         &mut inclusion_proof,
         verifier_state,
+        entry,
+        block_id,
     )
     .await?;
 
-    Ok((ratcheted_trusted_state, verifier_state))
+    Ok(PersistedClientState {
+        trusted_state: ratcheted_trusted_state,
+        validator_verifier_hash: verifier_state.0,
+        state_root: verifier_state.1,
+        epoch_change_proof: Some(verified_epoch_change_proof),
+        inclusion_proofs: BTreeMap::from([(entry.name.clone(), verified_inclusion_proof)]),
+        inclusion_versions: BTreeMap::from([(entry.name.clone(), inclusion_version)]),
+    })
 }
 
 /// This method calls the endpoint to fetch epoch change proof data from the Aptos node and returns
@@ -353,24 +523,22 @@ async fn fetch_epoch_change_proof_data(
     })
 }
 
-/// This method calls the endpoint to fetch epoch change proof data from the Aptos node and returns
+/// This method calls the endpoint to fetch inclusion proof data from the Aptos node and returns
 /// the deserialized payload.
 ///
 /// # Arguments
 ///
 /// * `aptos_node_url` - The URL of the Aptos node.
+/// * `entry` - The watched account to fetch the inclusion proof data of.
 ///
 /// # Returns
 ///
-/// The deserialized payload of the epoch change proof data.
+/// The deserialized payload of the inclusion proof data.
 async fn fetch_inclusion_proof_data(
     aptos_node_url: &str,
+    entry: &WatchEntry,
 ) -> Result<AccountInclusionProofResponse, ClientError> {
-    let request_address = format!(
-        "{}{}",
-        aptos_node_url,
-        aptos_inclusion_proof_endpoint(ACCOUNT)
-    );
+    let request_address = format!("{}{}", aptos_node_url, entry.endpoint());
 
     bcs::from_bytes(&request_aptos_node(&request_address).await?).map_err(|err| {
         ClientError::ResponsePayload {
@@ -418,7 +586,9 @@ async fn request_aptos_node(request_url: &str) -> Result<Vec<u8>, ClientError> {
     Ok(response_bytes.to_vec())
 }
 
-/// This method sends a request to the prover and returns the proof.
+/// This method sends a request to the prover and returns the proof. The
+/// request is authenticated with the credentials read by
+/// `Credentials::from_env`, if any.
 ///
 /// # Arguments
 ///
@@ -446,9 +616,22 @@ async fn request_prover(
     let request_bytes =
         bcs::to_bytes(request).map_err(|err| ClientError::Internal { source: err.into() })?;
 
-    let response = client
+    let mut request_builder = client
         .post(proof_server_address)
-        .header("Accept", "application/octet-stream")
+        .header("Accept", "application/octet-stream");
+    let credentials =
+        Credentials::from_env().map_err(|err| ClientError::Internal { source: err.into() })?;
+    if let Some(credentials) = credentials {
+        let url = url::Url::parse(proof_server_address)
+            .map_err(|err| ClientError::Internal { source: err.into() })?;
+        // The path is signed along with the query, if any
+        let path_and_query = &url[url::Position::BeforePath..url::Position::AfterQuery];
+        for (name, value) in credentials.headers("POST", path_and_query, &request_bytes) {
+            request_builder = request_builder.header(name, value);
+        }
+    }
+
+    let response = request_builder
         .body(request_bytes)
         .send()
         .await
@@ -503,6 +686,8 @@ fn assert_validator_verifier_predicate(
 /// * `proof_server_address` - The address of the proof server.
 /// * `aptos_node_url` - The URL of the Aptos node.
 /// * `epoch` - The epoch for which to generate the epoch change proof.
+/// * `waypoint` - An optional waypoint to use as the trusted state, instead of the
+///   one served by the Aptos node.
 ///
 /// # Errors
 ///
@@ -512,6 +697,7 @@ async fn epoch_change_proving_task(
     proof_server_address: Arc<String>,
     aptos_node_url: Arc<String>,
     epoch: u64,
+    waypoint: Option<Waypoint>,
 ) -> Result<(TrustedState, HashValue, SphinxProofWithPublicValues), ClientError> {
     info!("Starting epoch change proving task for epoch: {}", epoch);
 
@@ -519,14 +705,23 @@ async fn epoch_change_proving_task(
     let epoch_change_proof_data =
         fetch_epoch_change_proof_data(&aptos_node_url, Some(epoch)).await?;
 
-    // Retrieve the validator verifier hash for penultimate epoch.
-    let validator_verifier_hash = match epoch_change_proof_data.trusted_state() {
-        TrustedState::EpochState { epoch_state, .. } => epoch_state.verifier().hash(),
-        _ => {
-            return Err(ClientError::Internal {
-                source: "Expected epoch state".into(),
-            })
+    let trusted_state = match waypoint {
+        Some(waypoint) => {
+            debug!("Bootstrapping from waypoint: {}", waypoint);
+            bootstrap_from_waypoint(waypoint, &epoch_change_proof_data)?
         }
+        None => epoch_change_proof_data.trusted_state().clone(),
+    };
+
+    // Retrieve the prepared validator verifier hash for penultimate epoch, or the waypoint
+    // value that the program commits in its place.
+    let validator_verifier_hash = match &trusted_state {
+        TrustedState::EpochState { epoch_state, .. } => {
+            PreparedValidatorVerifier::new(epoch_state.verifier().clone())
+                .map_err(|err| ClientError::Internal { source: err.into() })?
+                .hash()
+        }
+        TrustedState::EpochWaypoint(waypoint) => waypoint.value(),
     };
 
     debug!(
@@ -539,7 +734,10 @@ async fn epoch_change_proving_task(
 
     let request = Request::ProveEpochChange(Box::new((
         get_proving_mode(),
-        epoch_change_proof_data.clone().into(),
+        EpochChangeData {
+            trusted_state: trusted_state.to_bytes(),
+            epoch_change_proof: epoch_change_proof_data.epoch_change_proof().to_bytes(),
+        },
     )));
 
     let epoch_change_proof: SphinxProofWithPublicValues = bcs::from_bytes(
@@ -581,6 +779,46 @@ async fn epoch_change_proving_task(
     Ok((ratcheted_state, validator_verifier_hash, epoch_change_proof))
 }
 
+/// This method checks that a waypoint commits to the first epoch change of the proof data
+/// received from the Aptos node, and returns the trusted state bootstrapped from it.
+///
+/// # Arguments
+///
+/// * `waypoint` - The waypoint to bootstrap from.
+/// * `epoch_change_proof_data` - The epoch change proof data received from the Aptos node.
+///
+/// # Returns
+///
+/// The `TrustedState` holding the waypoint.
+///
+/// # Errors
+///
+/// This method returns an error if the waypoint is a dummy one or if it does not match
+/// the epoch change proof data.
+fn bootstrap_from_waypoint(
+    waypoint: Waypoint,
+    epoch_change_proof_data: &EpochChangeProofResponse,
+) -> Result<TrustedState, ClientError> {
+    waypoint
+        .validate()
+        .map_err(|err| ClientError::Internal { source: err.into() })?;
+
+    let ledger_info = epoch_change_proof_data
+        .epoch_change_proof()
+        .ledger_info_with_sigs
+        .first()
+        .ok_or_else(|| ClientError::Internal {
+            source: "Epoch Change Proof has no LedgerInfoWithSignatures".into(),
+        })?
+        .ledger_info();
+
+    waypoint
+        .verify(ledger_info)
+        .map_err(|err| ClientError::Internal { source: err.into() })?;
+
+    Ok(TrustedState::EpochWaypoint(waypoint))
+}
+
 /// This method sends a request to the prover to verify an epoch change proof.
 ///
 /// # Arguments
@@ -588,6 +826,7 @@ async fn epoch_change_proving_task(
 /// * `proof_server_address` - The address of the proof server.
 /// * `epoch_change_proof` - The epoch change proof to verify.
 /// * `verifier_state` - The verifier state to verify.
+/// * `from_waypoint` - Whether the proof is expected to bootstrap from a waypoint.
 ///
 /// # Returns
 ///
@@ -596,6 +835,7 @@ async fn epoch_change_verifying_task(
     proof_server_address: Arc<String>,
     epoch_change_proof: &mut SphinxProofWithPublicValues,
     verifier_state: VerifierState,
+    from_waypoint: bool,
 ) -> Result<VerifierState, ClientError> {
     info!("Starting epoch change verification task");
     // Verifying the received epoch change proof and the validator verifier hash.
@@ -620,6 +860,19 @@ async fn epoch_change_verifying_task(
     assert_validator_verifier_predicate(epoch_change_proof, verifier_state.0)?;
 
     let new_validator_hash_slice = epoch_change_proof.public_values.read::<[u8; 32]>();
+    let _epoch = epoch_change_proof.public_values.read::<u64>();
+
+    // The first slot holds a waypoint value only when bootstrapping from a waypoint.
+    let expected_root_of_trust = if from_waypoint {
+        EPOCH_CHANGE_FROM_WAYPOINT
+    } else {
+        EPOCH_CHANGE_FROM_VALIDATOR_VERIFIER
+    };
+    if epoch_change_proof.public_values.read::<u8>() != expected_root_of_trust {
+        return Err(ClientError::Verification(String::from(
+            "Epoch Change Proof (unexpected root of trust)",
+        )));
+    }
 
     Ok((
         HashValue::from_slice(new_validator_hash_slice)
@@ -633,26 +886,27 @@ async fn epoch_change_verifying_task(
 /// # Arguments
 ///
 /// * `proof_server_address` - The address of the proof server.
-/// * `aptos_node_url` - The URL of the Aptos node.
-/// * `account` - The account to generate the inclusion proof.
+/// * `inclusion_proof_data` - The inclusion proof data fetched from the Aptos node.
+/// * `entry` - The watched account to generate the inclusion proof for.
 ///
 /// # Returns
 ///
 /// The account inclusion proof.
 async fn inclusion_proving_task(
     proof_server_address: Arc<String>,
-    aptos_node_url: Arc<String>,
-    account: String,
+    inclusion_proof_data: AccountInclusionProofResponse,
+    entry: Arc<WatchEntry>,
 ) -> Result<SphinxProofWithPublicValues, ClientError> {
-    info!("Starting account inclusion proving task");
-
-    debug!("Fetching account inclusion proof for account: {}", account);
-
-    let inclusion_proof_data = fetch_inclusion_proof_data(&aptos_node_url).await?;
+    info!(
+        "Starting account inclusion proving task for {} ({})",
+        entry.name, entry.address
+    );
 
     debug!("Sending account inclusion proof request to the prover");
-    let request =
-        Request::ProveInclusion(Box::new((get_proving_mode(), inclusion_proof_data.into())));
+    let inclusion_data = inclusion_proof_data
+        .into_inclusion_data(entry.state_key()?)
+        .map_err(|err: VerifyError| ClientError::Internal { source: err.into() })?;
+    let request = Request::ProveInclusion(Box::new((get_proving_mode(), inclusion_data)));
     let account_inclusion_proof: SphinxProofWithPublicValues = bcs::from_bytes(
         &request_prover(
             &format!("http://{}/inclusion/proof", proof_server_address),
@@ -677,15 +931,21 @@ async fn inclusion_proving_task(
 /// * `proof_server_address` - The address of the proof server.
 /// * `account_inclusion_proof` - The account inclusion proof to verify.
 /// * `verifier_state` - The verifier state to verify.
+/// * `entry` - The watched account the proof was requested for.
+/// * `block_id` - The identifier of the block of the ledger info the proof was requested against.
 ///
 /// # Returns
 ///
-/// The verifier state after the verification.
+/// The verifier state after the verification along with the version of the ledger info
+/// the proof was verified against, or an error if the proof does not prove the leaf of
+/// the watched account against the requested ledger info.
 async fn inclusion_verifying_task(
     proof_server_address: Arc<String>,
     account_inclusion_proof: &mut SphinxProofWithPublicValues,
     verifier_state: VerifierState,
-) -> Result<VerifierState, ClientError> {
+    entry: &WatchEntry,
+    block_id: HashValue,
+) -> Result<(VerifierState, Version), ClientError> {
     info!("Verifying account inclusion proof");
     // Verifying the received account inclusion proof and the validator verifier hash.
     let request = Request::VerifyInclusion(account_inclusion_proof.clone());
@@ -709,32 +969,62 @@ async fn inclusion_verifying_task(
     assert_validator_verifier_predicate(account_inclusion_proof, verifier_state.0)?;
 
     let new_state_root = account_inclusion_proof.public_values.read::<[u8; 32]>();
+    let proven_block_id = account_inclusion_proof.public_values.read::<[u8; 32]>();
+    let proven_key = account_inclusion_proof.public_values.read::<[u8; 32]>();
+
+    if proven_block_id != *block_id.as_ref() {
+        return Err(ClientError::Verification(String::from(
+            "Account Inclusion Proof (unexpected block id)",
+        )));
+    }
+    if proven_key != *entry.leaf_key()?.as_ref() {
+        return Err(ClientError::Verification(String::from(
+            "Account Inclusion Proof (unexpected key)",
+        )));
+    }
+
+    // Skip the value hash and the epoch to read the version of the ledger info
+    account_inclusion_proof.public_values.read::<[u8; 32]>();
+    account_inclusion_proof.public_values.read::<u64>();
+    let version = account_inclusion_proof.public_values.read::<u64>();
 
     Ok((
-        verifier_state.0,
-        HashValue::from_slice(new_state_root)
-            .map_err(|err| ClientError::Internal { source: err.into() })?,
+        (
+            verifier_state.0,
+            HashValue::from_slice(new_state_root)
+                .map_err(|err| ClientError::Internal { source: err.into() })?,
+        ),
+        version,
     ))
 }
 
-/// This method creates a listener for new tasks to verify proofs and processes them.
+/// This method creates a listener for new tasks to verify proofs and processes them. Each
+/// verified proof updates the persisted state of the client.
 ///
 /// # Arguments
 ///
 /// * `task_receiver` - The receiver channel for the tasks.
 /// * `proof_server_address` - The address of the proof server.
-/// * `initial_verifier_state` - The initial verifier state.
+/// * `initial_state` - The initial verified state of the client.
+/// * `state_file` - The file persisting the verified state of the client.
 /// * `client_state` - The client state.
+/// * `proof_sink` - The destinations the verified inclusion proofs are published to.
 async fn verifier_task(
     mut task_receiver: mpsc::Receiver<ProofType>,
     proof_server_address: Arc<String>,
-    initial_verifier_state: VerifierState,
+    initial_state: PersistedClientState,
+    mut state_file: StateFile,
     client_state: Arc<Mutex<ClientState>>,
+    proof_sink: ProofSink,
 ) {
-    let mut verifier_state = initial_verifier_state;
+    let mut verified_state = initial_state;
 
     while let Some(proof_type) = task_receiver.recv().await {
         info!("Received a new task to verify: {}", &proof_type);
+        let verifier_state = (
+            verified_state.validator_verifier_hash,
+            verified_state.state_root,
+        );
 
         match proof_type {
             ProofType::EpochChange { task, permit } => {
@@ -743,21 +1033,39 @@ async fn verifier_task(
                     Ok(result) => match result {
                         Ok((ratcheted_trusted_state, _, mut epoch_change_proof)) => {
                             debug!("Start verifying epoch change proof");
+                            let verified_proof = epoch_change_proof.clone();
                             let res = epoch_change_verifying_task(
                                 proof_server_address.clone(),
                                 &mut epoch_change_proof,
                                 verifier_state,
+                                false,
                             )
                             .await;
 
-                            if let Ok(updated_verifier_state) = res {
-                                verifier_state = updated_verifier_state;
-                            } else {
-                                error!("Epoch change proof verification failed: {:?}", res);
+                            match res {
+                                Ok((validator_verifier_hash, state_root)) => {
+                                    let new_state = PersistedClientState {
+                                        trusted_state: ratcheted_trusted_state.clone(),
+                                        validator_verifier_hash,
+                                        state_root,
+                                        epoch_change_proof: Some(verified_proof),
+                                        inclusion_proofs: verified_state.inclusion_proofs.clone(),
+                                        inclusion_versions: verified_state
+                                            .inclusion_versions
+                                            .clone(),
+                                    };
+
+                                    if persist_state(&mut state_file, &new_state) {
+                                        let mut client_state = client_state.lock().await;
+                                        *client_state = ratcheted_trusted_state;
+                                        verified_state = new_state;
+                                    }
+                                }
+                                Err(err) => {
+                                    error!("Epoch change proof verification failed: {:?}", err)
+                                }
                             }
 
-                            let mut client_state = client_state.lock().await;
-                            *client_state = ratcheted_trusted_state;
                             drop(permit)
                         }
                         Err(e) => {
@@ -770,23 +1078,57 @@ async fn verifier_task(
                     }
                 }
             }
-            ProofType::Inclusion { task, permit } => {
+            ProofType::Inclusion {
+                task,
+                permit,
+                entry,
+                block_id,
+            } => {
                 // Wait for the task to finish and handle the result.
                 match task.await {
                     Ok(result) => match result {
                         Ok(mut inclusion_proof) => {
                             debug!("Start verifying inclusion proof");
+                            let verified_proof = inclusion_proof.clone();
                             let res = inclusion_verifying_task(
                                 proof_server_address.clone(),
                                 &mut inclusion_proof,
                                 verifier_state,
+                                &entry,
+                                block_id,
                             )
                             .await;
 
-                            if let Ok(updated_verifier_state) = res {
-                                verifier_state = updated_verifier_state;
-                            } else {
-                                error!("Inclusion proof verification failed: {:?}", res);
+                            match res {
+                                Ok(((validator_verifier_hash, state_root), inclusion_version)) => {
+                                    let mut new_state = PersistedClientState {
+                                        validator_verifier_hash,
+                                        state_root,
+                                        ..verified_state.clone()
+                                    };
+                                    new_state
+                                        .inclusion_proofs
+                                        .insert(entry.name.clone(), verified_proof.clone());
+                                    new_state
+                                        .inclusion_versions
+                                        .insert(entry.name.clone(), inclusion_version);
+
+                                    if persist_state(&mut state_file, &new_state) {
+                                        verified_state = new_state;
+                                    }
+
+                                    if let Err(err) =
+                                        proof_sink.publish(&entry, &verified_proof).await
+                                    {
+                                        error!(
+                                            "Failed to publish the proof of {}: {err}",
+                                            entry.name
+                                        );
+                                    }
+                                }
+                                Err(err) => {
+                                    error!("Inclusion proof verification failed: {:?}", err)
+                                }
                             }
 
                             drop(permit)
@@ -805,6 +1147,32 @@ async fn verifier_task(
     }
 }
 
+/// This method persists a newly verified state of the client.
+///
+/// # Arguments
+///
+/// * `state_file` - The file persisting the verified state of the client.
+/// * `new_state` - The newly verified state.
+///
+/// # Returns
+///
+/// A boolean indicating if the client can move to the new state. A state that
+/// could not be written is still applied, but a state older than the persisted
+/// one, or holding an older inclusion proof, is refused.
+fn persist_state(state_file: &mut StateFile, new_state: &PersistedClientState) -> bool {
+    match state_file.save(new_state) {
+        Ok(()) => true,
+        Err(err @ (ClientError::StateRollback { .. } | ClientError::InclusionRollback { .. })) => {
+            error!("{err}");
+            false
+        }
+        Err(err) => {
+            error!("Failed to persist the client state: {err}");
+            true
+        }
+    }
+}
+
 fn get_proving_mode() -> ProvingMode {
     // Get proving mode for the light client.
     let mode_str: String = env::var("MODE").unwrap_or_else(|_| "STARK".into());
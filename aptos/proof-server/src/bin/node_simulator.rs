@@ -0,0 +1,350 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! # Node simulator
+//!
+//! Local stand-in for an Aptos Public Full Node, serving the endpoints used by the client for a
+//! chain simulated with an `AptosWrapper`. Control routes advance the chain or force an epoch
+//! change, so that the client, the proof server and the provers can run end to end on an isolated
+//! machine.
+//!
+//! ## Usage
+//!
+//! For a detailed usage guide, please refer to the dedicated README in `aptos/docs/src/run/setup_node_simulator.md`.
+
+use anyhow::Result;
+use axum::body::Body;
+use axum::extract::{Path, Query, State};
+use axum::http::header::CONTENT_TYPE;
+use axum::http::{Response, StatusCode};
+use axum::response::IntoResponse;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use clap::Parser;
+use log::{error, info, warn};
+use proof_server::error::SimulatorError;
+use proof_server::simulator::NodeSimulator;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::{net::TcpListener, task::spawn_blocking};
+
+/// Simulated Aptos node, serving the `v1/`, `v1/epoch/proof` and
+/// `v1/accounts/:address/proof` endpoints with BCS payloads for a local chain.
+#[derive(Parser)]
+struct Cli {
+    /// Address of this node. E.g. 127.0.0.1:8080
+    #[arg(short, long)]
+    addr: String,
+
+    /// Number of funded accounts on the chain, at least 2.
+    #[arg(long, default_value_t = 10)]
+    accounts: usize,
+
+    /// Number of validators of the chain.
+    #[arg(long, default_value_t = 130)]
+    validators: usize,
+
+    /// Number of validators signing each block.
+    #[arg(long, default_value_t = 95)]
+    signers_per_block: usize,
+
+    /// Interval, in seconds, at which a new block is executed. The chain only
+    /// advances through the control routes if none is given.
+    #[arg(long)]
+    block_interval: Option<u64>,
+}
+
+type SimulatorState = Arc<Mutex<NodeSimulator>>;
+
+#[derive(Deserialize)]
+struct EpochChangeProofQuery {
+    epoch_number: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct InclusionProofQuery {
+    resource_type: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AdvanceQuery {
+    blocks: Option<usize>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let Cli {
+        addr,
+        accounts,
+        validators,
+        signers_per_block,
+        block_interval,
+    } = Cli::parse();
+
+    env_logger::init();
+
+    info!("Initializing the simulated chain");
+    let simulator =
+        spawn_blocking(move || NodeSimulator::new(accounts, validators, signers_per_block))
+            .await??;
+    let state: SimulatorState = Arc::new(Mutex::new(simulator));
+
+    if let Some(block_interval) = block_interval {
+        tokio::spawn(advance_periodically(
+            state.clone(),
+            Duration::from_secs(block_interval),
+        ));
+    }
+
+    let app = app(state);
+
+    info!("Simulated Aptos node running on {}", addr);
+
+    let listener = TcpListener::bind(addr).await?;
+
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
+
+/// Routes of the simulated node.
+fn app(state: SimulatorState) -> Router {
+    Router::new()
+        .route("/health", get(health_check))
+        .route("/v1", get(ledger_info))
+        .route("/v1/", get(ledger_info))
+        .route("/v1/epoch/proof", get(epoch_change_proof))
+        .route("/v1/accounts/:address/proof", get(account_inclusion_proof))
+        .route("/control/status", get(status))
+        .route("/control/accounts", get(accounts_addresses))
+        .route("/control/advance", post(advance))
+        .route("/control/epoch", post(change_epoch))
+        .with_state(state)
+}
+
+async fn health_check() -> impl IntoResponse {
+    StatusCode::OK
+}
+
+async fn ledger_info(State(state): State<SimulatorState>) -> Result<impl IntoResponse, StatusCode> {
+    let ledger_info = with_simulator(state, |simulator| simulator.ledger_info()).await?;
+
+    bcs_response(&ledger_info)
+}
+
+async fn epoch_change_proof(
+    State(state): State<SimulatorState>,
+    Query(query): Query<EpochChangeProofQuery>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let epoch_change_proof = with_simulator(state, move |simulator| {
+        simulator.epoch_change_proof(query.epoch_number)
+    })
+    .await?;
+
+    bcs_response(&epoch_change_proof)
+}
+
+async fn account_inclusion_proof(
+    State(state): State<SimulatorState>,
+    Path(address): Path<String>,
+    Query(query): Query<InclusionProofQuery>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let inclusion_proof = with_simulator(state, move |simulator| {
+        simulator.account_inclusion_proof(&address, query.resource_type.as_deref())
+    })
+    .await?;
+
+    bcs_response(&inclusion_proof)
+}
+
+async fn status(State(state): State<SimulatorState>) -> Result<impl IntoResponse, StatusCode> {
+    let status = with_simulator(state, |simulator| Ok(simulator.status())).await?;
+
+    Ok(Json(status))
+}
+
+async fn accounts_addresses(
+    State(state): State<SimulatorState>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let addresses = with_simulator(state, |simulator| Ok(simulator.account_addresses())).await?;
+
+    Ok(Json(addresses))
+}
+
+async fn advance(
+    State(state): State<SimulatorState>,
+    Query(query): Query<AdvanceQuery>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let status = with_simulator(state, move |simulator| {
+        simulator.advance(query.blocks.unwrap_or(1))?;
+        Ok(simulator.status())
+    })
+    .await?;
+
+    Ok(Json(status))
+}
+
+async fn change_epoch(
+    State(state): State<SimulatorState>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let status = with_simulator(state, |simulator| {
+        simulator.change_epoch()?;
+        Ok(simulator.status())
+    })
+    .await?;
+
+    Ok(Json(status))
+}
+
+/// Executes a new block on every tick of the interval.
+async fn advance_periodically(state: SimulatorState, block_interval: Duration) {
+    let mut interval = tokio::time::interval(block_interval);
+    // The first tick completes immediately
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+        // Failures are already logged, and the next tick tries again
+        let _ = with_simulator(state.clone(), |simulator| simulator.advance(1)).await;
+    }
+}
+
+/// Runs an operation on the simulated chain, off the async runtime as executing
+/// blocks is CPU bound.
+async fn with_simulator<T: Send + 'static>(
+    state: SimulatorState,
+    operation: impl FnOnce(&mut NodeSimulator) -> Result<T, SimulatorError> + Send + 'static,
+) -> Result<T, StatusCode> {
+    spawn_blocking(move || {
+        // Recover a poisoned lock so that a panicking request does not take the node down
+        let mut simulator = state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        operation(&mut simulator)
+    })
+    .await
+    .map_err(|err| {
+        error!("Simulator task failed: {err}");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
+    .map_err(|err| match err {
+        SimulatorError::InvalidParameter { .. } => {
+            warn!("{err}");
+            StatusCode::BAD_REQUEST
+        }
+        SimulatorError::UnknownEpoch(_) => {
+            warn!("{err}");
+            StatusCode::NOT_FOUND
+        }
+        err => {
+            error!("{err}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    })
+}
+
+fn bcs_response<T: Serialize>(value: &T) -> Result<Response<Body>, StatusCode> {
+    let bytes = bcs::to_bytes(value).map_err(|err| {
+        error!("Failed to serialize response: {err}");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, "application/x-bcs")
+        .body(Body::from(bytes))
+        .map_err(|err| {
+            error!("Could not construct response for client: {err}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use proof_server::types::aptos::{
+        AccountInclusionProofResponse, EpochChangeProofResponse, LedgerInfoResponse,
+    };
+    use serde_json::Value;
+
+    use super::*;
+
+    /// Serves a small simulated chain on a random local port.
+    async fn spawn_node() -> String {
+        let simulator = spawn_blocking(|| NodeSimulator::new(2, 1, 1))
+            .await
+            .unwrap()
+            .unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = app(Arc::new(Mutex::new(simulator)));
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        format!("http://{addr}")
+    }
+
+    async fn get_bytes(url: &str) -> (StatusCode, Vec<u8>) {
+        let response = reqwest::Client::new()
+            .get(url)
+            .header("Accept", "application/x-bcs")
+            .send()
+            .await
+            .unwrap();
+        let status = StatusCode::from_u16(response.status().as_u16()).unwrap();
+
+        (status, response.bytes().await.unwrap().to_vec())
+    }
+
+    async fn post_json(url: &str) -> Value {
+        let response = reqwest::Client::new().post(url).send().await.unwrap();
+        assert!(response.status().is_success());
+
+        serde_json::from_slice(&response.bytes().await.unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_endpoints() {
+        let node = spawn_node().await;
+
+        let (status, bytes) = get_bytes(&format!("{node}/v1/")).await;
+        assert_eq!(status, StatusCode::OK);
+        let ledger_info: LedgerInfoResponse = bcs::from_bytes(&bytes).unwrap();
+        let initial_epoch: u64 = ledger_info.epoch().parse().unwrap();
+
+        let initial = post_json(&format!("{node}/control/advance?blocks=2")).await;
+        let advanced = post_json(&format!("{node}/control/advance?blocks=2")).await;
+        assert_eq!(
+            advanced["block_height"].as_u64().unwrap(),
+            initial["block_height"].as_u64().unwrap() + 2
+        );
+        assert!(advanced["version"].as_u64().unwrap() > initial["version"].as_u64().unwrap());
+        assert_eq!(advanced["epoch"].as_u64().unwrap(), initial_epoch);
+
+        let changed = post_json(&format!("{node}/control/epoch")).await;
+        let epoch = changed["epoch"].as_u64().unwrap();
+        assert_eq!(epoch, initial_epoch + 1);
+
+        let (status, bytes) =
+            get_bytes(&format!("{node}/v1/epoch/proof?epoch_number={epoch}")).await;
+        assert_eq!(status, StatusCode::OK);
+        bcs::from_bytes::<EpochChangeProofResponse>(&bytes).unwrap();
+
+        let (status, _) =
+            get_bytes(&format!("{node}/v1/epoch/proof?epoch_number={}", epoch + 1)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, bytes) = get_bytes(&format!("{node}/control/accounts")).await;
+        assert_eq!(status, StatusCode::OK);
+        let addresses: Vec<String> = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(addresses.len(), 2);
+
+        let (status, bytes) =
+            get_bytes(&format!("{node}/v1/accounts/{}/proof", addresses[0])).await;
+        assert_eq!(status, StatusCode::OK);
+        let inclusion_proof: AccountInclusionProofResponse = bcs::from_bytes(&bytes).unwrap();
+        assert!(inclusion_proof.into_inclusion_data(None).is_ok());
+
+        let (status, _) = get_bytes(&format!("{node}/v1/accounts/invalid/proof")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
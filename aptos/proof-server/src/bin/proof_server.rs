@@ -1,33 +1,43 @@
 // Copyright (c) Argument Computer Corporation
 // SPDX-License-Identifier: Apache-2.0
 
-//! # Server secondary
+//! # Proof server
 //!
-//! Server capable of handling proof generation and verification regarding epoch changes. Such
-//! requests are expected to come from the primary server.
+//! Server capable of handling proof generation and verification for inclusion, epoch change and
+//! aggregation proofs. In split mode, it acts as a primary server, forwarding its epoch change and
+//! aggregation proofs to its secondary servers, which run the same binary.
 //!
 //! ## Usage
 //!
 //! For a detailed usage guide, please refer to the dedicated README in `aptos/docs/src/run/setup_proof_server.md`.
 
 use anyhow::{Error, Result};
-use aptos_lc::{epoch_change, inclusion};
+use aptos_lc::key_store::{verifying_key_hash, KeyStore};
+use aptos_lc::{aggregation, epoch_change, inclusion};
 use axum::body::Body;
-use axum::extract::State;
-use axum::http::header::CONTENT_TYPE;
+use axum::extract::{Path, State};
+use axum::http::header::{CONTENT_LENGTH, CONTENT_TYPE};
 use axum::http::{Response, StatusCode};
 use axum::middleware::Next;
 use axum::response::IntoResponse;
 use axum::routing::{get, post};
+use axum::Extension;
 use axum::Router;
 use clap::{Parser, ValueEnum};
-use log::{error, info};
-use proof_server::types::proof_server::{EpochChangeData, ProvingMode};
-use proof_server::types::proof_server::{InclusionData, Request};
-use sphinx_sdk::{ProverClient, SphinxProvingKey, SphinxVerifyingKey};
+use log::{error, info, warn};
+use proof_server::auth::{ApiKeyId, AuthMode, Authenticator, Credentials};
+use proof_server::cache::{cache_key, CacheStatus, ProofCache, PROOF_CACHE_HEADER};
+use proof_server::error::{AuthError, JobError, SecondaryError};
+use proof_server::jobs::JobQueue;
+use proof_server::secondary::SecondaryPool;
+use proof_server::types::proof_server::{AggregationData, EpochChangeData, ProvingMode};
+use proof_server::types::proof_server::{InclusionData, JobId, Request};
+use sphinx_sdk::{ProverClient, SphinxProofWithPublicValues, SphinxProvingKey, SphinxVerifyingKey};
 use std::cmp::PartialEq;
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::{net::TcpListener, task::spawn_blocking};
 
 #[derive(ValueEnum, Clone, Debug, Eq, PartialEq)]
@@ -36,32 +46,83 @@ enum Mode {
     Split,
 }
 
-/// Server capable of handling proof generation and verification regarding epoch
-/// changes. Such requests are expected to come from the primary server.
+/// Server capable of handling proof generation and verification for inclusion,
+/// epoch change and aggregation proofs.
 ///
-/// Making requests to this server and handling responses from it follows the
-/// same logic from the primary server:
+/// In split mode, the server acts as a primary server and forwards its epoch
+/// change and aggregation proofs to the least busy of its secondary servers.
+/// Forwarded requests are signed with the credentials read from the
+/// `PROOF_SERVER_API_KEY_ID`, `PROOF_SERVER_API_KEY` and
+/// `PROOF_SERVER_AUTH_MODE` environment variables, if any.
 ///
-/// * Request data must be preceded by its size in bytes
-/// * Proof responses will follow the same logic
-/// * Verification responses will follow the same logic.
-///
-/// The request bytes must be deserializable into `proof_server::SecondaryRequest`
-/// by the `bcs` crate, so it's recommended to simply use that (pub) type when
-/// producing request data.
+/// The request bytes must be deserializable into
+/// `proof_server::types::proof_server::Request` by the `bcs` crate, so it's
+/// recommended to simply use that (pub) type when producing request data.
 #[derive(Parser)]
 struct Cli {
     /// Address of this server. E.g. 127.0.0.1:4321
     #[arg(short, long)]
     addr: String,
 
-    /// Address of the secondary server. E.g. 127.0.0.1:4321
-    #[arg(short, long)]
-    snd_addr: Option<String>,
+    /// Addresses of the secondary servers, comma separated or repeated. E.g. 127.0.0.1:4321
+    #[arg(short, long, value_delimiter = ',')]
+    snd_addr: Vec<String>,
 
     /// Mode of operation: either 'single' or 'split'
     #[arg(short, long)]
     mode: Mode,
+
+    /// Directory caching the proving and verifying keys of the programs across restarts. Keys
+    /// are only generated again when a program changes.
+    #[arg(long)]
+    key_cache_dir: Option<PathBuf>,
+
+    /// Directory persisting the proving jobs, so that their proofs survive a restart.
+    #[arg(long, default_value = "proof-server-jobs")]
+    jobs_dir: PathBuf,
+
+    /// Maximum number of queued proving jobs. Jobs submitted while the queue is full are rejected.
+    #[arg(long, default_value_t = 16)]
+    job_queue_size: usize,
+
+    /// Number of proving jobs processed concurrently.
+    #[arg(long, default_value_t = 1)]
+    job_workers: usize,
+
+    /// Maximum number of finished jobs kept, along with their proof. The files of the oldest
+    /// finished jobs are removed first.
+    #[arg(long, default_value_t = 256)]
+    job_history_size: usize,
+
+    /// File declaring the API keys allowed to use the server, one `<key-id> <secret> [rate-limit]`
+    /// per line. Requests are not authenticated if no file is given.
+    #[arg(long)]
+    api_keys_file: Option<PathBuf>,
+
+    /// Authentication mode of the requests: either 'api-key' or 'hmac'
+    #[arg(long, value_enum, default_value_t = AuthMode::ApiKey)]
+    auth_mode: AuthMode,
+
+    /// Maximum number of proving requests per minute for the API keys that do not declare their
+    /// own rate limit.
+    #[arg(long, requires = "api_keys_file")]
+    rate_limit: Option<u32>,
+
+    /// Maximum size of the request bodies, in bytes.
+    #[arg(long, default_value_t = 64 * 1024 * 1024)]
+    max_body_size: usize,
+
+    /// Maximum time spent generating a proof, in seconds.
+    #[arg(long)]
+    proving_timeout: Option<u64>,
+
+    /// Directory caching the generated proofs, keyed by their program, proving mode and inputs.
+    #[arg(long, default_value = "proof-server-cache")]
+    proof_cache_dir: PathBuf,
+
+    /// Maximum number of cached proofs. The least recently used proofs are evicted first.
+    #[arg(long, default_value_t = 64)]
+    proof_cache_size: usize,
 }
 
 #[derive(Clone)]
@@ -71,9 +132,16 @@ struct ServerState {
     inclusion_vk: Arc<SphinxVerifyingKey>,
     epoch_pk: Arc<SphinxProvingKey>,
     epoch_vk: Arc<SphinxVerifyingKey>,
-    snd_addr: Arc<Option<String>>,
+    aggregation_pk: Arc<SphinxProvingKey>,
+    aggregation_vk: Arc<SphinxVerifyingKey>,
+    secondaries: Arc<SecondaryPool>,
     mode: Mode,
     active_requests: Arc<AtomicUsize>,
+    jobs: Arc<JobQueue>,
+    authenticator: Arc<Option<Authenticator>>,
+    max_body_size: usize,
+    proving_timeout: Option<Duration>,
+    proof_cache: Arc<ProofCache<StatusCode>>,
 }
 
 #[allow(clippy::needless_return)]
@@ -83,9 +151,21 @@ async fn main() -> Result<()> {
         addr,
         snd_addr,
         mode,
+        key_cache_dir,
+        jobs_dir,
+        job_queue_size,
+        job_workers,
+        job_history_size,
+        api_keys_file,
+        auth_mode,
+        rate_limit,
+        max_body_size,
+        proving_timeout,
+        proof_cache_dir,
+        proof_cache_size,
     } = Cli::parse();
 
-    if mode == Mode::Split && snd_addr.is_none() {
+    if mode == Mode::Split && snd_addr.is_empty() {
         return Err(Error::msg(
             "Secondary server address is required in split mode",
         ));
@@ -93,9 +173,47 @@ async fn main() -> Result<()> {
 
     env_logger::init();
 
+    let authenticator = api_keys_file
+        .map(|api_keys_file| Authenticator::load(auth_mode, &api_keys_file, rate_limit))
+        .transpose()?;
+    if authenticator.is_none() {
+        info!("No API keys file given, requests are not authenticated");
+    }
+
+    let secondary_credentials = Credentials::from_env()?;
+    if mode == Mode::Split && secondary_credentials.is_none() {
+        info!("No API key set, requests forwarded to the secondary servers are not authenticated");
+    }
+
     let prover_client = Arc::new(ProverClient::default());
-    let (inclusion_pk, inclusion_vk) = inclusion::generate_keys(&prover_client);
-    let (epoch_pk, epoch_vk) = epoch_change::generate_keys(&prover_client);
+    let ((inclusion_pk, inclusion_vk), (epoch_pk, epoch_vk), (aggregation_pk, aggregation_vk)) =
+        match key_cache_dir {
+            Some(key_cache_dir) => {
+                let key_store = KeyStore::new(key_cache_dir)?;
+                (
+                    key_store.setup(&prover_client, aptos_programs::INCLUSION_PROGRAM)?,
+                    key_store.setup(&prover_client, aptos_programs::EPOCH_CHANGE_PROGRAM)?,
+                    key_store.setup(&prover_client, aptos_programs::AGGREGATION_PROGRAM)?,
+                )
+            }
+            None => (
+                inclusion::generate_keys(&prover_client),
+                epoch_change::generate_keys(&prover_client),
+                aggregation::generate_keys(&prover_client),
+            ),
+        };
+    info!(
+        "Inclusion program verifying key hash: {}",
+        verifying_key_hash(&inclusion_vk)
+    );
+    info!(
+        "Epoch change program verifying key hash: {}",
+        verifying_key_hash(&epoch_vk)
+    );
+    info!(
+        "Aggregation program verifying key hash: {}",
+        verifying_key_hash(&aggregation_vk)
+    );
 
     let state = ServerState {
         prover_client,
@@ -103,11 +221,22 @@ async fn main() -> Result<()> {
         inclusion_vk: Arc::new(inclusion_vk),
         epoch_pk: Arc::new(epoch_pk),
         epoch_vk: Arc::new(epoch_vk),
-        snd_addr: Arc::new(snd_addr),
+        aggregation_pk: Arc::new(aggregation_pk),
+        aggregation_vk: Arc::new(aggregation_vk),
+        secondaries: Arc::new(SecondaryPool::new(snd_addr, secondary_credentials)),
         mode,
         active_requests: Arc::new(AtomicUsize::new(0)),
+        jobs: Arc::new(JobQueue::open(jobs_dir, job_queue_size, job_history_size)?),
+        authenticator: Arc::new(authenticator),
+        max_body_size,
+        proving_timeout: proving_timeout.map(Duration::from_secs),
+        proof_cache: Arc::new(ProofCache::open(proof_cache_dir, proof_cache_size)?),
     };
 
+    for _ in 0..job_workers {
+        tokio::spawn(run_jobs(state.clone()));
+    }
+
     let app = Router::new()
         .route("/health", get(health_check))
         .route("/ready", get(ready_check))
@@ -115,6 +244,23 @@ async fn main() -> Result<()> {
         .route("/epoch/proof", post(epoch_proof))
         .route("/epoch/verify", post(epoch_verify))
         .route("/inclusion/verify", post(inclusion_verify))
+        .route("/aggregation/proof", post(aggregation_proof))
+        .route("/aggregation/verify", post(aggregation_verify))
+        .route("/jobs", post(submit_job))
+        .route("/jobs/:id", get(job_status).delete(cancel_job))
+        .route("/jobs/:id/proof", get(job_proof))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            proving_timeout_middleware,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            body_size_middleware,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            authentication_middleware,
+        ))
         .layer(axum::middleware::from_fn_with_state(
             state.clone(),
             count_requests_middleware,
@@ -147,7 +293,7 @@ async fn inclusion_proof(
     State(state): State<ServerState>,
     request: axum::extract::Request,
 ) -> Result<impl IntoResponse, StatusCode> {
-    let bytes = axum::body::to_bytes(request.into_body(), usize::MAX)
+    let bytes = axum::body::to_bytes(request.into_body(), state.max_body_size)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
@@ -160,54 +306,16 @@ async fn inclusion_proof(
 
     let request = res.unwrap();
 
-    let Request::ProveInclusion(boxed) = request else {
+    if !matches!(request, Request::ProveInclusion(_)) {
         error!("Invalid request type");
         return Err(StatusCode::BAD_REQUEST);
-    };
-    let res = {
-        info!("Start proving");
-
-        let (proof_type, inclusion_data) = boxed.as_ref();
-        let InclusionData {
-            sparse_merkle_proof_assets,
-            transaction_proof_assets,
-            validator_verifier_assets,
-        } = inclusion_data;
-        let stdin = inclusion::generate_stdin(
-            sparse_merkle_proof_assets,
-            transaction_proof_assets,
-            validator_verifier_assets,
-        );
-
-        let prover_client = state.prover_client.clone();
-        let pk = state.inclusion_pk.clone();
-
-        let proof_handle = if proof_type == &ProvingMode::SNARK {
-            spawn_blocking(move || prover_client.prove(&pk, stdin).plonk().run())
-        } else {
-            spawn_blocking(move || prover_client.prove(&pk, stdin).run())
-        };
-
-        let proof = proof_handle
-            .await
-            .map_err(|_| {
-                error!("Failed to handle generate inclusion proof task");
-                StatusCode::INTERNAL_SERVER_ERROR
-            })?
-            .map_err(|err| {
-                error!("Failed to generate inclusion proof: {err}");
-                StatusCode::INTERNAL_SERVER_ERROR
-            })?;
-        info!("Proof generated. Serializing");
-        bcs::to_bytes(&proof).map_err(|err| {
-            error!("Failed to serialize epoch change proof: {err}");
-            StatusCode::INTERNAL_SERVER_ERROR
-        })
-    }?;
+    }
+    let (res, cache_status) = prove_cached(&state, request, bytes.to_vec()).await?;
 
     let response = Response::builder()
         .status(StatusCode::OK)
         .header(CONTENT_TYPE, "application/octet-stream")
+        .header(PROOF_CACHE_HEADER, cache_status.as_str())
         .body(Body::from(res))
         .map_err(|err| {
             error!("Could not construct response for client: {err}");
@@ -217,11 +325,60 @@ async fn inclusion_proof(
     Ok(response)
 }
 
+async fn generate_inclusion_proof(
+    state: &ServerState,
+    (proof_type, inclusion_data): (ProvingMode, InclusionData),
+) -> Result<Vec<u8>, StatusCode> {
+    info!("Start proving");
+
+    let InclusionData {
+        sparse_merkle_proof_assets,
+        transaction_proof_assets,
+        validator_verifier_assets,
+        commit_status,
+        decode_resource,
+        max_age,
+    } = inclusion_data;
+    let stdin = inclusion::generate_stdin(
+        &sparse_merkle_proof_assets,
+        &transaction_proof_assets,
+        &validator_verifier_assets,
+        commit_status,
+        decode_resource,
+        max_age,
+    );
+
+    let prover_client = state.prover_client.clone();
+    let pk = state.inclusion_pk.clone();
+
+    let proof_handle = if proof_type == ProvingMode::SNARK {
+        spawn_blocking(move || prover_client.prove(&pk, stdin).plonk().run())
+    } else {
+        spawn_blocking(move || prover_client.prove(&pk, stdin).run())
+    };
+
+    let proof = proof_handle
+        .await
+        .map_err(|_| {
+            error!("Failed to handle generate inclusion proof task");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .map_err(|err| {
+            error!("Failed to generate inclusion proof: {err}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    info!("Proof generated. Serializing");
+    bcs::to_bytes(&proof).map_err(|err| {
+        error!("Failed to serialize epoch change proof: {err}");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
 async fn inclusion_verify(
     State(state): State<ServerState>,
     request: axum::extract::Request,
 ) -> Result<impl IntoResponse, StatusCode> {
-    let bytes = axum::body::to_bytes(request.into_body(), usize::MAX)
+    let bytes = axum::body::to_bytes(request.into_body(), state.max_body_size)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
@@ -270,7 +427,7 @@ async fn epoch_proof(
     State(state): State<ServerState>,
     request: axum::extract::Request,
 ) -> Result<impl IntoResponse, StatusCode> {
-    let bytes = axum::body::to_bytes(request.into_body(), usize::MAX)
+    let bytes = axum::body::to_bytes(request.into_body(), state.max_body_size)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
@@ -283,58 +440,16 @@ async fn epoch_proof(
 
     let request = res.unwrap();
 
-    let Request::ProveEpochChange(boxed) = request else {
+    if !matches!(request, Request::ProveEpochChange(_)) {
         error!("Invalid request type");
         return Err(StatusCode::BAD_REQUEST);
-    };
-    let res = {
-        match state.mode {
-            Mode::Single => {
-                let (proof_type, epoch_change_data) = boxed.as_ref();
-
-                let EpochChangeData {
-                    trusted_state,
-                    epoch_change_proof,
-                } = epoch_change_data;
-
-                let stdin = epoch_change::generate_stdin(trusted_state, epoch_change_proof);
-                info!("Start proving epoch change");
-
-                let prover_client = state.prover_client.clone();
-                let pk = state.epoch_pk.clone();
-
-                let proof_handle = if proof_type == &ProvingMode::SNARK {
-                    spawn_blocking(move || prover_client.prove(&pk, stdin).plonk().run())
-                } else {
-                    spawn_blocking(move || prover_client.prove(&pk, stdin).run())
-                };
-                let proof = proof_handle
-                    .await
-                    .map_err(|_| {
-                        error!("Failed to handle generate epoch change proof task");
-                        StatusCode::INTERNAL_SERVER_ERROR
-                    })?
-                    .map_err(|err| {
-                        error!("Failed to generate epoch change proof: {err}");
-                        StatusCode::INTERNAL_SERVER_ERROR
-                    })?;
-
-                info!("Epoch change proof generated. Serializing");
-                bcs::to_bytes(&proof).map_err(|err| {
-                    error!("Failed to serialize epoch change proof: {err}");
-                    StatusCode::INTERNAL_SERVER_ERROR
-                })
-            }
-            Mode::Split => {
-                let snd_addr = state.snd_addr.as_ref().clone().unwrap();
-                forward_request(bytes.to_vec(), &snd_addr).await
-            }
-        }
-    }?;
+    }
+    let (res, cache_status) = prove_cached(&state, request, bytes.to_vec()).await?;
 
     let response = Response::builder()
         .status(StatusCode::OK)
         .header(CONTENT_TYPE, "application/octet-stream")
+        .header(PROOF_CACHE_HEADER, cache_status.as_str())
         .body(Body::from(res))
         .map_err(|err| {
             error!("Could not construct response for client: {err}");
@@ -344,13 +459,67 @@ async fn epoch_proof(
     Ok(response)
 }
 
+async fn generate_epoch_change_proof(
+    state: &ServerState,
+    (proof_type, epoch_change_data): (ProvingMode, EpochChangeData),
+    request_bytes: Vec<u8>,
+) -> Result<Vec<u8>, StatusCode> {
+    if state.mode == Mode::Split {
+        match state
+            .secondaries
+            .forward(request_bytes, "epoch/proof")
+            .await
+        {
+            Ok(proof) => return Ok(proof),
+            Err(err @ SecondaryError::Unauthorized { .. }) => {
+                error!("{err}, check the credentials of the primary server");
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+            Err(err) => warn!("{err}, proving the epoch change locally"),
+        }
+    }
+
+    let EpochChangeData {
+        trusted_state,
+        epoch_change_proof,
+    } = epoch_change_data;
+
+    let stdin = epoch_change::generate_stdin(&trusted_state, &epoch_change_proof);
+    info!("Start proving epoch change");
+
+    let prover_client = state.prover_client.clone();
+    let pk = state.epoch_pk.clone();
+
+    let proof_handle = if proof_type == ProvingMode::SNARK {
+        spawn_blocking(move || prover_client.prove(&pk, stdin).plonk().run())
+    } else {
+        spawn_blocking(move || prover_client.prove(&pk, stdin).run())
+    };
+    let proof = proof_handle
+        .await
+        .map_err(|_| {
+            error!("Failed to handle generate epoch change proof task");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .map_err(|err| {
+            error!("Failed to generate epoch change proof: {err}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    info!("Epoch change proof generated. Serializing");
+    bcs::to_bytes(&proof).map_err(|err| {
+        error!("Failed to serialize epoch change proof: {err}");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
 async fn epoch_verify(
     State(state): State<ServerState>,
     request: axum::extract::Request,
 ) -> Result<impl IntoResponse, StatusCode> {
     info!("Start verifying epoch change proof");
 
-    let bytes = axum::body::to_bytes(request.into_body(), usize::MAX)
+    let bytes = axum::body::to_bytes(request.into_body(), state.max_body_size)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
@@ -390,32 +559,431 @@ async fn epoch_verify(
     Ok(response)
 }
 
-async fn forward_request(
-    secondary_request_bytes: Vec<u8>,
-    snd_addr: &str,
-) -> Result<Vec<u8>, StatusCode> {
-    info!("Connecting to the secondary server");
-    let client = reqwest::Client::new();
-    info!("Sending secondary request");
-    let res_bytes = client
-        .post(format!("http://{}/epoch/proof", snd_addr))
-        .body(secondary_request_bytes)
-        .send()
+async fn aggregation_proof(
+    State(state): State<ServerState>,
+    request: axum::extract::Request,
+) -> Result<impl IntoResponse, StatusCode> {
+    let bytes = axum::body::to_bytes(request.into_body(), state.max_body_size)
         .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let res = bcs::from_bytes::<Request>(&bytes);
+
+    if let Err(err) = res {
+        error!("Failed to deserialize request object: {err}");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let request = res.unwrap();
+
+    if !matches!(request, Request::ProveAggregation(_)) {
+        error!("Invalid request type");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let (res, cache_status) = prove_cached(&state, request, bytes.to_vec()).await?;
+
+    let response = Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, "application/octet-stream")
+        .header(PROOF_CACHE_HEADER, cache_status.as_str())
+        .body(Body::from(res))
         .map_err(|err| {
-            error!("Failed to send request to secondary server: {err}");
+            error!("Could not construct response for client: {err}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(response)
+}
+
+async fn generate_aggregation_proof(
+    state: &ServerState,
+    aggregation_data: AggregationData,
+    request_bytes: Vec<u8>,
+) -> Result<Vec<u8>, StatusCode> {
+    if state.mode == Mode::Split {
+        match state
+            .secondaries
+            .forward(request_bytes, "aggregation/proof")
+            .await
+        {
+            Ok(proof) => return Ok(proof),
+            Err(err @ SecondaryError::Unauthorized { .. }) => {
+                error!("{err}, check the credentials of the primary server");
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+            Err(err) => warn!("{err}, proving the aggregation locally"),
+        }
+    }
+
+    let AggregationData {
+        epoch_change_data,
+        inclusion_data,
+    } = aggregation_data;
+    let EpochChangeData {
+        trusted_state,
+        epoch_change_proof,
+    } = epoch_change_data;
+    let InclusionData {
+        sparse_merkle_proof_assets,
+        transaction_proof_assets,
+        validator_verifier_assets,
+        commit_status,
+        decode_resource,
+        max_age,
+    } = inclusion_data;
+
+    let epoch_change_stdin = epoch_change::generate_stdin(&trusted_state, &epoch_change_proof);
+    let inclusion_stdin = inclusion::generate_stdin(
+        &sparse_merkle_proof_assets,
+        &transaction_proof_assets,
+        &validator_verifier_assets,
+        commit_status,
+        decode_resource,
+        max_age,
+    );
+    info!("Start proving aggregation");
+
+    let prover_client = state.prover_client.clone();
+    let (epoch_pk, epoch_vk) = (state.epoch_pk.clone(), state.epoch_vk.clone());
+    let (inclusion_pk, inclusion_vk) = (state.inclusion_pk.clone(), state.inclusion_vk.clone());
+    let aggregation_pk = state.aggregation_pk.clone();
+
+    // Both proofs are compressed to be verified by the aggregation program, which
+    // is then wrapped in a PLONK proof
+    let proof_handle = spawn_blocking(move || -> Result<SphinxProofWithPublicValues> {
+        let epoch_change_proof = prover_client
+            .prove(&epoch_pk, epoch_change_stdin)
+            .compressed()
+            .run()?;
+        let inclusion_proof = prover_client
+            .prove(&inclusion_pk, inclusion_stdin)
+            .compressed()
+            .run()?;
+        let stdin = aggregation::generate_stdin(
+            &epoch_change_proof,
+            &epoch_vk,
+            &inclusion_proof,
+            &inclusion_vk,
+        )?;
+
+        prover_client.prove(&aggregation_pk, stdin).plonk().run()
+    });
+    let proof = proof_handle
+        .await
+        .map_err(|_| {
+            error!("Failed to handle generate aggregation proof task");
             StatusCode::INTERNAL_SERVER_ERROR
         })?
-        .bytes()
+        .map_err(|err| {
+            error!("Failed to generate aggregation proof: {err}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    info!("Aggregation proof generated. Serializing");
+    bcs::to_bytes(&proof).map_err(|err| {
+        error!("Failed to serialize aggregation proof: {err}");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+async fn aggregation_verify(
+    State(state): State<ServerState>,
+    request: axum::extract::Request,
+) -> Result<impl IntoResponse, StatusCode> {
+    info!("Start verifying aggregation proof");
+
+    let bytes = axum::body::to_bytes(request.into_body(), state.max_body_size)
         .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let res = bcs::from_bytes::<Request>(&bytes);
+
+    if let Err(err) = res {
+        error!("Failed to deserialize request object: {err}");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let request = res.unwrap();
+
+    let Request::VerifyAggregation(proof) = request else {
+        error!("Invalid request type");
+        return Err(StatusCode::BAD_REQUEST);
+    };
+    let res = {
+        let is_valid = state
+            .prover_client
+            .verify(&proof, &state.aggregation_vk)
+            .is_ok();
+
+        info!("Aggregation verification result: {}", is_valid);
+
+        bcs::to_bytes(&is_valid).map_err(|_| {
+            error!("Failed to serialize aggregation verification result");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+    }?;
+
+    let response = Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, "application/octet-stream")
+        .body(Body::from(res))
         .map_err(|err| {
-            error!("Failed to receive response from secondary server: {err}");
+            error!("Could not construct response for client: {err}");
             StatusCode::INTERNAL_SERVER_ERROR
         })?;
 
-    info!("Response received. Sending it to the client");
+    Ok(response)
+}
+
+async fn submit_job(
+    State(state): State<ServerState>,
+    key_id: Option<Extension<ApiKeyId>>,
+    request: axum::extract::Request,
+) -> Result<impl IntoResponse, StatusCode> {
+    let bytes = axum::body::to_bytes(request.into_body(), state.max_body_size)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let res = bcs::from_bytes::<Request>(&bytes);
+
+    if let Err(err) = res {
+        error!("Failed to deserialize request object: {err}");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let request = res.unwrap();
+
+    if !matches!(
+        request,
+        Request::ProveInclusion(_) | Request::ProveEpochChange(_) | Request::ProveAggregation(_)
+    ) {
+        error!("Invalid request type for a job: {}", &request);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let job_id = state
+        .jobs
+        .submit(owner(&key_id), &bytes)
+        .map_err(job_error_status)?;
+    info!("Job {job_id} submitted for {}", &request);
+
+    octet_stream_response(&job_id)
+}
+
+async fn job_status(
+    State(state): State<ServerState>,
+    key_id: Option<Extension<ApiKeyId>>,
+    Path(job_id): Path<JobId>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let status = state
+        .jobs
+        .status(owner(&key_id), job_id)
+        .map_err(job_error_status)?;
+
+    octet_stream_response(&status)
+}
+
+async fn job_proof(
+    State(state): State<ServerState>,
+    key_id: Option<Extension<ApiKeyId>>,
+    Path(job_id): Path<JobId>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let Some(proof) = state
+        .jobs
+        .proof(owner(&key_id), job_id)
+        .map_err(job_error_status)?
+    else {
+        return Err(StatusCode::CONFLICT);
+    };
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, "application/octet-stream")
+        .body(Body::from(proof))
+        .map_err(|err| {
+            error!("Could not construct response for client: {err}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+async fn cancel_job(
+    State(state): State<ServerState>,
+    key_id: Option<Extension<ApiKeyId>>,
+    Path(job_id): Path<JobId>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let status = state
+        .jobs
+        .cancel(owner(&key_id), job_id)
+        .map_err(job_error_status)?;
+    info!("Job {job_id} cancellation requested, status: {status}");
+
+    octet_stream_response(&status)
+}
+
+/// Returns the owner of the jobs of a request, that is the identifier of its
+/// API key if requests are authenticated.
+fn owner(key_id: &Option<Extension<ApiKeyId>>) -> Option<&str> {
+    key_id
+        .as_ref()
+        .map(|Extension(ApiKeyId(key_id))| key_id.as_str())
+}
+
+/// Processes the queued jobs, one at a time.
+async fn run_jobs(state: ServerState) {
+    loop {
+        let (job_id, request_bytes) = state.jobs.next().await;
+        info!("Start processing job {job_id}");
+
+        // A running job counts as an active request, so that the server is not
+        // reported ready while it is proving
+        state.active_requests.fetch_add(1, Ordering::SeqCst);
+        run_job(&state, job_id, request_bytes).await;
+        state.active_requests.fetch_sub(1, Ordering::SeqCst);
+
+        info!("Job {job_id} processed");
+    }
+}
+
+/// Generates the proof of a job and stores its result.
+async fn run_job(state: &ServerState, job_id: JobId, request_bytes: Vec<u8>) {
+    let mut proving = std::pin::pin!(prove_request(state, request_bytes));
+    let result = match state.proving_timeout {
+        Some(proving_timeout) => {
+            match tokio::time::timeout(proving_timeout, proving.as_mut()).await {
+                Ok(result) => result,
+                Err(_) => {
+                    error!("Job {job_id} timed out");
+                    if let Err(err) = state
+                        .jobs
+                        .finish(job_id, Err("Proof generation timed out".to_string()))
+                    {
+                        error!("Failed to store the result of job {job_id}: {err}");
+                    }
+                    // The proof generation cannot be interrupted, so the worker waits for
+                    // it before taking the next job. Its result is discarded.
+                    let _ = proving.await;
+                    return;
+                }
+            }
+        }
+        None => proving.await,
+    }
+    .map_err(|status| format!("Proof generation failed with status {status}"));
+
+    if let Err(err) = state.jobs.finish(job_id, result) {
+        error!("Failed to store the result of job {job_id}: {err}");
+    }
+}
+
+async fn prove_request(state: &ServerState, request_bytes: Vec<u8>) -> Result<Vec<u8>, StatusCode> {
+    let request = bcs::from_bytes::<Request>(&request_bytes).map_err(|err| {
+        error!("Failed to deserialize request object: {err}");
+        StatusCode::BAD_REQUEST
+    })?;
+
+    let (proof, cache_status) = prove_cached(state, request, request_bytes).await?;
+    if cache_status != CacheStatus::Miss {
+        info!("Proof served by the cache: {}", cache_status.as_str());
+    }
+
+    Ok(proof)
+}
+
+/// Generates the proof of a proving request, unless it is already cached or
+/// being generated for an identical request.
+async fn prove_cached(
+    state: &ServerState,
+    request: Request,
+    request_bytes: Vec<u8>,
+) -> Result<(Vec<u8>, CacheStatus), StatusCode> {
+    match request {
+        Request::ProveInclusion(boxed) => {
+            let (proof_type, inclusion_data) = *boxed;
+            let key = cache_key(
+                &[aptos_programs::INCLUSION_PROGRAM],
+                proof_type,
+                &serialize_inputs(&inclusion_data)?,
+            );
+            state
+                .proof_cache
+                .get_or_prove(&key, || {
+                    generate_inclusion_proof(state, (proof_type, inclusion_data))
+                })
+                .await
+        }
+        Request::ProveEpochChange(boxed) => {
+            let (proof_type, epoch_change_data) = *boxed;
+            let key = cache_key(
+                &[aptos_programs::EPOCH_CHANGE_PROGRAM],
+                proof_type,
+                &serialize_inputs(&epoch_change_data)?,
+            );
+            state
+                .proof_cache
+                .get_or_prove(&key, || {
+                    generate_epoch_change_proof(
+                        state,
+                        (proof_type, epoch_change_data),
+                        request_bytes,
+                    )
+                })
+                .await
+        }
+        Request::ProveAggregation(boxed) => {
+            // Aggregation proofs are always wrapped in a PLONK proof
+            let key = cache_key(
+                &[
+                    aptos_programs::EPOCH_CHANGE_PROGRAM,
+                    aptos_programs::INCLUSION_PROGRAM,
+                    aptos_programs::AGGREGATION_PROGRAM,
+                ],
+                ProvingMode::SNARK,
+                &serialize_inputs(&boxed)?,
+            );
+            state
+                .proof_cache
+                .get_or_prove(&key, || {
+                    generate_aggregation_proof(state, *boxed, request_bytes)
+                })
+                .await
+        }
+        _ => {
+            error!("Invalid request type for a proof");
+            Err(StatusCode::BAD_REQUEST)
+        }
+    }
+}
+
+fn serialize_inputs<T: serde::Serialize>(inputs: &T) -> Result<Vec<u8>, StatusCode> {
+    bcs::to_bytes(inputs).map_err(|err| {
+        error!("Failed to serialize proof inputs: {err}");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
 
-    Ok(res_bytes.to_vec())
+fn job_error_status(err: JobError) -> StatusCode {
+    error!("Job error: {err}");
+    match err {
+        JobError::NotFound(_) => StatusCode::NOT_FOUND,
+        JobError::QueueFull(_) => StatusCode::SERVICE_UNAVAILABLE,
+        JobError::Io { .. } | JobError::Serialization { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+fn octet_stream_response<T: serde::Serialize>(value: &T) -> Result<Response<Body>, StatusCode> {
+    let bytes = bcs::to_bytes(value).map_err(|err| {
+        error!("Failed to serialize response: {err}");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, "application/octet-stream")
+        .body(Body::from(bytes))
+        .map_err(|err| {
+            error!("Could not construct response for client: {err}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
 }
 
 async fn count_requests_middleware(
@@ -440,3 +1008,148 @@ async fn count_requests_middleware(
 
     Ok(response)
 }
+
+async fn body_size_middleware(
+    State(state): State<ServerState>,
+    req: axum::http::Request<Body>,
+    next: Next,
+) -> Result<impl IntoResponse, StatusCode> {
+    let (parts, body) = req.into_parts();
+
+    // Buffer the body so that its size is checked before reaching the handlers, whatever the
+    // announced content length.
+    let bytes = buffer_body(&parts, body, state.max_body_size).await?;
+
+    Ok(next
+        .run(axum::http::Request::from_parts(parts, Body::from(bytes)))
+        .await)
+}
+
+async fn authentication_middleware(
+    State(state): State<ServerState>,
+    req: axum::http::Request<Body>,
+    next: Next,
+) -> Result<impl IntoResponse, StatusCode> {
+    let Some(authenticator) = state.authenticator.as_ref() else {
+        return Ok(next.run(req).await);
+    };
+    // Probes are left open to the orchestration of the server.
+    if matches!(req.uri().path(), "/health" | "/ready") {
+        return Ok(next.run(req).await);
+    }
+
+    // The headers are checked first, so that the body of unauthenticated requests is never read.
+    let (mut parts, body) = req.into_parts();
+    let key_id = authenticator
+        .authenticate(&parts.headers)
+        .map_err(auth_error_status)?;
+    let body = if authenticator.mode() == AuthMode::Hmac {
+        let bytes = buffer_body(&parts, body, state.max_body_size).await?;
+        let path_and_query = parts
+            .uri
+            .path_and_query()
+            .map_or(parts.uri.path(), |path_and_query| path_and_query.as_str());
+        authenticator
+            .verify_signature(
+                &key_id,
+                parts.method.as_str(),
+                path_and_query,
+                &parts.headers,
+                &bytes,
+            )
+            .map_err(auth_error_status)?;
+        Body::from(bytes)
+    } else {
+        body
+    };
+    if is_proving_request(&parts.method, parts.uri.path()) {
+        authenticator
+            .check_rate_limit(&key_id)
+            .map_err(auth_error_status)?;
+    }
+
+    parts.extensions.insert(ApiKeyId(key_id));
+
+    Ok(next.run(axum::http::Request::from_parts(parts, body)).await)
+}
+
+/// Buffers the body of a request, rejecting it as soon as it exceeds the
+/// maximum size, or right away if its announced content length does.
+async fn buffer_body(
+    parts: &axum::http::request::Parts,
+    body: Body,
+    max_body_size: usize,
+) -> Result<axum::body::Bytes, StatusCode> {
+    let content_length = parts
+        .headers
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<usize>().ok());
+    if content_length.is_some_and(|content_length| content_length > max_body_size) {
+        error!("Rejected request body for {}: too large", parts.uri.path());
+        return Err(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    axum::body::to_bytes(body, max_body_size)
+        .await
+        .map_err(|err| {
+            error!("Rejected request body for {}: {err}", parts.uri.path());
+            StatusCode::PAYLOAD_TOO_LARGE
+        })
+}
+
+async fn proving_timeout_middleware(
+    State(state): State<ServerState>,
+    req: axum::http::Request<Body>,
+    next: Next,
+) -> Result<impl IntoResponse, StatusCode> {
+    let Some(proving_timeout) = state.proving_timeout else {
+        return Ok(next.run(req).await);
+    };
+    // Jobs are submitted right away, their proving time is bounded by the workers.
+    if req.uri().path() == "/jobs" || !is_proving_request(req.method(), req.uri().path()) {
+        return Ok(next.run(req).await);
+    }
+
+    let path = req.uri().path().to_string();
+    let mut response = tokio::spawn(next.run(req));
+    match tokio::time::timeout(proving_timeout, &mut response).await {
+        Ok(response) => response.map_err(|err| {
+            error!("Failed to handle request to {path}: {err}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }),
+        Err(_) => {
+            error!("Request to {path} timed out");
+            // The proof generation cannot be interrupted and keeps running in the background.
+            // It is counted as an active request until it completes, so that the server is not
+            // reported ready while still proving.
+            state.active_requests.fetch_add(1, Ordering::SeqCst);
+            let active_requests = state.active_requests.clone();
+            tokio::spawn(async move {
+                let _ = response.await;
+                active_requests.fetch_sub(1, Ordering::SeqCst);
+            });
+
+            Err(StatusCode::GATEWAY_TIMEOUT)
+        }
+    }
+}
+
+fn is_proving_request(method: &axum::http::Method, path: &str) -> bool {
+    method == axum::http::Method::POST && (path.ends_with("/proof") || path == "/jobs")
+}
+
+fn auth_error_status(err: AuthError) -> StatusCode {
+    error!("Rejected request: {err}");
+    match err {
+        AuthError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+        AuthError::MissingCredentials(_)
+        | AuthError::UnknownKey(_)
+        | AuthError::InvalidCredentials(_)
+        | AuthError::StaleTimestamp(_)
+        | AuthError::ReplayedRequest(_) => StatusCode::UNAUTHORIZED,
+        AuthError::KeysFile { .. }
+        | AuthError::InvalidKeysEntry { .. }
+        | AuthError::InvalidAuthMode(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
@@ -0,0 +1,541 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! # Cache
+//!
+//! Content-addressed cache of the proofs generated by the proof server. Proofs
+//! are keyed by a hash of the programs they are generated for, of their
+//! proving mode and of their serialized inputs. Concurrent requests for the
+//! same key share a single proof generation, along with its failure, and
+//! generated proofs are stored on disk, the least recently used ones being
+//! evicted once the cache is full.
+
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::SystemTime;
+
+use aptos_lc_core::crypto::hash::{hash_data, HASH_LENGTH};
+use log::{info, warn};
+use tokio::sync::OnceCell;
+
+use crate::error::CacheError;
+use crate::types::proof_server::ProvingMode;
+
+/// Response header reporting how the cache served a proof.
+pub const PROOF_CACHE_HEADER: &str = "x-proof-cache";
+
+/// Extension of the files holding the cached proofs.
+const PROOF_FILE_EXTENSION: &str = "proof";
+
+/// Key of a proof in the cache.
+pub type CacheKey = [u8; HASH_LENGTH];
+
+/// How a proof was served by the cache.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CacheStatus {
+    /// The proof was already stored in the cache.
+    Hit,
+    /// The proof was generated for a concurrent identical request.
+    Shared,
+    /// The proof was generated for this request.
+    Miss,
+}
+
+impl CacheStatus {
+    /// Returns the value reported for the status in the `x-proof-cache` header.
+    ///
+    /// # Returns
+    ///
+    /// The value of the header.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            CacheStatus::Hit => "hit",
+            CacheStatus::Shared => "shared",
+            CacheStatus::Miss => "miss",
+        }
+    }
+}
+
+/// Computes the cache key of a proof.
+///
+/// # Arguments
+///
+/// * `programs` - The ELFs of the programs the proof is generated for.
+/// * `mode` - The proving mode of the proof.
+/// * `inputs` - The serialized inputs of the proof.
+///
+/// # Returns
+///
+/// The cache key of the proof.
+pub fn cache_key(programs: &[&[u8]], mode: ProvingMode, inputs: &[u8]) -> CacheKey {
+    let program_hashes: Vec<CacheKey> = programs
+        .iter()
+        .map(|program| hash_data(&[], vec![*program]))
+        .collect();
+    let mode = [mode.to_bytes()];
+
+    let mut data: Vec<&[u8]> = program_hashes.iter().map(|hash| hash.as_slice()).collect();
+    data.push(&mode);
+    data.push(inputs);
+
+    hash_data(&[], data)
+}
+
+/// Persisted cache of proofs, deduplicating concurrent proof generations.
+/// `E` is the error type of the proof generations, shared with the requests
+/// waiting for them.
+#[derive(Debug)]
+pub struct ProofCache<E> {
+    dir: PathBuf,
+    capacity: usize,
+    state: Mutex<CacheState<E>>,
+}
+
+/// Outcome of a proof generation, shared by the requests waiting for it.
+type Generation<E> = Arc<OnceCell<Result<Vec<u8>, E>>>;
+
+#[derive(Debug)]
+struct CacheState<E> {
+    /// Keys of the stored proofs, from the least to the most recently used.
+    entries: VecDeque<CacheKey>,
+    /// Proof generations in progress.
+    in_flight: HashMap<CacheKey, Generation<E>>,
+}
+
+enum Lookup<E> {
+    Stored,
+    InFlight(Generation<E>),
+}
+
+impl<E: Clone> ProofCache<E> {
+    /// Opens the proof cache persisted in a directory, creating the directory if needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory the proofs are stored in.
+    /// * `capacity` - The maximum number of stored proofs.
+    ///
+    /// # Returns
+    ///
+    /// The `ProofCache`, with the proofs already stored in the directory.
+    pub fn open(dir: impl Into<PathBuf>, capacity: usize) -> Result<Self, CacheError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).map_err(|err| CacheError::Io {
+            path: dir.display().to_string(),
+            source: err.into(),
+        })?;
+
+        let cache = Self {
+            dir,
+            capacity,
+            state: Mutex::new(CacheState {
+                entries: VecDeque::new(),
+                in_flight: HashMap::new(),
+            }),
+        };
+        cache.restore()?;
+
+        Ok(cache)
+    }
+
+    /// Returns the proof stored for a key, or generates it. Concurrent calls
+    /// for the same key share the outcome of the generation started by one of
+    /// them: if it fails, they all fail with its error rather than retrying
+    /// one after the other.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The cache key of the proof.
+    /// * `prove` - The generation of the proof, called if it is neither
+    ///   stored nor being generated.
+    ///
+    /// # Returns
+    ///
+    /// The serialized proof, along with how it was served.
+    pub async fn get_or_prove<Fut>(
+        &self,
+        key: &CacheKey,
+        prove: impl FnOnce() -> Fut,
+    ) -> Result<(Vec<u8>, CacheStatus), E>
+    where
+        Fut: Future<Output = Result<Vec<u8>, E>>,
+    {
+        loop {
+            let cell = match self.lookup(key) {
+                Lookup::Stored => match self.read(key) {
+                    Ok(proof) => return Ok((proof, CacheStatus::Hit)),
+                    Err(err) => {
+                        warn!("Dropping unreadable cached proof: {err}");
+                        self.lock().entries.retain(|entry| entry != key);
+                        continue;
+                    }
+                },
+                Lookup::InFlight(cell) => cell,
+            };
+
+            // The generation is only run by one of the sharers, unless it is dropped
+            // before completing, in which case the next sharer runs its own
+            let mut generated = false;
+            let generated_ref = &mut generated;
+            let result = cell
+                .get_or_init(|| async move {
+                    *generated_ref = true;
+                    let result = prove().await;
+                    if let Ok(proof) = &result {
+                        self.store(key, proof);
+                    }
+                    result
+                })
+                .await
+                .clone();
+
+            // The proof is stored before its generation is forgotten, so that later
+            // requests find it in either of them. A failed generation is forgotten
+            // as well, later requests starting a new one.
+            if generated {
+                let mut state = self.lock();
+                if state
+                    .in_flight
+                    .get(key)
+                    .is_some_and(|in_flight| Arc::ptr_eq(in_flight, &cell))
+                {
+                    state.in_flight.remove(key);
+                }
+            }
+
+            let status = if generated {
+                CacheStatus::Miss
+            } else {
+                CacheStatus::Shared
+            };
+            return result.map(|proof| (proof, status));
+        }
+    }
+
+    /// Finds a key among the stored proofs, marking it as the most recently
+    /// used, or among the proof generations in progress, registering a new
+    /// generation if there is none.
+    fn lookup(&self, key: &CacheKey) -> Lookup<E> {
+        let mut state = self.lock();
+        if let Some(position) = state.entries.iter().position(|entry| entry == key) {
+            state.entries.remove(position);
+            state.entries.push_back(*key);
+            return Lookup::Stored;
+        }
+
+        Lookup::InFlight(state.in_flight.entry(*key).or_default().clone())
+    }
+
+    fn read(&self, key: &CacheKey) -> Result<Vec<u8>, CacheError> {
+        let path = self.path(key);
+        let proof = fs::read(&path).map_err(|err| CacheError::Io {
+            path: path.display().to_string(),
+            source: err.into(),
+        })?;
+
+        // Keep the modification time as the last use, to restore the eviction order
+        if let Err(err) = File::options()
+            .write(true)
+            .open(&path)
+            .and_then(|file| file.set_modified(SystemTime::now()))
+        {
+            warn!("Failed to update the last use of {}: {err}", path.display());
+        }
+
+        Ok(proof)
+    }
+
+    /// Stores a proof, evicting the least recently used proofs if the cache
+    /// is full. Failures are logged, the proof being generated again on the
+    /// next request.
+    fn store(&self, key: &CacheKey, proof: &[u8]) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let path = self.path(key);
+        if let Err(err) = write_file(&path, proof) {
+            warn!("Failed to store proof in the cache: {err}");
+            return;
+        }
+
+        let evicted: Vec<CacheKey> = {
+            let mut state = self.lock();
+            state.entries.retain(|entry| entry != key);
+            state.entries.push_back(*key);
+            let excess = state.entries.len().saturating_sub(self.capacity);
+            state.entries.drain(..excess).collect()
+        };
+        self.remove_files(&evicted);
+    }
+
+    /// Loads the keys of the proofs stored in the directory, ordered by last use.
+    fn restore(&self) -> Result<(), CacheError> {
+        let entries = fs::read_dir(&self.dir).map_err(|err| CacheError::Io {
+            path: self.dir.display().to_string(),
+            source: err.into(),
+        })?;
+
+        let mut stored = Vec::new();
+        for entry in entries {
+            let path = entry
+                .map_err(|err| CacheError::Io {
+                    path: self.dir.display().to_string(),
+                    source: err.into(),
+                })?
+                .path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(PROOF_FILE_EXTENSION) {
+                continue;
+            }
+            let Some(key) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| hex::decode(stem).ok())
+                .and_then(|bytes| CacheKey::try_from(bytes).ok())
+            else {
+                continue;
+            };
+            let last_use = fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+
+            stored.push((last_use, key));
+        }
+        stored.sort();
+
+        let excess = stored.len().saturating_sub(self.capacity);
+        let evicted: Vec<CacheKey> = stored.drain(..excess).map(|(_, key)| key).collect();
+        self.remove_files(&evicted);
+
+        let mut state = self.lock();
+        state.entries = stored.into_iter().map(|(_, key)| key).collect();
+        info!(
+            "Restored {} cached proofs from {}",
+            state.entries.len(),
+            self.dir.display()
+        );
+
+        Ok(())
+    }
+
+    fn remove_files(&self, keys: &[CacheKey]) {
+        for key in keys {
+            let path = self.path(key);
+            if let Err(err) = fs::remove_file(&path) {
+                warn!("Failed to evict {}: {err}", path.display());
+            }
+        }
+    }
+
+    fn path(&self, key: &CacheKey) -> PathBuf {
+        self.dir
+            .join(hex::encode(key))
+            .with_extension(PROOF_FILE_EXTENSION)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, CacheState<E>> {
+        // The cache state is always left consistent, so a poisoned lock can be recovered
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn write_file(path: &Path, bytes: &[u8]) -> Result<(), CacheError> {
+    // Write to a temporary file first so that a restart never loads partial proofs
+    let tmp_path = path.with_extension(format!("{PROOF_FILE_EXTENSION}.tmp"));
+    fs::write(&tmp_path, bytes)
+        .and_then(|_| fs::rename(&tmp_path, path))
+        .map_err(|err| CacheError::Io {
+            path: path.display().to_string(),
+            source: err.into(),
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use super::*;
+
+    fn cache_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn key(inputs: &[u8]) -> CacheKey {
+        cache_key(&[b"program"], ProvingMode::STARK, inputs)
+    }
+
+    /// Gets a proof from the cache, generating it as the inputs of its key.
+    async fn get(
+        cache: &ProofCache<String>,
+        inputs: &[u8],
+        generations: &AtomicUsize,
+    ) -> Result<(Vec<u8>, CacheStatus), String> {
+        cache
+            .get_or_prove(&key(inputs), || async {
+                generations.fetch_add(1, Ordering::SeqCst);
+                Ok(inputs.to_vec())
+            })
+            .await
+    }
+
+    #[test]
+    fn test_cache_key() {
+        assert_eq!(key(b"inputs"), key(b"inputs"));
+        assert_ne!(key(b"inputs"), key(b"other inputs"));
+        assert_ne!(
+            key(b"inputs"),
+            cache_key(&[b"program"], ProvingMode::SNARK, b"inputs")
+        );
+        assert_ne!(
+            key(b"inputs"),
+            cache_key(&[b"other program"], ProvingMode::STARK, b"inputs")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cache_hit() {
+        let dir = cache_dir("test_cache_hit");
+        let cache = ProofCache::open(&dir, 2).unwrap();
+        let generations = AtomicUsize::new(0);
+
+        let miss = get(&cache, b"proof", &generations).await.unwrap();
+        assert_eq!(miss, (b"proof".to_vec(), CacheStatus::Miss));
+        let hit = get(&cache, b"proof", &generations).await.unwrap();
+        assert_eq!(hit, (b"proof".to_vec(), CacheStatus::Hit));
+        assert_eq!(generations.load(Ordering::SeqCst), 1);
+
+        // An unreadable proof is generated again
+        fs::remove_file(cache.path(&key(b"proof"))).unwrap();
+        let miss = get(&cache, b"proof", &generations).await.unwrap();
+        assert_eq!(miss.1, CacheStatus::Miss);
+        assert_eq!(generations.load(Ordering::SeqCst), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_shared_generation() {
+        let dir = cache_dir("test_shared_generation");
+        let cache = ProofCache::open(&dir, 2).unwrap();
+        let generations = AtomicUsize::new(0);
+
+        let slow_generation = cache.get_or_prove(&key(b"proof"), || async {
+            generations.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Ok::<_, String>(b"proof".to_vec())
+        });
+        let (first, second) = tokio::join!(slow_generation, get(&cache, b"proof", &generations));
+
+        assert_eq!(first.unwrap(), (b"proof".to_vec(), CacheStatus::Miss));
+        assert_eq!(second.unwrap(), (b"proof".to_vec(), CacheStatus::Shared));
+        assert_eq!(generations.load(Ordering::SeqCst), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_shared_failure() {
+        let dir = cache_dir("test_shared_failure");
+        let cache = ProofCache::open(&dir, 2).unwrap();
+        let generations = AtomicUsize::new(0);
+
+        let failing_generation = cache.get_or_prove(&key(b"proof"), || async {
+            generations.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Err("failure".to_string())
+        });
+        let (first, second) = tokio::join!(failing_generation, get(&cache, b"proof", &generations));
+
+        // The waiting request fails along with the generation rather than retrying it
+        assert_eq!(first, Err("failure".to_string()));
+        assert_eq!(second, Err("failure".to_string()));
+        assert_eq!(generations.load(Ordering::SeqCst), 1);
+        assert!(!cache.path(&key(b"proof")).exists());
+
+        // Later requests start a new generation
+        let miss = get(&cache, b"proof", &generations).await.unwrap();
+        assert_eq!(miss.1, CacheStatus::Miss);
+        assert_eq!(generations.load(Ordering::SeqCst), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_eviction() {
+        let dir = cache_dir("test_eviction");
+        let cache = ProofCache::open(&dir, 2).unwrap();
+        let generations = AtomicUsize::new(0);
+
+        get(&cache, b"first", &generations).await.unwrap();
+        get(&cache, b"second", &generations).await.unwrap();
+        // Using the first proof makes the second one the least recently used
+        get(&cache, b"first", &generations).await.unwrap();
+        get(&cache, b"third", &generations).await.unwrap();
+
+        assert!(!cache.path(&key(b"second")).exists());
+        for inputs in [b"first".as_slice(), b"third".as_slice()] {
+            let hit = get(&cache, inputs, &generations).await.unwrap();
+            assert_eq!(hit.1, CacheStatus::Hit);
+        }
+        let miss = get(&cache, b"second", &generations).await.unwrap();
+        assert_eq!(miss.1, CacheStatus::Miss);
+
+        // A cache without capacity stores nothing
+        let empty_dir = cache_dir("test_eviction_empty");
+        let cache = ProofCache::open(&empty_dir, 0).unwrap();
+        get(&cache, b"first", &generations).await.unwrap();
+        let miss = get(&cache, b"first", &generations).await.unwrap();
+        assert_eq!(miss.1, CacheStatus::Miss);
+
+        fs::remove_dir_all(&dir).unwrap();
+        fs::remove_dir_all(&empty_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_restore() {
+        let dir = cache_dir("test_restore");
+        let generations = AtomicUsize::new(0);
+        {
+            let cache = ProofCache::<String>::open(&dir, 2).unwrap();
+            get(&cache, b"first", &generations).await.unwrap();
+            get(&cache, b"second", &generations).await.unwrap();
+
+            // The last use of a proof is restored from the modification time of its file
+            for (inputs, last_use) in [(b"first".as_slice(), 20), (b"second".as_slice(), 10)] {
+                File::options()
+                    .write(true)
+                    .open(cache.path(&key(inputs)))
+                    .unwrap()
+                    .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(last_use))
+                    .unwrap();
+            }
+        }
+
+        let cache = ProofCache::<String>::open(&dir, 2).unwrap();
+        let hit = get(&cache, b"second", &generations).await.unwrap();
+        assert_eq!(hit.1, CacheStatus::Hit);
+        // Reading a proof updates its last use
+        let last_use = fs::metadata(cache.path(&key(b"second")))
+            .and_then(|metadata| metadata.modified())
+            .unwrap();
+        assert!(last_use > SystemTime::UNIX_EPOCH + Duration::from_secs(20));
+        drop(cache);
+
+        // The least recently used proofs are evicted when the capacity shrinks
+        let cache = ProofCache::<String>::open(&dir, 1).unwrap();
+        assert!(!cache.path(&key(b"first")).exists());
+        let hit = get(&cache, b"second", &generations).await.unwrap();
+        assert_eq!(hit.1, CacheStatus::Hit);
+        assert_eq!(generations.load(Ordering::SeqCst), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,6 +1,7 @@
 // Copyright (c) Argument Computer Corporation
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::types::proof_server::JobId;
 use aptos_lc_core::crypto::hash::HashValue;
 use thiserror::Error;
 
@@ -42,4 +43,135 @@ pub enum ClientError {
         #[source]
         source: Box<dyn std::error::Error + Sync + Send>,
     },
+    #[error("Error with the client state file {path}: {source}")]
+    StateFile {
+        path: String,
+        #[source]
+        source: Box<dyn std::error::Error + Sync + Send>,
+    },
+    #[error(
+        "Refusing to move the client state backwards, from (epoch, version) {stored:?} to {position:?}"
+    )]
+    StateRollback {
+        stored: (u64, u64),
+        position: (u64, u64),
+    },
+    #[error(
+        "Refusing to move the last inclusion proof of {name} backwards, from version {stored} to {version}"
+    )]
+    InclusionRollback {
+        name: String,
+        stored: u64,
+        version: u64,
+    },
+    #[error("Error with the watch list {path}: {source}")]
+    WatchList {
+        path: String,
+        #[source]
+        source: Box<dyn std::error::Error + Sync + Send>,
+    },
+    #[error("Error while publishing a proof to {destination}: {source}")]
+    ProofOutput {
+        destination: String,
+        #[source]
+        source: Box<dyn std::error::Error + Sync + Send>,
+    },
+}
+
+/// Error type for the job queue of the proof server.
+#[derive(Debug, Error)]
+pub enum JobError {
+    #[error("I/O error for {path}: {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: Box<dyn std::error::Error + Sync + Send>,
+    },
+    #[error("Serialization error for {path}: {source}")]
+    Serialization {
+        path: String,
+        #[source]
+        source: Box<dyn std::error::Error + Sync + Send>,
+    },
+    #[error("Job queue is full, {0} jobs are already queued")]
+    QueueFull(usize),
+    #[error("Unknown job {0}")]
+    NotFound(JobId),
+}
+
+/// Error type for the authentication of the requests sent to the proof server.
+#[derive(Debug, Error)]
+pub enum AuthError {
+    #[error("Error while reading the API keys file {path}: {source}")]
+    KeysFile {
+        path: String,
+        #[source]
+        source: Box<dyn std::error::Error + Sync + Send>,
+    },
+    #[error("Invalid entry in the API keys file {path} at line {line}")]
+    InvalidKeysEntry { path: String, line: usize },
+    #[error("Invalid authentication mode: {0}")]
+    InvalidAuthMode(String),
+    #[error("Missing authentication header {0}")]
+    MissingCredentials(&'static str),
+    #[error("Unknown API key {0}")]
+    UnknownKey(String),
+    #[error("Invalid credentials for API key {0}")]
+    InvalidCredentials(String),
+    #[error("Request timestamp {0} is too far from the server time")]
+    StaleTimestamp(u64),
+    #[error("Replayed request for API key {0}")]
+    ReplayedRequest(String),
+    #[error("Rate limit exceeded for API key {0}")]
+    RateLimited(String),
+}
+
+/// Error type for the requests forwarded to the secondary servers.
+#[derive(Debug, Error)]
+pub enum SecondaryError {
+    #[error("Error while forwarding a request to the secondary server {addr}: {source}")]
+    Request {
+        addr: String,
+        #[source]
+        source: Box<dyn std::error::Error + Sync + Send>,
+    },
+    #[error("The secondary server {addr} rejected the credentials of the primary server")]
+    Unauthorized { addr: String },
+    #[error("No secondary server is available")]
+    Unavailable,
+}
+
+/// Error type for the proof cache of the proof server.
+#[derive(Debug, Error)]
+pub enum CacheError {
+    #[error("I/O error for {path}: {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: Box<dyn std::error::Error + Sync + Send>,
+    },
+}
+
+/// Error type for the simulated Aptos node.
+#[derive(Debug, Error)]
+pub enum SimulatorError {
+    #[error("Error in the simulated chain: {source}")]
+    Chain {
+        #[source]
+        source: Box<dyn std::error::Error + Sync + Send>,
+    },
+    #[error("Invalid {name} parameter: {source}")]
+    InvalidParameter {
+        name: &'static str,
+        #[source]
+        source: Box<dyn std::error::Error + Sync + Send>,
+    },
+    #[error("No epoch change proof for epoch {0}")]
+    UnknownEpoch(u64),
+    #[error("Error while converting {structure}: {source}")]
+    Conversion {
+        structure: &'static str,
+        #[source]
+        source: Box<dyn std::error::Error + Sync + Send>,
+    },
 }
@@ -0,0 +1,791 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! # Jobs
+//!
+//! Bounded queue of the proving jobs submitted to the proof server. Each job
+//! is persisted in a directory, with its request, its status and, once
+//! generated, its proof, so that jobs survive a restart of the server.
+//! Jobs that were queued or running when the server stopped are queued
+//! again when the directory is opened, up to the capacity of the queue, the
+//! extra ones being marked as failed. Only the most recently finished jobs
+//! are kept, the files of the older ones being removed.
+//!
+//! Jobs are identified by random identifiers, and belong to the API key that
+//! submitted them: the jobs of a key are hidden from the other keys.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::SystemTime;
+
+use log::{error, info, warn};
+use tokio::sync::Notify;
+
+use crate::error::JobError;
+use crate::types::proof_server::{JobId, JobStatus};
+
+/// Extension of the files holding the request of a job.
+const REQUEST_FILE_EXTENSION: &str = "request";
+/// Extension of the files holding the status of a job.
+const STATUS_FILE_EXTENSION: &str = "status";
+/// Extension of the files holding the proof of a job.
+const PROOF_FILE_EXTENSION: &str = "proof";
+/// Extension of the files holding the identifier of the API key owning a job.
+const OWNER_FILE_EXTENSION: &str = "owner";
+/// Extension of the temporary files, renamed once fully written.
+const TMP_FILE_EXTENSION: &str = "tmp";
+
+/// Persisted queue of proving jobs.
+#[derive(Debug)]
+pub struct JobQueue {
+    dir: PathBuf,
+    capacity: usize,
+    history: usize,
+    jobs: Mutex<Jobs>,
+    notify: Notify,
+}
+
+#[derive(Debug, Default)]
+struct Jobs {
+    entries: BTreeMap<JobId, Job>,
+    queue: VecDeque<JobId>,
+    /// Finished jobs, from the least to the most recently finished.
+    finished: VecDeque<JobId>,
+}
+
+#[derive(Debug)]
+struct Job {
+    /// Identifier of the API key that submitted the job, if requests are authenticated.
+    owner: Option<String>,
+    status: JobStatus,
+}
+
+impl JobQueue {
+    /// Opens the job queue persisted in a directory, creating the directory if needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory the jobs are persisted in.
+    /// * `capacity` - The maximum number of queued jobs.
+    /// * `history` - The maximum number of finished jobs kept, along with their proof.
+    ///
+    /// # Returns
+    ///
+    /// The `JobQueue`, with the unfinished jobs of the directory queued again.
+    pub fn open(
+        dir: impl Into<PathBuf>,
+        capacity: usize,
+        history: usize,
+    ) -> Result<Self, JobError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).map_err(|err| JobError::Io {
+            path: dir.display().to_string(),
+            source: err.into(),
+        })?;
+
+        let queue = Self {
+            dir,
+            capacity,
+            history,
+            jobs: Mutex::new(Jobs::default()),
+            notify: Notify::new(),
+        };
+        queue.restore()?;
+
+        Ok(queue)
+    }
+
+    /// Submits a new job.
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The identifier of the API key submitting the job, if requests are authenticated.
+    /// * `request` - The serialized proving request of the job.
+    ///
+    /// # Returns
+    ///
+    /// The identifier of the job, or an error if the queue is full.
+    pub fn submit(&self, owner: Option<&str>, request: &[u8]) -> Result<JobId, JobError> {
+        // The identifier is drawn first, so that the files of the job are written
+        // without holding the lock
+        let job_id = {
+            let jobs = self.lock();
+            if jobs.queue.len() >= self.capacity {
+                return Err(JobError::QueueFull(jobs.queue.len()));
+            }
+            loop {
+                let job_id = rand::random::<JobId>();
+                if !jobs.entries.contains_key(&job_id) {
+                    break job_id;
+                }
+            }
+        };
+
+        write_file(&self.path(job_id, REQUEST_FILE_EXTENSION), request)
+            .and_then(|_| match owner {
+                Some(owner) => {
+                    write_file(&self.path(job_id, OWNER_FILE_EXTENSION), owner.as_bytes())
+                }
+                None => Ok(()),
+            })
+            .and_then(|_| self.write_status(job_id, &JobStatus::Queued))?;
+
+        {
+            let mut jobs = self.lock();
+            // The queue may have filled up while the files were written
+            if jobs.queue.len() >= self.capacity {
+                let queued = jobs.queue.len();
+                drop(jobs);
+                self.remove_files(&[job_id]);
+                return Err(JobError::QueueFull(queued));
+            }
+            jobs.entries.insert(
+                job_id,
+                Job {
+                    owner: owner.map(str::to_string),
+                    status: JobStatus::Queued,
+                },
+            );
+            jobs.queue.push_back(job_id);
+        }
+
+        self.notify.notify_one();
+
+        Ok(job_id)
+    }
+
+    /// Returns the status of a job.
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The identifier of the API key of the request, if requests are authenticated.
+    /// * `job_id` - The identifier of the job.
+    ///
+    /// # Returns
+    ///
+    /// The status of the job, or an error if the job is unknown or belongs to another key.
+    pub fn status(&self, owner: Option<&str>, job_id: JobId) -> Result<JobStatus, JobError> {
+        self.lock()
+            .entries
+            .get(&job_id)
+            .filter(|job| job.owner.as_deref() == owner)
+            .map(|job| job.status.clone())
+            .ok_or(JobError::NotFound(job_id))
+    }
+
+    /// Returns the proof of a job.
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The identifier of the API key of the request, if requests are authenticated.
+    /// * `job_id` - The identifier of the job.
+    ///
+    /// # Returns
+    ///
+    /// The serialized proof of the job, or `None` if the job is not done.
+    pub fn proof(&self, owner: Option<&str>, job_id: JobId) -> Result<Option<Vec<u8>>, JobError> {
+        if self.status(owner, job_id)? != JobStatus::Done {
+            return Ok(None);
+        }
+
+        read_file(&self.path(job_id, PROOF_FILE_EXTENSION)).map(Some)
+    }
+
+    /// Cancels a job.
+    ///
+    /// Cancellation is best-effort: a queued job is never started, but the
+    /// proof generation of a running job cannot be interrupted. Its worker
+    /// stays busy until the proof is generated, the proof being discarded.
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The identifier of the API key of the request, if requests are authenticated.
+    /// * `job_id` - The identifier of the job.
+    ///
+    /// # Returns
+    ///
+    /// The status of the job, left untouched if the job was already finished.
+    pub fn cancel(&self, owner: Option<&str>, job_id: JobId) -> Result<JobStatus, JobError> {
+        let pruned = {
+            let mut jobs = self.lock();
+            let status = jobs
+                .entries
+                .get(&job_id)
+                .filter(|job| job.owner.as_deref() == owner)
+                .map(|job| job.status.clone())
+                .ok_or(JobError::NotFound(job_id))?;
+            if status.is_finished() {
+                return Ok(status);
+            }
+
+            jobs.queue.retain(|queued_id| *queued_id != job_id);
+            self.finish_locked(&mut jobs, job_id, JobStatus::Cancelled)
+        };
+
+        self.store_final_status(job_id, &JobStatus::Cancelled, &pruned)?;
+
+        Ok(JobStatus::Cancelled)
+    }
+
+    /// Waits for the next queued job and marks it as running.
+    ///
+    /// # Returns
+    ///
+    /// The identifier of the job and its serialized proving request.
+    pub async fn next(&self) -> (JobId, Vec<u8>) {
+        loop {
+            let notified = self.notify.notified();
+            if let Some(job) = self.start_next() {
+                return job;
+            }
+            notified.await;
+        }
+    }
+
+    /// Stores the result of a running job. The result is discarded if the job
+    /// was cancelled in the meantime.
+    ///
+    /// # Arguments
+    ///
+    /// * `job_id` - The identifier of the job.
+    /// * `result` - The serialized proof of the job, or the reason of its failure.
+    pub fn finish(&self, job_id: JobId, result: Result<Vec<u8>, String>) -> Result<(), JobError> {
+        if !self.is_running(job_id) {
+            info!("Discarding the result of job {job_id}, which is no longer running");
+            return Ok(());
+        }
+
+        // The proof is written before the job is marked as done, so that it can be
+        // fetched as soon as the status is visible
+        let status = match result {
+            Ok(proof) => match write_file(&self.path(job_id, PROOF_FILE_EXTENSION), &proof) {
+                Ok(()) => JobStatus::Done,
+                Err(err) => JobStatus::Failed(err.to_string()),
+            },
+            Err(reason) => JobStatus::Failed(reason),
+        };
+
+        let pruned = {
+            let mut jobs = self.lock();
+            if !jobs
+                .entries
+                .get(&job_id)
+                .is_some_and(|job| job.status == JobStatus::Running)
+            {
+                info!("Discarding the result of job {job_id}, which is no longer running");
+                return Ok(());
+            }
+            self.finish_locked(&mut jobs, job_id, status.clone())
+        };
+
+        self.store_final_status(job_id, &status, &pruned)
+    }
+
+    fn is_running(&self, job_id: JobId) -> bool {
+        self.lock()
+            .entries
+            .get(&job_id)
+            .is_some_and(|job| job.status == JobStatus::Running)
+    }
+
+    /// Pops the next queued job, if any, and marks it as running. Jobs whose
+    /// request cannot be read are marked as failed and skipped.
+    ///
+    /// The running status is only kept in memory: a job running when the
+    /// server stops is persisted as queued, and queued again on restart.
+    fn start_next(&self) -> Option<(JobId, Vec<u8>)> {
+        loop {
+            let job_id = {
+                let mut jobs = self.lock();
+                let job_id = jobs.queue.pop_front()?;
+                if let Some(job) = jobs.entries.get_mut(&job_id) {
+                    job.status = JobStatus::Running;
+                }
+                job_id
+            };
+
+            match read_file(&self.path(job_id, REQUEST_FILE_EXTENSION)) {
+                Ok(request) => return Some((job_id, request)),
+                Err(err) => {
+                    error!("Failed to start job {job_id}: {err}");
+                    if let Err(err) = self.finish(job_id, Err(err.to_string())) {
+                        error!("Failed to store the status of job {job_id}: {err}");
+                    }
+                }
+            }
+        }
+    }
+
+    /// Marks a job as finished in memory, forgetting the oldest finished jobs
+    /// beyond the history size.
+    ///
+    /// # Returns
+    ///
+    /// The forgotten jobs, whose files are to be removed.
+    fn finish_locked(&self, jobs: &mut Jobs, job_id: JobId, status: JobStatus) -> Vec<JobId> {
+        if let Some(job) = jobs.entries.get_mut(&job_id) {
+            job.status = status;
+        }
+        jobs.finished.push_back(job_id);
+
+        let excess = jobs.finished.len().saturating_sub(self.history);
+        let pruned: Vec<JobId> = jobs.finished.drain(..excess).collect();
+        for pruned_id in &pruned {
+            jobs.entries.remove(pruned_id);
+        }
+
+        pruned
+    }
+
+    /// Loads the jobs persisted in the directory, queuing again the unfinished
+    /// ones up to the capacity of the queue and removing the finished ones
+    /// beyond the history size. Temporary files left by an interrupted write
+    /// are removed.
+    fn restore(&self) -> Result<(), JobError> {
+        let entries = fs::read_dir(&self.dir).map_err(|err| JobError::Io {
+            path: self.dir.display().to_string(),
+            source: err.into(),
+        })?;
+
+        let mut stored = Vec::new();
+        for entry in entries {
+            let path = entry
+                .map_err(|err| JobError::Io {
+                    path: self.dir.display().to_string(),
+                    source: err.into(),
+                })?
+                .path();
+            match path.extension().and_then(|ext| ext.to_str()) {
+                Some(STATUS_FILE_EXTENSION) => (),
+                Some(TMP_FILE_EXTENSION) => {
+                    if let Err(err) = fs::remove_file(&path) {
+                        warn!("Failed to remove {}: {err}", path.display());
+                    }
+                    continue;
+                }
+                _ => continue,
+            }
+            let Some(job_id) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<JobId>().ok())
+            else {
+                continue;
+            };
+
+            let status: JobStatus =
+                bcs::from_bytes(&read_file(&path)?).map_err(|err| JobError::Serialization {
+                    path: path.display().to_string(),
+                    source: err.into(),
+                })?;
+            let owner_path = self.path(job_id, OWNER_FILE_EXTENSION);
+            let owner = match fs::read_to_string(&owner_path) {
+                Ok(owner) => Some(owner),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+                Err(err) => {
+                    return Err(JobError::Io {
+                        path: owner_path.display().to_string(),
+                        source: err.into(),
+                    })
+                }
+            };
+            // The status file is last written when the job is submitted or finished
+            let updated = fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+
+            stored.push((updated, job_id, owner, status));
+        }
+        stored.sort_by_key(|(updated, job_id, _, _)| (*updated, *job_id));
+
+        let mut jobs = self.lock();
+        let mut pruned = Vec::new();
+        let mut rejected = Vec::new();
+        for (_, job_id, owner, status) in stored {
+            if status.is_finished() {
+                let job = Job {
+                    owner,
+                    status: status.clone(),
+                };
+                jobs.entries.insert(job_id, job);
+                pruned.extend(self.finish_locked(&mut jobs, job_id, status));
+            } else if jobs.queue.len() >= self.capacity {
+                // The oldest jobs are queued first, the most recent ones beyond the
+                // capacity failing as they would have on submission
+                let job = Job {
+                    owner,
+                    status: JobStatus::Running,
+                };
+                jobs.entries.insert(job_id, job);
+                let status = JobStatus::Failed("Job queue full on restart".to_string());
+                pruned.extend(self.finish_locked(&mut jobs, job_id, status.clone()));
+                rejected.push((job_id, status));
+            } else {
+                let job = Job {
+                    owner,
+                    status: JobStatus::Queued,
+                };
+                jobs.entries.insert(job_id, job);
+                jobs.queue.push_back(job_id);
+            }
+        }
+        info!(
+            "Restored {} jobs from {}, {} of them queued again and {} rejected",
+            jobs.entries.len(),
+            self.dir.display(),
+            jobs.queue.len(),
+            rejected.len()
+        );
+        drop(jobs);
+
+        for (job_id, status) in &rejected {
+            warn!("Job {job_id} rejected, the queue being full");
+            if !pruned.contains(job_id) {
+                self.write_status(*job_id, status)?;
+            }
+        }
+        self.remove_files(&pruned);
+
+        Ok(())
+    }
+
+    /// Persists the final status of a job, then removes the files of the jobs
+    /// forgotten when it finished.
+    fn store_final_status(
+        &self,
+        job_id: JobId,
+        status: &JobStatus,
+        pruned: &[JobId],
+    ) -> Result<(), JobError> {
+        let stored = if pruned.contains(&job_id) {
+            Ok(())
+        } else {
+            self.write_status(job_id, status)
+        };
+        self.remove_files(pruned);
+
+        stored
+    }
+
+    /// Persists the status of a job.
+    fn write_status(&self, job_id: JobId, status: &JobStatus) -> Result<(), JobError> {
+        let path = self.path(job_id, STATUS_FILE_EXTENSION);
+        let bytes = bcs::to_bytes(status).map_err(|err| JobError::Serialization {
+            path: path.display().to_string(),
+            source: err.into(),
+        })?;
+
+        write_file(&path, &bytes)
+    }
+
+    fn remove_files(&self, job_ids: &[JobId]) {
+        for job_id in job_ids {
+            for extension in [
+                STATUS_FILE_EXTENSION,
+                REQUEST_FILE_EXTENSION,
+                PROOF_FILE_EXTENSION,
+                OWNER_FILE_EXTENSION,
+            ] {
+                let path = self.path(*job_id, extension);
+                match fs::remove_file(&path) {
+                    Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                        warn!("Failed to remove {}: {err}", path.display());
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn path(&self, job_id: JobId, extension: &str) -> PathBuf {
+        self.dir.join(job_id.to_string()).with_extension(extension)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Jobs> {
+        // The jobs are always left consistent, so a poisoned lock can be recovered
+        self.jobs
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, JobError> {
+    fs::read(path).map_err(|err| JobError::Io {
+        path: path.display().to_string(),
+        source: err.into(),
+    })
+}
+
+fn write_file(path: &Path, bytes: &[u8]) -> Result<(), JobError> {
+    // Write to a temporary file first so that a restart never loads partial files
+    let tmp_path = path.with_extension(format!(
+        "{}.{TMP_FILE_EXTENSION}",
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+    ));
+    fs::write(&tmp_path, bytes)
+        .and_then(|_| fs::rename(&tmp_path, path))
+        .map_err(|err| JobError::Io {
+            path: path.display().to_string(),
+            source: err.into(),
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn jobs_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_job_lifecycle() {
+        let dir = jobs_dir("test_job_lifecycle");
+        let jobs = JobQueue::open(&dir, 2, 8).unwrap();
+
+        let job_id = jobs.submit(None, b"request").unwrap();
+        assert_eq!(jobs.status(None, job_id).unwrap(), JobStatus::Queued);
+        assert_eq!(jobs.proof(None, job_id).unwrap(), None);
+
+        assert_eq!(jobs.next().await, (job_id, b"request".to_vec()));
+        assert_eq!(jobs.status(None, job_id).unwrap(), JobStatus::Running);
+
+        jobs.finish(job_id, Ok(b"proof".to_vec())).unwrap();
+        assert_eq!(jobs.status(None, job_id).unwrap(), JobStatus::Done);
+        assert_eq!(jobs.proof(None, job_id).unwrap(), Some(b"proof".to_vec()));
+
+        let failed_id = jobs.submit(None, b"request").unwrap();
+        assert_eq!(jobs.next().await.0, failed_id);
+        jobs.finish(failed_id, Err("failure".to_string())).unwrap();
+        assert_eq!(
+            jobs.status(None, failed_id).unwrap(),
+            JobStatus::Failed("failure".to_string())
+        );
+
+        assert!(matches!(jobs.status(None, 42), Err(JobError::NotFound(42))));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_job_owner() {
+        let dir = jobs_dir("test_job_owner");
+        let jobs = JobQueue::open(&dir, 2, 8).unwrap();
+
+        let job_id = jobs.submit(Some("key-a"), b"request").unwrap();
+        jobs.start_next().unwrap();
+        jobs.finish(job_id, Ok(b"proof".to_vec())).unwrap();
+
+        // The jobs of a key are hidden from the other keys
+        for owner in [Some("key-b"), None] {
+            assert!(matches!(
+                jobs.status(owner, job_id),
+                Err(JobError::NotFound(_))
+            ));
+            assert!(matches!(
+                jobs.proof(owner, job_id),
+                Err(JobError::NotFound(_))
+            ));
+            assert!(matches!(
+                jobs.cancel(owner, job_id),
+                Err(JobError::NotFound(_))
+            ));
+        }
+        assert_eq!(
+            jobs.proof(Some("key-a"), job_id).unwrap(),
+            Some(b"proof".to_vec())
+        );
+
+        // The owner of a job is restored along with it
+        drop(jobs);
+        let jobs = JobQueue::open(&dir, 2, 8).unwrap();
+        assert_eq!(jobs.status(Some("key-a"), job_id).unwrap(), JobStatus::Done);
+        assert!(jobs.status(Some("key-b"), job_id).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_queue_full() {
+        let dir = jobs_dir("test_queue_full");
+        let jobs = JobQueue::open(&dir, 1, 8).unwrap();
+
+        jobs.submit(None, b"request").unwrap();
+        assert!(matches!(
+            jobs.submit(None, b"request"),
+            Err(JobError::QueueFull(1))
+        ));
+
+        // Running jobs no longer count towards the queue size
+        jobs.start_next().unwrap();
+        jobs.submit(None, b"request").unwrap();
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cancel() {
+        let dir = jobs_dir("test_cancel");
+        let jobs = JobQueue::open(&dir, 2, 8).unwrap();
+
+        let running_id = jobs.submit(None, b"running").unwrap();
+        let queued_id = jobs.submit(None, b"queued").unwrap();
+        assert_eq!(jobs.start_next().unwrap().0, running_id);
+
+        // A cancelled queued job is never started
+        assert_eq!(jobs.cancel(None, queued_id).unwrap(), JobStatus::Cancelled);
+        assert!(jobs.start_next().is_none());
+
+        // The proof of a cancelled running job is discarded
+        assert_eq!(jobs.cancel(None, running_id).unwrap(), JobStatus::Cancelled);
+        jobs.finish(running_id, Ok(b"proof".to_vec())).unwrap();
+        assert_eq!(jobs.status(None, running_id).unwrap(), JobStatus::Cancelled);
+        assert_eq!(jobs.proof(None, running_id).unwrap(), None);
+
+        // Finished jobs are left untouched
+        let done_id = jobs.submit(None, b"done").unwrap();
+        jobs.start_next().unwrap();
+        jobs.finish(done_id, Ok(b"proof".to_vec())).unwrap();
+        assert_eq!(jobs.cancel(None, done_id).unwrap(), JobStatus::Done);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_restore() {
+        let dir = jobs_dir("test_restore");
+        let (done_id, running_id, queued_id, cancelled_id) = {
+            let jobs = JobQueue::open(&dir, 4, 8).unwrap();
+            let done_id = jobs.submit(None, b"done").unwrap();
+            let running_id = jobs.submit(None, b"running").unwrap();
+            let queued_id = jobs.submit(None, b"queued").unwrap();
+            let cancelled_id = jobs.submit(None, b"cancelled").unwrap();
+
+            jobs.start_next().unwrap();
+            jobs.finish(done_id, Ok(b"proof".to_vec())).unwrap();
+            jobs.start_next().unwrap();
+            jobs.cancel(None, cancelled_id).unwrap();
+
+            (done_id, running_id, queued_id, cancelled_id)
+        };
+
+        let jobs = JobQueue::open(&dir, 4, 8).unwrap();
+        assert_eq!(jobs.proof(None, done_id).unwrap(), Some(b"proof".to_vec()));
+        assert_eq!(
+            jobs.status(None, cancelled_id).unwrap(),
+            JobStatus::Cancelled
+        );
+
+        // The running and queued jobs are queued again
+        assert_eq!(jobs.status(None, running_id).unwrap(), JobStatus::Queued);
+        assert_eq!(jobs.status(None, queued_id).unwrap(), JobStatus::Queued);
+        let mut restarted = vec![jobs.start_next().unwrap(), jobs.start_next().unwrap()];
+        restarted.sort();
+        assert_eq!(
+            restarted,
+            vec![
+                (running_id, b"running".to_vec()),
+                (queued_id, b"queued".to_vec())
+            ]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_restore_beyond_capacity() {
+        let dir = jobs_dir("test_restore_beyond_capacity");
+        let job_ids: Vec<JobId> = {
+            let jobs = JobQueue::open(&dir, 3, 8).unwrap();
+            (0..3)
+                .map(|_| jobs.submit(None, b"request").unwrap())
+                .collect()
+        };
+        // A write interrupted by a restart leaves a temporary file behind
+        let tmp_path = dir.join(format!("{}.status.tmp", job_ids[0]));
+        fs::write(&tmp_path, b"partial").unwrap();
+
+        // The jobs beyond the capacity of the queue are failed
+        let jobs = JobQueue::open(&dir, 2, 8).unwrap();
+        let statuses: Vec<JobStatus> = job_ids
+            .iter()
+            .map(|job_id| jobs.status(None, *job_id).unwrap())
+            .collect();
+        assert_eq!(
+            statuses
+                .iter()
+                .filter(|status| **status == JobStatus::Queued)
+                .count(),
+            2
+        );
+        assert_eq!(
+            statuses
+                .iter()
+                .filter(|status| matches!(status, JobStatus::Failed(_)))
+                .count(),
+            1
+        );
+        assert!(!tmp_path.exists());
+
+        // The rejection is persisted
+        drop(jobs);
+        let jobs = JobQueue::open(&dir, 2, 8).unwrap();
+        let reloaded: Vec<JobStatus> = job_ids
+            .iter()
+            .map(|job_id| jobs.status(None, *job_id).unwrap())
+            .collect();
+        assert_eq!(reloaded, statuses);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_prune_finished_jobs() {
+        let dir = jobs_dir("test_prune_finished_jobs");
+        let jobs = JobQueue::open(&dir, 4, 2).unwrap();
+
+        let job_ids: Vec<JobId> = (0..3)
+            .map(|_| {
+                let job_id = jobs.submit(None, b"request").unwrap();
+                jobs.start_next().unwrap();
+                jobs.finish(job_id, Ok(b"proof".to_vec())).unwrap();
+                job_id
+            })
+            .collect();
+
+        // The oldest finished job is forgotten, along with its files
+        assert!(matches!(
+            jobs.status(None, job_ids[0]),
+            Err(JobError::NotFound(_))
+        ));
+        for extension in [
+            REQUEST_FILE_EXTENSION,
+            STATUS_FILE_EXTENSION,
+            PROOF_FILE_EXTENSION,
+        ] {
+            assert!(!jobs.path(job_ids[0], extension).exists());
+        }
+        assert_eq!(
+            jobs.proof(None, job_ids[2]).unwrap(),
+            Some(b"proof".to_vec())
+        );
+
+        // A smaller history prunes the restored jobs
+        drop(jobs);
+        let jobs = JobQueue::open(&dir, 4, 1).unwrap();
+        assert!(matches!(
+            jobs.status(None, job_ids[1]),
+            Err(JobError::NotFound(_))
+        ));
+        assert!(!jobs.path(job_ids[1], STATUS_FILE_EXTENSION).exists());
+        assert_eq!(jobs.status(None, job_ids[2]).unwrap(), JobStatus::Done);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
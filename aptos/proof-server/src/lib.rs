@@ -17,13 +17,28 @@
 //! - [secondary server](./bin/server): A secondary server that is in charge of handling
 //!   requests about epoch changes.
 
+/// Module containing the authentication and rate limiting of the requests sent to the proof server.
+pub mod auth;
+/// Module containing the cache of the proofs generated by the proof server.
+pub mod cache;
 /// Module containing the errors that can be thrown while using the client and the proof server.
 pub mod error;
+/// Module containing the persisted queue of the proving jobs submitted to the proof server.
+pub mod jobs;
+/// Module containing the pool of secondary servers the proving work is forwarded to in split mode.
+pub mod secondary;
+/// Module containing the simulated Aptos node, serving the payloads of a local chain.
+#[cfg(feature = "simulator")]
+pub mod simulator;
+/// Module containing the file persisting the latest verified state of the client.
+pub mod state_file;
 /// Module containing the types encountered while fetching data from an Aptos Public Full Node and
 /// interacting with the proof server.
 pub mod types;
 /// Module containing some utilities.
 pub mod utils;
+/// Module containing the list of accounts the client proves the inclusion of.
+pub mod watch;
 
 /// Endpoint of the Aptos node to fetch the current ledger info.
 pub const APTOS_LEDGER_INFO_ENDPOINT: &str = "v1/";
@@ -35,3 +50,11 @@ pub const APTOS_EPOCH_CHANGE_PROOF_ENDPOINT: &str = "v1/epoch/proof";
 pub fn aptos_inclusion_proof_endpoint(address: &str) -> String {
     format!("v1/accounts/{address}/proof")
 }
+
+/// Generates the endpoint to fetch the inclusion proof for a resource of a given address.
+pub fn aptos_resource_inclusion_proof_endpoint(address: &str, resource_type: &str) -> String {
+    format!(
+        "{}?resource_type={resource_type}",
+        aptos_inclusion_proof_endpoint(address)
+    )
+}
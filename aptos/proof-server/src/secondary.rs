@@ -0,0 +1,385 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! # Secondary
+//!
+//! Pool of the secondary servers a primary server forwards its proving work
+//! to in split mode. Requests go to the least busy secondary, as reported by
+//! the `/ready` route of each secondary and by the number of requests already
+//! forwarded to it, and are retried on the next secondary when one fails.
+//! Forwarded requests are signed with the credentials of the primary server,
+//! so that they are accepted by secondaries that authenticate their requests.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{error, info, warn};
+use reqwest::StatusCode;
+use tokio::task::JoinSet;
+
+use crate::auth::Credentials;
+use crate::error::SecondaryError;
+
+/// Maximum time waited for a secondary to answer its readiness check.
+const READY_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Pool of secondary servers.
+#[derive(Debug)]
+pub struct SecondaryPool {
+    client: reqwest::Client,
+    credentials: Option<Credentials>,
+    secondaries: Vec<Arc<Secondary>>,
+}
+
+#[derive(Debug)]
+struct Secondary {
+    addr: String,
+    in_flight: AtomicUsize,
+}
+
+/// Readiness of a secondary, ordered from the most to the least preferred.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Readiness {
+    Ready,
+    Busy,
+    Unavailable,
+}
+
+/// Counts a request forwarded to a secondary until it is dropped.
+struct InFlight<'a>(&'a AtomicUsize);
+
+impl<'a> InFlight<'a> {
+    fn new(counter: &'a AtomicUsize) -> Self {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Self(counter)
+    }
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl SecondaryPool {
+    /// Creates a new `SecondaryPool`.
+    ///
+    /// # Arguments
+    ///
+    /// * `addrs` - The addresses of the secondary servers.
+    /// * `credentials` - The credentials signing the forwarded requests, if the
+    ///   secondary servers authenticate their requests.
+    ///
+    /// # Returns
+    ///
+    /// A new `SecondaryPool`.
+    pub fn new(addrs: Vec<String>, credentials: Option<Credentials>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            credentials,
+            secondaries: addrs
+                .into_iter()
+                .map(|addr| {
+                    Arc::new(Secondary {
+                        addr,
+                        in_flight: AtomicUsize::new(0),
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    /// Forwards a request to the least busy available secondary, retrying
+    /// on the next one when a secondary fails. A secondary rejecting the
+    /// credentials of the request is a configuration error, reported right
+    /// away rather than retried.
+    ///
+    /// # Arguments
+    ///
+    /// * `request_bytes` - The serialized request.
+    /// * `route` - The route of the request on the secondaries.
+    ///
+    /// # Returns
+    ///
+    /// The response of the first secondary that handled the request, or an
+    /// error if none could.
+    pub async fn forward(
+        &self,
+        request_bytes: Vec<u8>,
+        route: &str,
+    ) -> Result<Vec<u8>, SecondaryError> {
+        for secondary in self.candidates().await {
+            let _in_flight = InFlight::new(&secondary.in_flight);
+            info!(
+                "Forwarding request to the secondary server {}",
+                secondary.addr
+            );
+
+            match self
+                .send(&secondary.addr, route, request_bytes.clone())
+                .await
+            {
+                Ok(response) => {
+                    info!(
+                        "Response received from the secondary server {}",
+                        secondary.addr
+                    );
+                    return Ok(response);
+                }
+                Err(err @ SecondaryError::Unauthorized { .. }) => {
+                    error!("{err}");
+                    return Err(err);
+                }
+                Err(err) => warn!("{err}"),
+            }
+        }
+
+        Err(SecondaryError::Unavailable)
+    }
+
+    /// Returns the available secondaries, from the least to the most busy.
+    async fn candidates(&self) -> Vec<Arc<Secondary>> {
+        let mut checks = JoinSet::new();
+        for secondary in &self.secondaries {
+            let client = self.client.clone();
+            let secondary = secondary.clone();
+            checks.spawn(async move {
+                let readiness = readiness(&client, &secondary.addr).await;
+                (secondary, readiness)
+            });
+        }
+
+        let mut candidates = Vec::with_capacity(self.secondaries.len());
+        while let Some(check) = checks.join_next().await {
+            match check {
+                Ok((secondary, Readiness::Unavailable)) => {
+                    warn!("Secondary server {} is unavailable", secondary.addr)
+                }
+                Ok((secondary, readiness)) => candidates.push((readiness, secondary)),
+                Err(err) => warn!("Failed to check the readiness of a secondary server: {err}"),
+            }
+        }
+        candidates.sort_by_key(|(readiness, secondary)| {
+            (*readiness, secondary.in_flight.load(Ordering::SeqCst))
+        });
+
+        candidates
+            .into_iter()
+            .map(|(_, secondary)| secondary)
+            .collect()
+    }
+
+    async fn send(
+        &self,
+        addr: &str,
+        route: &str,
+        request_bytes: Vec<u8>,
+    ) -> Result<Vec<u8>, SecondaryError> {
+        let request_error =
+            |source: Box<dyn std::error::Error + Sync + Send>| SecondaryError::Request {
+                addr: addr.to_string(),
+                source,
+            };
+
+        let path = format!("/{route}");
+        let mut request_builder = self.client.post(format!("http://{addr}{path}"));
+        if let Some(credentials) = &self.credentials {
+            for (name, value) in credentials.headers("POST", &path, &request_bytes) {
+                request_builder = request_builder.header(name, value);
+            }
+        }
+
+        let response = request_builder
+            .body(request_bytes)
+            .send()
+            .await
+            .map_err(|err| request_error(err.into()))?;
+        if response.status() == StatusCode::UNAUTHORIZED {
+            return Err(SecondaryError::Unauthorized {
+                addr: addr.to_string(),
+            });
+        }
+        let response = response
+            .error_for_status()
+            .map_err(|err| request_error(err.into()))?;
+        let response_bytes = response
+            .bytes()
+            .await
+            .map_err(|err| request_error(err.into()))?;
+
+        Ok(response_bytes.to_vec())
+    }
+}
+
+async fn readiness(client: &reqwest::Client, addr: &str) -> Readiness {
+    let response = client
+        .get(format!("http://{addr}/ready"))
+        .timeout(READY_CHECK_TIMEOUT)
+        .send()
+        .await;
+
+    match response.map(|response| response.status()) {
+        Ok(StatusCode::OK) => Readiness::Ready,
+        Ok(StatusCode::CONFLICT) => Readiness::Busy,
+        _ => Readiness::Unavailable,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use axum::body::Bytes;
+    use axum::extract::State;
+    use axum::http::HeaderMap;
+    use axum::routing::{get, post};
+    use axum::Router;
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::auth::{AuthMode, Authenticator};
+
+    #[derive(Clone)]
+    struct TestSecondary {
+        ready: StatusCode,
+        proof: StatusCode,
+        authenticator: Option<Arc<Authenticator>>,
+        requests: Arc<AtomicUsize>,
+    }
+
+    impl TestSecondary {
+        fn new(ready: StatusCode, proof: StatusCode) -> Self {
+            Self {
+                ready,
+                proof,
+                authenticator: None,
+                requests: Arc::new(AtomicUsize::new(0)),
+            }
+        }
+
+        fn requests(&self) -> usize {
+            self.requests.load(Ordering::SeqCst)
+        }
+
+        async fn spawn(&self) -> String {
+            let app = Router::new()
+                .route(
+                    "/ready",
+                    get(|State(secondary): State<TestSecondary>| async move { secondary.ready }),
+                )
+                .route("/epoch/proof", post(prove))
+                .with_state(self.clone());
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap().to_string();
+            tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+            addr
+        }
+    }
+
+    async fn prove(
+        State(secondary): State<TestSecondary>,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> Result<Vec<u8>, StatusCode> {
+        secondary.requests.fetch_add(1, Ordering::SeqCst);
+        if let Some(authenticator) = &secondary.authenticator {
+            let key_id = authenticator
+                .authenticate(&headers)
+                .map_err(|_| StatusCode::UNAUTHORIZED)?;
+            authenticator
+                .verify_signature(&key_id, "POST", "/epoch/proof", &headers, &body)
+                .map_err(|_| StatusCode::UNAUTHORIZED)?;
+        }
+        if secondary.proof != StatusCode::OK {
+            return Err(secondary.proof);
+        }
+
+        Ok([b"proof-".as_slice(), &body].concat())
+    }
+
+    fn authenticator(name: &str) -> Arc<Authenticator> {
+        let path = std::env::temp_dir().join(format!("{name}-{}", std::process::id()));
+        fs::write(&path, "primary secret\n").unwrap();
+        let authenticator = Authenticator::load(AuthMode::Hmac, &path, None).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        Arc::new(authenticator)
+    }
+
+    #[tokio::test]
+    async fn test_forward_to_ready_secondary() {
+        let busy = TestSecondary::new(StatusCode::CONFLICT, StatusCode::OK);
+        let ready = TestSecondary::new(StatusCode::OK, StatusCode::OK);
+        let pool = SecondaryPool::new(vec![busy.spawn().await, ready.spawn().await], None);
+
+        let response = pool.forward(b"request".to_vec(), "epoch/proof").await;
+        assert_eq!(response.unwrap(), b"proof-request");
+        assert_eq!((busy.requests(), ready.requests()), (0, 1));
+    }
+
+    #[tokio::test]
+    async fn test_retry_on_failure() {
+        let failing = TestSecondary::new(StatusCode::OK, StatusCode::INTERNAL_SERVER_ERROR);
+        let busy = TestSecondary::new(StatusCode::CONFLICT, StatusCode::OK);
+        let pool = SecondaryPool::new(vec![busy.spawn().await, failing.spawn().await], None);
+
+        let response = pool.forward(b"request".to_vec(), "epoch/proof").await;
+        assert_eq!(response.unwrap(), b"proof-request");
+        assert_eq!((failing.requests(), busy.requests()), (1, 1));
+
+        let pool = SecondaryPool::new(vec![failing.spawn().await], None);
+        assert!(matches!(
+            pool.forward(b"request".to_vec(), "epoch/proof").await,
+            Err(SecondaryError::Unavailable)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unavailable_secondary() {
+        // The port is released right away, so that nothing listens on it
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .to_string();
+
+        let pool = SecondaryPool::new(vec![addr], None);
+        assert!(matches!(
+            pool.forward(b"request".to_vec(), "epoch/proof").await,
+            Err(SecondaryError::Unavailable)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_signed_forward() {
+        let mut authenticating = TestSecondary::new(StatusCode::OK, StatusCode::OK);
+        authenticating.authenticator = Some(authenticator("test_signed_forward"));
+        let fallback = TestSecondary::new(StatusCode::CONFLICT, StatusCode::OK);
+        let addrs = vec![authenticating.spawn().await, fallback.spawn().await];
+
+        let credentials =
+            Credentials::new(AuthMode::Hmac, "primary".to_string(), "secret".to_string());
+        let pool = SecondaryPool::new(addrs.clone(), Some(credentials));
+        let response = pool.forward(b"request".to_vec(), "epoch/proof").await;
+        assert_eq!(response.unwrap(), b"proof-request");
+        assert_eq!((authenticating.requests(), fallback.requests()), (1, 0));
+
+        // Rejected credentials are reported rather than retried on the next secondary
+        let credentials = Credentials::new(
+            AuthMode::Hmac,
+            "primary".to_string(),
+            "wrong-secret".to_string(),
+        );
+        for credentials in [Some(credentials), None] {
+            let pool = SecondaryPool::new(addrs.clone(), credentials);
+            assert!(matches!(
+                pool.forward(b"request".to_vec(), "epoch/proof").await,
+                Err(SecondaryError::Unauthorized { .. })
+            ));
+        }
+        assert_eq!((authenticating.requests(), fallback.requests()), (3, 0));
+    }
+}
@@ -0,0 +1,439 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! # Simulator
+//!
+//! Simulated Aptos node, serving the payloads of an Aptos Public Full Node
+//! for a local chain run by an `AptosWrapper`. It lets the client and the
+//! proof server run end to end without access to a live network, the chain
+//! only moving forward when it is advanced or when an epoch change is forced.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use aptos_lc_core::aptos_test_utils::wrapper::{AptosWrapper, ExecuteBlockArgs};
+use aptos_lc_core::crypto::hash::HashValue;
+use aptos_lc_core::merkle::sparse_proof::SparseMerkleProof;
+use aptos_lc_core::merkle::transaction_proof::TransactionAccumulatorProof;
+use aptos_lc_core::types::ledger_info::LedgerInfoWithSignatures;
+use aptos_lc_core::types::state_value::StateValue;
+use aptos_lc_core::types::transaction::TransactionInfo;
+use aptos_lc_core::types::trusted_state::{EpochChangeProof, TrustedState};
+use aptos_sdk::move_types::language_storage::StructTag;
+use aptos_types::account_address::AccountAddress;
+use aptos_types::chain_id::ChainId;
+use log::info;
+use serde::Serialize;
+
+use crate::error::SimulatorError;
+use crate::types::aptos::{
+    AccountInclusionProofResponse, EpochChangeProofResponse, LedgerInfoResponse, NodeRole,
+};
+
+/// Simulated Aptos node.
+pub struct NodeSimulator {
+    wrapper: AptosWrapper,
+    /// Epoch change proofs served by the node, by the epoch they change to.
+    epoch_changes: BTreeMap<u64, EpochChangeProofResponse>,
+}
+
+/// Position of the simulated chain, reported by the control routes of the node.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainStatus {
+    /// Current epoch of the chain.
+    pub epoch: u64,
+    /// Current version of the chain.
+    pub version: u64,
+    /// Current block height of the chain.
+    pub block_height: u64,
+}
+
+impl NodeSimulator {
+    /// Creates a new `NodeSimulator`, running a new chain that already went
+    /// through an epoch change, so that an epoch change proof can be served
+    /// for its current epoch.
+    ///
+    /// # Arguments
+    ///
+    /// * `nbr_accounts` - The number of funded accounts of the chain, at least 2.
+    /// * `nbr_validators` - The number of validators of the chain.
+    /// * `signers_per_block` - The number of validators signing each block.
+    ///
+    /// # Returns
+    ///
+    /// A new `NodeSimulator`.
+    pub fn new(
+        nbr_accounts: usize,
+        nbr_validators: usize,
+        signers_per_block: usize,
+    ) -> Result<Self, SimulatorError> {
+        if nbr_accounts < 2 {
+            return Err(SimulatorError::InvalidParameter {
+                name: "nbr_accounts",
+                source: "traffic needs at least 2 accounts".into(),
+            });
+        }
+
+        let wrapper = AptosWrapper::new(nbr_accounts, nbr_validators, signers_per_block)
+            .map_err(|err| SimulatorError::Chain { source: err.into() })?;
+        let mut simulator = Self {
+            wrapper,
+            epoch_changes: BTreeMap::new(),
+        };
+        simulator.advance(1)?;
+        simulator.change_epoch()?;
+
+        Ok(simulator)
+    }
+
+    /// Advances the chain by executing blocks of transfers between its accounts.
+    ///
+    /// # Arguments
+    ///
+    /// * `blocks` - The number of blocks to execute.
+    pub fn advance(&mut self, blocks: usize) -> Result<(), SimulatorError> {
+        for _ in 0..blocks {
+            self.wrapper
+                .generate_traffic()
+                .map_err(|err| SimulatorError::Chain { source: err.into() })?;
+        }
+        info!("Advanced the simulated chain to {:?}", self.status());
+
+        Ok(())
+    }
+
+    /// Forces an epoch change, recording its proof, and executes a block in
+    /// the new epoch so that the latest ledger info is signed by its validators.
+    pub fn change_epoch(&mut self) -> Result<(), SimulatorError> {
+        let trusted_state = self.trusted_state()?;
+
+        let state_proof = self
+            .wrapper
+            .new_state_proof(trusted_state.version())
+            .map_err(|err| SimulatorError::Chain { source: err.into() })?;
+        let epoch_change_proof = EpochChangeProof::from_bytes(&to_bytes(
+            "EpochChangeProof",
+            state_proof.epoch_changes(),
+        )?)
+        .map_err(|err| conversion_error("EpochChangeProof", err.into()))?;
+
+        self.wrapper
+            .execute_block(ExecuteBlockArgs::StateProof(Box::new(state_proof)))
+            .map_err(|err| SimulatorError::Chain { source: err.into() })?;
+        self.epoch_changes.insert(
+            *self.wrapper.current_epoch(),
+            EpochChangeProofResponse::new(epoch_change_proof, trusted_state),
+        );
+        info!(
+            "Changed the epoch of the simulated chain to {}",
+            self.wrapper.current_epoch()
+        );
+
+        self.advance(1)
+    }
+
+    /// Returns the position of the chain.
+    ///
+    /// # Returns
+    ///
+    /// The `ChainStatus` of the chain.
+    pub fn status(&self) -> ChainStatus {
+        ChainStatus {
+            epoch: *self.wrapper.current_epoch(),
+            version: *self.wrapper.current_version(),
+            block_height: *self.wrapper.current_block() as u64,
+        }
+    }
+
+    /// Returns the addresses of the funded accounts of the chain.
+    ///
+    /// # Returns
+    ///
+    /// The hex encoded addresses of the accounts.
+    pub fn account_addresses(&self) -> Vec<String> {
+        self.wrapper
+            .accounts()
+            .iter()
+            .map(|account| account.address().to_hex_literal())
+            .collect()
+    }
+
+    /// Returns the payload served on `/v1/`.
+    ///
+    /// # Returns
+    ///
+    /// The `LedgerInfoResponse` of the latest ledger info.
+    pub fn ledger_info(&self) -> Result<LedgerInfoResponse, SimulatorError> {
+        let latest_li = self.latest_li()?;
+        let status = self.status();
+
+        Ok(LedgerInfoResponse::new(
+            ChainId::test().id(),
+            status.epoch.to_string(),
+            status.version.to_string(),
+            "0".into(),
+            latest_li.ledger_info().timestamp_usecs().to_string(),
+            NodeRole::FullNode,
+            "0".into(),
+            status.block_height.to_string(),
+        ))
+    }
+
+    /// Returns the payload served on `/v1/epoch/proof`.
+    ///
+    /// # Arguments
+    ///
+    /// * `epoch_number` - The epoch the proof changes to, the latest one if `None`.
+    ///
+    /// # Returns
+    ///
+    /// The `EpochChangeProofResponse` for the epoch, along with the trusted
+    /// state of the epoch before it.
+    pub fn epoch_change_proof(
+        &self,
+        epoch_number: Option<u64>,
+    ) -> Result<EpochChangeProofResponse, SimulatorError> {
+        let epoch_change = match epoch_number {
+            Some(epoch) => self.epoch_changes.get(&epoch),
+            None => self.epoch_changes.values().next_back(),
+        };
+
+        epoch_change
+            .cloned()
+            .ok_or_else(|| SimulatorError::UnknownEpoch(epoch_number.unwrap_or_default()))
+    }
+
+    /// Returns the payload served on `/v1/accounts/:address/proof`.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The hex encoded address of the account.
+    /// * `resource_type` - The Move type of the resource to prove, the
+    ///   `0x1::account::Account` resource being proven if `None`.
+    ///
+    /// # Returns
+    ///
+    /// The `AccountInclusionProofResponse` for the resource against the
+    /// latest ledger info, proving its absence if it does not exist.
+    pub fn account_inclusion_proof(
+        &self,
+        address: &str,
+        resource_type: Option<&str>,
+    ) -> Result<AccountInclusionProofResponse, SimulatorError> {
+        let address =
+            AccountAddress::from_str(address).map_err(|err| SimulatorError::InvalidParameter {
+                name: "address",
+                source: err.into(),
+            })?;
+        let proof_assets = match resource_type {
+            Some(resource_type) => {
+                let struct_tag = StructTag::from_str(resource_type).map_err(|err| {
+                    SimulatorError::InvalidParameter {
+                        name: "resource_type",
+                        source: err.into(),
+                    }
+                })?;
+                self.wrapper
+                    .get_latest_proof_resource(&address, &struct_tag)
+            }
+            None => self.wrapper.get_latest_proof_address(&address),
+        }
+        .map_err(|err| SimulatorError::Chain { source: err.into() })?;
+
+        let state_proof = SparseMerkleProof::from_bytes(&to_bytes(
+            "SparseMerkleProof",
+            proof_assets.state_proof(),
+        )?)
+        .map_err(|err| conversion_error("SparseMerkleProof", err.into()))?;
+        let element_key = HashValue::from_slice(proof_assets.key().as_ref())
+            .map_err(|err| conversion_error("HashValue", err.into()))?;
+        let element_value = proof_assets
+            .state_value_bytes()
+            .map_err(|err| SimulatorError::Chain { source: err.into() })?
+            .map(|bytes| StateValue::from_bytes(&bytes))
+            .transpose()
+            .map_err(|err| conversion_error("StateValue", err.into()))?;
+        let transaction_proof = TransactionAccumulatorProof::from_bytes(&to_bytes(
+            "TransactionAccumulatorProof",
+            proof_assets.transaction_proof(),
+        )?)
+        .map_err(|err| conversion_error("TransactionAccumulatorProof", err.into()))?;
+        let transaction =
+            TransactionInfo::from_bytes(&to_bytes("TransactionInfo", proof_assets.transaction())?)
+                .map_err(|err| conversion_error("TransactionInfo", err.into()))?;
+
+        // The latest ledger info is signed by the validators of the current trusted state
+        let validator_verifier = match self.trusted_state()? {
+            TrustedState::EpochState { epoch_state, .. } => epoch_state.verifier,
+            TrustedState::EpochWaypoint(_) => {
+                return Err(SimulatorError::Chain {
+                    source: "the simulated chain has no epoch state".into(),
+                })
+            }
+        };
+
+        Ok(AccountInclusionProofResponse::new(
+            state_proof,
+            element_key,
+            element_value,
+            transaction_proof,
+            transaction,
+            *proof_assets.transaction_version(),
+            self.latest_li()?,
+            validator_verifier,
+        ))
+    }
+
+    fn trusted_state(&self) -> Result<TrustedState, SimulatorError> {
+        TrustedState::from_bytes(&to_bytes("TrustedState", self.wrapper.trusted_state())?)
+            .map_err(|err| conversion_error("TrustedState", err.into()))
+    }
+
+    fn latest_li(&self) -> Result<LedgerInfoWithSignatures, SimulatorError> {
+        let latest_li_bytes = self
+            .wrapper
+            .get_latest_li_bytes()
+            .map_err(|err| SimulatorError::Chain { source: err.into() })?;
+
+        LedgerInfoWithSignatures::from_bytes(&latest_li_bytes)
+            .map_err(|err| conversion_error("LedgerInfoWithSignatures", err.into()))
+    }
+}
+
+/// Serializes a value of the Aptos types, to read it back as the matching
+/// type of the light client.
+fn to_bytes<T: Serialize>(structure: &'static str, value: &T) -> Result<Vec<u8>, SimulatorError> {
+    bcs::to_bytes(value).map_err(|err| conversion_error(structure, err.into()))
+}
+
+fn conversion_error(
+    structure: &'static str,
+    source: Box<dyn std::error::Error + Sync + Send>,
+) -> SimulatorError {
+    SimulatorError::Conversion { structure, source }
+}
+
+#[cfg(test)]
+mod test {
+    use aptos_lc_core::types::trusted_state::TrustedStateChange;
+    use serde::de::DeserializeOwned;
+
+    use super::*;
+
+    /// Serializes a payload as served by the node, and reads it back as the client does.
+    fn round_trip<T: Serialize + DeserializeOwned>(payload: &T) -> T {
+        bcs::from_bytes(&bcs::to_bytes(payload).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_invalid_accounts() {
+        assert!(matches!(
+            NodeSimulator::new(1, 1, 1),
+            Err(SimulatorError::InvalidParameter {
+                name: "nbr_accounts",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_ledger_info() {
+        let simulator = NodeSimulator::new(2, 1, 1).unwrap();
+
+        let ledger_info = round_trip(&simulator.ledger_info().unwrap());
+        assert_eq!(ledger_info.epoch(), simulator.status().epoch.to_string());
+    }
+
+    #[test]
+    fn test_advance() {
+        let mut simulator = NodeSimulator::new(2, 1, 1).unwrap();
+        let status = simulator.status();
+
+        simulator.advance(2).unwrap();
+        let advanced = simulator.status();
+        assert_eq!(advanced.epoch, status.epoch);
+        assert_eq!(advanced.block_height, status.block_height + 2);
+        assert!(advanced.version > status.version);
+
+        // The inclusion proofs are served against the latest ledger info
+        let address = &simulator.account_addresses()[0];
+        let inclusion_proof = simulator.account_inclusion_proof(address, None).unwrap();
+        assert_eq!(
+            inclusion_proof.ledger_info().ledger_info().version(),
+            advanced.version
+        );
+    }
+
+    #[test]
+    fn test_change_epoch() {
+        let mut simulator = NodeSimulator::new(2, 1, 1).unwrap();
+        let epoch = simulator.status().epoch;
+
+        simulator.change_epoch().unwrap();
+        assert_eq!(simulator.status().epoch, epoch + 1);
+        assert_eq!(
+            round_trip(&simulator.ledger_info().unwrap()).epoch(),
+            (epoch + 1).to_string()
+        );
+
+        // The latest epoch change proof ratchets the trusted state of the previous epoch
+        // to the new one
+        let epoch_change_proof = round_trip(&simulator.epoch_change_proof(None).unwrap());
+        assert_eq!(epoch_change_proof.trusted_state().epoch(), Some(epoch));
+        match epoch_change_proof
+            .trusted_state()
+            .verify_and_ratchet_inner(epoch_change_proof.epoch_change_proof())
+            .unwrap()
+        {
+            TrustedStateChange::Epoch { new_state, .. } => {
+                assert_eq!(new_state.epoch(), Some(epoch + 1))
+            }
+            _ => panic!("Expected an epoch change"),
+        }
+
+        // The proofs of the previous epochs are still served
+        let previous = simulator.epoch_change_proof(Some(epoch)).unwrap();
+        assert!(previous.trusted_state().version() < epoch_change_proof.trusted_state().version());
+        assert!(matches!(
+            simulator.epoch_change_proof(Some(epoch + 2)),
+            Err(SimulatorError::UnknownEpoch(_))
+        ));
+    }
+
+    #[test]
+    fn test_account_inclusion_proof() {
+        let simulator = NodeSimulator::new(2, 1, 1).unwrap();
+        let status = simulator.status();
+        let address = &simulator.account_addresses()[0];
+
+        let inclusion_proof =
+            round_trip(&simulator.account_inclusion_proof(address, None).unwrap());
+        assert!(inclusion_proof.element_value().is_some());
+        assert_eq!(
+            inclusion_proof.ledger_info().ledger_info().epoch(),
+            status.epoch
+        );
+        inclusion_proof.into_inclusion_data(None).unwrap();
+
+        // Resources are proven along with their type, and absent ones are proven absent
+        let coin_store = "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>";
+        let inclusion_proof = simulator
+            .account_inclusion_proof(address, Some(coin_store))
+            .unwrap();
+        assert!(round_trip(&inclusion_proof).element_value().is_some());
+        let inclusion_proof = simulator
+            .account_inclusion_proof(&format!("0x{}", "ab".repeat(32)), None)
+            .unwrap();
+        assert!(round_trip(&inclusion_proof).element_value().is_none());
+
+        for (address, resource_type) in [
+            ("not an address", None),
+            (address.as_str(), Some("not a type")),
+        ] {
+            assert!(matches!(
+                simulator.account_inclusion_proof(address, resource_type),
+                Err(SimulatorError::InvalidParameter { .. })
+            ));
+        }
+    }
+}
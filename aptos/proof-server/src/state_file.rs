@@ -0,0 +1,285 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! # State file
+//!
+//! Local file persisting the latest verified state of the client, so that it
+//! resumes from it after a restart instead of trusting the Aptos node again.
+//! The file is replaced atomically, and never with a state older than the one
+//! it holds, be it its trusted state or the ledger info the last inclusion
+//! proof of a watched account was verified against.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use aptos_lc_core::crypto::hash::HashValue;
+use aptos_lc_core::types::trusted_state::TrustedState;
+use aptos_lc_core::types::Version;
+use serde::{Deserialize, Serialize};
+use sphinx_sdk::SphinxProofWithPublicValues;
+
+use crate::error::ClientError;
+
+/// Latest verified state of the client.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PersistedClientState {
+    /// The latest verified trusted state.
+    pub trusted_state: TrustedState,
+    /// The hash of the latest verified validator verifier.
+    pub validator_verifier_hash: HashValue,
+    /// The latest verified state root.
+    pub state_root: HashValue,
+    /// The last verified epoch change proof.
+    pub epoch_change_proof: Option<SphinxProofWithPublicValues>,
+    /// The last verified inclusion proof of each watched account, by name.
+    pub inclusion_proofs: BTreeMap<String, SphinxProofWithPublicValues>,
+    /// The version of the ledger info the last inclusion proof of each watched
+    /// account was verified against, by name.
+    pub inclusion_versions: BTreeMap<String, Version>,
+}
+
+impl PersistedClientState {
+    /// Returns the position of the state in the chain.
+    ///
+    /// # Returns
+    ///
+    /// The epoch and version of the trusted state.
+    pub fn position(&self) -> (u64, Version) {
+        (
+            self.trusted_state.epoch().unwrap_or_default(),
+            self.trusted_state.version(),
+        )
+    }
+}
+
+/// File persisting the state of the client.
+#[derive(Debug)]
+pub struct StateFile {
+    path: PathBuf,
+    position: Option<(u64, Version)>,
+    inclusion_versions: BTreeMap<String, Version>,
+}
+
+impl StateFile {
+    /// Opens the state file at a path.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the state file.
+    ///
+    /// # Returns
+    ///
+    /// The `StateFile`, along with the state it holds, if the file exists.
+    pub fn open(
+        path: impl Into<PathBuf>,
+    ) -> Result<(Self, Option<PersistedClientState>), ClientError> {
+        let path = path.into();
+        if !path.exists() {
+            return Ok((
+                Self {
+                    path,
+                    position: None,
+                    inclusion_versions: BTreeMap::new(),
+                },
+                None,
+            ));
+        }
+
+        let bytes = fs::read(&path).map_err(|err| state_file_error(&path, err.into()))?;
+        let state: PersistedClientState =
+            bcs::from_bytes(&bytes).map_err(|err| state_file_error(&path, err.into()))?;
+
+        Ok((
+            Self {
+                path,
+                position: Some(state.position()),
+                inclusion_versions: state.inclusion_versions.clone(),
+            },
+            Some(state),
+        ))
+    }
+
+    /// Returns the path of the state file.
+    ///
+    /// # Returns
+    ///
+    /// The path of the state file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Replaces the persisted state.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The new state of the client.
+    ///
+    /// # Returns
+    ///
+    /// An error if the state, or the inclusion proof of one of the watched
+    /// accounts, is older than the persisted one, or if it could not be written.
+    pub fn save(&mut self, state: &PersistedClientState) -> Result<(), ClientError> {
+        let position = state.position();
+        if let Some(stored) = self.position {
+            if position < stored {
+                return Err(ClientError::StateRollback { stored, position });
+            }
+        }
+        // Each watched account is proven at its own cadence, so the versions of
+        // their inclusion proofs are only compared account by account
+        for (name, stored) in &self.inclusion_versions {
+            match state.inclusion_versions.get(name) {
+                Some(version) if version >= stored => (),
+                version => {
+                    return Err(ClientError::InclusionRollback {
+                        name: name.clone(),
+                        stored: *stored,
+                        version: version.copied().unwrap_or_default(),
+                    })
+                }
+            }
+        }
+
+        let bytes = bcs::to_bytes(state).map_err(|err| state_file_error(&self.path, err.into()))?;
+
+        // Write and sync a temporary file first, then rename it over the state file so that
+        // a crash never leaves a partial state behind
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        let tmp_path = PathBuf::from(tmp_path);
+        File::create(&tmp_path)
+            .and_then(|mut file| {
+                file.write_all(&bytes)?;
+                file.sync_all()
+            })
+            .and_then(|_| fs::rename(&tmp_path, &self.path))
+            .map_err(|err| state_file_error(&self.path, err.into()))?;
+
+        self.position = Some(position);
+        self.inclusion_versions = state.inclusion_versions.clone();
+
+        Ok(())
+    }
+}
+
+fn state_file_error(path: &Path, source: Box<dyn std::error::Error + Sync + Send>) -> ClientError {
+    ClientError::StateFile {
+        path: path.display().to_string(),
+        source,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use aptos_lc_core::types::waypoint::Waypoint;
+
+    use super::*;
+
+    fn state(version: Version, inclusion_versions: &[(&str, Version)]) -> PersistedClientState {
+        let waypoint = Waypoint::from_str(&format!("{version}:{}", "ab".repeat(32))).unwrap();
+
+        PersistedClientState {
+            trusted_state: TrustedState::EpochWaypoint(waypoint),
+            validator_verifier_hash: HashValue::default(),
+            state_root: HashValue::default(),
+            epoch_change_proof: None,
+            inclusion_proofs: BTreeMap::new(),
+            inclusion_versions: inclusion_versions
+                .iter()
+                .map(|(name, version)| (name.to_string(), *version))
+                .collect(),
+        }
+    }
+
+    fn state_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("{name}-{}", std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_save_and_open() {
+        let path = state_path("test_save_and_open");
+
+        let (mut state_file, stored) = StateFile::open(&path).unwrap();
+        assert!(stored.is_none());
+        state_file.save(&state(10, &[("treasury", 12)])).unwrap();
+
+        // The temporary file is renamed over the state file
+        let mut tmp_path = path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        assert!(!PathBuf::from(tmp_path).exists());
+
+        let (_, stored) = StateFile::open(&path).unwrap();
+        let stored = stored.unwrap();
+        assert_eq!(stored.position(), (0, 10));
+        assert_eq!(stored.inclusion_versions.get("treasury"), Some(&12));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_refuse_rollback() {
+        let path = state_path("test_refuse_rollback");
+
+        let (mut state_file, _) = StateFile::open(&path).unwrap();
+        state_file.save(&state(10, &[("treasury", 12)])).unwrap();
+
+        assert!(matches!(
+            state_file.save(&state(9, &[("treasury", 12)])),
+            Err(ClientError::StateRollback { .. })
+        ));
+        assert!(matches!(
+            state_file.save(&state(10, &[("treasury", 11)])),
+            Err(ClientError::InclusionRollback { .. })
+        ));
+        assert!(matches!(
+            state_file.save(&state(10, &[])),
+            Err(ClientError::InclusionRollback { .. })
+        ));
+        state_file.save(&state(11, &[("treasury", 12)])).unwrap();
+
+        // The guard also holds for the state read back from the file
+        let (mut state_file, _) = StateFile::open(&path).unwrap();
+        assert!(matches!(
+            state_file.save(&state(11, &[("treasury", 11)])),
+            Err(ClientError::InclusionRollback { .. })
+        ));
+        state_file.save(&state(11, &[("treasury", 13)])).unwrap();
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_watched_accounts() {
+        let path = state_path("test_concurrent_watched_accounts");
+
+        let (mut state_file, _) = StateFile::open(&path).unwrap();
+        state_file.save(&state(10, &[("treasury", 15)])).unwrap();
+
+        // An account proven against an older ledger info than another one is
+        // not a rollback
+        state_file
+            .save(&state(10, &[("treasury", 15), ("bridge", 12)]))
+            .unwrap();
+        state_file
+            .save(&state(10, &[("treasury", 15), ("bridge", 14)]))
+            .unwrap();
+
+        // Each account is still guarded against its own last version
+        match state_file.save(&state(10, &[("treasury", 15), ("bridge", 13)])) {
+            Err(ClientError::InclusionRollback {
+                name,
+                stored,
+                version,
+            }) => assert_eq!((name.as_str(), stored, version), ("bridge", 14, 13)),
+            res => panic!("unexpected result: {res:?}"),
+        }
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -8,10 +8,13 @@ use aptos_lc::inclusion::{
 use aptos_lc_core::crypto::hash::HashValue;
 use aptos_lc_core::merkle::sparse_proof::SparseMerkleProof;
 use aptos_lc_core::merkle::transaction_proof::TransactionAccumulatorProof;
+use aptos_lc_core::types::error::VerifyError;
 use aptos_lc_core::types::ledger_info::LedgerInfoWithSignatures;
+use aptos_lc_core::types::state_key::StateKey;
+use aptos_lc_core::types::state_value::StateValue;
 use aptos_lc_core::types::transaction::TransactionInfo;
 use aptos_lc_core::types::trusted_state::{EpochChangeProof, TrustedState};
-use aptos_lc_core::types::validator::ValidatorVerifier;
+use aptos_lc_core::types::validator::{PreparedValidatorVerifier, ValidatorVerifier};
 use serde::{Deserialize, Serialize};
 
 /// The role of the Aptos node the client connects to. Can be Validator or Full Node.
@@ -35,6 +38,29 @@ pub struct LedgerInfoResponse {
 }
 
 impl LedgerInfoResponse {
+    #[allow(clippy::too_many_arguments)]
+    pub const fn new(
+        chain_id: u8,
+        epoch: String,
+        ledger_version: String,
+        oldest_ledger_version: String,
+        ledger_timestamp: String,
+        node_role: NodeRole,
+        oldest_block_height: String,
+        block_height: String,
+    ) -> Self {
+        Self {
+            chain_id,
+            epoch,
+            ledger_version,
+            oldest_ledger_version,
+            ledger_timestamp,
+            node_role,
+            oldest_block_height,
+            block_height,
+        }
+    }
+
     pub fn epoch(&self) -> String {
         self.epoch.clone()
     }
@@ -48,6 +74,13 @@ pub struct EpochChangeProofResponse {
 }
 
 impl EpochChangeProofResponse {
+    pub const fn new(epoch_change_proof: EpochChangeProof, trusted_state: TrustedState) -> Self {
+        Self {
+            epoch_change_proof,
+            trusted_state,
+        }
+    }
+
     pub const fn epoch_change_proof(&self) -> &EpochChangeProof {
         &self.epoch_change_proof
     }
@@ -73,8 +106,8 @@ pub struct AccountInclusionProofResponse {
     state_proof: SparseMerkleProof,
     /// Account leaf key
     element_key: HashValue,
-    /// Account state value
-    element_hash: HashValue,
+    /// Account state value, `None` if the account is absent from the state
+    element_value: Option<StateValue>,
     /// Proof for the transaction inclusion
     transaction_proof: TransactionAccumulatorProof,
     /// Hashed representation of the transaction
@@ -87,23 +120,80 @@ pub struct AccountInclusionProofResponse {
     validator_verifier: ValidatorVerifier,
 }
 
-impl From<AccountInclusionProofResponse> for InclusionData {
-    fn from(val: AccountInclusionProofResponse) -> Self {
-        InclusionData {
+impl AccountInclusionProofResponse {
+    #[allow(clippy::too_many_arguments)]
+    pub const fn new(
+        state_proof: SparseMerkleProof,
+        element_key: HashValue,
+        element_value: Option<StateValue>,
+        transaction_proof: TransactionAccumulatorProof,
+        transaction: TransactionInfo,
+        transaction_index: u64,
+        ledger_info_v0: LedgerInfoWithSignatures,
+        validator_verifier: ValidatorVerifier,
+    ) -> Self {
+        Self {
+            state_proof,
+            element_key,
+            element_value,
+            transaction_proof,
+            transaction,
+            transaction_index,
+            ledger_info_v0,
+            validator_verifier,
+        }
+    }
+
+    pub const fn element_value(&self) -> Option<&StateValue> {
+        self.element_value.as_ref()
+    }
+
+    pub const fn ledger_info(&self) -> &LedgerInfoWithSignatures {
+        &self.ledger_info_v0
+    }
+
+    /// Converts the response into the data of an inclusion proof.
+    ///
+    /// # Arguments
+    ///
+    /// * `state_key` - The key the proven leaf must have, checked by the
+    ///   inclusion program if set.
+    ///
+    /// # Returns
+    ///
+    /// The `InclusionData` of the response, committing none of the optional
+    /// public values of the inclusion program.
+    pub fn into_inclusion_data(
+        self,
+        state_key: Option<StateKey>,
+    ) -> Result<InclusionData, VerifyError> {
+        Ok(InclusionData {
             sparse_merkle_proof_assets: SparseMerkleProofAssets::new(
-                val.state_proof.to_bytes(),
-                *val.element_key.as_ref(),
-                *val.element_hash.as_ref(),
+                self.state_proof.to_bytes(),
+                *self.element_key.as_ref(),
+                state_key,
+                self.element_value.map(|value| value.to_bytes()),
             ),
             transaction_proof_assets: TransactionProofAssets::new(
-                val.transaction.to_bytes(),
-                val.transaction_index,
-                val.transaction_proof.to_bytes(),
-                val.ledger_info_v0.to_bytes(),
+                self.transaction.to_bytes(),
+                self.transaction_index,
+                self.transaction_proof.to_bytes(),
+                self.ledger_info_v0.to_bytes(),
             ),
             validator_verifier_assets: ValidatorVerifierAssets::new(
-                val.validator_verifier.to_bytes(),
+                PreparedValidatorVerifier::new(self.validator_verifier)?.to_bytes(),
             ),
-        }
+            commit_status: false,
+            decode_resource: None,
+            max_age: None,
+        })
+    }
+}
+
+impl TryFrom<AccountInclusionProofResponse> for InclusionData {
+    type Error = VerifyError;
+
+    fn try_from(val: AccountInclusionProofResponse) -> Result<Self, Self::Error> {
+        val.into_inclusion_data(None)
     }
 }
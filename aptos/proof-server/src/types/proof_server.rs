@@ -1,81 +1,16 @@
 // Copyright (c) Argument Computer Corporation
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::{anyhow, Result};
 use aptos_lc::inclusion::{
     SparseMerkleProofAssets, TransactionProofAssets, ValidatorVerifierAssets,
 };
+pub use aptos_lc::prover::ProvingMode;
+use aptos_lc_core::types::ledger_info::MaxAge;
+use aptos_lc_core::types::resource::ResourceKind;
 use serde::{Deserialize, Serialize};
 use sphinx_sdk::SphinxProofWithPublicValues;
 use std::fmt::Display;
 
-/// The proving mode for the prover.
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
-pub enum ProvingMode {
-    STARK,
-    SNARK,
-}
-
-impl ProvingMode {
-    /// Returns a boolean indicating if the proving mode is STARK.
-    ///
-    /// # Returns
-    ///
-    /// A boolean indicating if the proving mode is STARK.
-    pub const fn is_stark(&self) -> bool {
-        matches!(self, ProvingMode::STARK)
-    }
-
-    /// Returns a serialized representation of the enum.
-    ///
-    /// # Returns
-    ///
-    /// A u8 representing the enum.
-    pub const fn to_bytes(&self) -> u8 {
-        match self {
-            ProvingMode::STARK => 0,
-            ProvingMode::SNARK => 1,
-        }
-    }
-
-    /// Returns a ProvingMode from a serialized representation.
-    ///
-    /// # Arguments
-    ///
-    /// * `bytes` - The serialized representation of the enum.
-    ///
-    /// # Returns
-    ///
-    /// The ProvingMode.
-    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
-        match bytes[0] {
-            0 => Ok(ProvingMode::STARK),
-            1 => Ok(ProvingMode::SNARK),
-            _ => Err(anyhow!("Invalid proving mode")),
-        }
-    }
-}
-impl From<ProvingMode> for String {
-    fn from(mode: ProvingMode) -> String {
-        match mode {
-            ProvingMode::STARK => "STARK".to_string(),
-            ProvingMode::SNARK => "SNARK".to_string(),
-        }
-    }
-}
-
-impl TryFrom<&str> for ProvingMode {
-    type Error = anyhow::Error;
-
-    fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
-        match value {
-            "STARK" => Ok(ProvingMode::STARK),
-            "SNARK" => Ok(ProvingMode::SNARK),
-            _ => Err(anyhow!("Invalid proving mode")),
-        }
-    }
-}
-
 /// Data structure used as a payload to request an epoch change proof generation from the proof
 /// server.
 #[derive(Serialize, Deserialize)]
@@ -90,6 +25,20 @@ pub struct InclusionData {
     pub sparse_merkle_proof_assets: SparseMerkleProofAssets,
     pub transaction_proof_assets: TransactionProofAssets,
     pub validator_verifier_assets: ValidatorVerifierAssets,
+    /// Whether to commit the `ExecutionStatus` of the proven transaction.
+    pub commit_status: bool,
+    /// Move resource to decode from the leaf value and whose fields are committed, if any.
+    pub decode_resource: Option<ResourceKind>,
+    /// Bound on the age of the latest `LedgerInfo`, if any.
+    pub max_age: Option<MaxAge>,
+}
+
+/// Data structure used as a payload to request an aggregated proof of an epoch change and of an
+/// inclusion in the new epoch from the proof server.
+#[derive(Serialize, Deserialize)]
+pub struct AggregationData {
+    pub epoch_change_data: EpochChangeData,
+    pub inclusion_data: InclusionData,
 }
 
 /// Main request type for the proof server. It can be used to request both inclusion and epoch
@@ -101,6 +50,8 @@ pub enum Request {
     ProveEpochChange(Box<(ProvingMode, EpochChangeData)>),
     VerifyInclusion(SphinxProofWithPublicValues),
     VerifyEpochChange(SphinxProofWithPublicValues),
+    ProveAggregation(Box<AggregationData>),
+    VerifyAggregation(SphinxProofWithPublicValues),
 }
 
 impl Display for &Request {
@@ -110,6 +61,49 @@ impl Display for &Request {
             Request::ProveEpochChange(_) => write!(f, "ProveEpochChange"),
             Request::VerifyInclusion(_) => write!(f, "VerifyInclusion"),
             Request::VerifyEpochChange(_) => write!(f, "VerifyEpochChange"),
+            Request::ProveAggregation(_) => write!(f, "ProveAggregation"),
+            Request::VerifyAggregation(_) => write!(f, "VerifyAggregation"),
+        }
+    }
+}
+
+/// Identifier of a proving job submitted to the proof server.
+pub type JobId = u64;
+
+/// Status of a proving job submitted to the proof server.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JobStatus {
+    /// The job is waiting for a worker.
+    Queued,
+    /// The proof is being generated.
+    Running,
+    /// The proof is generated and can be fetched.
+    Done,
+    /// The proof generation failed, with the reason of the failure.
+    Failed(String),
+    /// The job was cancelled before its proof was generated.
+    Cancelled,
+}
+
+impl JobStatus {
+    /// Returns a boolean indicating if the job reached a final status.
+    ///
+    /// # Returns
+    ///
+    /// A boolean indicating if the job is done, failed or cancelled.
+    pub const fn is_finished(&self) -> bool {
+        matches!(self, Self::Done | Self::Failed(_) | Self::Cancelled)
+    }
+}
+
+impl Display for JobStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JobStatus::Queued => write!(f, "Queued"),
+            JobStatus::Running => write!(f, "Running"),
+            JobStatus::Done => write!(f, "Done"),
+            JobStatus::Failed(reason) => write!(f, "Failed: {reason}"),
+            JobStatus::Cancelled => write!(f, "Cancelled"),
         }
     }
 }
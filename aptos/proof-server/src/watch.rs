@@ -0,0 +1,379 @@
+// Copyright (c) Argument Computer Corporation
+// SPDX-License-Identifier: Apache-2.0
+
+//! # Watch
+//!
+//! List of the accounts the client proves the inclusion of, read from a JSON
+//! file. Each watched account is proven at its own cadence, either on every
+//! tick or only when its state value changed, and its verified proofs are
+//! published to an output directory and/or a webhook.
+//!
+//! ```json
+//! {
+//!   "output_dir": "proofs",
+//!   "webhook_url": "http://127.0.0.1:9000/proofs",
+//!   "accounts": [
+//!     { "name": "treasury", "address": "0x1234", "resource": "AptosCoinStore", "interval_secs": 60 },
+//!     { "name": "bridge", "address": "0x5678", "trigger": "VersionChange" }
+//!   ]
+//! }
+//! ```
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use aptos_lc_core::crypto::hash::{CryptoHash, HashValue};
+use aptos_lc_core::types::resource::ResourceKind;
+use aptos_lc_core::types::state_key::StateKey;
+use aptos_lc_core::types::{AccountAddress, ACCOUNT_ADDRESS_SIZE};
+use log::info;
+use serde::{Deserialize, Serialize};
+use sphinx_sdk::SphinxProofWithPublicValues;
+
+use crate::error::ClientError;
+use crate::{aptos_inclusion_proof_endpoint, aptos_resource_inclusion_proof_endpoint};
+
+/// Header carrying the name of the watched account of a proof sent to the webhook.
+pub const WATCH_NAME_HEADER: &str = "x-watch-name";
+
+/// Cadence, in seconds, of the accounts that do not declare one.
+const DEFAULT_INTERVAL_SECS: u64 = 10;
+
+/// Extension of the files holding the published proofs.
+const PROOF_FILE_EXTENSION: &str = "proof";
+
+/// When a watched account is proven.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Trigger {
+    /// The account is proven on every tick of its cadence.
+    #[default]
+    Interval,
+    /// The account is checked on every tick of its cadence, and only proven
+    /// when its state value changed since the last proof.
+    VersionChange,
+}
+
+/// An account watched by the client.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WatchEntry {
+    /// Name of the entry, used to identify its proofs.
+    pub name: String,
+    /// Hex encoded address of the account.
+    pub address: String,
+    /// Resource of the account to prove, the account leaf served by the Aptos
+    /// node being proven if `None`.
+    #[serde(default)]
+    pub resource: Option<ResourceKind>,
+    /// Cadence of the entry, in seconds.
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+    /// When the entry is proven.
+    #[serde(default)]
+    pub trigger: Trigger,
+}
+
+impl WatchEntry {
+    /// Creates a new `WatchEntry` proving the account leaf on every tick of
+    /// the default cadence.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the entry.
+    /// * `address` - The hex encoded address of the account.
+    ///
+    /// # Returns
+    ///
+    /// A new `WatchEntry`.
+    pub fn new(name: &str, address: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            address: address.to_string(),
+            resource: None,
+            interval_secs: DEFAULT_INTERVAL_SECS,
+            trigger: Trigger::Interval,
+        }
+    }
+
+    /// Returns the cadence of the entry.
+    ///
+    /// # Returns
+    ///
+    /// The interval between two checks of the entry.
+    pub const fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs)
+    }
+
+    /// Returns the endpoint of the Aptos node serving the inclusion proof of the entry.
+    ///
+    /// # Returns
+    ///
+    /// The endpoint, relative to the URL of the Aptos node.
+    pub fn endpoint(&self) -> String {
+        match self.resource {
+            Some(resource) => {
+                aptos_resource_inclusion_proof_endpoint(&self.address, resource_type(resource))
+            }
+            None => aptos_inclusion_proof_endpoint(&self.address),
+        }
+    }
+
+    /// Returns the state key the leaf proven for the entry must have.
+    ///
+    /// # Returns
+    ///
+    /// The `StateKey` of the watched resource, or `None` if the account leaf
+    /// is proven.
+    pub fn state_key(&self) -> Result<Option<StateKey>, ClientError> {
+        self.resource
+            .map(|resource| Ok(resource.state_key(parse_address(&self.address)?)))
+            .transpose()
+    }
+
+    /// Returns the key the leaf proven for the entry must have, the account
+    /// leaf served by the Aptos node being the `0x1::account::Account`
+    /// resource of the address.
+    ///
+    /// # Returns
+    ///
+    /// The hash of the `StateKey` of the proven leaf.
+    pub fn leaf_key(&self) -> Result<HashValue, ClientError> {
+        let resource = self.resource.unwrap_or(ResourceKind::Account);
+
+        Ok(resource.state_key(parse_address(&self.address)?).hash())
+    }
+}
+
+/// Accounts watched by the client, along with where their proofs are published.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WatchList {
+    /// Directory the verified proofs are written to.
+    #[serde(default)]
+    pub output_dir: Option<PathBuf>,
+    /// URL the verified proofs are posted to.
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    /// The watched accounts.
+    pub accounts: Vec<WatchEntry>,
+}
+
+impl WatchList {
+    /// Loads the watch list of a JSON file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the watch list file.
+    ///
+    /// # Returns
+    ///
+    /// The `WatchList`, or an error if it could not be read or declares an
+    /// invalid entry.
+    pub fn load(path: &Path) -> Result<Self, ClientError> {
+        let watch_list_error =
+            |source: Box<dyn std::error::Error + Sync + Send>| ClientError::WatchList {
+                path: path.display().to_string(),
+                source,
+            };
+
+        let content = fs::read(path).map_err(|err| watch_list_error(err.into()))?;
+        let watch_list: Self =
+            serde_json::from_slice(&content).map_err(|err| watch_list_error(err.into()))?;
+        watch_list.validate().map_err(watch_list_error)?;
+
+        Ok(watch_list)
+    }
+
+    /// Creates a watch list proving a single account leaf on every tick of
+    /// the default cadence, without publishing its proofs.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The hex encoded address of the account.
+    ///
+    /// # Returns
+    ///
+    /// A new `WatchList`.
+    pub fn single(address: &str) -> Self {
+        Self {
+            output_dir: None,
+            webhook_url: None,
+            accounts: vec![WatchEntry::new("account", address)],
+        }
+    }
+
+    fn validate(&self) -> Result<(), Box<dyn std::error::Error + Sync + Send>> {
+        if self.accounts.is_empty() {
+            return Err("the watch list declares no account".into());
+        }
+
+        let mut names = HashSet::new();
+        for entry in &self.accounts {
+            // Names are part of the published file names
+            if entry.name.is_empty()
+                || !entry
+                    .name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            {
+                return Err(format!(
+                    "invalid name {:?}, expected ASCII alphanumerics, '-' or '_'",
+                    entry.name
+                )
+                .into());
+            }
+            if !names.insert(entry.name.as_str()) {
+                return Err(format!("duplicate name {:?}", entry.name).into());
+            }
+            if entry.interval_secs == 0 {
+                return Err(format!("null interval for {:?}", entry.name).into());
+            }
+            parse_address(&entry.address)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Destinations the verified proofs of the watched accounts are published to.
+#[derive(Debug, Clone)]
+pub struct ProofSink {
+    client: reqwest::Client,
+    output_dir: Option<PathBuf>,
+    webhook_url: Option<String>,
+}
+
+impl ProofSink {
+    /// Creates a new `ProofSink` for the destinations of a watch list,
+    /// creating its output directory if needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `watch_list` - The watch list.
+    ///
+    /// # Returns
+    ///
+    /// A new `ProofSink`.
+    pub fn new(watch_list: &WatchList) -> Result<Self, ClientError> {
+        if let Some(output_dir) = &watch_list.output_dir {
+            fs::create_dir_all(output_dir).map_err(|err| ClientError::ProofOutput {
+                destination: output_dir.display().to_string(),
+                source: err.into(),
+            })?;
+        }
+
+        Ok(Self {
+            client: reqwest::Client::new(),
+            output_dir: watch_list.output_dir.clone(),
+            webhook_url: watch_list.webhook_url.clone(),
+        })
+    }
+
+    /// Publishes a verified proof. Proofs are written to the output directory
+    /// as `<name>-<unix-millis>.proof`, and posted to the webhook along with
+    /// the name of their entry in the `x-watch-name` header, both as BCS
+    /// serialized `SphinxProofWithPublicValues`.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry` - The watched account the proof was generated for.
+    /// * `proof` - The verified proof.
+    ///
+    /// # Returns
+    ///
+    /// An error if the proof could not be published to one of the destinations.
+    pub async fn publish(
+        &self,
+        entry: &WatchEntry,
+        proof: &SphinxProofWithPublicValues,
+    ) -> Result<(), ClientError> {
+        if self.output_dir.is_none() && self.webhook_url.is_none() {
+            return Ok(());
+        }
+
+        let proof_bytes =
+            bcs::to_bytes(proof).map_err(|err| ClientError::Internal { source: err.into() })?;
+
+        if let Some(output_dir) = &self.output_dir {
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_millis())
+                .unwrap_or_default();
+            let path = output_dir
+                .join(format!("{}-{timestamp}", entry.name))
+                .with_extension(PROOF_FILE_EXTENSION);
+            write_file(&path, &proof_bytes)?;
+            info!("Proof for {} written to {}", entry.name, path.display());
+        }
+
+        if let Some(webhook_url) = &self.webhook_url {
+            let output_error =
+                |source: Box<dyn std::error::Error + Sync + Send>| ClientError::ProofOutput {
+                    destination: webhook_url.clone(),
+                    source,
+                };
+            self.client
+                .post(webhook_url)
+                .header("Content-Type", "application/octet-stream")
+                .header(WATCH_NAME_HEADER, &entry.name)
+                .body(proof_bytes)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .map_err(|err| output_error(err.into()))?;
+            info!("Proof for {} posted to {webhook_url}", entry.name);
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses a hex encoded account address, optionally prefixed by `0x` and
+/// stripped of its leading zeros.
+///
+/// # Arguments
+///
+/// * `address` - The hex encoded address.
+///
+/// # Returns
+///
+/// The `AccountAddress`.
+pub fn parse_address(address: &str) -> Result<AccountAddress, ClientError> {
+    let invalid_address = |reason: &str| ClientError::Internal {
+        source: format!("Invalid account address {address:?}: {reason}").into(),
+    };
+
+    let digits = address.strip_prefix("0x").unwrap_or(address);
+    if digits.is_empty() || digits.len() > ACCOUNT_ADDRESS_SIZE * 2 {
+        return Err(invalid_address("unexpected length"));
+    }
+    let padded = format!("{digits:0>width$}", width = ACCOUNT_ADDRESS_SIZE * 2);
+    let bytes = hex::decode(padded).map_err(|err| invalid_address(&err.to_string()))?;
+
+    AccountAddress::from_bytes(&bytes).map_err(|err| invalid_address(&err.to_string()))
+}
+
+/// Returns the Move type of a resource, as expected by the Aptos node.
+const fn resource_type(resource: ResourceKind) -> &'static str {
+    match resource {
+        ResourceKind::Account => "0x1::account::Account",
+        ResourceKind::AptosCoinStore => "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>",
+    }
+}
+
+const fn default_interval_secs() -> u64 {
+    DEFAULT_INTERVAL_SECS
+}
+
+fn write_file(path: &Path, bytes: &[u8]) -> Result<(), ClientError> {
+    // Write to a temporary file first so that consumers never read partial proofs
+    let tmp_path = path.with_extension(format!("{PROOF_FILE_EXTENSION}.tmp"));
+    fs::write(&tmp_path, bytes)
+        .and_then(|_| fs::rename(&tmp_path, path))
+        .map_err(|err| ClientError::ProofOutput {
+            destination: path.display().to_string(),
+            source: err.into(),
+        })
+}
@@ -13,9 +13,31 @@ struct SphinxProofFixture {
 contract Wrapper is SphinxPlonkVerifier, Ownable(msg.sender) {
     error ErrorUnexpectedSignerHash();
     error ErrorUnexpectedInclusionFixture();
+    error ErrorUnexpectedInclusionFlags();
     error ErrorUnexpectedEpochChangeFixture();
+    error ErrorUnexpectedEpoch();
+    error ErrorUnexpectedRootOfTrust();
+
+    // root of trust committed by the epoch change program when ratcheting from a validator verifier,
+    // rather than bootstrapping from a waypoint
+    uint8 private constant ROOT_OF_TRUST_VALIDATOR_VERIFIER = 0;
+
+    // flags committed by the inclusion program, telling which optional values follow the ledger info
+    uint8 private constant INCLUSION_MAX_AGE_FLAG = 0x01;
+    uint8 private constant INCLUSION_STATUS_FLAG = 0x02;
+    uint8 private constant INCLUSION_ACCOUNT_FLAG = 0x04;
+    uint8 private constant INCLUSION_COIN_STORE_FLAG = 0x08;
+    // flag set when the proven key is present under the merkle root hash, unset when it is absent from it
+    uint8 private constant INCLUSION_PRESENT_FLAG = 0x10;
+    // flag set when the state key the proven key is derived from follows the execution status
+    uint8 private constant INCLUSION_STATE_KEY_FLAG = 0x20;
+
+    // signer hash, merkle root hash, block id, key and value, then the epoch, version and timestamp of the
+    // ledger info, and the flags
+    uint256 private constant INCLUSION_FIXED_LENGTH = 32 * 5 + 8 * 3 + 1;
 
     bytes32 private signerHash;
+    uint64 private epoch;
 
     constructor(bytes32 signerHash_) {
         signerHash = signerHash_;
@@ -29,10 +51,72 @@ contract Wrapper is SphinxPlonkVerifier, Ownable(msg.sender) {
         return signerHash;
     }
 
-    function verifyInclusion(SphinxProofFixture memory fixture) public view {
-        if (fixture.publicValues.length != 32 + 32 + 32 + 32 + 32) {
+    function getEpoch() public view returns (uint64) {
+        return epoch;
+    }
+
+    // reads a u64 committed by the programs, which encode it as little-endian
+    function readUint64(bytes memory data, uint256 offset) internal pure returns (uint64 value) {
+        for (uint256 i = 0; i < 8; i++) {
+            value |= uint64(uint8(data[offset + i])) << (8 * i);
+        }
+    }
+
+    // reads 32 bytes committed by the programs
+    function readBytes32(bytes memory data, uint256 offset) internal pure returns (bytes32 value) {
+        for (uint256 i = 0; i < 32; i++) {
+            value |= bytes32(data[offset + i]) >> (8 * i);
+        }
+    }
+
+    // returns the length of the optional values committed by the inclusion program for the given flags
+    function inclusionOptionalLength(uint8 flags) internal pure returns (uint256 length) {
+        if ((flags & ~(INCLUSION_MAX_AGE_FLAG | INCLUSION_STATUS_FLAG | INCLUSION_ACCOUNT_FLAG | INCLUSION_COIN_STORE_FLAG | INCLUSION_PRESENT_FLAG | INCLUSION_STATE_KEY_FLAG)) != 0) {
+            revert ErrorUnexpectedInclusionFlags();
+        }
+        if ((flags & INCLUSION_ACCOUNT_FLAG) != 0 && (flags & INCLUSION_COIN_STORE_FLAG) != 0) {
+            revert ErrorUnexpectedInclusionFlags();
+        }
+        // decoded fields are only meaningful along with the address holding them, committed in the state key
+        if ((flags & (INCLUSION_ACCOUNT_FLAG | INCLUSION_COIN_STORE_FLAG)) != 0 && (flags & INCLUSION_STATE_KEY_FLAG) == 0) {
+            revert ErrorUnexpectedInclusionFlags();
+        }
+
+        if ((flags & INCLUSION_MAX_AGE_FLAG) != 0) {
+            // reference timestamp and maximum age
+            length += 8 + 8;
+        }
+        if ((flags & INCLUSION_STATUS_FLAG) != 0) {
+            // status variant and abort code
+            length += 1 + 8;
+        }
+        if ((flags & INCLUSION_STATE_KEY_FLAG) != 0) {
+            // state key variant, address and identifier
+            length += 1 + 32 + 32;
+        }
+        if ((flags & INCLUSION_ACCOUNT_FLAG) != 0) {
+            // sequence number and authentication key
+            length += 8 + 32;
+        }
+        if ((flags & INCLUSION_COIN_STORE_FLAG) != 0) {
+            // balance
+            length += 8;
+        }
+    }
+
+    // checks that the public values of an inclusion proof are as long as their flags tell, and returns the flags
+    function inclusionFlags(bytes memory publicValues) public pure returns (uint8 flags) {
+        if (publicValues.length < INCLUSION_FIXED_LENGTH) {
             revert ErrorUnexpectedInclusionFixture();
         }
+        flags = uint8(publicValues[INCLUSION_FIXED_LENGTH - 1]);
+        if (publicValues.length != INCLUSION_FIXED_LENGTH + inclusionOptionalLength(flags)) {
+            revert ErrorUnexpectedInclusionFixture();
+        }
+    }
+
+    function verifyInclusion(SphinxProofFixture memory fixture) public view returns (uint8 flags) {
+        flags = inclusionFlags(fixture.publicValues);
 
         // it reverts execution if core verification fails, so no special handling is required
         this.verifyProof(fixture.vkey, fixture.publicValues, fixture.proof);
@@ -68,6 +152,7 @@ contract Wrapper is SphinxPlonkVerifier, Ownable(msg.sender) {
         for (i = 0; i < 32; i++) {
             value[i] = fixture.publicValues[i + offset];
         }
+        offset += 32;
 
         if (signerHash != bytes32(signerHashFixture)) {
             revert ErrorUnexpectedSignerHash();
@@ -77,33 +162,84 @@ contract Wrapper is SphinxPlonkVerifier, Ownable(msg.sender) {
         console.log("block identifier is: ", uint256(bytes32(blockId)));
         console.log("key is: ", uint256(bytes32(key)));
         console.log("value is: ", uint256(bytes32(value)));
+        // an absent key is stated by the flags, its value being all zeroes
+        console.log("key is present: ", (flags & INCLUSION_PRESENT_FLAG) != 0);
+
+        logInclusionLedgerInfo(fixture.publicValues, offset, flags);
 
         // allow funds transfer
     }
 
+    function logInclusionLedgerInfo(bytes memory publicValues, uint256 offset, uint8 flags) internal pure {
+        console.log("epoch is: ", uint256(readUint64(publicValues, offset)));
+        console.log("version is: ", uint256(readUint64(publicValues, offset + 8)));
+        console.log("timestamp (usecs) is: ", uint256(readUint64(publicValues, offset + 16)));
+        // skip the epoch, version, timestamp and flags
+        offset += 8 * 3 + 1;
+
+        if ((flags & INCLUSION_MAX_AGE_FLAG) != 0) {
+            console.log("reference timestamp (usecs) is: ", uint256(readUint64(publicValues, offset)));
+            console.log("maximum age (usecs) is: ", uint256(readUint64(publicValues, offset + 8)));
+            offset += 8 + 8;
+        }
+        if ((flags & INCLUSION_STATUS_FLAG) != 0) {
+            console.log("status variant is: ", uint256(uint8(publicValues[offset])));
+            console.log("abort code is: ", uint256(readUint64(publicValues, offset + 1)));
+            offset += 1 + 8;
+        }
+        // address holding the resource or the table item, the decoded fields belonging to it
+        bytes32 stateKeyAddress;
+        if ((flags & INCLUSION_STATE_KEY_FLAG) != 0) {
+            stateKeyAddress = readBytes32(publicValues, offset + 1);
+            // the identifier is the keccak256 hash of the BCS serialized struct tag of a resource, or of the key of
+            // a table item, so that it can be compared to the one of the expected resource
+            console.log("state key variant is: ", uint256(uint8(publicValues[offset])));
+            console.log("address is: ", uint256(stateKeyAddress));
+            console.log("identifier is: ", uint256(readBytes32(publicValues, offset + 1 + 32)));
+            offset += 1 + 32 + 32;
+        }
+        if ((flags & INCLUSION_ACCOUNT_FLAG) != 0) {
+            console.log("account address is: ", uint256(stateKeyAddress));
+            console.log("sequence number is: ", uint256(readUint64(publicValues, offset)));
+            console.log("authentication key is: ", uint256(readBytes32(publicValues, offset + 8)));
+        }
+        if ((flags & INCLUSION_COIN_STORE_FLAG) != 0) {
+            console.log("coin store address is: ", uint256(stateKeyAddress));
+            console.log("balance is: ", uint256(readUint64(publicValues, offset)));
+        }
+    }
+
     function verifyEpochChange(SphinxProofFixture memory fixture) public {
-        if (fixture.publicValues.length != 64) {
+        // previous signer hash, new signer hash, new epoch and root of trust
+        if (fixture.publicValues.length != 32 + 32 + 8 + 1) {
             revert ErrorUnexpectedEpochChangeFixture();
         }
 
-        // it reverts execution if core verification fails, so no special handling is required
-        this.verifyProof(fixture.vkey, fixture.publicValues, fixture.proof);
+        // extract previous and new signer hashes from public values, which are checked against the contract state
+        // before the more expensive proof verification
+        bytes32 prevSignerHash = readBytes32(fixture.publicValues, 0);
+        bytes32 newSignerHash = readBytes32(fixture.publicValues, 32);
 
-        // extract previous and new signer hashes from public values (we are safe here as input validation is performed in the core contract)
-        bytes memory prevSignerHash = new bytes(32);
-        bytes memory newSignerHash = new bytes(32);
-        uint256 offset = 32;
-        uint256 length = 32;
-        for (uint8 i = 0; i < length; i++) {
-            prevSignerHash[i] = fixture.publicValues[i];
-            newSignerHash[i] = fixture.publicValues[i + offset];
+        // the first slot holds a waypoint value for proofs bootstrapping from a waypoint, which the contract does not trust
+        if (uint8(fixture.publicValues[32 + 32 + 8]) != ROOT_OF_TRUST_VALIDATOR_VERIFIER) {
+            revert ErrorUnexpectedRootOfTrust();
         }
 
-        if (signerHash != bytes32(prevSignerHash)) {
+        if (signerHash != prevSignerHash) {
             revert ErrorUnexpectedSignerHash();
         }
 
-        // update signer hash
-        setSignerHash(bytes32(newSignerHash));
+        // the proof may ratchet over several epochs, but never backwards
+        uint64 newEpoch = readUint64(fixture.publicValues, 32 + 32);
+        if (newEpoch <= epoch) {
+            revert ErrorUnexpectedEpoch();
+        }
+
+        // it reverts execution if core verification fails, so no special handling is required
+        this.verifyProof(fixture.vkey, fixture.publicValues, fixture.proof);
+
+        // update signer hash and epoch
+        setSignerHash(newSignerHash);
+        epoch = newEpoch;
     }
 }
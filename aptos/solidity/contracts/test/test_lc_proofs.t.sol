@@ -10,15 +10,45 @@ import {SphinxVerifier} from "sphinx-contracts/solidity/src/SphinxVerifier.sol";
 contract SolidityVerificationTest is Test {
     using stdJson for string;
 
-    // Values, taken from public values (two 32 byte chunks) of the fixture: src/plonk_fixtures/epoch_change_fixture.json
-    uint256 private constant TestValidSignerHash = 0x205829098a4c0273312e8bc4fdbde28fc12abdc540c88bdd9abeef0a85d706ec;
-    uint256 private constant TestUpdatedSignerHash = 0xc071f215064bfe6f1c24295135199ce6f6dec2974115fad50989e666915453ad;
+    // Offset of the inclusion flags, following the five 32 byte chunks and the epoch, version and timestamp of the
+    // ledger info
+    uint256 private constant InclusionFlagsOffset = 32 * 5 + 8 * 3;
+    // Flags of the fixture: src/plonk_fixtures/inclusion_fixture.json, proving a present key along with the execution
+    // status, the state key and the decoded account fields
+    uint8 private constant TestInclusionFlags = 0x10 | 0x02 | 0x20 | 0x04;
+
+    // Values, taken from public values (first two 32 byte chunks) of the fixture: src/plonk_fixtures/epoch_change_fixture.json
+    bytes32 private testValidSignerHash;
+    bytes32 private testUpdatedSignerHash;
 
     Wrapper wrapper;
 
     function setUp() public {
-        bytes32 signer_hash = bytes32(TestValidSignerHash);
-        wrapper = new Wrapper(signer_hash);
+        SphinxProofFixture memory fixture = loadPlonkEpochChangeFixture();
+        testValidSignerHash = bytes32(fixture.publicValues);
+        testUpdatedSignerHash = bytes32(publicValuesSlice(fixture.publicValues, 32));
+        wrapper = new Wrapper(testValidSignerHash);
+    }
+
+    // returns the 32 bytes of the public values at the given offset
+    function publicValuesSlice(bytes memory publicValues, uint256 offset) internal pure returns (bytes memory slice) {
+        slice = new bytes(32);
+        for (uint256 i = 0; i < 32; i++) {
+            slice[i] = publicValues[offset + i];
+        }
+    }
+
+    // returns the public values committed by the epoch change program, the epoch being encoded as little-endian
+    function epochChangePublicValues(bytes32 prevSignerHash, bytes32 newSignerHash, uint64 newEpoch, uint8 rootOfTrust)
+        internal
+        pure
+        returns (bytes memory)
+    {
+        bytes memory epochBytes = new bytes(8);
+        for (uint256 i = 0; i < 8; i++) {
+            epochBytes[i] = bytes1(uint8(newEpoch >> (8 * i)));
+        }
+        return abi.encodePacked(prevSignerHash, newSignerHash, epochBytes, rootOfTrust);
     }
 
     function loadPlonkInclusionFixture() public view returns (SphinxProofFixture memory) {
@@ -53,6 +83,28 @@ contract SolidityVerificationTest is Test {
         require(gasCost - gasleft() < 300000, "Too big gas cost");
     }
 
+    function testVerifyInclusion() public view {
+        SphinxProofFixture memory fixture = loadPlonkInclusionFixture();
+        uint8 flags = wrapper.verifyInclusion(fixture);
+        require(flags == TestInclusionFlags, "Unexpected inclusion flags");
+    }
+
+    // returns zeroed inclusion public values with the given flags, followed by optional values of the given length
+    function inclusionPublicValues(uint8 flags, uint256 optionalLength) internal pure returns (bytes memory publicValues) {
+        publicValues = new bytes(InclusionFlagsOffset + 1 + optionalLength);
+        publicValues[InclusionFlagsOffset] = bytes1(flags);
+    }
+
+    function testInclusionFlags() public view {
+        // status variant and abort code, state key variant, address and identifier, sequence number and
+        // authentication key
+        bytes memory publicValues = inclusionPublicValues(TestInclusionFlags, 1 + 8 + 1 + 32 + 32 + 8 + 32);
+        require(wrapper.inclusionFlags(publicValues) == TestInclusionFlags, "Unexpected inclusion flags");
+
+        // an absent key, with no optional values
+        require(wrapper.inclusionFlags(inclusionPublicValues(0x00, 0)) == 0x00, "Unexpected inclusion flags");
+    }
+
     // Negative tests
     function testFail_FakeProofInclusion() public view {
         SphinxProofFixture memory fixture = loadPlonkInclusionFixture();
@@ -107,17 +159,85 @@ contract SolidityVerificationTest is Test {
         wrapper.verifyInclusion(fixture);
     }
 
-    function testFailInvalidSignerHashEpochChange() public {
+    function testInclusionFlagsMismatch() public {
+        SphinxProofFixture memory fixture = loadPlonkInclusionFixture();
+        // claim a maximum age that is not part of the public values
+        fixture.publicValues[InclusionFlagsOffset] = bytes1(TestInclusionFlags | 0x01);
+        vm.expectRevert(Wrapper.ErrorUnexpectedInclusionFixture.selector);
+        wrapper.verifyInclusion(fixture);
+
+        // claim an execution status that is not part of the public values
+        vm.expectRevert(Wrapper.ErrorUnexpectedInclusionFixture.selector);
+        wrapper.inclusionFlags(inclusionPublicValues(0x10 | 0x02, 0));
+    }
+
+    function testInclusionUnknownFlag() public {
+        SphinxProofFixture memory fixture = loadPlonkInclusionFixture();
+        fixture.publicValues[InclusionFlagsOffset] = 0x80;
+        vm.expectRevert(Wrapper.ErrorUnexpectedInclusionFlags.selector);
+        wrapper.verifyInclusion(fixture);
+
+        vm.expectRevert(Wrapper.ErrorUnexpectedInclusionFlags.selector);
+        wrapper.inclusionFlags(inclusionPublicValues(0x80, 0));
+    }
+
+    function testInclusionResourceWithoutStateKey() public {
+        // balance of a coin store, without the address holding it
+        vm.expectRevert(Wrapper.ErrorUnexpectedInclusionFlags.selector);
+        wrapper.inclusionFlags(inclusionPublicValues(0x10 | 0x08, 8));
+    }
+
+    function testInvalidSignerHashEpochChange() public {
         SphinxProofFixture memory fixture = loadPlonkEpochChangeFixture();
         // alter signer hash which is first 32 bytes
         fixture.publicValues[0] = 0xff;
+        vm.expectRevert(Wrapper.ErrorUnexpectedSignerHash.selector);
         wrapper.verifyEpochChange(fixture);
     }
 
     function testEpochChangeSignerHashUpdate() public {
         SphinxProofFixture memory fixture = loadPlonkEpochChangeFixture();
-        require(wrapper.getSignerHash() == bytes32(TestValidSignerHash), "Unexpected value of signer hash during setup");
+        require(wrapper.getSignerHash() == testValidSignerHash, "Unexpected value of signer hash during setup");
+        wrapper.verifyEpochChange(fixture);
+        require(wrapper.getSignerHash() == testUpdatedSignerHash, "Signer hash was not updated");
+        require(wrapper.getEpoch() > 0, "Epoch was not updated");
+    }
+
+    function testEpochChangeReplay() public {
+        SphinxProofFixture memory fixture = loadPlonkEpochChangeFixture();
+        wrapper.verifyEpochChange(fixture);
+        // the signer hash of the fixture can be restored, but its epoch is no longer ahead
+        wrapper.setSignerHash(testValidSignerHash);
+        vm.expectRevert(Wrapper.ErrorUnexpectedEpoch.selector);
+        wrapper.verifyEpochChange(fixture);
+
+        // an epoch that is not ahead of the contract one is rejected before the proof is verified
+        fixture.publicValues = epochChangePublicValues(testValidSignerHash, testUpdatedSignerHash, wrapper.getEpoch(), 0);
+        vm.expectRevert(Wrapper.ErrorUnexpectedEpoch.selector);
+        wrapper.verifyEpochChange(fixture);
+    }
+
+    function testWaypointRootOfTrustEpochChange() public {
+        SphinxProofFixture memory fixture = loadPlonkEpochChangeFixture();
+        // claim that the first slot holds a waypoint value, committed last
+        fixture.publicValues[fixture.publicValues.length - 1] = 0x01;
+        vm.expectRevert(Wrapper.ErrorUnexpectedRootOfTrust.selector);
+        wrapper.verifyEpochChange(fixture);
+
+        fixture.publicValues = epochChangePublicValues(testValidSignerHash, testUpdatedSignerHash, 1, 1);
+        vm.expectRevert(Wrapper.ErrorUnexpectedRootOfTrust.selector);
+        wrapper.verifyEpochChange(fixture);
+    }
+
+    function testTruncatedPublicValuesEpochChange() public {
+        SphinxProofFixture memory fixture = loadPlonkEpochChangeFixture();
+        // drop the epoch and root of trust committed after the signer hashes
+        bytes memory truncatedPublicValues = new bytes(64);
+        for (uint256 i = 0; i < 64; i++) {
+            truncatedPublicValues[i] = fixture.publicValues[i];
+        }
+        fixture.publicValues = truncatedPublicValues;
+        vm.expectRevert(Wrapper.ErrorUnexpectedEpochChangeFixture.selector);
         wrapper.verifyEpochChange(fixture);
-        require(wrapper.getSignerHash() == bytes32(TestUpdatedSignerHash), "Signer hash was not updated");
     }
 }
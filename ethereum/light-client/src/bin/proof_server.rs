@@ -16,10 +16,12 @@ use axum::{
 use clap::{Parser, ValueEnum};
 use ethereum_lc::proofs::committee_change::CommitteeChangeProver;
 use ethereum_lc::proofs::inclusion::StorageInclusionProver;
+use ethereum_lc::proofs::key_store::{verifying_key_hash, KeyStore};
 use ethereum_lc::proofs::Prover;
 use ethereum_lc::types::network::Request;
 use ethers_core::k256::elliptic_curve::ff::derive::bitvec::macros::internal::funty::Fundamental;
 use log::{error, info};
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use tokio::net::TcpListener;
@@ -44,6 +46,11 @@ struct Cli {
     /// Mode of operation: either 'single' or 'split'
     #[arg(short, long)]
     mode: Mode,
+
+    /// Directory caching the proving and verifying keys of the programs across restarts. Keys
+    /// are only generated again when a program changes.
+    #[arg(long)]
+    key_cache_dir: Option<PathBuf>,
 }
 
 #[derive(Clone)]
@@ -62,6 +69,7 @@ async fn main() -> Result<()> {
         addr,
         snd_addr,
         mode,
+        key_cache_dir,
     } = Cli::parse();
 
     if mode == Mode::Split && snd_addr.is_none() {
@@ -72,9 +80,28 @@ async fn main() -> Result<()> {
 
     env_logger::init();
 
+    let (committee_prover, inclusion_prover) = match key_cache_dir {
+        Some(key_cache_dir) => {
+            let key_store = KeyStore::new(key_cache_dir)?;
+            (
+                CommitteeChangeProver::with_key_store(&key_store)?,
+                StorageInclusionProver::with_key_store(&key_store)?,
+            )
+        }
+        None => (CommitteeChangeProver::new(), StorageInclusionProver::new()),
+    };
+    info!(
+        "Committee change program verifying key hash: {}",
+        verifying_key_hash(committee_prover.get_vk())
+    );
+    info!(
+        "Inclusion program verifying key hash: {}",
+        verifying_key_hash(inclusion_prover.get_vk())
+    );
+
     let state = ServerState {
-        committee_prover: Arc::new(CommitteeChangeProver::new()),
-        inclusion_prover: Arc::new(StorageInclusionProver::new()),
+        committee_prover: Arc::new(committee_prover),
+        inclusion_prover: Arc::new(inclusion_prover),
         snd_addr: Arc::new(snd_addr),
         mode,
         active_requests: Arc::new(AtomicUsize::new(0)),
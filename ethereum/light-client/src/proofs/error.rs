@@ -1,6 +1,7 @@
 // Copyright (c) Argument Computer Corporation
 // SPDX-License-Identifier: APACHE-2.0
 
+pub use lc_key_store::KeyStoreError;
 use thiserror::Error;
 
 /// The error type for errors being thrown while proving program execution.
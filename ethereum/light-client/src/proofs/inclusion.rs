@@ -6,7 +6,8 @@
 //! This module provides the prover implementation for the storage inclusion proof. The prover
 //! is responsible for generating, executing, proving, and verifying proofs for the light client.
 
-use crate::proofs::error::ProverError;
+use crate::proofs::error::{KeyStoreError, ProverError};
+use crate::proofs::key_store::KeyStore;
 use crate::proofs::{ProofType, Prover, ProvingMode};
 use anyhow::Result;
 use ethereum_lc_core::crypto::hash::{HashValue, HASH_LENGTH};
@@ -48,6 +49,22 @@ impl StorageInclusionProver {
         Self { client, keys }
     }
 
+    /// Create a new `StorageInclusionProver`, loading its keys from a `KeyStore`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_store` - The store holding the keys, generated and stored if missing.
+    ///
+    /// # Returns
+    ///
+    /// A new `StorageInclusionProver`.
+    pub fn with_key_store(key_store: &KeyStore) -> Result<Self, KeyStoreError> {
+        let client = ProverClient::new();
+        let keys = key_store.setup(&client, INCLUSION_PROGRAM)?;
+
+        Ok(Self { client, keys })
+    }
+
     /// Gets a `SphinxVerifyingKey`.
     ///
     /// # Returns
@@ -27,6 +27,8 @@ pub mod committee_change;
 pub mod error;
 pub mod inclusion;
 
+pub use lc_key_store as key_store;
+
 /// The proving mode for the prover.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
 pub enum ProvingMode {
@@ -5,6 +5,8 @@ use sphinx_sdk::{ProverClient, SphinxProof, SphinxProofWithPublicValues};
 use std::fmt::Display;
 use std::path::PathBuf;
 
+use aptos_lc_core::types::resource::ResourceKind;
+
 use ethereum_lc::proofs::committee_change::{CommitteeChangeIn, CommitteeChangeProver};
 use ethereum_lc::proofs::inclusion::{StorageInclusionIn, StorageInclusionProver};
 use ethereum_lc::proofs::{ProofType, Prover, ProvingMode};
@@ -170,10 +172,15 @@ fn generate_fixture_inclusion_aptos_lc() {
     let elf = APTOS_INCLUSION_ELF;
     let (sparse_merkle_proof_assets, transaction_proof_assets, validator_verifier_assets) =
         aptos_lc::inclusion::setup_assets();
+    // Commit the execution status and the decoded account fields, so that the Solidity tests read
+    // optional public values
     let stdin = aptos_lc::inclusion::generate_stdin(
         &sparse_merkle_proof_assets,
         &transaction_proof_assets,
         &validator_verifier_assets,
+        true,
+        Some(ResourceKind::Account),
+        None,
     );
 
     let prover = ProverClient::new();
@@ -319,24 +326,7 @@ fn generate_fixture_epoch_change_aptos_lc() {
         proof: proof_bytes(&proof),
     };
 
-    let signer_hash_string =
-        proof.public_values.bytes().to_string().as_str()[2..2 + 64].to_string();
-    let updated_signer_hash_string =
-        proof.public_values.bytes().to_string().as_str()[2 + 64..2 + 64 * 2].to_string();
-
-    println!("Copy paste to aptos/solidity/contracts/test/test_lc_proof.sol (in case of the new Sphinx version):");
-    println!();
-    println!("// Values, taken from public values (two 32 byte chunks) of the fixture: src/plonk_fixtures/epoch_change_fixture.json");
-    println!(
-        "uint256 private constant TestValidSignerHash = 0x{};",
-        signer_hash_string
-    );
-    println!(
-        "uint256 private constant TestUpdatedSignerHash = 0x{};",
-        updated_signer_hash_string
-    );
-    println!();
-
+    // The Solidity tests read the signer hashes from the public values of the fixture
     save_fixture(
         &Fixture::Base(fixture),
         &fixture_path,
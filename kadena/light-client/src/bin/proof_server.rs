@@ -9,11 +9,13 @@ use axum::middleware::Next;
 use axum::routing::post;
 use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::get, Router};
 use clap::{Parser, ValueEnum};
+use kadena_lc::proofs::key_store::{verifying_key_hash, KeyStore};
 use kadena_lc::proofs::longest_chain::LongestChainProver;
 use kadena_lc::proofs::spv::SpvProver;
 use kadena_lc::proofs::Prover;
 use kadena_lc::types::network::Request;
 use log::{error, info};
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use tokio::net::TcpListener;
@@ -38,6 +40,11 @@ struct Cli {
     /// Mode of operation: either 'single' or 'split'
     #[arg(short, long)]
     mode: Mode,
+
+    /// Directory caching the proving and verifying keys of the programs across restarts. Keys
+    /// are only generated again when a program changes.
+    #[arg(long)]
+    key_cache_dir: Option<PathBuf>,
 }
 
 #[derive(Clone)]
@@ -57,6 +64,7 @@ async fn main() -> Result<()> {
         addr,
         snd_addr,
         mode,
+        key_cache_dir,
     } = Cli::parse();
 
     if mode == Mode::Split && snd_addr.is_none() {
@@ -67,12 +75,31 @@ async fn main() -> Result<()> {
 
     env_logger::init();
 
+    let (longest_chain_prover, spv_prover) = match key_cache_dir {
+        Some(key_cache_dir) => {
+            let key_store = KeyStore::new(key_cache_dir)?;
+            (
+                LongestChainProver::with_key_store(&key_store)?,
+                SpvProver::with_key_store(&key_store)?,
+            )
+        }
+        None => (LongestChainProver::new(), SpvProver::new()),
+    };
+    info!(
+        "Longest chain program verifying key hash: {}",
+        verifying_key_hash(longest_chain_prover.get_vk())
+    );
+    info!(
+        "SPV program verifying key hash: {}",
+        verifying_key_hash(spv_prover.get_vk())
+    );
+
     let state = ServerState {
         snd_addr: Arc::new(snd_addr),
         mode,
         active_requests: Arc::new(AtomicUsize::new(0)),
-        longest_chain_prover: Arc::new(LongestChainProver::new()),
-        spv_prover: Arc::new(SpvProver::new()),
+        longest_chain_prover: Arc::new(longest_chain_prover),
+        spv_prover: Arc::new(spv_prover),
     };
 
     let app = Router::new()
@@ -17,6 +17,8 @@ pub mod error;
 pub mod longest_chain;
 pub mod spv;
 
+pub use lc_key_store as key_store;
+
 /// The proving mode for the prover.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
 pub enum ProvingMode {
@@ -6,7 +6,8 @@
 //! This module provides the prover implementation for the SPV proof. The prover
 //! is responsible for generating, executing, proving, and verifying proofs for the light client.
 
-use crate::proofs::error::ProverError;
+use crate::proofs::error::{KeyStoreError, ProverError};
+use crate::proofs::key_store::KeyStore;
 use crate::proofs::{ProofType, Prover, ProvingMode};
 use anyhow::Result;
 use getset::Getters;
@@ -47,6 +48,22 @@ impl SpvProver {
         Self { client, keys }
     }
 
+    /// Create a new `SpvProver`, loading its keys from a `KeyStore`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_store` - The store holding the keys, generated and stored if missing.
+    ///
+    /// # Returns
+    ///
+    /// A new `SpvProver`.
+    pub fn with_key_store(key_store: &KeyStore) -> Result<Self, KeyStoreError> {
+        let client = ProverClient::new();
+        let keys = key_store.setup(&client, SPV_PROGRAM)?;
+
+        Ok(Self { client, keys })
+    }
+
     /// Gets a `SphinxVerifyingKey`.
     ///
     /// # Returns